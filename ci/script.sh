@@ -7,6 +7,9 @@ main() {
     cross build --target $TARGET
     cross build --target $TARGET --release
 
+    rustup target add wasm32-unknown-unknown
+    cargo check --lib --target wasm32-unknown-unknown --features wasm
+
     if [ ! -z $DISABLE_TESTS ]; then
         return
     fi
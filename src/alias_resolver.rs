@@ -6,14 +6,14 @@ where
   'a: 'b,
 {
   aliases: &'b BTreeMap<&'a str, Alias<'a>>,
-  recipes: &'b BTreeMap<&'a str, Recipe<'a>>,
+  recipes: &'b OrderedMap<&'a str, Recipe<'a>>,
   alias_tokens: &'b BTreeMap<&'a str, Token<'a>>,
 }
 
 impl<'a: 'b, 'b> AliasResolver<'a, 'b> {
   pub(crate) fn resolve_aliases(
     aliases: &BTreeMap<&'a str, Alias<'a>>,
-    recipes: &BTreeMap<&'a str, Recipe<'a>>,
+    recipes: &OrderedMap<&'a str, Recipe<'a>>,
     alias_tokens: &BTreeMap<&'a str, Token<'a>>,
   ) -> CompilationResult<'a, ()> {
     let resolver = AliasResolver {
@@ -1,41 +1,52 @@
 use crate::common::*;
 
 pub(crate) struct AssignmentEvaluator<'a: 'b, 'b> {
-  pub(crate) assignments: &'b BTreeMap<&'a str, Expression<'a>>,
+  pub(crate) assignments: &'b OrderedMap<&'a str, Expression<'a>>,
   pub(crate) invocation_directory: &'b Result<PathBuf, String>,
+  pub(crate) justfile: Option<&'b Path>,
+  pub(crate) justfile_directory: Option<&'b Path>,
+  pub(crate) run_directory: Option<&'b Path>,
   pub(crate) dotenv: &'b BTreeMap<String, String>,
   pub(crate) dry_run: bool,
+  pub(crate) environment: &'b BTreeMap<OsString, OsString>,
   pub(crate) evaluated: BTreeMap<&'a str, String>,
-  pub(crate) exports: &'b BTreeSet<&'a str>,
+  pub(crate) exports: &'b BTreeMap<&'a str, Export>,
   pub(crate) overrides: &'b BTreeMap<&'b str, &'b str>,
   pub(crate) quiet: bool,
+  pub(crate) recipe_context: Option<&'b RecipeContext<'a>>,
   pub(crate) scope: &'b BTreeMap<&'a str, String>,
   pub(crate) shell: &'b str,
+  pub(crate) shell_arguments: &'b [&'b str],
 }
 
 impl<'a, 'b> AssignmentEvaluator<'a, 'b> {
   pub(crate) fn evaluate_assignments(
-    assignments: &BTreeMap<&'a str, Expression<'a>>,
-    invocation_directory: &Result<PathBuf, String>,
+    assignments: &OrderedMap<&'a str, Expression<'a>>,
+    config: &'a Config<'a>,
     dotenv: &'b BTreeMap<String, String>,
-    overrides: &BTreeMap<&str, &str>,
-    quiet: bool,
-    shell: &'a str,
-    dry_run: bool,
   ) -> RunResult<'a, BTreeMap<&'a str, String>> {
     let mut evaluator = AssignmentEvaluator {
       evaluated: empty(),
       exports: &empty(),
       scope: &empty(),
+      recipe_context: None,
+      invocation_directory: &config.invocation_directory,
+      justfile: config.justfile_path.as_deref(),
+      justfile_directory: config.justfile_directory.as_deref(),
+      run_directory: config.run_directory.as_deref(),
+      dry_run: config.dry_run || config.no_execute_backticks,
+      environment: &config.environment_snapshot,
+      overrides: &config.overrides,
+      quiet: config.quiet,
+      shell: config.shell,
+      shell_arguments: &config.shell_arguments,
       assignments,
-      invocation_directory,
       dotenv,
-      dry_run,
-      overrides,
-      quiet,
-      shell,
     };
 
+    // Assignments are evaluated in source order, except that variables are
+    // evaluated when first used, if that's earlier, so backticks run in the
+    // order they're written
     for name in assignments.keys() {
       evaluator.evaluate_assignment(name)?;
     }
@@ -43,6 +54,36 @@ impl<'a, 'b> AssignmentEvaluator<'a, 'b> {
     Ok(evaluator.evaluated)
   }
 
+  /// Evaluate `expression`, given with `--expr`, along with only those of
+  /// `assignments` that it uses
+  pub(crate) fn evaluate_standalone(
+    expression: &Expression<'a>,
+    assignments: &OrderedMap<&'a str, Expression<'a>>,
+    config: &'a Config<'a>,
+    dotenv: &'b BTreeMap<String, String>,
+  ) -> RunResult<'a, String> {
+    let mut evaluator = AssignmentEvaluator {
+      evaluated: empty(),
+      exports: &empty(),
+      scope: &empty(),
+      recipe_context: None,
+      invocation_directory: &config.invocation_directory,
+      justfile: config.justfile_path.as_deref(),
+      justfile_directory: config.justfile_directory.as_deref(),
+      run_directory: config.run_directory.as_deref(),
+      dry_run: config.dry_run || config.no_execute_backticks,
+      environment: &config.environment_snapshot,
+      overrides: &config.overrides,
+      quiet: config.quiet,
+      shell: config.shell,
+      shell_arguments: &config.shell_arguments,
+      assignments,
+      dotenv,
+    };
+
+    evaluator.evaluate_expression(expression, &empty())
+  }
+
   pub(crate) fn evaluate_line(
     &mut self,
     line: &[Fragment<'a>],
@@ -51,7 +92,7 @@ impl<'a, 'b> AssignmentEvaluator<'a, 'b> {
     let mut evaluated = String::new();
     for fragment in line {
       match *fragment {
-        Fragment::Text { ref text } => evaluated += text.lexeme(),
+        Fragment::Text { ref text } => evaluated += &Fragment::unescape(text),
         Fragment::Expression { ref expression } => {
           evaluated += &self.evaluate_expression(expression, arguments)?;
         }
@@ -112,9 +153,17 @@ impl<'a, 'b> AssignmentEvaluator<'a, 'b> {
           .iter()
           .map(|argument| self.evaluate_expression(argument, arguments))
           .collect::<Result<Vec<String>, RuntimeError>>()?;
+        if name == "invoke" {
+          return self.invoke(token, &call_arguments);
+        }
         let context = FunctionContext {
           invocation_directory: &self.invocation_directory,
           dotenv: self.dotenv,
+          environment: self.environment,
+          justfile: self.justfile,
+          justfile_directory: self.justfile_directory,
+          run_directory: self.run_directory,
+          dry_run: self.dry_run,
         };
         Function::evaluate(token, name, &context, &call_arguments)
       }
@@ -129,21 +178,114 @@ impl<'a, 'b> AssignmentEvaluator<'a, 'b> {
       Expression::Concatination { ref lhs, ref rhs } => {
         Ok(self.evaluate_expression(lhs, arguments)? + &self.evaluate_expression(rhs, arguments)?)
       }
+      Expression::Join { ref lhs, ref rhs } => Ok(Expression::join(
+        &self.evaluate_expression(lhs, arguments)?,
+        &self.evaluate_expression(rhs, arguments)?,
+      )),
+      Expression::Conditional {
+        ref lhs,
+        ref rhs,
+        ref then,
+        ref otherwise,
+      } => {
+        let lhs = self.evaluate_expression(lhs, arguments)?;
+        let rhs = self.evaluate_expression(rhs, arguments)?;
+        if lhs == rhs {
+          self.evaluate_expression(then, arguments)
+        } else {
+          self.evaluate_expression(otherwise, arguments)
+        }
+      }
+      Expression::InterpolatedString { ref fragments, .. } => {
+        let mut evaluated = String::new();
+        for fragment in fragments {
+          match fragment {
+            StringFragment::Text { cooked } => evaluated += cooked,
+            StringFragment::Expression { expression } => {
+              evaluated += &self.evaluate_expression(expression, arguments)?;
+            }
+          }
+        }
+        Ok(evaluated)
+      }
       Expression::Group { ref expression } => self.evaluate_expression(&expression, arguments),
     }
   }
 
+  /// Run the recipe named by the first argument with the rest of the
+  /// arguments, evaluating to the empty string. Recipes invoked without
+  /// arguments are skipped if they have already run, like dependencies.
+  fn invoke(&self, token: &Token<'a>, arguments: &[String]) -> RunResult<'a, String> {
+    let context = self
+      .recipe_context
+      .ok_or_else(|| RuntimeError::FunctionCall {
+        token: token.clone(),
+        message: "`invoke` may only be used in recipes".to_string(),
+      })?;
+
+    let (name, arguments) = arguments
+      .split_first()
+      .ok_or_else(|| RuntimeError::Internal {
+        message: "attempted to evaluate `invoke` without arguments".to_string(),
+      })?;
+
+    let recipe = context
+      .justfile
+      .get_recipe(name)
+      .ok_or_else(|| RuntimeError::FunctionCall {
+        token: token.clone(),
+        message: format!("unknown recipe `{}`", name),
+      })?;
+
+    if !recipe.argument_range().range_contains(&arguments.len()) {
+      return Err(RuntimeError::FunctionCall {
+        token: token.clone(),
+        message: format!(
+          "recipe `{}` got {} {} but takes {}",
+          recipe.name,
+          arguments.len(),
+          Count("argument", arguments.len()),
+          if recipe.min_arguments() == recipe.max_arguments() {
+            recipe.min_arguments().to_string()
+          } else if recipe
+            .parameters
+            .iter()
+            .any(|parameter| parameter.kind.is_variadic())
+          {
+            format!("at least {}", recipe.min_arguments())
+          } else {
+            format!("{} to {}", recipe.min_arguments(), recipe.max_arguments())
+          },
+        ),
+      });
+    }
+
+    if arguments.is_empty() && context.ran.borrow().contains(recipe.name) {
+      return Ok(String::new());
+    }
+
+    let arguments = arguments.iter().map(String::as_str).collect::<Vec<&str>>();
+
+    context
+      .justfile
+      .run_recipe(context, recipe, &arguments, self.dotenv)?;
+
+    Ok(String::new())
+  }
+
   fn run_backtick(
     &self,
     dotenv: &BTreeMap<String, String>,
     raw: &str,
     token: &Token<'a>,
   ) -> RunResult<'a, String> {
-    let mut cmd = Command::new(self.shell);
+    let mut cmd = Platform::make_shell_command(self.shell, self.shell_arguments, raw);
 
-    cmd.arg("-cu").arg(raw);
+    if let Some(run_directory) = self.run_directory {
+      cmd.current_dir(run_directory);
+    }
 
-    cmd.export_environment_variables(self.scope, dotenv, self.exports)?;
+    cmd.export_environment_variables(self.scope, dotenv, self.exports, self.environment)?;
 
     cmd.stdin(process::Stdio::inherit());
 
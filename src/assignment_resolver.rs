@@ -3,7 +3,7 @@ use crate::common::*;
 use CompilationErrorKind::*;
 
 pub(crate) struct AssignmentResolver<'a: 'b, 'b> {
-  assignments: &'b BTreeMap<&'a str, Expression<'a>>,
+  assignments: &'b OrderedMap<&'a str, Expression<'a>>,
   assignment_tokens: &'b BTreeMap<&'a str, Token<'a>>,
   stack: Vec<&'a str>,
   seen: BTreeSet<&'a str>,
@@ -12,7 +12,7 @@ pub(crate) struct AssignmentResolver<'a: 'b, 'b> {
 
 impl<'a: 'b, 'b> AssignmentResolver<'a, 'b> {
   pub(crate) fn resolve_assignments(
-    assignments: &BTreeMap<&'a str, Expression<'a>>,
+    assignments: &OrderedMap<&'a str, Expression<'a>>,
     assignment_tokens: &BTreeMap<&'a str, Token<'a>>,
   ) -> CompilationResult<'a, ()> {
     let mut resolver = AssignmentResolver {
@@ -49,6 +49,7 @@ impl<'a: 'b, 'b> AssignmentResolver<'a, 'b> {
         line: 0,
         column: 0,
         width: 0,
+        secondary: None,
         kind: Internal { message },
       });
     }
@@ -78,10 +79,28 @@ impl<'a: 'b, 'b> AssignmentResolver<'a, 'b> {
         ref arguments,
         ..
       } => Function::resolve(token, arguments.len())?,
-      Expression::Concatination { ref lhs, ref rhs } => {
+      Expression::Concatination { ref lhs, ref rhs } | Expression::Join { ref lhs, ref rhs } => {
         self.resolve_expression(lhs)?;
         self.resolve_expression(rhs)?;
       }
+      Expression::Conditional {
+        ref lhs,
+        ref rhs,
+        ref then,
+        ref otherwise,
+      } => {
+        self.resolve_expression(lhs)?;
+        self.resolve_expression(rhs)?;
+        self.resolve_expression(then)?;
+        self.resolve_expression(otherwise)?;
+      }
+      Expression::InterpolatedString { ref fragments, .. } => {
+        for fragment in fragments {
+          if let StringFragment::Expression { expression } = fragment {
+            self.resolve_expression(expression)?;
+          }
+        }
+      }
       Expression::String { .. } | Expression::Backtick { .. } => {}
       Expression::Group { expression } => self.resolve_expression(expression)?,
     }
@@ -0,0 +1,930 @@
+use crate::common::*;
+
+/// Format the message `key` from the catalog, replacing each `{name}` in it
+/// with the argument `name = value`
+macro_rules! message {
+  ($key:expr $(, $name:ident = $value:expr)* $(,)?) => {
+    crate::catalog::message($key, &[$((stringify!($name), &$value as &dyn Display)),*])
+  };
+}
+
+/// The English messages, by key, which are used when `JUST_LANG` isn't set,
+/// or for messages that are missing from its catalog
+const ENGLISH: &[(&str, &str)] = &[
+  ("label.arguments", "arguments:"),
+  ("label.command", "command:"),
+  ("label.error", "error:"),
+  ("label.usage", "usage:"),
+  ("label.warning", "warning:"),
+  (
+    "compile.alias-shadows-recipe",
+    "Alias `{alias}` defined on `{line}` shadows recipe defined on `{recipe_line}`",
+  ),
+  (
+    "compile.attribute-without-assignment",
+    "Attribute `{attribute}` must be followed by an assignment",
+  ),
+  (
+    "compile.attribute-without-item",
+    "Attribute `{attribute}` must be followed by a recipe or an assignment",
+  ),
+  (
+    "compile.attribute-without-recipe",
+    "Attribute must be followed by a recipe",
+  ),
+  (
+    "compile.circular-module",
+    "Module `{module}` contains itself",
+  ),
+  (
+    "compile.circular-recipe-dependency",
+    "Recipe `{recipe}` has circular dependency `{circle}`",
+  ),
+  (
+    "compile.circular-variable-dependency",
+    "Variable `{variable}` depends on its own value: `{circle}`",
+  ),
+  (
+    "compile.conflicting-attributes",
+    "Attributes `{first}` and `{second}` may not be used together",
+  ),
+  (
+    "compile.conflicting-import",
+    "{kind} `{name}` is defined in both `{first}` and `{second}`",
+  ),
+  (
+    "compile.dependency-has-parameters",
+    "Recipe `{recipe}` depends on `{dependency}` which requires arguments. Dependencies may not \
+     require arguments",
+  ),
+  (
+    "compile.duplicate-alias",
+    "Alias `{alias}` first defined on line `{first}` is redefined on line `{line}`",
+  ),
+  (
+    "compile.duplicate-attribute",
+    "Recipe has attribute `{attribute}` more than once",
+  ),
+  (
+    "compile.duplicate-dependency",
+    "Recipe `{recipe}` has duplicate dependency `{dependency}`",
+  ),
+  (
+    "compile.duplicate-matrix-parameter",
+    "Matrix has parameter `{parameter}` more than once",
+  ),
+  (
+    "compile.duplicate-metadata-key",
+    "Recipe has metadata key `{key}` more than once",
+  ),
+  (
+    "compile.duplicate-module",
+    "Module `{module}` first declared on line {first} is redeclared on line {line}",
+  ),
+  (
+    "compile.duplicate-parameter",
+    "Recipe `{recipe}` has duplicate parameter `{parameter}`",
+  ),
+  (
+    "compile.duplicate-recipe",
+    "Recipe `{recipe}` first defined on line {first} is redefined on line {line}",
+  ),
+  (
+    "compile.duplicate-setting",
+    "Setting `{setting}` has multiple definitions",
+  ),
+  (
+    "compile.duplicate-variable",
+    "Variable `{variable}` has multiple definitions",
+  ),
+  (
+    "compile.empty-shell",
+    "Setting `shell` must contain at least the shell to run",
+  ),
+  (
+    "compile.expected-keyword",
+    "Expected keyword `{expected}` but found `{found}`",
+  ),
+  (
+    "compile.extra-leading-whitespace",
+    "Recipe line has extra leading whitespace",
+  ),
+  (
+    "compile.function-argument-count-mismatch.one",
+    "Function `{function}` called with {found} argument but takes {expected}",
+  ),
+  (
+    "compile.function-argument-count-mismatch.other",
+    "Function `{function}` called with {found} arguments but takes {expected}",
+  ),
+  (
+    "compile.inconsistent-leading-whitespace",
+    "Recipe line has inconsistent leading whitespace. Recipe started with `{expected}` but found \
+     line with `{found}`",
+  ),
+  (
+    "compile.internal",
+    "Internal error, this may indicate a bug in just: {message}\nconsider filing an issue: \
+     https://github.com/casey/just/issues/new",
+  ),
+  (
+    "compile.invalid-attribute-value",
+    "Invalid value `{value}` for attribute `{attribute}`",
+  ),
+  (
+    "compile.invalid-escape-sequence",
+    "`\\{sequence}` is not a valid escape sequence",
+  ),
+  (
+    "compile.invalid-setting-value",
+    "Setting `{setting}` has invalid value `{value}`, expected `true` or `false`",
+  ),
+  (
+    "compile.invalid-shebang",
+    "Recipe `{recipe}` has shebang line `{line}`, which does not name an interpreter",
+  ),
+  (
+    "compile.invalid-verbosity",
+    "Setting `verbosity` has invalid value `{value}`, expected `taciturn`, `loquacious`, \
+     `grandiloquent`, or a number",
+  ),
+  (
+    "compile.matrix-missing-parameter",
+    "Parameter `{parameter}` of recipe `{recipe}` must be included in its matrix",
+  ),
+  (
+    "compile.missing-module",
+    "Source file for module `{module}` not found, expected `{module}/justfile` or `{module}.just`",
+  ),
+  (
+    "compile.mixed-leading-whitespace",
+    "Found a mix of tabs and spaces in leading whitespace: `{whitespace}`\nLeading whitespace may \
+     consist of tabs or spaces, but not both",
+  ),
+  (
+    "compile.parameter-follows-variadic-parameter",
+    "Parameter `{parameter}` follows variadic parameter",
+  ),
+  (
+    "compile.parameter-shadows-variable",
+    "Parameter `{parameter}` shadows variable of the same name",
+  ),
+  (
+    "compile.recipe-depends-on-itself",
+    "Recipe `{recipe}` depends on itself",
+  ),
+  (
+    "compile.required-parameter-follows-default-parameter",
+    "Non-default parameter `{parameter}` follows default parameter",
+  ),
+  (
+    "compile.undefined-constant",
+    "Constant `{constant}` not defined, constants are given with `--define`",
+  ),
+  (
+    "compile.undefined-variable",
+    "Variable `{variable}` not defined",
+  ),
+  (
+    "compile.unexpected-token",
+    "Expected {expected}, but found {found}",
+  ),
+  (
+    "compile.unknown-alias-target",
+    "Alias `{alias}` has an unknown target `{target}`",
+  ),
+  (
+    "compile.unknown-attribute",
+    "Unknown attribute `{attribute}`",
+  ),
+  (
+    "compile.unknown-dependency",
+    "Recipe `{recipe}` has unknown dependency `{dependency}`",
+  ),
+  (
+    "compile.unknown-function",
+    "Call to unknown function `{function}`",
+  ),
+  (
+    "compile.unknown-matrix-parameter",
+    "Matrix of recipe `{recipe}` has unknown parameter `{parameter}`",
+  ),
+  ("compile.unknown-setting", "Unknown setting `{setting}`"),
+  ("compile.unknown-start-of-token", "Unknown start of token:"),
+  (
+    "compile.unpaired-carriage-return",
+    "Unpaired carriage return",
+  ),
+  (
+    "compile.unreadable-import",
+    "Failed to read imported justfile `{path}`: {message}",
+  ),
+  (
+    "compile.unreadable-module",
+    "Failed to read source file `{path}` for module `{module}`: {message}",
+  ),
+  ("compile.unterminated-backtick", "Unterminated backtick"),
+  (
+    "compile.unterminated-heredoc",
+    "Heredoc is not closed by a line containing only `{delimiter}`",
+  ),
+  (
+    "compile.unterminated-interpolation",
+    "Unterminated interpolation",
+  ),
+  ("compile.unterminated-string", "Unterminated string"),
+  (
+    "compile.variable-depends-on-itself",
+    "Variable `{variable}` is defined in terms of itself",
+  ),
+  (
+    "config.each-without-recipe",
+    "--each requires the name of a recipe to run",
+  ),
+  (
+    "config.internal",
+    "Internal config error, this may indicate a bug in just: {message} \
+     consider filing an issue: https://github.com/casey/just/issues/new",
+  ),
+  (
+    "config.invalid-completion-index",
+    "Invalid argument `{value}` to --complete, expected the index of the word to complete",
+  ),
+  (
+    "config.invalid-define",
+    "Invalid argument `{argument}` to --define, expected `KEY=VALUE` where `KEY` is a valid name",
+  ),
+  (
+    "config.invalid-environment-variable",
+    "Invalid argument `{argument}` to --env, expected `KEY=VALUE`",
+  ),
+  (
+    "config.invalid-jobs",
+    "Invalid argument `{value}` to --jobs, expected a positive integer",
+  ),
+  (
+    "config.invalid-override",
+    "Cannot override `{name}` on the command line, it is not a valid variable name",
+  ),
+  (
+    "config.invalid-runs",
+    "Invalid argument `{value}` to --runs, expected a positive integer",
+  ),
+  (
+    "config.invalid-timestamp-format",
+    "Invalid argument `{format}` to --timestamp-format: {message}",
+  ),
+  (
+    "config.invalid-verbosity",
+    "Invalid value `{value}` for `JUST_VERBOSE`, expected `taciturn`, `loquacious`, \
+     `grandiloquent`, or a number",
+  ),
+  (
+    "config.missing-env-file",
+    "Expected a path after `--env-file`",
+  ),
+  (
+    "config.missing-runs",
+    "Expected a number of runs after `--runs`",
+  ),
+  (
+    "runtime.argument-count-mismatch.one",
+    "Recipe `{recipe}` got {found} argument but takes {expected}",
+  ),
+  (
+    "runtime.argument-count-mismatch.other",
+    "Recipe `{recipe}` got {found} arguments but takes {expected}",
+  ),
+  (
+    "runtime.argument-count-mismatch-at-least.one",
+    "Recipe `{recipe}` got {found} argument but takes at least {min}",
+  ),
+  (
+    "runtime.argument-count-mismatch-at-least.other",
+    "Recipe `{recipe}` got {found} arguments but takes at least {min}",
+  ),
+  (
+    "runtime.argument-count-mismatch-at-most.one",
+    "Recipe `{recipe}` got {found} argument but takes at most {max}",
+  ),
+  (
+    "runtime.argument-count-mismatch-at-most.other",
+    "Recipe `{recipe}` got {found} arguments but takes at most {max}",
+  ),
+  (
+    "runtime.argument-count-mismatch-only.one",
+    "Recipe `{recipe}` got {found} argument but only takes {expected}",
+  ),
+  (
+    "runtime.argument-count-mismatch-only.other",
+    "Recipe `{recipe}` got {found} arguments but only takes {expected}",
+  ),
+  (
+    "runtime.backtick-code",
+    "Backtick failed with exit code {code}",
+  ),
+  (
+    "runtime.backtick-io",
+    "Backtick could not be run because of an IO error while launching `sh`:\n{io_error}",
+  ),
+  (
+    "runtime.backtick-not-found",
+    "Backtick could not be run because just could not find `sh`:\n{io_error}",
+  ),
+  (
+    "runtime.backtick-permission-denied",
+    "Backtick could not be run because just could not run `sh`:\n{io_error}",
+  ),
+  (
+    "runtime.backtick-signal",
+    "Backtick was terminated by signal {signal}",
+  ),
+  (
+    "runtime.backtick-unknown",
+    "Backtick failed for an unknown reason",
+  ),
+  (
+    "runtime.backtick-utf8",
+    "Backtick succeeded but stdout was not utf8: {utf8_error}",
+  ),
+  (
+    "runtime.ci-only",
+    "Recipe `{recipe}` may only be run in CI, but `CI` is not set",
+  ),
+  (
+    "runtime.code",
+    "Recipe `{recipe}` failed with exit code {code}",
+  ),
+  (
+    "runtime.code-on-line",
+    "Recipe `{recipe}` failed on line {line} with exit code {code}",
+  ),
+  (
+    "runtime.cygpath-code",
+    "Cygpath failed with exit code {code} while translating recipe `{recipe}` shebang interpreter \
+     path",
+  ),
+  (
+    "runtime.cygpath-io",
+    "Could not run `cygpath` executable:\n{io_error}",
+  ),
+  (
+    "runtime.cygpath-not-found",
+    "Could not find `cygpath` executable to translate recipe `{recipe}` shebang interpreter \
+     path:\n{io_error}",
+  ),
+  (
+    "runtime.cygpath-permission-denied",
+    "Could not run `cygpath` executable to translate recipe `{recipe}` shebang interpreter \
+     path:\n{io_error}",
+  ),
+  (
+    "runtime.cygpath-signal",
+    "Cygpath terminated by signal {signal} while translating recipe `{recipe}` shebang \
+     interpreter path",
+  ),
+  (
+    "runtime.cygpath-unknown",
+    "Cygpath experienced an unknown failure while translating recipe `{recipe}` shebang \
+     interpreter path",
+  ),
+  (
+    "runtime.cygpath-utf8",
+    "Cygpath successfully translated recipe `{recipe}` shebang interpreter path, but output was \
+     not utf8: {utf8_error}",
+  ),
+  ("runtime.did-you-mean", "Did you mean `{suggestion}`?"),
+  ("runtime.did-you-mean-any", "Did you mean {alternatives}?"),
+  (
+    "runtime.each.one",
+    "Recipe `{recipe}` failed for {failed} of {total} argument: {arguments}",
+  ),
+  (
+    "runtime.each.other",
+    "Recipe `{recipe}` failed for {failed} of {total} arguments: {arguments}",
+  ),
+  (
+    "runtime.env-file-load",
+    "Failed to load env file `{path}`: {dotenv_error}",
+  ),
+  (
+    "runtime.env-file-write",
+    "I/O error writing env file: {io_error}",
+  ),
+  (
+    "runtime.external-dependency",
+    "External dependency `{dependency}` failed",
+  ),
+  (
+    "runtime.external-dependency-io",
+    "I/O error running external dependency `{dependency}`: {io_error}",
+  ),
+  (
+    "runtime.function-call",
+    "Call to function `{function}` failed: {message}",
+  ),
+  (
+    "runtime.internal",
+    "Internal runtime error, this may indicate a bug in just: {message} consider filing an issue: \
+     https://github.com/casey/just/issues/new",
+  ),
+  (
+    "runtime.io",
+    "Recipe `{recipe}` could not be run because of an IO error while launching `sh`:{io_error}",
+  ),
+  (
+    "runtime.io-not-found",
+    "Recipe `{recipe}` could not be run because just could not find `sh`:{io_error}",
+  ),
+  (
+    "runtime.io-permission-denied",
+    "Recipe `{recipe}` could not be run because just could not run `sh`:{io_error}",
+  ),
+  (
+    "runtime.local-only",
+    "Recipe `{recipe}` may only be run locally, but `CI` is set",
+  ),
+  (
+    "runtime.network-isolation-unsupported",
+    "Recipe `{recipe}` has the `[no-network]` attribute, but running recipes without network \
+     access is only supported on Linux",
+  ),
+  (
+    "runtime.no-execute-backticks",
+    "Recipes may only be run with `--no-execute-backticks` if `--dry-run` is also given",
+  ),
+  (
+    "runtime.not-confirmed",
+    "Recipe `{recipe}` was not confirmed",
+  ),
+  (
+    "runtime.recursive-invocation",
+    "Recipe `{recipe}` was invoked while it was already running",
+  ),
+  (
+    "runtime.report.one",
+    "{failed} of {total} recipe failed: {recipes}",
+  ),
+  (
+    "runtime.report.other",
+    "{failed} of {total} recipes failed: {recipes}",
+  ),
+  (
+    "runtime.report-write",
+    "Failed to write report to `{path}`: {io_error}",
+  ),
+  (
+    "runtime.shebang",
+    "Recipe `{recipe}` with shebang `#!{shebang}` execution error: {io_error}",
+  ),
+  ("runtime.show-script", "Script for recipe `{recipe}`:"),
+  (
+    "runtime.signal",
+    "Recipe `{recipe}` was terminated by signal {signal}",
+  ),
+  (
+    "runtime.signal-on-line",
+    "Recipe `{recipe}` was terminated on line {line} by signal {signal}",
+  ),
+  (
+    "runtime.tmpdir-io",
+    "Recipe `{recipe}` could not be run because of an IO error while trying to create a temporary \
+     directory or write a file to that directory`:{io_error}",
+  ),
+  (
+    "runtime.unavailable-recipe",
+    "Recipe `{recipe}` is only available on {platforms}, not `{os}`.",
+  ),
+  (
+    "runtime.unknown-on-line",
+    "Recipe `{recipe}` failed on line {line} for an unknown reason",
+  ),
+  (
+    "runtime.unknown-overrides.one",
+    "Variable {overrides} overridden on the command line but not present in justfile",
+  ),
+  (
+    "runtime.unknown-overrides.other",
+    "Variables {overrides} overridden on the command line but not present in justfile",
+  ),
+  (
+    "runtime.unknown-recipes.one",
+    "Justfile does not contain recipe {recipes}.",
+  ),
+  (
+    "runtime.unknown-recipes.other",
+    "Justfile does not contain recipes {recipes}.",
+  ),
+  (
+    "search.io",
+    "I/O error reading directory `{directory}`: {io_error}",
+  ),
+  (
+    "search.multiple-candidates",
+    "Multiple candidate justfiles found in `{directory}`: {candidates}",
+  ),
+  (
+    "search.not-found",
+    "No justfile found, run `just --init` to create one in the current directory",
+  ),
+  ("search.preferred", "{error}, using `{path}`"),
+  ("subcommand.alias-doc", "alias for `{recipe}`"),
+  ("subcommand.available-functions", "Available functions:"),
+  ("subcommand.available-recipes", "Available recipes:"),
+  ("subcommand.bench.one", "Ran `{command}` {runs} time"),
+  ("subcommand.bench.other", "Ran `{command}` {runs} times"),
+  ("subcommand.bench-max", "max:  {duration}"),
+  ("subcommand.bench-mean", "mean: {duration}"),
+  ("subcommand.bench-min", "min:  {duration}"),
+  ("subcommand.completions", "completions"),
+  (
+    "subcommand.completions-fpath",
+    "Add `{directory}` to $fpath to enable them",
+  ),
+  (
+    "subcommand.current-dir",
+    "Error getting current dir: {io_error}",
+  ),
+  ("subcommand.editor-failed", "Editor failed: {status}"),
+  (
+    "subcommand.editor-io",
+    "Failed to invoke editor: {io_error}",
+  ),
+  (
+    "subcommand.editor-unset",
+    "Error getting EDITOR environment variable",
+  ),
+  (
+    "subcommand.grammar-format",
+    "Unsupported grammar format: {format}",
+  ),
+  (
+    "subcommand.history-empty",
+    "No recipes have been run with this justfile.",
+  ),
+  (
+    "subcommand.import-error",
+    "Error in imported justfile `{path}`:",
+  ),
+  ("subcommand.init-exists", "Justfile `{path}` already exists"),
+  (
+    "subcommand.init-write",
+    "Failed to write justfile to `{path}`: {io_error}",
+  ),
+  ("subcommand.init-wrote", "Wrote justfile to `{path}`"),
+  (
+    "subcommand.install-create-directory",
+    "Failed to create directory `{directory}`: {io_error}",
+  ),
+  (
+    "subcommand.install-directory",
+    "Error finding install directory: neither ${variable} nor $HOME is set",
+  ),
+  (
+    "subcommand.install-write",
+    "Failed to write {what} to `{path}`: {io_error}",
+  ),
+  ("subcommand.install-wrote", "Wrote {what} to `{path}`"),
+  ("subcommand.man-page", "man page"),
+  ("subcommand.no-recipes", "Justfile contains no recipes."),
+  (
+    "subcommand.read-justfile",
+    "Error reading justfile: {io_error}",
+  ),
+  (
+    "subcommand.resume-none",
+    "There is no failed run of this justfile to resume.",
+  ),
+  (
+    "subcommand.why-independent",
+    "Recipe `{recipe}` does not depend on `{dependency}`.",
+  ),
+  (
+    "subcommand.working-directory-access",
+    "Error accessing working directory {directory}: {io_error}",
+  ),
+  (
+    "subcommand.working-directory-io",
+    "Error finding working directory for justfile `{path}`: {io_error}",
+  ),
+  (
+    "subcommand.working-directory-not-directory",
+    "Working directory {directory} is not a directory",
+  ),
+  (
+    "warning.deprecated-equals",
+    "`=` in assignments, exports, and aliases is being phased out on favor of `:=`",
+  ),
+  (
+    "warning.deprecated-equals-help",
+    "Please see this issue for more details: https://github.com/casey/just/issues/379",
+  ),
+  (
+    "warning.lost-directory-change",
+    "Recipe `{recipe}` changes directory with `cd` on a line of its own, but each line is run \
+     by a new shell, so later lines run in the original directory",
+  ),
+  (
+    "warning.lost-directory-change-help",
+    "Use `cd DIR && COMMAND`, a shebang recipe, or `set one-shell` to run commands in DIR",
+  ),
+  (
+    "warning.ignored-failure",
+    "Recipe `{recipe}` failed with exit code {code}, ignoring",
+  ),
+  (
+    "warning.ignored-failure-on-line",
+    "Recipe `{recipe}` failed on line {line} with exit code {code}, ignoring",
+  ),
+];
+
+lazy_static! {
+  static ref TRANSLATION: BTreeMap<String, String> = load();
+}
+
+/// Format the message `key` in the language selected by `JUST_LANG`, falling
+/// back to English
+pub(crate) fn message(key: &str, arguments: &[(&str, &dyn Display)]) -> String {
+  let template = match TRANSLATION.get(key) {
+    Some(template) => template.as_str(),
+    None => english(key),
+  };
+
+  format(template, arguments)
+}
+
+/// The English message for `key`, or the key itself if it's missing, which
+/// the `message_keys_exist` test guards against
+fn english(key: &str) -> &str {
+  ENGLISH
+    .iter()
+    .find(|(english_key, _)| *english_key == key)
+    .map_or(key, |(_, template)| *template)
+}
+
+/// The key of the singular or plural form of the message `key`, for
+/// `count` things
+pub(crate) fn plural(key: &str, count: usize) -> String {
+  if count == 1 {
+    format!("{}.one", key)
+  } else {
+    format!("{}.other", key)
+  }
+}
+
+/// Replace each `{name}` in `template` with the value of the argument
+/// `name`. `{{` and `}}` are literal braces, and unknown names are left as
+/// they are, so that a mistake in a translation doesn't lose the message.
+fn format(template: &str, arguments: &[(&str, &dyn Display)]) -> String {
+  let mut output = String::new();
+  let mut rest = template;
+
+  while let Some(start) = rest.find(&['{', '}'][..]) {
+    output.push_str(&rest[..start]);
+    rest = &rest[start..];
+
+    if rest.starts_with("{{") || rest.starts_with("}}") {
+      output.push_str(&rest[..1]);
+      rest = &rest[2..];
+      continue;
+    }
+
+    let end = rest.find('}').filter(|_| rest.starts_with('{'));
+
+    let argument = end.and_then(|end| {
+      arguments
+        .iter()
+        .find(|(name, _)| *name == &rest[1..end])
+        .map(|(_, value)| (end, value))
+    });
+
+    match argument {
+      Some((end, value)) => {
+        output.push_str(&value.to_string());
+        rest = &rest[end + 1..];
+      }
+      None => {
+        output.push_str(&rest[..1]);
+        rest = &rest[1..];
+      }
+    }
+  }
+
+  output.push_str(rest);
+  output
+}
+
+/// Load the catalog for the language in `JUST_LANG`, from
+/// `just/messages/LANG.messages` in `$XDG_DATA_HOME`, or one of the
+/// directories in `$XDG_DATA_DIRS`
+fn load() -> BTreeMap<String, String> {
+  let lang = match env::var("JUST_LANG") {
+    Ok(lang) if !lang.is_empty() => lang,
+    _ => return BTreeMap::new(),
+  };
+
+  for name in names(&lang) {
+    for directory in directories() {
+      let path = directory
+        .join("just")
+        .join("messages")
+        .join(format!("{}.messages", name));
+
+      match fs::read_to_string(&path) {
+        Ok(text) => return parse(&path, &text),
+        Err(io_error) if io_error.kind() == io::ErrorKind::NotFound => {}
+        Err(io_error) => warn!("Failed to read `{}`: {}", path.display(), io_error),
+      }
+    }
+  }
+
+  BTreeMap::new()
+}
+
+/// The catalog names to try for `lang`, most specific first, ignoring the
+/// encoding and modifier, so that `de_AT.UTF-8` tries `de_AT`, then `de`
+fn names(lang: &str) -> Vec<&str> {
+  let lang = lang.split(&['.', '@'][..]).next().unwrap_or_default();
+
+  let mut names = vec![lang];
+
+  if let Some(language) = lang.split(&['_', '-'][..]).next() {
+    if language != lang {
+      names.push(language);
+    }
+  }
+
+  names.retain(|name| !name.is_empty());
+  names
+}
+
+fn directories() -> Vec<PathBuf> {
+  let mut directories = Vec::new();
+
+  match env::var_os("XDG_DATA_HOME").filter(|home| !home.is_empty()) {
+    Some(home) => directories.push(PathBuf::from(home)),
+    None => {
+      if let Some(home) = env::var_os("HOME").filter(|home| !home.is_empty()) {
+        directories.push(Path::new(&home).join(".local/share"));
+      }
+    }
+  }
+
+  let dirs = env::var_os("XDG_DATA_DIRS")
+    .filter(|dirs| !dirs.is_empty())
+    .unwrap_or_else(|| "/usr/local/share:/usr/share".into());
+
+  directories.extend(env::split_paths(&dirs));
+
+  directories
+}
+
+/// Parse a catalog, which has one `KEY = MESSAGE` per line, with `\n` for
+/// line breaks in the message. Blank lines and lines starting with `#` are
+/// ignored.
+fn parse(path: &Path, text: &str) -> BTreeMap<String, String> {
+  let mut messages = BTreeMap::new();
+
+  for (i, line) in text.lines().enumerate() {
+    let line = line.trim();
+
+    if line.is_empty() || line.starts_with('#') {
+      continue;
+    }
+
+    match line.find('=') {
+      Some(equals) => {
+        messages.insert(
+          line[..equals].trim().to_owned(),
+          line[equals + 1..].trim().replace("\\n", "\n"),
+        );
+      }
+      None => warn!(
+        "Ignoring line {} of `{}`, expected `KEY = MESSAGE`",
+        i + 1,
+        path.display()
+      ),
+    }
+  }
+
+  messages
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn format_arguments() {
+    assert_eq!(
+      format(
+        "`{recipe}` failed with {code} {{code}} {unknown}",
+        &[("recipe", &"foo"), ("code", &2)]
+      ),
+      "`foo` failed with 2 {code} {unknown}"
+    );
+  }
+
+  #[test]
+  fn english_keys_unique() {
+    for (i, (key, _)) in ENGLISH.iter().enumerate() {
+      assert!(
+        ENGLISH[i + 1..].iter().all(|(other, _)| other != key),
+        "duplicate key `{}`",
+        key
+      );
+    }
+  }
+
+  /// The keys passed to `message!` and `plural` in the source
+  fn source_keys() -> Vec<String> {
+    let mut keys = Vec::new();
+
+    for entry in fs::read_dir(Path::new(env!("CARGO_MANIFEST_DIR")).join("src")).unwrap() {
+      let path = entry.unwrap().path();
+
+      if path.extension() != Some("rs".as_ref()) {
+        continue;
+      }
+
+      let text = fs::read_to_string(&path).unwrap();
+
+      for (prefix, suffixes) in &[
+        ("message!(", &[""][..]),
+        ("plural(", &[".one", ".other"][..]),
+      ] {
+        for (start, _) in text.match_indices(prefix) {
+          let rest = text[start + prefix.len()..].trim_start();
+
+          if !rest.starts_with('"') {
+            continue;
+          }
+
+          let key = &rest[1..rest[1..].find('"').unwrap() + 1];
+
+          if !key
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '.' || c == '-')
+          {
+            continue;
+          }
+
+          for suffix in *suffixes {
+            keys.push(format!("{}{}", key, suffix));
+          }
+        }
+      }
+    }
+
+    keys
+  }
+
+  #[test]
+  fn message_keys_exist() {
+    let keys = source_keys();
+
+    assert!(keys.len() > ENGLISH.len() / 2);
+
+    for key in &keys {
+      assert!(
+        ENGLISH.iter().any(|(english_key, _)| english_key == key),
+        "key `{}` missing from the English catalog",
+        key
+      );
+    }
+
+    for (key, _) in ENGLISH {
+      assert!(
+        keys.iter().any(|used| used == key),
+        "key `{}` is unused",
+        key
+      );
+    }
+  }
+
+  #[test]
+  fn parse_catalog() {
+    let messages = parse(
+      Path::new("de.messages"),
+      "# German\n\nlabel.error = Fehler:\nbogus\n\
+       compile.internal = Interner Fehler\\nBitte melden\nsearch.io=E/A-Fehler: {io_error}\n",
+    );
+
+    assert_eq!(messages.len(), 3);
+    assert_eq!(messages["label.error"], "Fehler:");
+    assert_eq!(
+      messages["compile.internal"],
+      "Interner Fehler\nBitte melden"
+    );
+    assert_eq!(messages["search.io"], "E/A-Fehler: {io_error}");
+  }
+
+  #[test]
+  fn lang_names() {
+    assert_eq!(names("de_AT.UTF-8"), &["de_AT", "de"]);
+    assert_eq!(names("pt-BR"), &["pt-BR", "pt"]);
+    assert_eq!(names("fr"), &["fr"]);
+    assert_eq!(names("sr@latin"), &["sr"]);
+    assert!(names(".UTF-8").is_empty());
+  }
+}
@@ -5,7 +5,8 @@ pub(crate) trait CommandExt {
     &mut self,
     scope: &BTreeMap<&'a str, String>,
     dotenv: &BTreeMap<String, String>,
-    exports: &BTreeSet<&'a str>,
+    exports: &BTreeMap<&'a str, Export>,
+    environment: &BTreeMap<OsString, OsString>,
   ) -> RunResult<'a, ()>;
 }
 
@@ -14,14 +15,15 @@ impl CommandExt for Command {
     &mut self,
     scope: &BTreeMap<&'a str, String>,
     dotenv: &BTreeMap<String, String>,
-    exports: &BTreeSet<&'a str>,
+    exports: &BTreeMap<&'a str, Export>,
+    environment: &BTreeMap<OsString, OsString>,
   ) -> RunResult<'a, ()> {
     for (name, value) in dotenv {
       self.env(name, value);
     }
-    for name in exports {
+    for (name, export) in exports {
       if let Some(value) = scope.get(name) {
-        self.env(name, value);
+        self.env(&export.variable, export.value(value, dotenv, environment));
       } else {
         return Err(RuntimeError::Internal {
           message: format!("scope does not contain exported variable `{}`", name),
@@ -1,29 +1,37 @@
 // stdlib
 pub(crate) use std::{
   borrow::Cow,
+  cell::RefCell,
   cmp,
   collections::{BTreeMap, BTreeSet},
   convert::AsRef,
   env,
-  ffi::OsStr,
+  ffi::{OsStr, OsString},
   fmt::{self, Display, Formatter},
-  fs, io, iter,
+  fs, io, iter, mem,
   ops::{Range, RangeInclusive},
   path::{Path, PathBuf},
   process::{self, Command},
-  str::Chars,
-  sync::{Mutex, MutexGuard},
-  usize, vec,
+  str::{Chars, FromStr},
+  sync::{
+    atomic::{AtomicUsize, Ordering},
+    Condvar, Mutex, MutexGuard,
+  },
+  thread, usize, vec,
 };
 
 // dependencies
 pub(crate) use edit_distance::edit_distance;
-pub(crate) use libc::EXIT_FAILURE;
 pub(crate) use log::warn;
 pub(crate) use unicode_width::UnicodeWidthChar;
 
 // modules
-pub(crate) use crate::search;
+pub(crate) use crate::{github, grammar, history, progress, request, search};
+
+// constants
+pub(crate) use crate::exit_code::{
+  EXIT_COMPILE_ERROR, EXIT_FAILURE, EXIT_INTERNAL, EXIT_UNKNOWN_RECIPE, EXIT_USAGE,
+};
 
 // modules used in tests
 #[cfg(test)]
@@ -31,24 +39,42 @@ pub(crate) use crate::testing;
 
 // functions
 pub(crate) use crate::{
-  default::default, empty::empty, load_dotenv::load_dotenv, output::output,
+  catalog::plural,
+  complete::complete,
+  default::default,
+  empty::empty,
+  lint::lint,
+  load_dotenv::{load_dotenv, load_env_file},
+  merge::merge,
+  output::output,
+  rename::rename,
+  request::request,
   write_message_context::write_message_context,
 };
 
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use crate::repl::repl;
+
 // structs and enums
 pub(crate) use crate::{
   alias::Alias, alias_resolver::AliasResolver, assignment_evaluator::AssignmentEvaluator,
   assignment_resolver::AssignmentResolver, color::Color, compilation_error::CompilationError,
   compilation_error_kind::CompilationErrorKind, config::Config, config_error::ConfigError,
-  count::Count, enclosure::Enclosure, expression::Expression, fragment::Fragment,
-  function::Function, function_context::FunctionContext, functions::Functions,
-  interrupt_guard::InterruptGuard, interrupt_handler::InterruptHandler, justfile::Justfile,
-  lexer::Lexer, list::List, output_error::OutputError, parameter::Parameter, parser::Parser,
-  platform::Platform, position::Position, recipe::Recipe, recipe_context::RecipeContext,
-  recipe_resolver::RecipeResolver, runtime_error::RuntimeError, search_error::SearchError,
-  shebang::Shebang, show_whitespace::ShowWhitespace, state::State, string_literal::StringLiteral,
-  subcommand::Subcommand, token::Token, token_kind::TokenKind, use_color::UseColor,
-  variables::Variables, verbosity::Verbosity, warning::Warning,
+  count::Count, date_time::DateTime, dump_order::DumpOrder, enclosure::Enclosure, export::Export,
+  expression::Expression, external_dependency::ExternalDependency, fragment::Fragment,
+  function::Function, function_context::FunctionContext, functions::Functions, heredoc::Heredoc,
+  history::Invocation, interrupt_guard::InterruptGuard, interrupt_handler::InterruptHandler,
+  invocation_context::InvocationContext, io_priority::IoPriority, item::Item, job_slots::JobSlots,
+  justfile::Justfile, lexer::Lexer, list::List, loader::Loader, matrix_axis::MatrixAxis,
+  ordered_map::OrderedMap, output_error::OutputError, output_format::OutputFormat,
+  parameter::Parameter, parameter_kind::ParameterKind, parser::Parser, path_export::PathExport,
+  platform::Platform, position::Position, process_options::ProcessOptions, progress::Progress,
+  quoted::Quoted, recipe::Recipe, recipe_context::RecipeContext, recipe_output::RecipeOutput,
+  recipe_resolver::RecipeResolver, report::Report, report_format::ReportFormat,
+  runtime_error::RuntimeError, scheduler::Scheduler, search_error::SearchError, settings::Settings,
+  shebang::Shebang, show_whitespace::ShowWhitespace, state::State, string_fragment::StringFragment,
+  string_literal::StringLiteral, subcommand::Subcommand, token::Token, token_kind::TokenKind,
+  use_color::UseColor, variables::Variables, verbosity::Verbosity, warning::Warning,
 };
 
 pub(crate) type CompilationResult<'a, T> = Result<T, CompilationError<'a>>;
@@ -7,55 +7,67 @@ pub(crate) struct CompilationError<'a> {
   pub(crate) line: usize,
   pub(crate) column: usize,
   pub(crate) width: usize,
+  /// Another location involved in the error, like the first definition of
+  /// something that's defined twice
+  pub(crate) secondary: Option<Box<Token<'a>>>,
   pub(crate) kind: CompilationErrorKind<'a>,
 }
 
+impl<'a> CompilationError<'a> {
+  pub(crate) fn exit_code(&self) -> i32 {
+    match self.kind {
+      CompilationErrorKind::Internal { .. } => EXIT_INTERNAL,
+      _ => EXIT_COMPILE_ERROR,
+    }
+  }
+}
+
 impl<'a> Display for CompilationError<'a> {
   fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
     use CompilationErrorKind::*;
     let error = Color::fmt(f).error();
     let message = Color::fmt(f).message();
 
-    write!(f, "{} {}", error.paint("error:"), message.prefix())?;
+    write!(
+      f,
+      "{} {}",
+      error.paint(&message!("label.error")),
+      message.prefix()
+    )?;
 
-    match self.kind {
-      AliasShadowsRecipe { alias, recipe_line } => {
-        writeln!(
-          f,
-          "Alias `{}` defined on `{}` shadows recipe defined on `{}`",
-          alias,
-          self.line.ordinal(),
-          recipe_line.ordinal(),
-        )?;
-      }
+    let text = match self.kind {
+      AliasShadowsRecipe { alias, recipe_line } => message!(
+        "compile.alias-shadows-recipe",
+        alias = alias,
+        line = self.line.ordinal(),
+        recipe_line = recipe_line.ordinal(),
+      ),
       CircularRecipeDependency { recipe, ref circle } => {
         if circle.len() == 2 {
-          writeln!(f, "Recipe `{}` depends on itself", recipe)?;
+          message!("compile.recipe-depends-on-itself", recipe = recipe)
         } else {
-          writeln!(
-            f,
-            "Recipe `{}` has circular dependency `{}`",
-            recipe,
-            circle.join(" -> ")
-          )?;
+          message!(
+            "compile.circular-recipe-dependency",
+            recipe = recipe,
+            circle = circle.join(" -> "),
+          )
         }
       }
+      CircularModule { module } => message!("compile.circular-module", module = module),
       CircularVariableDependency {
         variable,
         ref circle,
       } => {
         if circle.len() == 2 {
-          writeln!(f, "Variable `{}` is defined in terms of itself", variable)?;
+          message!("compile.variable-depends-on-itself", variable = variable)
         } else {
-          writeln!(
-            f,
-            "Variable `{}` depends on its own value: `{}`",
-            variable,
-            circle.join(" -> ")
-          )?;
+          message!(
+            "compile.circular-variable-dependency",
+            variable = variable,
+            circle = circle.join(" -> "),
+          )
         }
       }
-
       InvalidEscapeSequence { character } => {
         let representation = match character {
           '`' => r"\`".to_string(),
@@ -64,151 +76,210 @@ impl<'a> Display for CompilationError<'a> {
           '"' => r#"""#.to_string(),
           _ => character.escape_default().collect(),
         };
-        writeln!(f, "`\\{}` is not a valid escape sequence", representation)?;
-      }
-      DuplicateParameter { recipe, parameter } => {
-        writeln!(
-          f,
-          "Recipe `{}` has duplicate parameter `{}`",
-          recipe, parameter
-        )?;
+        message!("compile.invalid-escape-sequence", sequence = representation)
       }
-      DuplicateVariable { variable } => {
-        writeln!(f, "Variable `{}` has multiple definitions", variable)?;
+      DuplicateModule { module, first } => message!(
+        "compile.duplicate-module",
+        module = module,
+        first = first.ordinal(),
+        line = self.line.ordinal(),
+      ),
+      DuplicateParameter { recipe, parameter } => message!(
+        "compile.duplicate-parameter",
+        recipe = recipe,
+        parameter = parameter,
+      ),
+      DuplicateMatrixParameter { parameter } => {
+        message!("compile.duplicate-matrix-parameter", parameter = parameter)
       }
-      UnexpectedToken {
-        ref expected,
-        found,
-      } => {
-        writeln!(f, "Expected {}, but found {}", List::or(expected), found)?;
-      }
-      DuplicateAlias { alias, first } => {
-        writeln!(
-          f,
-          "Alias `{}` first defined on line `{}` is redefined on line `{}`",
-          alias,
-          first.ordinal(),
-          self.line.ordinal(),
-        )?;
+      MatrixMissingParameter { recipe, parameter } => message!(
+        "compile.matrix-missing-parameter",
+        recipe = recipe,
+        parameter = parameter,
+      ),
+      AttributeWithoutAssignment { attribute } => message!(
+        "compile.attribute-without-assignment",
+        attribute = attribute
+      ),
+      AttributeWithoutItem { attribute } => {
+        message!("compile.attribute-without-item", attribute = attribute)
       }
-      DuplicateDependency { recipe, dependency } => {
-        writeln!(
-          f,
-          "Recipe `{}` has duplicate dependency `{}`",
-          recipe, dependency
-        )?;
+      AttributeWithoutRecipe => message!("compile.attribute-without-recipe"),
+      ConflictingAttributes { first, second } => message!(
+        "compile.conflicting-attributes",
+        first = first,
+        second = second,
+      ),
+      ConflictingImport {
+        kind,
+        first,
+        second,
+      } => message!(
+        "compile.conflicting-import",
+        kind = kind,
+        name = &self.text[self.offset..self.offset + self.width],
+        first = first.display(),
+        second = second.display(),
+      ),
+      DuplicateAttribute { attribute } => {
+        message!("compile.duplicate-attribute", attribute = attribute)
       }
-      DuplicateRecipe { recipe, first } => {
-        writeln!(
-          f,
-          "Recipe `{}` first defined on line {} is redefined on line {}",
-          recipe,
-          first.ordinal(),
-          self.line.ordinal()
-        )?;
+      DuplicateMetadataKey { key } => message!("compile.duplicate-metadata-key", key = key),
+      UnknownAttribute { attribute } => {
+        message!("compile.unknown-attribute", attribute = attribute)
       }
-      DependencyHasParameters { recipe, dependency } => {
-        writeln!(
-          f,
-          "Recipe `{}` depends on `{}` which requires arguments. \
-           Dependencies may not require arguments",
-          recipe, dependency
-        )?;
+      UnknownMatrixParameter { recipe, parameter } => message!(
+        "compile.unknown-matrix-parameter",
+        recipe = recipe,
+        parameter = parameter,
+      ),
+      DuplicateSetting { setting } => message!("compile.duplicate-setting", setting = setting),
+      InvalidAttributeValue {
+        attribute,
+        ref value,
+      } => message!(
+        "compile.invalid-attribute-value",
+        attribute = attribute,
+        value = value,
+      ),
+      InvalidShebang { recipe, ref line } => {
+        message!("compile.invalid-shebang", recipe = recipe, line = line)
       }
+      InvalidVerbosity { ref value } => message!("compile.invalid-verbosity", value = value),
+      InvalidSettingValue { setting, value } => message!(
+        "compile.invalid-setting-value",
+        setting = setting,
+        value = value,
+      ),
+      UnknownSetting { setting } => message!("compile.unknown-setting", setting = setting),
+      DuplicateVariable { variable } => message!("compile.duplicate-variable", variable = variable),
+      EmptyShell => message!("compile.empty-shell"),
+      UnexpectedToken {
+        ref expected,
+        found,
+      } => message!(
+        "compile.unexpected-token",
+        expected = List::or(expected),
+        found = found,
+      ),
+      DuplicateAlias { alias, first } => message!(
+        "compile.duplicate-alias",
+        alias = alias,
+        first = first.ordinal(),
+        line = self.line.ordinal(),
+      ),
+      DuplicateDependency { recipe, dependency } => message!(
+        "compile.duplicate-dependency",
+        recipe = recipe,
+        dependency = dependency,
+      ),
+      DuplicateRecipe { recipe, first } => message!(
+        "compile.duplicate-recipe",
+        recipe = recipe,
+        first = first.ordinal(),
+        line = self.line.ordinal(),
+      ),
+      DependencyHasParameters { recipe, dependency } => message!(
+        "compile.dependency-has-parameters",
+        recipe = recipe,
+        dependency = dependency,
+      ),
       ParameterShadowsVariable { parameter } => {
-        writeln!(
-          f,
-          "Parameter `{}` shadows variable of the same name",
-          parameter
-        )?;
-      }
-      RequiredParameterFollowsDefaultParameter { parameter } => {
-        writeln!(
-          f,
-          "Non-default parameter `{}` follows default parameter",
-          parameter
-        )?;
-      }
-      ParameterFollowsVariadicParameter { parameter } => {
-        writeln!(f, "Parameter `{}` follows variadic parameter", parameter)?;
-      }
-      MixedLeadingWhitespace { whitespace } => {
-        writeln!(
-          f,
-          "Found a mix of tabs and spaces in leading whitespace: `{}`\n\
-           Leading whitespace may consist of tabs or spaces, but not both",
-          ShowWhitespace(whitespace)
-        )?;
-      }
-      ExtraLeadingWhitespace => {
-        writeln!(f, "Recipe line has extra leading whitespace")?;
+        message!("compile.parameter-shadows-variable", parameter = parameter)
       }
+      RequiredParameterFollowsDefaultParameter { parameter } => message!(
+        "compile.required-parameter-follows-default-parameter",
+        parameter = parameter
+      ),
+      ParameterFollowsVariadicParameter { parameter } => message!(
+        "compile.parameter-follows-variadic-parameter",
+        parameter = parameter
+      ),
+      MissingModule { module } => message!("compile.missing-module", module = module),
+      MixedLeadingWhitespace { whitespace } => message!(
+        "compile.mixed-leading-whitespace",
+        whitespace = ShowWhitespace(whitespace)
+      ),
+      ExpectedKeyword { expected, found } => message!(
+        "compile.expected-keyword",
+        expected = expected,
+        found = found,
+      ),
+      ExtraLeadingWhitespace => message!("compile.extra-leading-whitespace"),
       FunctionArgumentCountMismatch {
         function,
         found,
         expected,
-      } => {
-        writeln!(
-          f,
-          "Function `{}` called with {} {} but takes {}",
-          function,
-          found,
-          Count("argument", found),
-          expected
-        )?;
-      }
-      InconsistentLeadingWhitespace { expected, found } => {
-        writeln!(
-          f,
-          "Recipe line has inconsistent leading whitespace. \
-           Recipe started with `{}` but found line with `{}`",
-          ShowWhitespace(expected),
-          ShowWhitespace(found)
-        )?;
-      }
-      UnknownAliasTarget { alias, target } => {
-        writeln!(f, "Alias `{}` has an unknown target `{}`", alias, target)?;
-      }
-      UnknownDependency { recipe, unknown } => {
-        writeln!(
-          f,
-          "Recipe `{}` has unknown dependency `{}`",
-          recipe, unknown
-        )?;
-      }
-      UndefinedVariable { variable } => {
-        writeln!(f, "Variable `{}` not defined", variable)?;
-      }
-      UnknownFunction { function } => {
-        writeln!(f, "Call to unknown function `{}`", function)?;
-      }
-      UnknownStartOfToken => {
-        writeln!(f, "Unknown start of token:")?;
-      }
-      UnpairedCarriageReturn => {
-        writeln!(f, "Unpaired carriage return")?;
+      } => message!(
+        &plural("compile.function-argument-count-mismatch", found),
+        function = function,
+        found = found,
+        expected = expected,
+      ),
+      InconsistentLeadingWhitespace { expected, found } => message!(
+        "compile.inconsistent-leading-whitespace",
+        expected = ShowWhitespace(expected),
+        found = ShowWhitespace(found),
+      ),
+      UnknownAliasTarget { alias, target } => message!(
+        "compile.unknown-alias-target",
+        alias = alias,
+        target = target,
+      ),
+      UnknownDependency { recipe, unknown } => message!(
+        "compile.unknown-dependency",
+        recipe = recipe,
+        dependency = unknown,
+      ),
+      UndefinedConstant { constant } => message!("compile.undefined-constant", constant = constant),
+      UndefinedVariable { variable } => message!("compile.undefined-variable", variable = variable),
+      UnknownFunction { function } => message!("compile.unknown-function", function = function),
+      UnknownStartOfToken => message!("compile.unknown-start-of-token"),
+      UnpairedCarriageReturn => message!("compile.unpaired-carriage-return"),
+      UnreadableImport {
+        ref path,
+        ref message,
+      } => message!("compile.unreadable-import", path = path, message = message,),
+      UnreadableModule {
+        module,
+        ref path,
+        ref message,
+      } => message!(
+        "compile.unreadable-module",
+        module = module,
+        path = path,
+        message = message,
+      ),
+      UnterminatedHeredoc { delimiter } => {
+        message!("compile.unterminated-heredoc", delimiter = delimiter)
       }
-      UnterminatedInterpolation => {
-        writeln!(f, "Unterminated interpolation")?;
-      }
-      UnterminatedString => {
-        writeln!(f, "Unterminated string")?;
-      }
-      UnterminatedBacktick => {
-        writeln!(f, "Unterminated backtick")?;
-      }
-      Internal { ref message } => {
-        writeln!(
-          f,
-          "Internal error, this may indicate a bug in just: {}\n\
-           consider filing an issue: https://github.com/casey/just/issues/new",
-          message
-        )?;
-      }
-    }
+      UnterminatedInterpolation => message!("compile.unterminated-interpolation"),
+      UnterminatedString => message!("compile.unterminated-string"),
+      UnterminatedBacktick => message!("compile.unterminated-backtick"),
+      Internal { ref message } => message!("compile.internal", message = message),
+    };
+
+    writeln!(f, "{}", text)?;
 
     write!(f, "{}", message.suffix())?;
 
+    // The locations of definitions in different files are labeled with the
+    // file they're in
+    let (primary_path, secondary_path) = match self.kind {
+      ConflictingImport { first, second, .. } => (Some(second), Some(first)),
+      _ => (None, None),
+    };
+
+    if let Some(path) = primary_path {
+      writeln!(
+        f,
+        " --> {}:{}:{}",
+        path.display(),
+        self.line.ordinal(),
+        self.column.ordinal()
+      )?;
+    }
+
     write_message_context(
       f,
       Color::fmt(f).error(),
@@ -217,6 +288,32 @@ impl<'a> Display for CompilationError<'a> {
       self.line,
       self.column,
       self.width,
-    )
+    )?;
+
+    if let Some(token) = &self.secondary {
+      writeln!(f)?;
+
+      if let Some(path) = secondary_path {
+        writeln!(
+          f,
+          " --> {}:{}:{}",
+          path.display(),
+          token.line.ordinal(),
+          token.column.ordinal()
+        )?;
+      }
+
+      write_message_context(
+        f,
+        Color::fmt(f).error(),
+        token.text,
+        token.offset,
+        token.line,
+        token.column,
+        token.length,
+      )?;
+    }
+
+    Ok(())
   }
 }
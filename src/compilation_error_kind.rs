@@ -6,10 +6,31 @@ pub(crate) enum CompilationErrorKind<'a> {
     alias: &'a str,
     recipe_line: usize,
   },
+  AttributeWithoutAssignment {
+    attribute: &'a str,
+  },
+  AttributeWithoutItem {
+    attribute: &'a str,
+  },
+  AttributeWithoutRecipe,
   CircularRecipeDependency {
     recipe: &'a str,
     circle: Vec<&'a str>,
   },
+  ConflictingAttributes {
+    first: &'a str,
+    second: &'a str,
+  },
+  /// An item defined in two different files. Its name is that of the error's
+  /// token, and the other definition is the error's secondary location.
+  ConflictingImport {
+    kind: &'static str,
+    first: &'a Path,
+    second: &'a Path,
+  },
+  CircularModule {
+    module: &'a str,
+  },
   CircularVariableDependency {
     variable: &'a str,
     circle: Vec<&'a str>,
@@ -22,10 +43,23 @@ pub(crate) enum CompilationErrorKind<'a> {
     alias: &'a str,
     first: usize,
   },
+  DuplicateAttribute {
+    attribute: &'a str,
+  },
   DuplicateDependency {
     recipe: &'a str,
     dependency: &'a str,
   },
+  DuplicateMatrixParameter {
+    parameter: &'a str,
+  },
+  DuplicateMetadataKey {
+    key: &'a str,
+  },
+  DuplicateModule {
+    module: &'a str,
+    first: usize,
+  },
   DuplicateParameter {
     recipe: &'a str,
     parameter: &'a str,
@@ -34,9 +68,17 @@ pub(crate) enum CompilationErrorKind<'a> {
     recipe: &'a str,
     first: usize,
   },
+  DuplicateSetting {
+    setting: &'a str,
+  },
   DuplicateVariable {
     variable: &'a str,
   },
+  EmptyShell,
+  ExpectedKeyword {
+    expected: &'a str,
+    found: &'a str,
+  },
   ExtraLeadingWhitespace,
   FunctionArgumentCountMismatch {
     function: &'a str,
@@ -50,9 +92,31 @@ pub(crate) enum CompilationErrorKind<'a> {
   Internal {
     message: String,
   },
+  InvalidAttributeValue {
+    attribute: &'a str,
+    value: String,
+  },
   InvalidEscapeSequence {
     character: char,
   },
+  InvalidShebang {
+    recipe: &'a str,
+    line: String,
+  },
+  InvalidSettingValue {
+    setting: &'a str,
+    value: &'a str,
+  },
+  InvalidVerbosity {
+    value: String,
+  },
+  MatrixMissingParameter {
+    recipe: &'a str,
+    parameter: &'a str,
+  },
+  MissingModule {
+    module: &'a str,
+  },
   MixedLeadingWhitespace {
     whitespace: &'a str,
   },
@@ -65,6 +129,9 @@ pub(crate) enum CompilationErrorKind<'a> {
   RequiredParameterFollowsDefaultParameter {
     parameter: &'a str,
   },
+  UndefinedConstant {
+    constant: &'a str,
+  },
   UndefinedVariable {
     variable: &'a str,
   },
@@ -80,11 +147,33 @@ pub(crate) enum CompilationErrorKind<'a> {
     recipe: &'a str,
     unknown: &'a str,
   },
+  UnknownAttribute {
+    attribute: &'a str,
+  },
   UnknownFunction {
     function: &'a str,
   },
+  UnknownMatrixParameter {
+    recipe: &'a str,
+    parameter: &'a str,
+  },
+  UnknownSetting {
+    setting: &'a str,
+  },
   UnknownStartOfToken,
   UnpairedCarriageReturn,
+  UnreadableImport {
+    path: String,
+    message: String,
+  },
+  UnreadableModule {
+    module: &'a str,
+    path: String,
+    message: String,
+  },
+  UnterminatedHeredoc {
+    delimiter: &'a str,
+  },
   UnterminatedInterpolation,
   UnterminatedString,
   UnterminatedBacktick,
@@ -0,0 +1,93 @@
+//! The errors, warnings, and recipes of a justfile, as JSON, for the C and
+//! WebAssembly interfaces
+
+use crate::common::*;
+
+use serde::Serialize;
+
+use crate::request::summarize;
+
+/// An error or warning found while compiling a justfile
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub(crate) struct Diagnostic {
+  /// `error` or `warning`
+  severity: &'static str,
+  /// The diagnostic, as `just` would print it
+  message: String,
+  line: usize,
+  column: usize,
+  width: usize,
+}
+
+impl Diagnostic {
+  pub(crate) fn error(error: &CompilationError) -> Diagnostic {
+    Diagnostic {
+      severity: "error",
+      message: error.to_string(),
+      line: error.line,
+      column: error.column,
+      width: error.width,
+    }
+  }
+
+  pub(crate) fn warning(warning: &Warning) -> Diagnostic {
+    let (line, column, width) = warning
+      .context()
+      .map(|token| (token.line, token.column, token.length))
+      .unwrap_or((0, 0, 0));
+    Diagnostic {
+      severity: "warning",
+      message: warning.to_string(),
+      line,
+      column,
+      width,
+    }
+  }
+}
+
+/// The errors and warnings found in `text`, along with its recipes if
+/// `list`, which there are none of if it has errors
+pub(crate) fn report(text: &str, list: bool) -> serde_json::Value {
+  match Parser::parse(text) {
+    Ok(justfile) => {
+      let diagnostics = justfile
+        .warnings
+        .iter()
+        .map(Diagnostic::warning)
+        .collect::<Vec<Diagnostic>>();
+      result(&recipes(&justfile), &diagnostics, list)
+    }
+    Err(error) => result(&serde_json::json!([]), &[Diagnostic::error(&error)], list),
+  }
+}
+
+/// A report of an error found before compiling, such as invalid UTF-8
+pub(crate) fn error(message: String, list: bool) -> serde_json::Value {
+  let diagnostic = Diagnostic {
+    severity: "error",
+    message,
+    line: 0,
+    column: 0,
+    width: 0,
+  };
+  result(&serde_json::json!([]), &[diagnostic], list)
+}
+
+/// The recipes of `justfile`, as described by `just --request`
+pub(crate) fn recipes(justfile: &Justfile) -> serde_json::Value {
+  let mut recipes = Vec::new();
+  summarize(justfile, "", &mut recipes);
+  serde_json::json!(recipes)
+}
+
+pub(crate) fn result(
+  recipes: &serde_json::Value,
+  diagnostics: &[Diagnostic],
+  list: bool,
+) -> serde_json::Value {
+  if list {
+    serde_json::json!({ "recipes": recipes, "diagnostics": diagnostics })
+  } else {
+    serde_json::json!({ "diagnostics": diagnostics })
+  }
+}
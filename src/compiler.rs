@@ -0,0 +1,249 @@
+//! Compilation of a justfile that is open in an editor, and is compiled
+//! again after every edit
+
+use crate::common::*;
+
+use crate::compilation_report::{self, Diagnostic};
+
+/// A justfile that is compiled again after every edit. The body of a recipe
+/// can't affect the rest of the justfile, so after an edit within a single
+/// line of a recipe's body, the justfile is compiled with the bodies of the
+/// other recipes left out, and their warnings are kept from the last
+/// compilation. Other edits compile the whole justfile.
+pub(crate) struct Compiler {
+  text: String,
+  /// The lines of each recipe's body, by recipe name, if the justfile
+  /// compiled without errors
+  bodies: BTreeMap<String, Range<usize>>,
+  /// The warnings about each recipe's body, by recipe name
+  body_warnings: BTreeMap<String, Diagnostic>,
+  diagnostics: Vec<Diagnostic>,
+  recipes: serde_json::Value,
+}
+
+impl Compiler {
+  pub(crate) fn new(text: String) -> Compiler {
+    let mut compiler = Compiler {
+      text,
+      bodies: BTreeMap::new(),
+      body_warnings: BTreeMap::new(),
+      diagnostics: Vec::new(),
+      recipes: serde_json::json!([]),
+    };
+    compiler.compile(None);
+    compiler
+  }
+
+  /// Replace the text in the byte range `range` with `new_text`, and compile
+  /// the justfile again
+  pub(crate) fn update(&mut self, range: Range<usize>, new_text: &str) -> Result<(), String> {
+    if range.start > range.end
+      || !self.text.is_char_boundary(range.start)
+      || !self.text.is_char_boundary(range.end)
+    {
+      return Err(format!(
+        "Edit range {}..{} is not within the justfile",
+        range.start, range.end
+      ));
+    }
+
+    let before = &self.text[..range.start];
+    let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let line = before.matches('\n').count();
+
+    // Leaving the first character of the line alone keeps the line indented,
+    // and so in the same recipe's body
+    let within_line = range.start > line_start
+      && !self.text[range.clone()].contains(['\n', '\r'])
+      && !new_text.contains(['\n', '\r']);
+
+    let edited = if within_line {
+      self
+        .bodies
+        .iter()
+        .find(|(_, lines)| lines.contains(&line))
+        .map(|(name, _)| name.clone())
+    } else {
+      None
+    };
+
+    self.text.replace_range(range, new_text);
+    self.compile(edited.as_deref());
+
+    Ok(())
+  }
+
+  /// The errors and warnings in the justfile, along with its recipes if
+  /// `list`, as returned by `compilation_report::report`
+  pub(crate) fn report(&self, list: bool) -> serde_json::Value {
+    compilation_report::result(&self.recipes, &self.diagnostics, list)
+  }
+
+  /// Compile the justfile, leaving out the bodies of the recipes other than
+  /// `edited`, if given
+  fn compile(&mut self, edited: Option<&str>) {
+    let text = match edited {
+      Some(edited) => self.without_bodies(edited),
+      None => self.text.clone(),
+    };
+
+    let justfile = match Parser::parse(&text) {
+      Ok(justfile) => justfile,
+      Err(error) => {
+        self.bodies.clear();
+        self.body_warnings.clear();
+        self.diagnostics = vec![Diagnostic::error(&error)];
+        self.recipes = serde_json::json!([]);
+        return;
+      }
+    };
+
+    match edited {
+      Some(edited) => {
+        self.body_warnings.remove(edited);
+      }
+      None => {
+        self.bodies = justfile
+          .recipes
+          .values()
+          .map(|recipe| {
+            let start = recipe.line_number + 1;
+            (recipe.name.to_owned(), start..start + recipe.lines.len())
+          })
+          .collect();
+        self.body_warnings.clear();
+      }
+    }
+
+    let mut diagnostics = Vec::new();
+    for warning in &justfile.warnings {
+      match warning {
+        Warning::LostDirectoryChange { recipe, .. } => {
+          self
+            .body_warnings
+            .insert((*recipe).to_owned(), Diagnostic::warning(warning));
+        }
+        Warning::DeprecatedEquals { .. } => diagnostics.push(Diagnostic::warning(warning)),
+      }
+    }
+
+    // Warnings about bodies follow the others, in the order of the recipes
+    diagnostics.extend(
+      justfile
+        .recipes
+        .values()
+        .filter_map(|recipe| self.body_warnings.get(recipe.name).cloned()),
+    );
+
+    self.diagnostics = diagnostics;
+    self.recipes = compilation_report::recipes(&justfile);
+  }
+
+  /// The justfile, with the lines of the bodies of the recipes other than
+  /// `edited` left empty, so that lines and columns are unchanged
+  fn without_bodies(&self, edited: &str) -> String {
+    let mut omitted = self
+      .bodies
+      .iter()
+      .filter(|(name, _)| name.as_str() != edited)
+      .map(|(_, lines)| lines.clone())
+      .collect::<Vec<Range<usize>>>();
+    omitted.sort_by_key(|lines| lines.start);
+    let mut omitted = omitted.into_iter().peekable();
+
+    let mut text = String::with_capacity(self.text.len());
+    for (i, line) in self.text.split_inclusive('\n').enumerate() {
+      while omitted.peek().map(|lines| lines.end <= i).unwrap_or(false) {
+        omitted.next();
+      }
+
+      if omitted
+        .peek()
+        .map(|lines| lines.contains(&i))
+        .unwrap_or(false)
+      {
+        if line.ends_with("\r\n") {
+          text.push_str("\r\n");
+        } else if line.ends_with('\n') {
+          text.push('\n');
+        }
+      } else {
+        text.push_str(line);
+      }
+    }
+
+    text
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Apply each edit, given as the text to replace and its replacement, to
+  /// the first occurrence of that text, and check that the report is the
+  /// same as that of compiling the edited justfile from scratch
+  fn edits(text: &str, edits: &[(&str, &str)]) {
+    let mut compiler = Compiler::new(text.to_owned());
+    for (old, new) in edits {
+      let start = compiler.text.find(old).unwrap();
+      compiler.update(start..start + old.len(), new).unwrap();
+      assert_eq!(
+        compiler.report(true),
+        compilation_report::report(&compiler.text, true),
+        "after replacing {:?} with {:?} in:\n{}",
+        old,
+        new,
+        compiler.text,
+      );
+    }
+  }
+
+  #[test]
+  fn body_edits() {
+    edits(
+      "x := 'a'\n\nfoo:\n  echo {{x}}\n  echo bar\n\nbar a:\n  cd baz\n  echo {{a}}\n",
+      &[
+        ("echo bar", "echo baz"),
+        ("{{x}}", "{{y}}"),
+        ("{{y}}", "{{x}}"),
+        ("echo {{x}}", "cd qux"),
+        ("cd baz", "echo baz"),
+        ("{{a}}", "{{a + x}}"),
+      ],
+    );
+  }
+
+  #[test]
+  fn structural_edits() {
+    edits(
+      "foo:\n  echo foo\n\nbar:\n  echo bar\n",
+      &[
+        ("bar:", "bar: foo"),
+        ("  echo bar\n", ""),
+        ("echo foo", "echo foo\n  echo qux"),
+        ("bar: foo", "bar: baz"),
+        ("  echo qux", "  echo {{qux}}"),
+        ("bar: baz", "bar:"),
+      ],
+    );
+  }
+
+  #[test]
+  fn without_bodies() {
+    let compiler = Compiler::new("foo:\n  echo foo\n\nbar:\n  echo bar\r\n  echo baz".into());
+    assert_eq!(
+      compiler.without_bodies("foo"),
+      "foo:\n  echo foo\n\nbar:\n\r\n"
+    );
+  }
+
+  #[test]
+  fn invalid_range() {
+    let mut compiler = Compiler::new("foo:\n  echo é\n".into());
+    assert!(compiler.update(13..13, "").is_err());
+    assert!(compiler.update(16..16, "").is_err());
+    let (start, end) = (3, 2);
+    assert!(compiler.update(start..end, "").is_err());
+  }
+}
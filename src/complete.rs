@@ -0,0 +1,171 @@
+use crate::common::*;
+
+/// Options that take values, and how many values each takes, so that the
+/// words after them aren't mistaken for recipes or arguments. `--merge` takes
+/// any number of justfiles, but only the first is completed here.
+const OPTIONS_WITH_VALUES: &[(&str, usize)] = &[
+  ("--color", 1),
+  ("--define", 1),
+  ("--env", 1),
+  ("--env-file", 1),
+  ("--expr", 1),
+  ("--grammar", 1),
+  ("--install-completions", 1),
+  ("--jobs", 1),
+  ("--justfile", 1),
+  ("--justfile-name", 1),
+  ("--linter", 1),
+  ("--merge", 1),
+  ("--order", 1),
+  ("--output", 1),
+  ("--rename", 2),
+  ("--set", 2),
+  ("--shell", 1),
+  ("--show", 1),
+  ("--why", 2),
+  ("--working-directory", 1),
+  ("-d", 1),
+  ("-f", 1),
+  ("-j", 1),
+  ("-o", 1),
+  ("-s", 1),
+];
+
+/// The candidates for the word at `index` in `words`, the words of a `just`
+/// command line starting with the name of the program, which begin with
+/// the part of that word already typed
+pub(crate) fn complete(justfile: &Justfile, index: usize, words: &[&str]) -> Vec<String> {
+  let current = words.get(index).cloned().unwrap_or("");
+
+  let mut option: Option<(&str, usize)> = None;
+  let mut recipe: Option<(&Recipe, usize)> = None;
+  let mut overrides = true;
+
+  for word in words.iter().take(index).skip(1).cloned() {
+    if let Some((name, value)) = option {
+      let count = OPTIONS_WITH_VALUES
+        .iter()
+        .find(|(option, _)| *option == name)
+        .map(|(_, count)| *count)
+        .unwrap_or(1);
+      option = if value + 1 < count {
+        Some((name, value + 1))
+      } else {
+        None
+      };
+      continue;
+    }
+
+    // Everything after `--` is passed through to the last recipe
+    if word == "--" {
+      return Vec::new();
+    }
+
+    if word.starts_with('-') && word.len() > 1 {
+      if OPTIONS_WITH_VALUES
+        .iter()
+        .any(|(option, _)| *option == word)
+      {
+        option = Some((word, 0));
+      }
+      continue;
+    }
+
+    if overrides && word.chars().skip(1).any(|c| c == '=') {
+      continue;
+    }
+
+    overrides = false;
+
+    recipe = match recipe {
+      Some((recipe, arguments)) if arguments < recipe.max_arguments() => {
+        Some((recipe, arguments + 1))
+      }
+      _ => justfile
+        .get_recipe_path(word)
+        .map(|(_, recipe)| (recipe, 0)),
+    };
+  }
+
+  let mut candidates = Vec::new();
+
+  match option {
+    Some(("--set", 0)) => candidates.extend(variable_names(justfile)),
+    Some(("--rename", 0)) => {
+      candidates.extend(recipe_names(justfile));
+      candidates.extend(variable_names(justfile));
+    }
+    Some(("--show", _)) | Some(("-s", _)) | Some(("--why", _)) => {
+      candidates.extend(recipe_names(justfile))
+    }
+    Some(_) => {}
+    None if current.starts_with('-') || current.contains('=') => {}
+    None => match recipe {
+      Some((recipe, arguments)) if arguments < recipe.max_arguments() => {
+        let parameter = &recipe.parameters[cmp::min(arguments, recipe.parameters.len() - 1)];
+
+        if let Some(axis) = recipe
+          .matrix
+          .iter()
+          .find(|axis| axis.name == parameter.name)
+        {
+          candidates.extend(axis.values.iter().map(|value| value.cooked.to_string()));
+        }
+
+        // Optional parameters may be skipped in favor of another recipe
+        if arguments >= recipe.min_arguments() {
+          candidates.extend(recipe_names(justfile));
+        }
+      }
+      _ => {
+        candidates.extend(recipe_names(justfile));
+        if overrides {
+          candidates.extend(
+            variable_names(justfile)
+              .into_iter()
+              .map(|name| format!("{}=", name)),
+          );
+        }
+      }
+    },
+  }
+
+  candidates.retain(|candidate| candidate.starts_with(current));
+  candidates.dedup();
+  candidates
+}
+
+/// The names of the public recipes and aliases in `justfile`, followed by
+/// those in its modules, prefixed by the module name and `::`
+fn recipe_names(justfile: &Justfile) -> Vec<String> {
+  let mut names = justfile
+    .recipes
+    .sorted_values()
+    .filter(|recipe| !recipe.private)
+    .map(|recipe| recipe.name)
+    .chain(
+      justfile
+        .aliases
+        .values()
+        .filter(|alias| !alias.private)
+        .map(|alias| alias.name),
+    )
+    .map(str::to_owned)
+    .collect::<Vec<String>>();
+
+  for (name, module) in &justfile.modules {
+    for path in recipe_names(module) {
+      names.push(format!("{}::{}", name, path));
+    }
+  }
+
+  names
+}
+
+fn variable_names(justfile: &Justfile) -> Vec<String> {
+  justfile
+    .assignments
+    .sorted_keys()
+    .map(|name| (*name).to_owned())
+    .collect()
+}
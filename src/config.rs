@@ -4,35 +4,151 @@ use clap::{App, AppSettings, Arg, ArgGroup, ArgMatches};
 
 pub(crate) const DEFAULT_SHELL: &str = "sh";
 
+pub(crate) const DEFAULT_SHELL_ARGUMENTS: &[&str] = &["-cu"];
+
+pub(crate) const DEFAULT_LINTER: &str = "shellcheck --format=gcc -";
+
+pub(crate) const DEFAULT_TIMESTAMP_FORMAT: &str = "%H:%M:%S";
+
 pub(crate) struct Config<'a> {
   pub(crate) subcommand: Subcommand<'a>,
+  pub(crate) all: bool,
   pub(crate) dry_run: bool,
+  pub(crate) each: bool,
+  /// Constants given with `--define`, which are substituted while parsing
+  pub(crate) defines: BTreeMap<&'a str, &'a str>,
+  pub(crate) environment: BTreeMap<&'a str, &'a str>,
+  pub(crate) env_files: Vec<PathBuf>,
   pub(crate) evaluate: bool,
+  /// The group to list the recipes of with `--list`, from `--group`
+  pub(crate) group: Option<&'a str>,
   pub(crate) highlight: bool,
+  pub(crate) jobs: usize,
+  /// Whether to print the recipes as JSON with `--list`, from `--json`
+  pub(crate) json: bool,
+  pub(crate) matrix: bool,
+  pub(crate) no_execute_backticks: bool,
+  pub(crate) output_format: OutputFormat,
   pub(crate) overrides: BTreeMap<&'a str, &'a str>,
   pub(crate) quiet: bool,
+  pub(crate) quiet_warnings: bool,
+  /// Where to write a report of the results of the recipes given on the
+  /// command line, from `--report`
+  pub(crate) report: Option<&'a Path>,
+  pub(crate) report_format: ReportFormat,
   pub(crate) shell: &'a str,
+  /// Arguments passed to `shell` before each command
+  pub(crate) shell_arguments: Vec<&'a str>,
+  /// Whether `--shell` was given, in which case it takes precedence over
+  /// the `shell` setting
+  pub(crate) shell_present: bool,
+  pub(crate) show_script: bool,
+  /// Whether to print the time before each echoed command, from
+  /// `--timestamps`
+  pub(crate) timestamps: bool,
+  /// The strftime-style format of those times
+  pub(crate) timestamp_format: &'a str,
   pub(crate) color: Color,
   pub(crate) verbosity: Verbosity,
   pub(crate) arguments: Vec<&'a str>,
+  /// The number of arguments at the end of `arguments` that were given after
+  /// `--`, which are passed to the last recipe verbatim
+  pub(crate) pass_through: usize,
   pub(crate) justfile: Option<&'a Path>,
+  pub(crate) justfile_names: Vec<&'a str>,
+  /// The path of the justfile, once it's been found, made absolute
+  pub(crate) justfile_path: Option<PathBuf>,
+  /// The directory containing the justfile, once it's been found, where
+  /// `.env` files are looked for
+  pub(crate) justfile_directory: Option<PathBuf>,
   pub(crate) working_directory: Option<&'a Path>,
+  /// The directory to search for the justfile from, if the first recipe is
+  /// prefixed with a path, instead of the current directory
+  pub(crate) search_directory: Option<PathBuf>,
+  /// The directory that recipes, backticks, and other commands are run in,
+  /// once the justfile has been found. Commands are run in the current
+  /// directory until then.
+  pub(crate) run_directory: Option<PathBuf>,
+  /// Whether to run recipes with `[confirm]` without asking, from `--yes`
+  pub(crate) yes: bool,
   pub(crate) invocation_directory: Result<PathBuf, String>,
+  /// just's environment, captured at startup, which `env_var` and
+  /// `env_var_or_default` read from
+  pub(crate) environment_snapshot: BTreeMap<OsString, OsString>,
 }
 
 mod arg {
+  pub(crate) const ALL: &str = "ALL";
+  pub(crate) const BENCH: &str = "BENCH";
+  pub(crate) const COMPLETE: &str = "COMPLETE";
+  pub(crate) const DEFINE: &str = "DEFINE";
   pub(crate) const DUMP: &str = "DUMP";
   pub(crate) const COLOR: &str = "COLOR";
+  pub(crate) const EACH: &str = "EACH";
   pub(crate) const EDIT: &str = "EDIT";
+  pub(crate) const ENV: &str = "ENV";
+  pub(crate) const ENV_FILE: &str = "ENV-FILE";
+  pub(crate) const EXPR: &str = "EXPR";
+  pub(crate) const GRAMMAR: &str = "GRAMMAR";
+  pub(crate) const GROUP: &str = "GROUP";
+  pub(crate) const GROUPS: &str = "GROUPS";
+  pub(crate) const HISTORY: &str = "HISTORY";
+  pub(crate) const INIT: &str = "INIT";
+  pub(crate) const INSTALL_COMPLETIONS: &str = "INSTALL-COMPLETIONS";
+  pub(crate) const INSTALL_MAN: &str = "INSTALL-MAN";
+  pub(crate) const JOBS: &str = "JOBS";
+  pub(crate) const JSON: &str = "JSON";
+  pub(crate) const JUSTFILE_NAME: &str = "JUSTFILE-NAME";
+  pub(crate) const LAST: &str = "LAST";
+  pub(crate) const LINT: &str = "LINT";
+  pub(crate) const LINTER: &str = "LINTER";
   pub(crate) const LIST: &str = "LIST";
+  pub(crate) const LIST_FUNCTIONS: &str = "LIST-FUNCTIONS";
+  pub(crate) const MATRIX: &str = "MATRIX";
+  pub(crate) const MERGE: &str = "MERGE";
+  pub(crate) const QUIET_WARNINGS: &str = "QUIET-WARNINGS";
+  pub(crate) const NO_EXECUTE_BACKTICKS: &str = "NO-EXECUTE-BACKTICKS";
+  pub(crate) const ORDER: &str = "ORDER";
+  pub(crate) const OUTPUT: &str = "OUTPUT";
+  pub(crate) const OUTPUT_FORMAT: &str = "OUTPUT-FORMAT";
+  pub(crate) const RENAME: &str = "RENAME";
+  pub(crate) const REPL: &str = "REPL";
+  pub(crate) const REPORT: &str = "REPORT";
+  pub(crate) const REPORT_FORMAT: &str = "REPORT-FORMAT";
+  pub(crate) const REQUEST: &str = "REQUEST";
+  pub(crate) const RESUME: &str = "RESUME";
+  pub(crate) const RUNS: &str = "RUNS";
   pub(crate) const SHOW: &str = "SHOW";
+  pub(crate) const SHOW_SCRIPT: &str = "SHOW-SCRIPT";
   pub(crate) const SUMMARY: &str = "SUMMARY";
+  pub(crate) const SYSTEM_INFO: &str = "SYSTEM-INFO";
+  pub(crate) const TIMESTAMPS: &str = "TIMESTAMPS";
+  pub(crate) const TIMESTAMP_FORMAT: &str = "TIMESTAMP-FORMAT";
+  pub(crate) const WHERE: &str = "WHERE";
+  pub(crate) const WHY: &str = "WHY";
   pub(crate) const WORKING_DIRECTORY: &str = "WORKING-DIRECTORY";
+  pub(crate) const YES: &str = "YES";
 
   pub(crate) const COLOR_AUTO: &str = "auto";
   pub(crate) const COLOR_ALWAYS: &str = "always";
   pub(crate) const COLOR_NEVER: &str = "never";
   pub(crate) const COLOR_VALUES: &[&str] = &[COLOR_AUTO, COLOR_ALWAYS, COLOR_NEVER];
+
+  pub(crate) const COMPLETION_SHELLS: &[&str] = &["bash", "fish", "zsh"];
+
+  pub(crate) const GRAMMAR_FORMATS: &[&str] = &["ebnf", "tree-sitter"];
+
+  pub(crate) const ORDER_ALPHABETICAL: &str = "alphabetical";
+  pub(crate) const ORDER_SOURCE: &str = "source";
+  pub(crate) const ORDER_VALUES: &[&str] = &[ORDER_ALPHABETICAL, ORDER_SOURCE];
+
+  pub(crate) const OUTPUT_FORMAT_HUMAN: &str = "human";
+  pub(crate) const OUTPUT_FORMAT_GITHUB: &str = "github";
+  pub(crate) const OUTPUT_FORMAT_VALUES: &[&str] = &[OUTPUT_FORMAT_HUMAN, OUTPUT_FORMAT_GITHUB];
+
+  pub(crate) const REPORT_FORMAT_JUNIT: &str = "junit";
+  pub(crate) const REPORT_FORMAT_TAP: &str = "tap";
+  pub(crate) const REPORT_FORMAT_VALUES: &[&str] = &[REPORT_FORMAT_JUNIT, REPORT_FORMAT_TAP];
 }
 
 impl<'a> Config<'a> {
@@ -45,8 +161,19 @@ impl<'a> Config<'a> {
       .arg(
         Arg::with_name("ARGUMENTS")
           .multiple(true)
+          .allow_hyphen_values(true)
           .help("The recipe(s) to run, defaults to the first recipe in the justfile"),
       )
+      .arg(
+        Arg::with_name(arg::ALL)
+          .long("all")
+          .help("Run recipes with a matrix once for every combination of its values"),
+      )
+      .arg(
+        Arg::with_name(arg::BENCH)
+          .long("bench")
+          .help("Run the recipe(s) repeatedly without output and report how long they took"),
+      )
       .arg(
         Arg::with_name(arg::COLOR)
           .long("color")
@@ -61,17 +188,102 @@ impl<'a> Config<'a> {
           .help("Print what just would do without doing it")
           .conflicts_with("QUIET"),
       )
+      .arg(
+        Arg::with_name(arg::COMPLETE)
+          .long("complete")
+          .takes_value(true)
+          .value_name("INDEX")
+          .hidden(true)
+          .help(
+            "Print completions for word <INDEX> of the command line given as the remaining \
+             arguments, for use by completion scripts",
+          ),
+      )
       .arg(
         Arg::with_name(arg::DUMP)
           .long("dump")
           .help("Print entire justfile"),
       )
+      .arg(
+        Arg::with_name(arg::EACH)
+          .long("each")
+          .help("Run the first recipe given once for each of the remaining arguments"),
+      )
       .arg(
         Arg::with_name(arg::EDIT)
           .short("e")
           .long("edit")
           .help("Open justfile with $EDITOR"),
       )
+      .arg(
+        Arg::with_name(arg::DEFINE)
+          .long("define")
+          .takes_value(true)
+          .number_of_values(1)
+          .value_name("KEY=VALUE")
+          .multiple(true)
+          .help(
+            "Define constant <KEY> as <VALUE>, for use in place of strings in attributes and \
+             settings",
+          ),
+      )
+      .arg(
+        Arg::with_name(arg::ENV)
+          .long("env")
+          .takes_value(true)
+          .number_of_values(1)
+          .value_name("KEY=VALUE")
+          .multiple(true)
+          .help("Set environment variable <KEY> to <VALUE> for all commands"),
+      )
+      .arg(
+        Arg::with_name(arg::ENV_FILE)
+          .long("env-file")
+          .takes_value(true)
+          .number_of_values(1)
+          .value_name("FILE")
+          .multiple(true)
+          .help(
+            "Load environment variables from <FILE>, after `.env` and before --env. May also \
+             be given after recipe names",
+          ),
+      )
+      .arg(
+        Arg::with_name(arg::EXPR)
+          .long("expr")
+          .takes_value(true)
+          .value_name("EXPRESSION")
+          .help("Print the value of <EXPRESSION>, which may use the justfile's variables"),
+      )
+      .arg(
+        Arg::with_name(arg::GRAMMAR)
+          .long("grammar")
+          .takes_value(true)
+          .value_name("FORMAT")
+          .possible_values(arg::GRAMMAR_FORMATS)
+          .help("Print the justfile grammar in <FORMAT>, for syntax highlighters"),
+      )
+      .arg(
+        Arg::with_name(arg::GROUP)
+          .long("group")
+          .takes_value(true)
+          .value_name("GROUP")
+          .requires(arg::LIST)
+          .help("With --list, only list recipes in <GROUP>"),
+      )
+      .arg(
+        Arg::with_name(arg::GROUPS)
+          .long("groups")
+          .help("List the groups that recipes are listed under"),
+      )
+      .arg(
+        Arg::with_name(arg::JOBS)
+          .short("j")
+          .long("jobs")
+          .takes_value(true)
+          .value_name("N")
+          .help("Run up to <N> recipes at once with --each, or dependencies written in brackets"),
+      )
       .arg(
         Arg::with_name("EVALUATE")
           .long("evaluate")
@@ -82,6 +294,29 @@ impl<'a> Config<'a> {
           .long("highlight")
           .help("Highlight echoed recipe lines in bold"),
       )
+      .arg(
+        Arg::with_name(arg::HISTORY)
+          .long("history")
+          .help("List recent invocations of recipes in the justfile"),
+      )
+      .arg(
+        Arg::with_name(arg::INIT)
+          .long("init")
+          .help("Initialize new justfile in the current directory"),
+      )
+      .arg(
+        Arg::with_name(arg::INSTALL_COMPLETIONS)
+          .long("install-completions")
+          .takes_value(true)
+          .value_name("SHELL")
+          .possible_values(arg::COMPLETION_SHELLS)
+          .help("Install completions for <SHELL> in the per-user completions directory"),
+      )
+      .arg(
+        Arg::with_name(arg::INSTALL_MAN)
+          .long("install-man")
+          .help("Install the man page in the per-user man directory"),
+      )
       .arg(
         Arg::with_name("JUSTFILE")
           .short("f")
@@ -89,12 +324,116 @@ impl<'a> Config<'a> {
           .takes_value(true)
           .help("Use <JUSTFILE> as justfile."),
       )
+      .arg(
+        Arg::with_name(arg::JUSTFILE_NAME)
+          .long("justfile-name")
+          .takes_value(true)
+          .value_name("NAME")
+          .multiple(true)
+          .number_of_values(1)
+          .use_delimiter(true)
+          .env("JUST_JUSTFILE_NAME")
+          .help(
+            "Prefer justfiles named <NAME>, in the order given, if more than one candidate \
+             justfile is found",
+          ),
+      )
+      .arg(
+        Arg::with_name(arg::LAST)
+          .long("last")
+          .help("Run the recipes and arguments of the previous invocation again"),
+      )
+      .arg(
+        Arg::with_name(arg::JSON)
+          .long("json")
+          .requires(arg::LIST)
+          .help("With --list, print the recipes, their arguments, and their metadata as JSON"),
+      )
       .arg(
         Arg::with_name(arg::LIST)
           .short("l")
           .long("list")
           .help("List available recipes and their arguments"),
       )
+      .arg(
+        Arg::with_name(arg::LIST_FUNCTIONS)
+          .long("list-functions")
+          .help("List built-in functions and their arguments"),
+      )
+      .arg(
+        Arg::with_name(arg::LINT)
+          .long("lint")
+          .help("Run recipes written in a shell through a linter, `shellcheck` by default"),
+      )
+      .arg(
+        Arg::with_name(arg::LINTER)
+          .long("linter")
+          .takes_value(true)
+          .value_name("COMMAND")
+          .requires(arg::LINT)
+          .help(
+            "Lint with <COMMAND>, which is given a script on standard input and prints \
+             diagnostics in the `-:LINE:COLUMN: MESSAGE` format",
+          ),
+      )
+      .arg(
+        Arg::with_name(arg::MATRIX)
+          .long("matrix")
+          .requires(arg::LIST)
+          .help("List every combination of values of recipes with a matrix"),
+      )
+      .arg(
+        Arg::with_name(arg::MERGE)
+          .long("merge")
+          .takes_value(true)
+          .value_name("JUSTFILE")
+          .multiple(true)
+          .min_values(1)
+          .help(
+            "Combine the given justfiles into one, reporting recipes and variables they define \
+             differently",
+          ),
+      )
+      .arg(
+        Arg::with_name(arg::NO_EXECUTE_BACKTICKS)
+          .long("no-execute-backticks")
+          .help(
+            "Don't run backticks, so an untrusted justfile can be inspected safely. Recipes may \
+             only be run with `--dry-run`",
+          ),
+      )
+      .arg(
+        Arg::with_name(arg::ORDER)
+          .long("order")
+          .takes_value(true)
+          .possible_values(arg::ORDER_VALUES)
+          .requires(arg::DUMP)
+          .help(
+            "Print items with --dump in <ORDER>. `alphabetical`, the default, groups items by \
+             kind and sorts them by name, and `source` keeps them in the order they are written",
+          ),
+      )
+      .arg(
+        Arg::with_name(arg::OUTPUT)
+          .short("o")
+          .long("output")
+          .takes_value(true)
+          .value_name("PATH")
+          .requires(arg::MERGE)
+          .help("Write the merged justfile to <PATH> instead of standard output"),
+      )
+      .arg(
+        Arg::with_name(arg::OUTPUT_FORMAT)
+          .long("output-format")
+          .takes_value(true)
+          .value_name("FORMAT")
+          .possible_values(arg::OUTPUT_FORMAT_VALUES)
+          .default_value(arg::OUTPUT_FORMAT_HUMAN)
+          .help(
+            "Print output in <FORMAT>. `github` adds GitHub Actions workflow commands that group \
+             the output of each recipe and annotate errors",
+          ),
+      )
       .arg(
         Arg::with_name("QUIET")
           .short("q")
@@ -102,6 +441,66 @@ impl<'a> Config<'a> {
           .help("Suppress all output")
           .conflicts_with("DRY-RUN"),
       )
+      .arg(
+        Arg::with_name(arg::QUIET_WARNINGS)
+          .long("quiet-warnings")
+          .help("Don't print warnings about the justfile"),
+      )
+      .arg(
+        Arg::with_name(arg::REPORT)
+          .long("report")
+          .takes_value(true)
+          .value_name("PATH")
+          .conflicts_with(arg::EACH)
+          .help(
+            "Treat each recipe given on the command line as a test case, running the rest if one \
+             fails, and write a report of their results to <PATH>",
+          ),
+      )
+      .arg(
+        Arg::with_name(arg::REPORT_FORMAT)
+          .long("report-format")
+          .takes_value(true)
+          .value_name("FORMAT")
+          .possible_values(arg::REPORT_FORMAT_VALUES)
+          .default_value(arg::REPORT_FORMAT_TAP)
+          .help("Write the report in <FORMAT>, either TAP or JUnit XML"),
+      )
+      .arg(
+        Arg::with_name(arg::RENAME)
+          .long("rename")
+          .takes_value(true)
+          .number_of_values(2)
+          .value_names(&["OLD", "NEW"])
+          .help(
+            "Rename the recipe or variable <OLD> to <NEW>, along with references to it, and \
+             write the result back to the justfile",
+          ),
+      )
+      .arg(
+        Arg::with_name(arg::REPL)
+          .long("repl")
+          .help("Read expressions to evaluate and recipes to run from an interactive prompt"),
+      )
+      .arg(
+        Arg::with_name(arg::REQUEST)
+          .long("request")
+          .help("Read a JSON request from standard input, and write a JSON response"),
+      )
+      .arg(
+        Arg::with_name(arg::RESUME)
+          .long("resume")
+          .help("Run the previous invocation again if it failed, starting from the failed recipe"),
+      )
+      .arg(
+        Arg::with_name(arg::RUNS)
+          .short("n")
+          .long("runs")
+          .takes_value(true)
+          .value_name("N")
+          .requires(arg::BENCH)
+          .help("Run recipes <N> times with --bench, 10 by default"),
+      )
       .arg(
         Arg::with_name("SET")
           .long("set")
@@ -126,11 +525,34 @@ impl<'a> Config<'a> {
           .value_name("RECIPE")
           .help("Show information about <RECIPE>"),
       )
+      .arg(
+        Arg::with_name(arg::SHOW_SCRIPT)
+          .long("show-script")
+          .help("Print the scripts generated for shebang recipes before running them"),
+      )
       .arg(
         Arg::with_name(arg::SUMMARY)
           .long("summary")
           .help("List names of available recipes"),
       )
+      .arg(
+        Arg::with_name(arg::SYSTEM_INFO)
+          .long("system-info")
+          .help("Print information about the current system"),
+      )
+      .arg(
+        Arg::with_name(arg::TIMESTAMPS)
+          .long("timestamps")
+          .help("Print the time before each echoed recipe line"),
+      )
+      .arg(
+        Arg::with_name(arg::TIMESTAMP_FORMAT)
+          .long("timestamp-format")
+          .takes_value(true)
+          .value_name("FORMAT")
+          .default_value(DEFAULT_TIMESTAMP_FORMAT)
+          .help("Print timestamps with the strftime-style <FORMAT>"),
+      )
       .arg(
         Arg::with_name("VERBOSE")
           .short("v")
@@ -138,6 +560,23 @@ impl<'a> Config<'a> {
           .multiple(true)
           .help("Use verbose output"),
       )
+      .arg(
+        Arg::with_name(arg::WHERE)
+          .long("where")
+          .requires(arg::SHOW)
+          .help(
+            "With --show, print where the recipe and each of its dependencies are defined, as \
+             `PATH:LINE:COLUMN: NAME`",
+          ),
+      )
+      .arg(
+        Arg::with_name(arg::WHY)
+          .long("why")
+          .takes_value(true)
+          .number_of_values(2)
+          .value_names(&["RECIPE", "DEPENDENCY"])
+          .help("Print the chains of dependencies by which running <RECIPE> runs <DEPENDENCY>"),
+      )
       .arg(
         Arg::with_name(arg::WORKING_DIRECTORY)
           .short("d")
@@ -146,12 +585,34 @@ impl<'a> Config<'a> {
           .help("Use <WORKING-DIRECTORY> as working directory. --justfile must also be set")
           .requires("JUSTFILE"),
       )
+      .arg(
+        Arg::with_name(arg::YES)
+          .long("yes")
+          .help("Run recipes with the `[confirm]` attribute without asking"),
+      )
       .group(ArgGroup::with_name("EARLY-EXIT").args(&[
         arg::DUMP,
         arg::EDIT,
+        arg::EXPR,
+        arg::GRAMMAR,
+        arg::GROUPS,
+        arg::HISTORY,
+        arg::INIT,
+        arg::INSTALL_COMPLETIONS,
+        arg::INSTALL_MAN,
+        arg::LAST,
+        arg::LINT,
         arg::LIST,
+        arg::LIST_FUNCTIONS,
+        arg::MERGE,
+        arg::RENAME,
+        arg::REPL,
+        arg::REQUEST,
+        arg::RESUME,
         arg::SHOW,
         arg::SUMMARY,
+        arg::SYSTEM_INFO,
+        arg::WHY,
         "ARGUMENTS",
         "EVALUATE",
       ]));
@@ -174,6 +635,19 @@ impl<'a> Config<'a> {
     }
   }
 
+  /// The prefix of an echoed recipe line, which is the current time if
+  /// `--timestamps` was given
+  pub(crate) fn timestamp(&self) -> String {
+    if !self.timestamps {
+      return String::new();
+    }
+
+    match DateTime::now_local().format(self.timestamp_format) {
+      Ok(time) => format!("[{}] ", time),
+      Err(_) => String::new(),
+    }
+  }
+
   fn color_from_value(value: &str) -> ConfigResult<Color> {
     match value {
       arg::COLOR_AUTO => Ok(Color::auto()),
@@ -185,11 +659,43 @@ impl<'a> Config<'a> {
     }
   }
 
+  /// Check that `name` could refer to a justfile variable, so that malformed
+  /// overrides are reported before the justfile is evaluated
+  fn check_override(name: &str) -> ConfigResult<()> {
+    let mut chars = name.chars();
+
+    let valid = chars
+      .next()
+      .map(|c| c.is_ascii_alphabetic() || c == '_')
+      .unwrap_or(false)
+      && chars.all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+
+    if valid {
+      Ok(())
+    } else {
+      Err(ConfigError::InvalidOverride {
+        name: name.to_owned(),
+      })
+    }
+  }
+
   pub(crate) fn from_matches(matches: &'a ArgMatches<'a>) -> ConfigResult<Config<'a>> {
     let invocation_directory =
       env::current_dir().map_err(|e| format!("Error getting current directory: {}", e));
 
-    let verbosity = Verbosity::from_flag_occurrences(matches.occurrences_of("VERBOSE"));
+    let mut verbosity = Verbosity::from_flag_occurrences(matches.occurrences_of("VERBOSE"));
+
+    // `JUST_VERBOSE` can only make just more verbose than its flags
+    if let Some(value) = env::var_os("JUST_VERBOSE").filter(|value| !value.is_empty()) {
+      let value = value.to_string_lossy();
+      let from_environment =
+        value
+          .parse::<Verbosity>()
+          .map_err(|()| ConfigError::InvalidVerbosity {
+            value: value.clone().into_owned(),
+          })?;
+      verbosity = cmp::max(verbosity, from_environment);
+    }
 
     let color = Self::color_from_value(
       matches
@@ -202,19 +708,114 @@ impl<'a> Config<'a> {
     if set_count > 0 {
       let mut values = matches.values_of("SET").unwrap();
       for _ in 0..set_count {
-        overrides.insert(values.next().unwrap(), values.next().unwrap());
+        let name = values.next().unwrap();
+        Self::check_override(name)?;
+        overrides.insert(name, values.next().unwrap());
+      }
+    }
+
+    let mut defines = BTreeMap::new();
+    if let Some(values) = matches.values_of(arg::DEFINE) {
+      for value in values {
+        match value.find('=') {
+          Some(i) if Self::check_override(&value[..i]).is_ok() => {
+            defines.insert(&value[..i], &value[i + 1..]);
+          }
+          _ => {
+            return Err(ConfigError::InvalidDefine {
+              argument: value.to_owned(),
+            })
+          }
+        }
+      }
+    }
+
+    let mut environment = BTreeMap::new();
+    if let Some(values) = matches.values_of(arg::ENV) {
+      for value in values {
+        let i = match value.find('=') {
+          Some(i) if i > 0 => i,
+          _ => {
+            return Err(ConfigError::InvalidEnvironmentVariable {
+              argument: value.to_owned(),
+            })
+          }
+        };
+
+        environment.insert(&value[..i], &value[i + 1..]);
       }
     }
 
+    let timestamp_format = matches
+      .value_of(arg::TIMESTAMP_FORMAT)
+      .expect("`--timestamp-format` had no value");
+
+    if let Err(message) = DateTime::now_utc().format(timestamp_format) {
+      return Err(ConfigError::InvalidTimestampFormat {
+        format: timestamp_format.to_owned(),
+        message,
+      });
+    }
+
+    let jobs = match matches.value_of(arg::JOBS) {
+      Some(value) => match value.parse::<usize>() {
+        Ok(jobs) if jobs > 0 => jobs,
+        _ => {
+          return Err(ConfigError::InvalidJobs {
+            value: value.to_owned(),
+          })
+        }
+      },
+      None => 1,
+    };
+
     fn is_override(arg: &&str) -> bool {
       arg.chars().skip(1).any(|c| c == '=')
     }
 
-    let raw_arguments: Vec<&str> = matches
+    let mut raw_arguments: Vec<&str> = matches
       .values_of("ARGUMENTS")
       .map(Iterator::collect)
       .unwrap_or_default();
 
+    // Completion scripts pass the command line being completed as the
+    // arguments, which are not recipes to run
+    let complete = match matches.value_of(arg::COMPLETE) {
+      Some(index) => Some((
+        index
+          .parse::<usize>()
+          .map_err(|_| ConfigError::InvalidCompletionIndex {
+            value: index.to_owned(),
+          })?,
+        mem::take(&mut raw_arguments),
+      )),
+      None => None,
+    };
+
+    // Everything after a literal `--` is passed through to the last named
+    // recipe verbatim, even if it looks like an override or a flag
+    let pass_through = match raw_arguments.iter().position(|&argument| argument == "--") {
+      Some(i) => {
+        let pass_through = raw_arguments.split_off(i + 1);
+        raw_arguments.pop();
+        pass_through
+      }
+      None => Vec::new(),
+    };
+
+    let bench = matches.is_present(arg::BENCH);
+    let runs = match matches.value_of(arg::RUNS) {
+      Some(value) => Self::runs(value)?,
+      None => 10,
+    };
+
+    let env_files = matches
+      .values_of(arg::ENV_FILE)
+      .into_iter()
+      .flatten()
+      .map(|path| Self::env_file(&invocation_directory, path))
+      .collect();
+
     for argument in raw_arguments.iter().cloned().take_while(is_override) {
       let i = argument
         .char_indices()
@@ -226,9 +827,13 @@ impl<'a> Config<'a> {
       let name = &argument[..i];
       let value = &argument[i + 1..];
 
+      Self::check_override(name)?;
+
       overrides.insert(name, value);
     }
 
+    let mut search_directory = None;
+
     let arguments = raw_arguments
       .into_iter()
       .skip_while(is_override)
@@ -242,9 +847,10 @@ impl<'a> Config<'a> {
 
             let (dir, recipe) = argument.split_at(i + 1);
 
-            if let Err(error) = env::set_current_dir(dir) {
-              die!("Error changing directory: {}", error);
-            }
+            search_directory = Some(match invocation_directory {
+              Ok(ref directory) => directory.join(dir),
+              Err(_) => PathBuf::from(dir),
+            });
 
             if recipe.is_empty() {
               return None;
@@ -256,55 +862,260 @@ impl<'a> Config<'a> {
 
         Some(argument)
       })
+      .chain(pass_through.iter().cloned())
       .collect::<Vec<&str>>();
 
-    let subcommand = if matches.is_present(arg::EDIT) {
+    let each = matches.is_present(arg::EACH);
+
+    if each && arguments.is_empty() {
+      return Err(ConfigError::EachWithoutRecipe);
+    }
+
+    let subcommand = if let Some((index, words)) = complete {
+      Subcommand::Complete { index, words }
+    } else if matches.is_present(arg::EDIT) {
       Subcommand::Edit
+    } else if matches.is_present(arg::HISTORY) {
+      Subcommand::History
+    } else if matches.is_present(arg::LAST) {
+      Subcommand::Last
+    } else if matches.is_present(arg::RESUME) {
+      Subcommand::Resume
+    } else if matches.is_present(arg::INIT) {
+      Subcommand::Init
+    } else if let Some(shell) = matches.value_of(arg::INSTALL_COMPLETIONS) {
+      Subcommand::InstallCompletions { shell }
+    } else if matches.is_present(arg::INSTALL_MAN) {
+      Subcommand::InstallMan
+    } else if matches.is_present(arg::LINT) {
+      Subcommand::Lint {
+        linter: matches.value_of(arg::LINTER).unwrap_or(DEFAULT_LINTER),
+      }
+    } else if let Some(justfiles) = matches.values_of_os(arg::MERGE) {
+      Subcommand::Merge {
+        justfiles: justfiles.map(Path::new).collect(),
+        output: matches.value_of_os(arg::OUTPUT).map(Path::new),
+      }
+    } else if matches.is_present(arg::LIST_FUNCTIONS) {
+      Subcommand::ListFunctions
+    } else if let Some(format) = matches.value_of(arg::GRAMMAR) {
+      Subcommand::Grammar { format }
+    } else if matches.is_present(arg::SYSTEM_INFO) {
+      Subcommand::SystemInfo
     } else if matches.is_present(arg::SUMMARY) {
       Subcommand::Summary
     } else if matches.is_present(arg::DUMP) {
-      Subcommand::Dump
+      Subcommand::Dump {
+        order: match matches.value_of(arg::ORDER) {
+          Some(arg::ORDER_SOURCE) => DumpOrder::Source,
+          _ => DumpOrder::Alphabetical,
+        },
+      }
     } else if matches.is_present(arg::LIST) {
       Subcommand::List
+    } else if matches.is_present(arg::GROUPS) {
+      Subcommand::Groups
+    } else if let Some(mut values) = matches.values_of(arg::RENAME) {
+      Subcommand::Rename {
+        old: values.next().unwrap(),
+        new: values.next().unwrap(),
+      }
     } else if let Some(name) = matches.value_of(arg::SHOW) {
-      Subcommand::Show { name }
+      Subcommand::Show {
+        name,
+        locate: matches.is_present(arg::WHERE),
+      }
+    } else if let Some(mut values) = matches.values_of(arg::WHY) {
+      Subcommand::Why {
+        recipe: values.next().unwrap(),
+        dependency: values.next().unwrap(),
+      }
+    } else if let Some(expression) = matches.value_of(arg::EXPR) {
+      Subcommand::Expression { expression }
+    } else if matches.is_present(arg::REPL) {
+      Subcommand::Repl
+    } else if matches.is_present(arg::REQUEST) {
+      Subcommand::Request
+    } else if bench {
+      Subcommand::Bench { runs }
     } else {
       Subcommand::Run
     };
 
     Ok(Config {
+      all: matches.is_present(arg::ALL),
+      json: matches.is_present(arg::JSON),
+      matrix: matches.is_present(arg::MATRIX),
+      no_execute_backticks: matches.is_present(arg::NO_EXECUTE_BACKTICKS),
+      output_format: match matches.value_of(arg::OUTPUT_FORMAT) {
+        Some(arg::OUTPUT_FORMAT_GITHUB) => OutputFormat::Github,
+        _ => OutputFormat::Human,
+      },
       dry_run: matches.is_present("DRY-RUN"),
       evaluate: matches.is_present("EVALUATE"),
+      group: matches.value_of(arg::GROUP),
       highlight: matches.is_present("HIGHLIGHT"),
       quiet: matches.is_present("QUIET"),
+      quiet_warnings: matches.is_present(arg::QUIET_WARNINGS),
+      report: matches.value_of_os(arg::REPORT).map(Path::new),
+      report_format: match matches.value_of(arg::REPORT_FORMAT) {
+        Some(arg::REPORT_FORMAT_JUNIT) => ReportFormat::Junit,
+        _ => ReportFormat::Tap,
+      },
       shell: matches.value_of("SHELL").unwrap(),
+      shell_arguments: DEFAULT_SHELL_ARGUMENTS.to_vec(),
+      shell_present: matches.occurrences_of("SHELL") > 0,
+      show_script: matches.is_present(arg::SHOW_SCRIPT),
+      timestamps: matches.is_present(arg::TIMESTAMPS),
+      timestamp_format,
       justfile: matches.value_of("JUSTFILE").map(Path::new),
+      justfile_names: matches
+        .values_of(arg::JUSTFILE_NAME)
+        .map(Iterator::collect)
+        .unwrap_or_default(),
+      justfile_path: None,
+      justfile_directory: None,
       working_directory: matches.value_of("WORKING-DIRECTORY").map(Path::new),
+      search_directory,
+      run_directory: None,
+      yes: matches.is_present(arg::YES),
       invocation_directory,
+      environment_snapshot: env::vars_os().collect(),
       subcommand,
       verbosity,
       color,
+      each,
+      defines,
+      environment,
+      env_files,
+      jobs,
       overrides,
       arguments,
+      pass_through: pass_through.len(),
     })
   }
+
+  /// The path of the env file `path`, given on the command line, which is
+  /// relative to the invocation directory, since just changes directory
+  /// before loading it
+  fn env_file(invocation_directory: &Result<PathBuf, String>, path: &str) -> PathBuf {
+    match invocation_directory {
+      Ok(directory) => directory.join(path),
+      Err(_) => PathBuf::from(path),
+    }
+  }
+
+  /// The number of times to run recipes with `--bench`, from `--runs`
+  fn runs(value: &str) -> ConfigResult<usize> {
+    match value.parse::<usize>() {
+      Ok(runs) if runs > 0 => Ok(runs),
+      _ => Err(ConfigError::InvalidRuns {
+        value: value.to_owned(),
+      }),
+    }
+  }
+
+  /// Take `--env-file`, and `--runs` with `--bench`, from the arguments,
+  /// where they may also be given after a recipe and its arguments, so that
+  /// the environment can be changed for a single invocation without moving
+  /// the recipe name, as in `just test --env-file ci.env`, and
+  /// `just --bench build -n 5` runs `build` five times. They're only
+  /// recognized where the name of the next recipe would be, since a recipe
+  /// argument may look like them, as in `just --bench grep -n foo`.
+  pub(crate) fn take_options(&mut self, justfile: &Justfile) -> ConfigResult<()> {
+    let bench = matches!(self.subcommand, Subcommand::Bench { .. });
+    let end = self.arguments.len() - self.pass_through;
+    let pass_through = self.arguments.split_off(end);
+    let mut arguments = mem::take(&mut self.arguments).into_iter();
+
+    while let Some(argument) = arguments.next() {
+      if argument == "--env-file" {
+        match arguments.next() {
+          Some(path) => self
+            .env_files
+            .push(Self::env_file(&self.invocation_directory, path)),
+          None => return Err(ConfigError::MissingEnvFile),
+        }
+      } else if let Some(path) = argument.strip_prefix("--env-file=") {
+        self
+          .env_files
+          .push(Self::env_file(&self.invocation_directory, path));
+      } else if bench && (argument == "--runs" || argument == "-n") {
+        match arguments.next() {
+          Some(value) => {
+            self.subcommand = Subcommand::Bench {
+              runs: Self::runs(value)?,
+            }
+          }
+          None => return Err(ConfigError::MissingRuns),
+        }
+      } else if let Some(value) = argument.strip_prefix("--runs=").filter(|_| bench) {
+        self.subcommand = Subcommand::Bench {
+          runs: Self::runs(value)?,
+        };
+      } else {
+        self.arguments.push(argument);
+        if let Some((_, recipe)) = justfile.get_recipe_path(argument) {
+          let count = if self.each {
+            usize::MAX
+          } else if recipe.parameters.is_empty() || (self.all && !recipe.matrix.is_empty()) {
+            0
+          } else {
+            recipe.max_arguments()
+          };
+          self.arguments.extend(arguments.by_ref().take(count));
+        }
+      }
+    }
+
+    self.arguments.extend(pass_through);
+
+    Ok(())
+  }
 }
 
 impl<'a> Default for Config<'a> {
   fn default() -> Config<'static> {
     Config {
       subcommand: Subcommand::Run,
+      all: false,
       dry_run: false,
+      each: false,
+      defines: empty(),
+      environment: empty(),
+      env_files: empty(),
       evaluate: false,
+      group: None,
       highlight: false,
+      jobs: 1,
+      json: false,
+      matrix: false,
+      no_execute_backticks: false,
+      output_format: OutputFormat::Human,
       overrides: empty(),
       arguments: empty(),
+      pass_through: 0,
       quiet: false,
+      quiet_warnings: false,
+      report: None,
+      report_format: ReportFormat::Tap,
       shell: DEFAULT_SHELL,
+      shell_arguments: DEFAULT_SHELL_ARGUMENTS.to_vec(),
+      shell_present: false,
+      show_script: false,
+      timestamps: false,
+      timestamp_format: DEFAULT_TIMESTAMP_FORMAT,
       color: default(),
       verbosity: Verbosity::from_flag_occurrences(0),
       justfile: None,
+      justfile_names: empty(),
+      justfile_path: None,
+      justfile_directory: None,
       working_directory: None,
+      search_directory: None,
+      run_directory: None,
+      yes: false,
+      environment_snapshot: env::vars_os().collect(),
       invocation_directory: env::current_dir()
         .map_err(|e| format!("Error getting current directory: {}", e)),
     }
@@ -1,20 +1,68 @@
 use crate::common::*;
 
 pub(crate) enum ConfigError {
+  EachWithoutRecipe,
   Internal { message: String },
+  InvalidCompletionIndex { value: String },
+  InvalidDefine { argument: String },
+  InvalidEnvironmentVariable { argument: String },
+  InvalidJobs { value: String },
+  InvalidOverride { name: String },
+  InvalidRuns { value: String },
+  InvalidTimestampFormat { format: String, message: String },
+  InvalidVerbosity { value: String },
+  MissingEnvFile,
+  MissingRuns,
+}
+
+impl ConfigError {
+  pub(crate) fn exit_code(&self) -> i32 {
+    use ConfigError::*;
+
+    match self {
+      Internal { .. } => EXIT_INTERNAL,
+      EachWithoutRecipe
+      | InvalidCompletionIndex { .. }
+      | InvalidDefine { .. }
+      | InvalidEnvironmentVariable { .. }
+      | InvalidJobs { .. }
+      | InvalidOverride { .. }
+      | InvalidRuns { .. }
+      | InvalidTimestampFormat { .. }
+      | InvalidVerbosity { .. }
+      | MissingEnvFile
+      | MissingRuns => EXIT_USAGE,
+    }
+  }
 }
 
 impl Display for ConfigError {
   fn fmt(&self, f: &mut Formatter) -> fmt::Result {
     use ConfigError::*;
 
-    match self {
-      Internal { message } => write!(
-        f,
-        "Internal config error, this may indicate a bug in just: {} \
-         consider filing an issue: https://github.com/casey/just/issues/new",
-        message
+    let message = match self {
+      EachWithoutRecipe => message!("config.each-without-recipe"),
+      Internal { message } => message!("config.internal", message = message),
+      InvalidCompletionIndex { value } => {
+        message!("config.invalid-completion-index", value = value)
+      }
+      InvalidDefine { argument } => message!("config.invalid-define", argument = argument),
+      InvalidEnvironmentVariable { argument } => {
+        message!("config.invalid-environment-variable", argument = argument)
+      }
+      InvalidJobs { value } => message!("config.invalid-jobs", value = value),
+      InvalidOverride { name } => message!("config.invalid-override", name = name),
+      InvalidRuns { value } => message!("config.invalid-runs", value = value),
+      InvalidTimestampFormat { format, message } => message!(
+        "config.invalid-timestamp-format",
+        format = format,
+        message = message
       ),
-    }
+      InvalidVerbosity { value } => message!("config.invalid-verbosity", value = value),
+      MissingEnvFile => message!("config.missing-env-file"),
+      MissingRuns => message!("config.missing-runs"),
+    };
+
+    write!(f, "{}", message)
   }
 }
@@ -0,0 +1,257 @@
+use crate::common::*;
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const WEEKDAYS: &[&str] = &[
+  "Sunday",
+  "Monday",
+  "Tuesday",
+  "Wednesday",
+  "Thursday",
+  "Friday",
+  "Saturday",
+];
+
+const MONTHS: &[&str] = &[
+  "January",
+  "February",
+  "March",
+  "April",
+  "May",
+  "June",
+  "July",
+  "August",
+  "September",
+  "October",
+  "November",
+  "December",
+];
+
+/// A point in time, broken down into a date and time of day in a time zone
+/// with a fixed offset from UTC, for the `datetime` functions
+#[derive(Debug, PartialEq)]
+pub(crate) struct DateTime {
+  /// Seconds since the Unix epoch
+  timestamp: i64,
+  /// Seconds east of UTC
+  offset: i64,
+  year: i64,
+  /// 1 to 12
+  month: i64,
+  /// 1 to 31
+  day: i64,
+  hour: i64,
+  minute: i64,
+  second: i64,
+  /// 0 to 6, starting on Sunday
+  weekday: i64,
+  /// 1 to 366
+  yearday: i64,
+}
+
+impl DateTime {
+  pub(crate) fn now_utc() -> DateTime {
+    DateTime::new(Self::now(), 0)
+  }
+
+  /// The current time in the local time zone, or, if the offset of the local
+  /// time zone can't be determined, in UTC
+  pub(crate) fn now_local() -> DateTime {
+    let timestamp = Self::now();
+    DateTime::new(timestamp, Platform::local_offset(timestamp).unwrap_or(0))
+  }
+
+  fn now() -> i64 {
+    match SystemTime::now().duration_since(UNIX_EPOCH) {
+      Ok(duration) => duration.as_secs() as i64,
+      Err(error) => -(error.duration().as_secs() as i64),
+    }
+  }
+
+  /// The time `timestamp` seconds after the Unix epoch, in the time zone
+  /// `offset` seconds east of UTC
+  pub(crate) fn new(timestamp: i64, offset: i64) -> DateTime {
+    let local = timestamp + offset;
+    let days = local.div_euclid(86400);
+    let seconds = local.rem_euclid(86400);
+
+    // Convert days since the epoch to a date in the proleptic Gregorian
+    // calendar, counting from the 1st of March so that leap days come last
+    let shifted = days + 719_468;
+    let era = shifted.div_euclid(146_097);
+    let day_of_era = shifted.rem_euclid(146_097);
+    let year_of_era =
+      (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_from_march + 2) / 5 + 1;
+    let month = if month_from_march < 10 {
+      month_from_march + 3
+    } else {
+      month_from_march - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    DateTime {
+      timestamp,
+      offset,
+      year,
+      month,
+      day,
+      hour: seconds / 3600,
+      minute: seconds % 3600 / 60,
+      second: seconds % 60,
+      weekday: (days + 4).rem_euclid(7),
+      yearday: days - Self::days_from_civil(year, 1, 1) + 1,
+    }
+  }
+
+  /// The number of days from the Unix epoch to `year`-`month`-`day`
+  pub(crate) fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+  }
+
+  /// Format with a strftime-style `format` string, supporting the most
+  /// commonly used conversion specifications
+  pub(crate) fn format(&self, format: &str) -> Result<String, String> {
+    let mut output = String::new();
+    let mut chars = format.chars();
+
+    while let Some(c) = chars.next() {
+      if c != '%' {
+        output.push(c);
+        continue;
+      }
+
+      let formatted = match chars.next() {
+        Some('a') => WEEKDAYS[self.weekday as usize][..3].to_owned(),
+        Some('A') => WEEKDAYS[self.weekday as usize].to_owned(),
+        Some('b') | Some('h') => MONTHS[self.month as usize - 1][..3].to_owned(),
+        Some('B') => MONTHS[self.month as usize - 1].to_owned(),
+        Some('d') => format!("{:02}", self.day),
+        Some('e') => format!("{:2}", self.day),
+        Some('F') => format!("{:04}-{:02}-{:02}", self.year, self.month, self.day),
+        Some('H') => format!("{:02}", self.hour),
+        Some('I') => format!("{:02}", (self.hour + 11) % 12 + 1),
+        Some('j') => format!("{:03}", self.yearday),
+        Some('m') => format!("{:02}", self.month),
+        Some('M') => format!("{:02}", self.minute),
+        Some('p') => if self.hour < 12 { "AM" } else { "PM" }.to_owned(),
+        Some('R') => format!("{:02}:{:02}", self.hour, self.minute),
+        Some('s') => self.timestamp.to_string(),
+        Some('S') => format!("{:02}", self.second),
+        Some('T') => format!("{:02}:{:02}:{:02}", self.hour, self.minute, self.second),
+        Some('u') => (if self.weekday == 0 { 7 } else { self.weekday }).to_string(),
+        Some('w') => self.weekday.to_string(),
+        Some('y') => format!("{:02}", self.year.rem_euclid(100)),
+        Some('Y') => self.year.to_string(),
+        Some('z') => format!(
+          "{}{:02}{:02}",
+          if self.offset < 0 { '-' } else { '+' },
+          self.offset.abs() / 3600,
+          self.offset.abs() % 3600 / 60
+        ),
+        Some('%') => "%".to_owned(),
+        Some(specifier) => {
+          return Err(format!(
+            "Unsupported conversion specification `%{}`",
+            specifier
+          ))
+        }
+        None => return Err("Format ends with an incomplete conversion specification `%`".into()),
+      };
+
+      output.push_str(&formatted);
+    }
+
+    Ok(output)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn epoch() {
+    assert_eq!(
+      DateTime::new(0, 0),
+      DateTime {
+        timestamp: 0,
+        offset: 0,
+        year: 1970,
+        month: 1,
+        day: 1,
+        hour: 0,
+        minute: 0,
+        second: 0,
+        weekday: 4,
+        yearday: 1,
+      }
+    );
+  }
+
+  #[test]
+  fn leap_day() {
+    let leap_day = DateTime::new(951_782_400 + 45_296, 0);
+    assert_eq!(
+      leap_day.format("%F %T %a %j").unwrap(),
+      "2000-02-29 12:34:56 Tue 060"
+    );
+  }
+
+  #[test]
+  fn before_epoch() {
+    assert_eq!(
+      DateTime::new(-1, 0).format("%Y-%m-%d %H:%M:%S").unwrap(),
+      "1969-12-31 23:59:59"
+    );
+  }
+
+  #[test]
+  fn offset() {
+    let date_time = DateTime::new(0, -(5 * 3600 + 30 * 60));
+    assert_eq!(
+      date_time.format("%F %R %z %s").unwrap(),
+      "1969-12-31 18:30 -0530 0"
+    );
+  }
+
+  #[test]
+  fn names() {
+    assert_eq!(
+      DateTime::new(1_600_000_000, 0)
+        .format("%A %B %e %I%p %u %w %y %b %h %%")
+        .unwrap(),
+      "Sunday September 13 12PM 7 0 20 Sep Sep %"
+    );
+  }
+
+  #[test]
+  fn days_from_civil() {
+    for days in -800_000..800_000 {
+      let date_time = DateTime::new(days * 86400, 0);
+      assert_eq!(
+        DateTime::days_from_civil(date_time.year, date_time.month, date_time.day),
+        days
+      );
+    }
+  }
+
+  #[test]
+  fn unsupported() {
+    assert_eq!(
+      DateTime::new(0, 0).format("%Q"),
+      Err("Unsupported conversion specification `%Q`".to_owned())
+    );
+    assert_eq!(
+      DateTime::new(0, 0).format("%Y%"),
+      Err("Format ends with an incomplete conversion specification `%`".to_owned())
+    );
+  }
+}
@@ -0,0 +1,9 @@
+/// The order in which `--dump` prints the items of a justfile
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub(crate) enum DumpOrder {
+  /// Modules, variables, aliases, and then recipes, each sorted by name
+  Alphabetical,
+  /// The order they appear in the source, with those from imported
+  /// justfiles where they are imported
+  Source,
+}
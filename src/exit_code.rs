@@ -0,0 +1,20 @@
+//! Exit codes returned when just itself, rather than a recipe, fails.
+//!
+//! When a recipe or backtick exits with a non-zero code, just exits with that
+//! same code. The values below are chosen from the range reserved by
+//! `sysexits.h`, and are stable.
+
+/// A recipe could not be run, or just failed for another reason
+pub(crate) const EXIT_FAILURE: i32 = 1;
+
+/// An argument count mismatch, or invalid or unknown overrides
+pub(crate) const EXIT_USAGE: i32 = 64;
+
+/// The justfile could not be compiled
+pub(crate) const EXIT_COMPILE_ERROR: i32 = 65;
+
+/// A recipe given on the command line does not exist
+pub(crate) const EXIT_UNKNOWN_RECIPE: i32 = 66;
+
+/// An internal error occurred, which indicates a bug in just
+pub(crate) const EXIT_INTERNAL: i32 = 70;
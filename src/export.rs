@@ -0,0 +1,34 @@
+use crate::common::*;
+
+/// How an exported variable is passed to recipes
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) struct Export {
+  /// The name of the environment variable
+  pub(crate) variable: String,
+  /// Set by `[export-path]` to join the value onto the inherited value of
+  /// the environment variable, instead of replacing it
+  pub(crate) path: Option<PathExport>,
+}
+
+impl Export {
+  /// The value the environment variable should be set to. Its inherited
+  /// value comes from `dotenv`, or else from just's `environment`, as
+  /// captured at startup.
+  pub(crate) fn value(
+    &self,
+    value: &str,
+    dotenv: &BTreeMap<String, String>,
+    environment: &BTreeMap<OsString, OsString>,
+  ) -> OsString {
+    match self.path {
+      None => value.into(),
+      Some(path) => {
+        let inherited = match dotenv.get(&self.variable) {
+          Some(inherited) => Some(OsString::from(inherited)),
+          None => environment.get(OsStr::new(&self.variable)).cloned(),
+        };
+        path.join(inherited, value)
+      }
+    }
+  }
+}
@@ -15,9 +15,24 @@ pub(crate) enum Expression<'a> {
     lhs: Box<Expression<'a>>,
     rhs: Box<Expression<'a>>,
   },
+  /// `lhs / rhs`, which joins two paths with the platform's path separator
+  Join {
+    lhs: Box<Expression<'a>>,
+    rhs: Box<Expression<'a>>,
+  },
+  Conditional {
+    lhs: Box<Expression<'a>>,
+    rhs: Box<Expression<'a>>,
+    then: Box<Expression<'a>>,
+    otherwise: Box<Expression<'a>>,
+  },
   String {
     cooked_string: StringLiteral<'a>,
   },
+  InterpolatedString {
+    raw: &'a str,
+    fragments: Vec<StringFragment<'a>>,
+  },
   Variable {
     name: &'a str,
     token: Token<'a>,
@@ -28,13 +43,55 @@ pub(crate) enum Expression<'a> {
 }
 
 impl<'a> Expression<'a> {
-  pub(crate) fn variables(&'a self) -> Variables<'a> {
+  pub(crate) fn variables<'b>(&'b self) -> Variables<'a, 'b> {
     Variables::new(self)
   }
 
-  pub(crate) fn functions(&'a self) -> Functions<'a> {
+  pub(crate) fn functions<'b>(&'b self) -> Functions<'a, 'b> {
     Functions::new(self)
   }
+
+  /// The value of the expression, if it can be determined without evaluating
+  /// variables, function calls, or backticks
+  pub(crate) fn constant(&self) -> Option<String> {
+    match *self {
+      Expression::String { ref cooked_string } => Some(cooked_string.cooked.to_string()),
+      Expression::Concatination { ref lhs, ref rhs } => Some(lhs.constant()? + &rhs.constant()?),
+      Expression::Join { ref lhs, ref rhs } => {
+        Some(Expression::join(&lhs.constant()?, &rhs.constant()?))
+      }
+      Expression::Conditional {
+        ref lhs,
+        ref rhs,
+        ref then,
+        ref otherwise,
+      } => {
+        if lhs.constant()? == rhs.constant()? {
+          then.constant()
+        } else {
+          otherwise.constant()
+        }
+      }
+      Expression::InterpolatedString { ref fragments, .. } => {
+        let mut constant = String::new();
+        for fragment in fragments {
+          match fragment {
+            StringFragment::Text { cooked } => constant += cooked,
+            StringFragment::Expression { expression } => constant += &expression.constant()?,
+          }
+        }
+        Some(constant)
+      }
+      Expression::Group { ref expression } => expression.constant(),
+      Expression::Backtick { .. } | Expression::Call { .. } | Expression::Variable { .. } => None,
+    }
+  }
+
+  /// Join the paths `lhs` and `rhs`, the value of `lhs / rhs`. Unlike
+  /// `Path::join`, an absolute `rhs` doesn't replace `lhs`.
+  pub(crate) fn join(lhs: &str, rhs: &str) -> String {
+    format!("{}{}{}", lhs, std::path::MAIN_SEPARATOR, rhs)
+  }
 }
 
 impl<'a> Display for Expression<'a> {
@@ -42,7 +99,19 @@ impl<'a> Display for Expression<'a> {
     match *self {
       Expression::Backtick { raw, .. } => write!(f, "`{}`", raw)?,
       Expression::Concatination { ref lhs, ref rhs } => write!(f, "{} + {}", lhs, rhs)?,
+      Expression::Join { ref lhs, ref rhs } => write!(f, "{} / {}", lhs, rhs)?,
+      Expression::Conditional {
+        ref lhs,
+        ref rhs,
+        ref then,
+        ref otherwise,
+      } => write!(
+        f,
+        "if {} == {} {{ {} }} else {{ {} }}",
+        lhs, rhs, then, otherwise
+      )?,
       Expression::String { ref cooked_string } => write!(f, "{}", cooked_string)?,
+      Expression::InterpolatedString { raw, .. } => write!(f, "\"{}\"", raw)?,
       Expression::Variable { name, .. } => write!(f, "{}", name)?,
       Expression::Call {
         name,
@@ -0,0 +1,120 @@
+use crate::common::*;
+
+use crate::config::{DEFAULT_SHELL, DEFAULT_SHELL_ARGUMENTS};
+
+/// A dependency on a recipe in another justfile, written
+/// `path/to/justfile::recipe`
+#[derive(PartialEq, Debug)]
+pub(crate) struct ExternalDependency<'a> {
+  /// The dependency as written, used to run it at most once
+  pub(crate) key: &'a str,
+  pub(crate) path: &'a str,
+  pub(crate) recipe: &'a str,
+  pub(crate) token: Token<'a>,
+  /// The justfile that declares the dependency, if it was read from a file
+  pub(crate) importer: Option<&'a Path>,
+}
+
+impl<'a> ExternalDependency<'a> {
+  /// Run the recipe from the other justfile, whose path is relative to the
+  /// directory of the justfile that declares the dependency, or to the
+  /// directory that `config` runs commands in if it wasn't read from a file,
+  /// in that justfile's working directory. Errors from the other justfile are
+  /// printed here, since they borrow from its text.
+  pub(crate) fn run(&self, config: &Config) -> RunResult<'a, ()> {
+    let path = match (self.importer, &config.run_directory) {
+      (Some(importer), _) => Loader::resolve(importer, self.path),
+      (None, Some(run_directory)) => run_directory.join(self.path),
+      (None, None) => PathBuf::from(self.path),
+    };
+    let path = path.as_path();
+
+    let text = fs::read_to_string(path).map_err(|io_error| RuntimeError::ExternalDependencyIo {
+      dependency: self.key,
+      io_error,
+    })?;
+
+    let loader = Loader::load(path, text, &config.defines);
+
+    let justfile = match Parser::parse_with_loader(&loader, &config.defines) {
+      Ok(justfile) => justfile,
+      Err(error) => {
+        if config.color.stderr().active() {
+          eprintln!("{:#}", error);
+        } else {
+          eprintln!("{}", error);
+        }
+        return Err(RuntimeError::ExternalDependency {
+          dependency: self.key,
+          code: error.exit_code(),
+        });
+      }
+    };
+
+    let working_directory = search::working_directory(path, justfile.settings.follow_symlinks)
+      .map_err(|io_error| RuntimeError::ExternalDependencyIo {
+        dependency: self.key,
+        io_error,
+      })?;
+
+    // Like other settings, the `shell` setting of the outer justfile doesn't
+    // apply to recipes in the inner justfile
+    let (shell, shell_arguments) = match &justfile.settings.shell {
+      _ if config.shell_present => (config.shell, config.shell_arguments.clone()),
+      Some(shell) => (
+        shell[0].as_str(),
+        shell[1..].iter().map(String::as_str).collect(),
+      ),
+      None => (DEFAULT_SHELL, DEFAULT_SHELL_ARGUMENTS.to_vec()),
+    };
+
+    let inner = Config {
+      dry_run: config.dry_run,
+      defines: config.defines.clone(),
+      environment: config.environment.clone(),
+      env_files: config.env_files.clone(),
+      highlight: config.highlight,
+      no_execute_backticks: config.no_execute_backticks,
+      output_format: config.output_format,
+      quiet: config.quiet,
+      shell,
+      shell_arguments,
+      shell_present: config.shell_present,
+      show_script: config.show_script,
+      timestamps: config.timestamps,
+      timestamp_format: config.timestamp_format,
+      color: config.color,
+      verbosity: cmp::max(
+        config.verbosity,
+        justfile.settings.verbosity.unwrap_or(config.verbosity),
+      ),
+      invocation_directory: config.invocation_directory.clone(),
+      environment_snapshot: config.environment_snapshot.clone(),
+      justfile_path: Some(path.to_owned()),
+      justfile_directory: Some(working_directory.clone()),
+      run_directory: Some(working_directory),
+      yes: config.yes,
+      ..Config::default()
+    };
+
+    justfile.run(&[self.recipe], &inner).map_err(|error| {
+      if !config.quiet {
+        if config.color.stderr().active() {
+          eprintln!("{:#}", error);
+        } else {
+          eprintln!("{}", error);
+        }
+      }
+      RuntimeError::ExternalDependency {
+        dependency: self.key,
+        code: error.exit_code(),
+      }
+    })
+  }
+}
+
+impl<'a> Display for ExternalDependency<'a> {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    write!(f, "{}::{}", self.path, self.recipe)
+  }
+}
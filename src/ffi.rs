@@ -0,0 +1,223 @@
+//! A C interface to the compiler, so that tools that aren't written in Rust,
+//! such as editor plugins, can use just's own parser instead of
+//! reimplementing the grammar.
+//!
+//! This module is only built with the `ffi` feature. To build a library
+//! that can be linked from C:
+//!
+//! ```sh
+//! cargo rustc --release --features ffi --crate-type cdylib
+//! ```
+//!
+//! Source text is passed as a NUL-terminated UTF-8 string, and results are
+//! returned as NUL-terminated JSON strings, which must be freed with
+//! `just_string_free`. Lines and columns in diagnostics start at zero.
+//! Fields may be added to results in later versions, and should be ignored
+//! if they aren't recognized.
+
+use std::{
+  ffi::{CStr, CString},
+  os::raw::c_char,
+  ptr,
+};
+
+use crate::{compilation_report, compiler::Compiler};
+
+/// A justfile that is open in an editor, and is compiled again after every
+/// edit, reusing what it can from the last compilation
+pub struct JustCompiler(Compiler);
+
+/// Compile the justfile `source`, returning `{"diagnostics": [...]}`, which
+/// is empty if the justfile has no errors or warnings. Returns null if
+/// `source` is null.
+///
+/// # Safety
+///
+/// `source` must be null or point to a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn just_compile(source: *const c_char) -> *mut c_char {
+  compile(source, false)
+}
+
+/// Compile the justfile `source`, returning
+/// `{"recipes": [...], "diagnostics": [...]}`. Recipes are described as by
+/// `just --request` with `{"kind": "list"}`, and there are none if the
+/// justfile has errors. Returns null if `source` is null.
+///
+/// # Safety
+///
+/// `source` must be null or point to a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn just_list_recipes(source: *const c_char) -> *mut c_char {
+  compile(source, true)
+}
+
+/// Compile the justfile `source`, returning a compiler to pass to
+/// `just_compiler_update` as the justfile is edited, which must be freed
+/// with `just_compiler_free`. Returns null if `source` is null or isn't valid
+/// UTF-8.
+///
+/// # Safety
+///
+/// `source` must be null or point to a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn just_compiler_new(source: *const c_char) -> *mut JustCompiler {
+  if source.is_null() {
+    return ptr::null_mut();
+  }
+
+  match CStr::from_ptr(source).to_str() {
+    Ok(text) => Box::into_raw(Box::new(JustCompiler(Compiler::new(text.to_owned())))),
+    Err(_) => ptr::null_mut(),
+  }
+}
+
+/// Replace the bytes from `start` up to `end` of the justfile with `text`,
+/// and compile it again, returning `{"recipes": [...], "diagnostics": [...]}`
+/// as `just_list_recipes` does. Only the recipe being edited is parsed again
+/// if the edit is within a single line of its body. Returns null if `compiler` or `text` is
+/// null, `text` isn't valid UTF-8, or `start` and `end` aren't character
+/// boundaries of the justfile, with `start` before `end`, in which case the
+/// justfile isn't changed.
+///
+/// # Safety
+///
+/// `compiler` must be null or have been returned by `just_compiler_new`,
+/// and not already freed. `text` must be null or point to a NUL-terminated
+/// string.
+#[no_mangle]
+pub unsafe extern "C" fn just_compiler_update(
+  compiler: *mut JustCompiler,
+  start: usize,
+  end: usize,
+  text: *const c_char,
+) -> *mut c_char {
+  if compiler.is_null() || text.is_null() {
+    return ptr::null_mut();
+  }
+
+  let compiler = &mut (*compiler).0;
+
+  let text = match CStr::from_ptr(text).to_str() {
+    Ok(text) => text,
+    Err(_) => return ptr::null_mut(),
+  };
+
+  if compiler.update(start..end, text).is_err() {
+    return ptr::null_mut();
+  }
+
+  json(compiler.report(true))
+}
+
+/// Free a compiler returned by `just_compiler_new`
+///
+/// # Safety
+///
+/// `compiler` must be null or have been returned by `just_compiler_new`,
+/// and not already freed.
+#[no_mangle]
+pub unsafe extern "C" fn just_compiler_free(compiler: *mut JustCompiler) {
+  if !compiler.is_null() {
+    drop(Box::from_raw(compiler));
+  }
+}
+
+/// Free a string returned by one of the functions above
+///
+/// # Safety
+///
+/// `string` must be null or have been returned by one of the functions
+/// above, and not already freed.
+#[no_mangle]
+pub unsafe extern "C" fn just_string_free(string: *mut c_char) {
+  if !string.is_null() {
+    drop(CString::from_raw(string));
+  }
+}
+
+/// Compile `source`, returning its diagnostics as JSON, along with its
+/// recipes if `list`
+unsafe fn compile(source: *const c_char, list: bool) -> *mut c_char {
+  if source.is_null() {
+    return ptr::null_mut();
+  }
+
+  let value = match CStr::from_ptr(source).to_str() {
+    Ok(text) => compilation_report::report(text, list),
+    Err(utf8_error) => compilation_report::error(
+      format!("error: Justfile is not valid UTF-8: {}", utf8_error),
+      list,
+    ),
+  };
+
+  json(value)
+}
+
+/// Return `value` as a string to be freed with `just_string_free`
+fn json(value: serde_json::Value) -> *mut c_char {
+  // JSON never contains NUL, since it is escaped in strings
+  CString::new(value.to_string())
+    .map(CString::into_raw)
+    .unwrap_or(ptr::null_mut())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn call(function: unsafe extern "C" fn(*const c_char) -> *mut c_char, source: &str) -> String {
+    let source = CString::new(source).unwrap();
+    unsafe {
+      let result = function(source.as_ptr());
+      let json = CStr::from_ptr(result).to_str().unwrap().to_owned();
+      just_string_free(result);
+      json
+    }
+  }
+
+  #[test]
+  fn compile_error() {
+    assert_eq!(
+      call(just_compile, "foo: bar"),
+      r#"{"diagnostics":[{"column":5,"line":0,"message":"error: Recipe `foo` has unknown dependency `bar`\n  |\n1 | foo: bar\n  |      ^^^","severity":"error","width":3}]}"#,
+    );
+  }
+
+  #[test]
+  fn list_recipes() {
+    assert_eq!(
+      call(
+        just_list_recipes,
+        "# build it\nbuild mode='debug':\n  cargo build"
+      ),
+      r#"{"diagnostics":[],"recipes":[{"dependencies":[],"doc":"build it","groups":[],"meta":{},"name":"build","parameters":[{"default":"debug","default_expression":null,"name":"mode","variadic":false}],"private":false}]}"#,
+    );
+  }
+
+  #[test]
+  fn compiler() {
+    let source = CString::new("foo:\n  echo {{x}}\n").unwrap();
+    let text = CString::new("'x'").unwrap();
+    unsafe {
+      let compiler = just_compiler_new(source.as_ptr());
+      assert!(!compiler.is_null());
+
+      assert!(just_compiler_update(compiler, 100, 100, text.as_ptr()).is_null());
+
+      let result = just_compiler_update(compiler, 14, 15, text.as_ptr());
+      assert_eq!(
+        CStr::from_ptr(result).to_str().unwrap(),
+        r#"{"diagnostics":[],"recipes":[{"dependencies":[],"doc":null,"groups":[],"meta":{},"name":"foo","parameters":[],"private":false}]}"#,
+      );
+      just_string_free(result);
+
+      just_compiler_free(compiler);
+    }
+  }
+
+  #[test]
+  fn null() {
+    assert!(unsafe { just_compile(ptr::null()) }.is_null());
+  }
+}
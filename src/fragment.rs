@@ -13,4 +13,15 @@ impl<'a> Fragment<'a> {
       _ => false,
     }
   }
+
+  /// The contents of the text fragment `text`, with each escaped `{{{{`
+  /// replaced by `{{`
+  pub(crate) fn unescape(text: &Token<'a>) -> Cow<'a, str> {
+    let lexeme = text.lexeme();
+    if lexeme.contains("{{{{") {
+      Cow::Owned(lexeme.replace("{{{{", "{{"))
+    } else {
+      Cow::Borrowed(lexeme)
+    }
+  }
 }
@@ -1,27 +1,175 @@
 use crate::common::*;
 
-use target;
+use std::env::VarError;
+
+use rand::{rngs::OsRng, Rng};
+use sha2::{Digest, Sha256};
 
 lazy_static! {
-  static ref FUNCTIONS: BTreeMap<&'static str, Function> = vec![
-    ("arch", Function::Nullary(arch)),
-    ("os", Function::Nullary(os)),
-    ("os_family", Function::Nullary(os_family)),
-    ("env_var", Function::Unary(env_var)),
-    ("env_var_or_default", Function::Binary(env_var_or_default)),
+  static ref FUNCTIONS: BTreeMap<&'static str, (Function, &'static str)> = vec![
+    (
+      "arch",
+      Function::Nullary(arch),
+      "Instruction set architecture, e.g. `x86_64`"
+    ),
+    (
+      "os",
+      Function::Nullary(os),
+      "Operating system, e.g. `linux`"
+    ),
+    (
+      "os_family",
+      Function::Nullary(os_family),
+      "Operating system family, `unix` or `windows`"
+    ),
+    (
+      "changed_files",
+      Function::Unary(changed_files),
+      "Files changed since a git ref, separated by spaces"
+    ),
+    (
+      "datetime",
+      Function::Unary(datetime),
+      "The current local time, formatted with strftime-style specifications"
+    ),
+    (
+      "datetime_utc",
+      Function::Unary(datetime_utc),
+      "The current UTC time, formatted with strftime-style specifications"
+    ),
+    (
+      "env_var",
+      Function::Unary(env_var),
+      "Value of an environment variable, failing if it is not present"
+    ),
+    (
+      "env_var_or_default",
+      Function::Binary(env_var_or_default),
+      "Value of an environment variable, or a default if it is not present"
+    ),
     (
       "invocation_directory",
-      Function::Nullary(invocation_directory)
+      Function::Nullary(invocation_directory),
+      "Directory from which just was invoked"
+    ),
+    (
+      "justfile",
+      Function::Nullary(justfile),
+      "Path of the current justfile"
+    ),
+    (
+      "justfile_directory",
+      Function::Nullary(justfile_directory),
+      "Directory of the current justfile"
+    ),
+    (
+      "just_executable",
+      Function::Nullary(just_executable),
+      "Path of the just executable"
+    ),
+    (
+      "invoke",
+      Function::Invoke,
+      "Run a recipe with the given arguments, evaluating to the empty string"
+    ),
+    (
+      "join",
+      Function::Binary(join),
+      "Two paths joined with the platform's path separator"
+    ),
+    (
+      "parent_directory",
+      Function::Unary(parent_directory),
+      "The parent directory of a path"
+    ),
+    (
+      "file_name",
+      Function::Unary(file_name),
+      "The final component of a path"
+    ),
+    (
+      "file_stem",
+      Function::Unary(file_stem),
+      "The final component of a path, without its extension"
+    ),
+    (
+      "extension",
+      Function::Unary(extension),
+      "The extension of the final component of a path"
+    ),
+    (
+      "without_extension",
+      Function::Unary(without_extension),
+      "A path without the extension of its final component"
+    ),
+    (
+      "sha256",
+      Function::Unary(sha256),
+      "SHA-256 digest of a string, in hexadecimal"
+    ),
+    (
+      "sha256_file",
+      Function::Unary(sha256_file),
+      "SHA-256 digest of a file's contents, in hexadecimal"
+    ),
+    (
+      "uuid",
+      Function::Nullary(uuid),
+      "A random version 4 UUID"
+    ),
+    (
+      "choose",
+      Function::Binary(choose),
+      "A random string of a given length, drawn from an alphabet"
+    ),
+    (
+      "error",
+      Function::Error,
+      "Fail with an error message"
+    ),
+    (
+      "lowercase",
+      Function::Unary(lowercase),
+      "A string converted to lowercase"
+    ),
+    (
+      "replace",
+      Function::Ternary(replace),
+      "A string with every occurrence of a substring replaced by another"
+    ),
+    (
+      "trim",
+      Function::Unary(trim),
+      "A string without leading and trailing whitespace"
+    ),
+    (
+      "uppercase",
+      Function::Unary(uppercase),
+      "A string converted to uppercase"
     ),
   ]
   .into_iter()
+  .map(|(name, function, description)| (name, (function, description)))
   .collect();
+
+  /// The output of `changed_files` for each ref it has been called with, so
+  /// that `git` only runs once per ref
+  static ref CHANGED_FILES: Mutex<BTreeMap<String, Result<String, String>>> =
+    Mutex::new(BTreeMap::new());
 }
 
 pub(crate) enum Function {
   Nullary(fn(&FunctionContext) -> Result<String, String>),
   Unary(fn(&FunctionContext, &str) -> Result<String, String>),
   Binary(fn(&FunctionContext, &str, &str) -> Result<String, String>),
+  Ternary(fn(&FunctionContext, &str, &str, &str) -> Result<String, String>),
+  /// Takes the name of a recipe followed by its arguments. Evaluated by
+  /// `AssignmentEvaluator`, since running a recipe needs more context than
+  /// other functions are given.
+  Invoke,
+  /// Fails with its argument as the error message, to report unsupported
+  /// configurations from within a justfile
+  Error,
 }
 
 impl Function {
@@ -31,15 +179,37 @@ impl Function {
       Nullary(_) => 0,
       Unary(_) => 1,
       Binary(_) => 2,
+      Ternary(_) => 3,
+      Invoke => 1,
+      Error => 1,
     }
   }
 
+  fn variadic(&self) -> bool {
+    use self::Function::*;
+    match *self {
+      Nullary(_) | Unary(_) | Binary(_) | Ternary(_) | Error => false,
+      Invoke => true,
+    }
+  }
+
+  /// Iterate over the name, argument count, whether it takes additional
+  /// arguments, and description of every built-in function
+  pub(crate) fn list() -> impl Iterator<Item = (&'static str, usize, bool, &'static str)> {
+    FUNCTIONS.iter().map(|(name, (function, description))| {
+      (*name, function.argc(), function.variadic(), *description)
+    })
+  }
+
   pub(crate) fn resolve<'a>(token: &Token<'a>, argc: usize) -> CompilationResult<'a, ()> {
     let name = token.lexeme();
-    if let Some(function) = FUNCTIONS.get(&name) {
+    if let Some((function, _)) = FUNCTIONS.get(&name) {
       use self::Function::*;
       match (function, argc) {
-        (&Nullary(_), 0) | (&Unary(_), 1) | (&Binary(_), 2) => Ok(()),
+        (&Nullary(_), 0) | (&Unary(_), 1) | (&Binary(_), 2) | (&Ternary(_), 3) | (&Error, 1) => {
+          Ok(())
+        }
+        (&Invoke, argc) if argc >= 1 => Ok(()),
         _ => Err(
           token.error(CompilationErrorKind::FunctionArgumentCountMismatch {
             function: name,
@@ -61,7 +231,7 @@ impl Function {
     context: &FunctionContext,
     arguments: &[String],
   ) -> RunResult<'a, String> {
-    if let Some(function) = FUNCTIONS.get(name) {
+    if let Some((function, _)) = FUNCTIONS.get(name) {
       use self::Function::*;
       let argc = arguments.len();
       match (function, argc) {
@@ -79,6 +249,18 @@ impl Function {
             message,
           })
         }
+        (&Ternary(f), 3) => {
+          f(context, &arguments[0], &arguments[1], &arguments[2]).map_err(|message| {
+            RuntimeError::FunctionCall {
+              token: token.clone(),
+              message,
+            }
+          })
+        }
+        (&Error, 1) => Err(RuntimeError::ErrorFunction {
+          token: token.clone(),
+          message: arguments[0].clone(),
+        }),
         _ => Err(RuntimeError::Internal {
           message: format!(
             "attempted to evaluate function `{}` with {} arguments",
@@ -95,15 +277,15 @@ impl Function {
 }
 
 pub(crate) fn arch(_context: &FunctionContext) -> Result<String, String> {
-  Ok(target::arch().to_string())
+  Ok(env::consts::ARCH.to_string())
 }
 
 pub(crate) fn os(_context: &FunctionContext) -> Result<String, String> {
-  Ok(target::os().to_string())
+  Ok(env::consts::OS.to_string())
 }
 
 pub(crate) fn os_family(_context: &FunctionContext) -> Result<String, String> {
-  Ok(target::os_family().to_string())
+  Ok(env::consts::FAMILY.to_string())
 }
 
 pub(crate) fn invocation_directory(context: &FunctionContext) -> Result<String, String> {
@@ -112,6 +294,42 @@ pub(crate) fn invocation_directory(context: &FunctionContext) -> Result<String,
   })
 }
 
+pub(crate) fn justfile(context: &FunctionContext) -> Result<String, String> {
+  let justfile = context
+    .justfile
+    .ok_or_else(|| String::from("Justfile path is not known"))?;
+  path_to_string(justfile)
+}
+
+pub(crate) fn justfile_directory(context: &FunctionContext) -> Result<String, String> {
+  let directory = context
+    .justfile_directory
+    .ok_or_else(|| String::from("Justfile directory is not known"))?;
+  path_to_string(directory)
+}
+
+pub(crate) fn just_executable(_context: &FunctionContext) -> Result<String, String> {
+  let executable = env::current_exe()
+    .map_err(|io_error| format!("Error getting just executable: {}", io_error))?;
+  path_to_string(&executable)
+}
+
+fn path_to_string(path: &Path) -> Result<String, String> {
+  path
+    .to_str()
+    .map(str::to_owned)
+    .ok_or_else(|| format!("Path `{}` is not valid unicode", path.display()))
+}
+
+/// Look up `key` in the environment snapshot taken at startup, so the
+/// value doesn't depend on when it's evaluated
+fn environment_variable(context: &FunctionContext, key: &str) -> Result<String, VarError> {
+  match context.environment.get(OsStr::new(key)) {
+    None => Err(VarError::NotPresent),
+    Some(value) => value.clone().into_string().map_err(VarError::NotUnicode),
+  }
+}
+
 pub(crate) fn env_var(context: &FunctionContext, key: &str) -> Result<String, String> {
   use std::env::VarError::*;
 
@@ -119,7 +337,7 @@ pub(crate) fn env_var(context: &FunctionContext, key: &str) -> Result<String, St
     return Ok(value.clone());
   }
 
-  match env::var(key) {
+  match environment_variable(context, key) {
     Err(NotPresent) => Err(format!("environment variable `{}` not present", key)),
     Err(NotUnicode(os_string)) => Err(format!(
       "environment variable `{}` not unicode: {:?}",
@@ -139,7 +357,7 @@ pub(crate) fn env_var_or_default(
   }
 
   use std::env::VarError::*;
-  match env::var(key) {
+  match environment_variable(context, key) {
     Err(NotPresent) => Ok(default.to_string()),
     Err(NotUnicode(os_string)) => Err(format!(
       "environment variable `{}` not unicode: {:?}",
@@ -148,3 +366,205 @@ pub(crate) fn env_var_or_default(
     Ok(value) => Ok(value),
   }
 }
+
+/// The files in the working tree that differ from `base`, relative to the
+/// current directory and not including deleted files, separated by spaces
+pub(crate) fn changed_files(context: &FunctionContext, base: &str) -> Result<String, String> {
+  if base.starts_with('-') {
+    return Err(format!(
+      "Invalid git ref `{}`, refs may not begin with `-`",
+      base
+    ));
+  }
+
+  if context.dry_run {
+    return Ok(format!("changed_files('{}')", base));
+  }
+
+  let mut cache = CHANGED_FILES.lock().unwrap();
+
+  if let Some(files) = cache.get(base) {
+    return files.clone();
+  }
+
+  let mut command = Command::new("git");
+  if let Some(run_directory) = context.run_directory {
+    command.current_dir(run_directory);
+  }
+  command.args([
+    "diff",
+    "--name-only",
+    "--relative",
+    "--diff-filter=d",
+    base,
+    "--",
+  ]);
+  command.stderr(process::Stdio::inherit());
+
+  let files = output(command)
+    .map(|files| files.lines().collect::<Vec<&str>>().join(" "))
+    .map_err(|output_error| {
+      format!(
+        "Could not list files changed since `{}`: {}",
+        base, output_error
+      )
+    });
+
+  cache.insert(base.to_owned(), files.clone());
+
+  files
+}
+
+pub(crate) fn lowercase(_context: &FunctionContext, s: &str) -> Result<String, String> {
+  Ok(s.to_lowercase())
+}
+
+pub(crate) fn uppercase(_context: &FunctionContext, s: &str) -> Result<String, String> {
+  Ok(s.to_uppercase())
+}
+
+pub(crate) fn trim(_context: &FunctionContext, s: &str) -> Result<String, String> {
+  Ok(s.trim().to_owned())
+}
+
+pub(crate) fn replace(
+  _context: &FunctionContext,
+  s: &str,
+  from: &str,
+  to: &str,
+) -> Result<String, String> {
+  Ok(s.replace(from, to))
+}
+
+pub(crate) fn join(_context: &FunctionContext, base: &str, with: &str) -> Result<String, String> {
+  Ok(Path::new(base).join(with).to_string_lossy().into_owned())
+}
+
+pub(crate) fn parent_directory(_context: &FunctionContext, path: &str) -> Result<String, String> {
+  Path::new(path)
+    .parent()
+    .map(|parent| parent.to_string_lossy().into_owned())
+    .ok_or_else(|| format!("Could not extract parent directory from `{}`", path))
+}
+
+pub(crate) fn file_name(_context: &FunctionContext, path: &str) -> Result<String, String> {
+  Path::new(path)
+    .file_name()
+    .map(|name| name.to_string_lossy().into_owned())
+    .ok_or_else(|| format!("Could not extract file name from `{}`", path))
+}
+
+pub(crate) fn file_stem(_context: &FunctionContext, path: &str) -> Result<String, String> {
+  Path::new(path)
+    .file_stem()
+    .map(|stem| stem.to_string_lossy().into_owned())
+    .ok_or_else(|| format!("Could not extract file stem from `{}`", path))
+}
+
+pub(crate) fn extension(_context: &FunctionContext, path: &str) -> Result<String, String> {
+  Path::new(path)
+    .extension()
+    .map(|extension| extension.to_string_lossy().into_owned())
+    .ok_or_else(|| format!("Could not extract extension from `{}`", path))
+}
+
+pub(crate) fn without_extension(_context: &FunctionContext, path: &str) -> Result<String, String> {
+  let path = Path::new(path);
+
+  let stem = path
+    .file_stem()
+    .ok_or_else(|| format!("Could not extract file stem from `{}`", path.display()))?;
+
+  let parent = path
+    .parent()
+    .ok_or_else(|| format!("Could not extract parent from `{}`", path.display()))?;
+
+  Ok(parent.join(stem).to_string_lossy().into_owned())
+}
+
+/// The current local time, formatted with `format`
+pub(crate) fn datetime(_context: &FunctionContext, format: &str) -> Result<String, String> {
+  DateTime::now_local().format(format)
+}
+
+/// The current UTC time, formatted with `format`
+pub(crate) fn datetime_utc(_context: &FunctionContext, format: &str) -> Result<String, String> {
+  DateTime::now_utc().format(format)
+}
+
+/// A version 4 UUID, with its random bits from the operating system's
+/// cryptographically secure random number generator
+pub(crate) fn uuid(_context: &FunctionContext) -> Result<String, String> {
+  let mut bytes = OsRng.gen::<[u8; 16]>();
+  bytes[6] = (bytes[6] & 0x0f) | 0x40;
+  bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+  let hex = bytes
+    .iter()
+    .map(|byte| format!("{:02x}", byte))
+    .collect::<String>();
+
+  Ok(format!(
+    "{}-{}-{}-{}-{}",
+    &hex[0..8],
+    &hex[8..12],
+    &hex[12..16],
+    &hex[16..20],
+    &hex[20..32]
+  ))
+}
+
+/// A string of `n` characters, each chosen independently from `alphabet`
+/// with the operating system's cryptographically secure random number
+/// generator. Repeated characters are rejected, since they would make some
+/// characters more likely than others.
+pub(crate) fn choose(
+  _context: &FunctionContext,
+  n: &str,
+  alphabet: &str,
+) -> Result<String, String> {
+  let n = n
+    .parse::<usize>()
+    .map_err(|error| format!("Invalid length `{}`: {}", n, error))?;
+
+  let mut seen = BTreeSet::new();
+  for c in alphabet.chars() {
+    if !seen.insert(c) {
+      return Err(format!("Alphabet contains repeated character `{}`", c));
+    }
+  }
+
+  let alphabet = alphabet.chars().collect::<Vec<char>>();
+
+  if alphabet.is_empty() {
+    return Err("Alphabet is empty".to_owned());
+  }
+
+  Ok(
+    (0..n)
+      .map(|_| alphabet[OsRng.gen_range(0, alphabet.len())])
+      .collect(),
+  )
+}
+
+pub(crate) fn sha256(_context: &FunctionContext, text: &str) -> Result<String, String> {
+  Ok(format!("{:x}", Sha256::digest(text)))
+}
+
+/// The digest of the file at `path`, which, if relative, is relative to the
+/// justfile's directory
+pub(crate) fn sha256_file(context: &FunctionContext, path: &str) -> Result<String, String> {
+  let resolved = match context.justfile_directory {
+    Some(directory) => directory.join(path),
+    None => PathBuf::from(path),
+  };
+
+  let mut file = fs::File::open(&resolved)
+    .map_err(|io_error| format!("Failed to open `{}`: {}", path, io_error))?;
+
+  let mut hasher = Sha256::new();
+  io::copy(&mut file, &mut hasher)
+    .map_err(|io_error| format!("Failed to read `{}`: {}", path, io_error))?;
+
+  Ok(format!("{:x}", hasher.finalize()))
+}
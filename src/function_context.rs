@@ -3,4 +3,14 @@ use crate::common::*;
 pub(crate) struct FunctionContext<'a> {
   pub(crate) invocation_directory: &'a Result<PathBuf, String>,
   pub(crate) dotenv: &'a BTreeMap<String, String>,
+  pub(crate) environment: &'a BTreeMap<OsString, OsString>,
+  /// The path of the justfile, once it's been found
+  pub(crate) justfile: Option<&'a Path>,
+  /// The directory that relative paths given to functions are relative to
+  pub(crate) justfile_directory: Option<&'a Path>,
+  /// The directory that commands run by functions are run in
+  pub(crate) run_directory: Option<&'a Path>,
+  /// Whether commands shouldn't be run, as with `--dry-run` or
+  /// `--no-execute-backticks`
+  pub(crate) dry_run: bool,
 }
@@ -1,17 +1,17 @@
 use crate::common::*;
 
-pub(crate) struct Functions<'a> {
-  stack: Vec<&'a Expression<'a>>,
+pub(crate) struct Functions<'a: 'b, 'b> {
+  stack: Vec<&'b Expression<'a>>,
 }
 
-impl<'a> Functions<'a> {
-  pub(crate) fn new(root: &'a Expression<'a>) -> Functions<'a> {
+impl<'a, 'b> Functions<'a, 'b> {
+  pub(crate) fn new(root: &'b Expression<'a>) -> Functions<'a, 'b> {
     Functions { stack: vec![root] }
   }
 }
 
-impl<'a> Iterator for Functions<'a> {
-  type Item = (&'a Token<'a>, usize);
+impl<'a, 'b> Iterator for Functions<'a, 'b> {
+  type Item = (&'b Token<'a>, usize);
 
   fn next(&mut self) -> Option<Self::Item> {
     match self.stack.pop() {
@@ -22,11 +22,31 @@ impl<'a> Iterator for Functions<'a> {
       Some(Expression::Call {
         token, arguments, ..
       }) => Some((token, arguments.len())),
-      Some(Expression::Concatination { lhs, rhs }) => {
+      Some(Expression::Concatination { lhs, rhs }) | Some(Expression::Join { lhs, rhs }) => {
         self.stack.push(lhs);
         self.stack.push(rhs);
         self.next()
       }
+      Some(Expression::Conditional {
+        lhs,
+        rhs,
+        then,
+        otherwise,
+      }) => {
+        self.stack.push(lhs);
+        self.stack.push(rhs);
+        self.stack.push(then);
+        self.stack.push(otherwise);
+        self.next()
+      }
+      Some(Expression::InterpolatedString { fragments, .. }) => {
+        for fragment in fragments {
+          if let StringFragment::Expression { expression } = fragment {
+            self.stack.push(expression);
+          }
+        }
+        self.next()
+      }
       Some(Expression::Group { expression }) => {
         self.stack.push(expression);
         self.next()
@@ -0,0 +1,84 @@
+//! GitHub Actions workflow commands, printed with `--output-format github`.
+//! The runner reads them from both standard output and standard error, so
+//! they're printed to standard error along with echoed commands.
+
+use crate::common::*;
+
+/// Start a collapsible group of log lines titled `title`
+pub(crate) fn group(title: &str) -> String {
+  format!("::group::{}", escape_data(title))
+}
+
+/// End the group started by the last `group`
+pub(crate) const END_GROUP: &str = "::endgroup::";
+
+/// An annotation for `error`, shown on `line` and `column`, counted from
+/// one, of `file`. Only the first line of the error is used, without the
+/// `error:` prefix, since the annotation already shows where it occurred.
+pub(crate) fn error(
+  file: &Path,
+  line: Option<usize>,
+  column: Option<usize>,
+  error: impl Display,
+) -> String {
+  let error = error.to_string();
+  let message = error.lines().next().unwrap_or_default();
+  let label = format!("{} ", message!("label.error"));
+  let message = message.strip_prefix(label.as_str()).unwrap_or(message);
+
+  let mut properties = format!("file={}", escape_property(&file.to_string_lossy()));
+
+  if let Some(line) = line {
+    properties.push_str(&format!(",line={}", line));
+  }
+
+  if let Some(column) = column {
+    properties.push_str(&format!(",col={}", column));
+  }
+
+  format!("::error {}::{}", properties, escape_data(message))
+}
+
+/// The path of `justfile` relative to the invocation directory, which is
+/// usually the root of the repository in a workflow, so that annotations
+/// are shown on the right file
+pub(crate) fn annotation_path(config: &Config, justfile: &Path) -> PathBuf {
+  config
+    .invocation_directory
+    .as_ref()
+    .ok()
+    .and_then(|directory| justfile.strip_prefix(directory).ok())
+    .unwrap_or(justfile)
+    .to_owned()
+}
+
+fn escape_data(data: &str) -> String {
+  data
+    .replace('%', "%25")
+    .replace('\r', "%0D")
+    .replace('\n', "%0A")
+}
+
+fn escape_property(property: &str) -> String {
+  escape_data(property)
+    .replace(':', "%3A")
+    .replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn escaping() {
+    assert_eq!(group("100%\nfoo"), "::group::100%25%0Afoo");
+    assert_eq!(
+      error(Path::new("a:b,c"), Some(3), None, "error: 100% bad\nthing"),
+      "::error file=a%3Ab%2Cc,line=3::100%25 bad"
+    );
+    assert_eq!(
+      error(Path::new("justfile"), Some(1), Some(5), "x"),
+      "::error file=justfile,line=1,col=5::x"
+    );
+  }
+}
@@ -0,0 +1,852 @@
+use crate::common::*;
+
+use crate::parser::OS_ATTRIBUTES;
+
+use TokenKind::*;
+
+/// How the lexer recognizes a token that doesn't always have the same text
+enum Pattern {
+  /// The token matches a regular expression
+  Regex(&'static str),
+  /// The token is emitted by the context-sensitive part of the lexer
+  Context(&'static str),
+}
+
+use Pattern::*;
+
+/// The tokens that don't always have the same text, with the names used for
+/// them in the grammar
+const TERMINALS: &[(TokenKind, &str, Pattern)] = &[
+  (Backtick, "BACKTICK", Regex(r"`[^`\n\r]*`")),
+  (Comment, "COMMENT", Regex(r"#[^\n\r]*")),
+  (
+    Dedent,
+    "DEDENT",
+    Context("emitted when indentation decreases, except within a heredoc"),
+  ),
+  (Eof, "EOF", Context("emitted at the end of the file")),
+  (
+    Indent,
+    "INDENT",
+    Context("emitted when indentation increases"),
+  ),
+  (Line, "LINE", Context("emitted before a recipe line")),
+  (Name, "NAME", Regex(r"[a-zA-Z_][a-zA-Z0-9_-]*")),
+  (Eol, "NEWLINE", Regex(r"\n|\r\n")),
+  (Number, "NUMBER", Regex(r"[0-9]+")),
+  (
+    Path,
+    "PATH",
+    Context("`.` or `/` and the text up to whitespace or `:`, only matches in dependencies"),
+  ),
+  (StringRaw, "RAW_STRING", Regex(r"'[^']*'")),
+  (
+    StringCooked,
+    "STRING",
+    Regex(r#""([^"\\\n\r]|\\[^\n\r])*""#),
+  ),
+  (
+    Text,
+    "TEXT",
+    Context("recipe text, only matches in a recipe body"),
+  ),
+];
+
+/// The right hand side of a grammar rule
+enum Symbol {
+  Token(TokenKind),
+  Keyword(&'static str),
+  Rule(&'static str),
+  Sequence(Vec<Symbol>),
+  Choice(Vec<Symbol>),
+  Optional(Box<Symbol>),
+  Repeat(Box<Symbol>),
+  Repeat1(Box<Symbol>),
+}
+
+use Symbol::*;
+
+fn opt(symbol: Symbol) -> Symbol {
+  Optional(Box::new(symbol))
+}
+
+fn rep(symbol: Symbol) -> Symbol {
+  Repeat(Box::new(symbol))
+}
+
+fn rep1(symbol: Symbol) -> Symbol {
+  Repeat1(Box::new(symbol))
+}
+
+fn keywords(keywords: &[&'static str]) -> Symbol {
+  Choice(keywords.iter().cloned().map(Keyword).collect())
+}
+
+/// A bracketed attribute containing `contents`, on a line of its own
+fn attribute(contents: Vec<Symbol>) -> Symbol {
+  let mut sequence = vec![Token(BracketL)];
+  sequence.extend(contents);
+  sequence.extend(vec![Token(BracketR), Rule("eol")]);
+  Sequence(sequence)
+}
+
+/// The rules of the grammar, each with its alternatives. The names of
+/// attributes are taken from the tables the parser uses to recognize them.
+fn rules() -> Vec<(&'static str, Vec<Symbol>)> {
+  let invocation_contexts = [InvocationContext::Ci, InvocationContext::Local]
+    .iter()
+    .map(|context| context.attribute())
+    .collect::<Vec<&str>>();
+
+  let constant_list = || {
+    vec![
+      Rule("constant"),
+      rep(Sequence(vec![Token(Comma), Rule("constant")])),
+      opt(Token(Comma)),
+    ]
+  };
+
+  vec![
+    (
+      "justfile",
+      vec![Sequence(vec![rep(Rule("item")), Token(Eof)])],
+    ),
+    (
+      "item",
+      vec![
+        Sequence(vec![
+          opt(Rule("cfg_attribute")),
+          rep(Rule("attribute")),
+          Rule("recipe"),
+        ]),
+        Rule("alias"),
+        Sequence(vec![
+          opt(Rule("cfg_attribute")),
+          opt(Rule("export_attribute")),
+          Rule("assignment"),
+        ]),
+        Sequence(vec![opt(Rule("cfg_attribute")), Rule("export")]),
+        Rule("setting"),
+        Rule("import"),
+        Rule("module"),
+        Rule("eol"),
+      ],
+    ),
+    (
+      "eol",
+      vec![Token(Eol), Sequence(vec![Token(Comment), Token(Eol)])],
+    ),
+    (
+      "alias",
+      vec![Sequence(vec![
+        Keyword("alias"),
+        Token(Name),
+        Token(ColonEquals),
+        Token(Name),
+        Rule("eol"),
+      ])],
+    ),
+    (
+      "assignment",
+      vec![Sequence(vec![
+        Token(Name),
+        Token(ColonEquals),
+        Rule("expression"),
+        Rule("eol"),
+      ])],
+    ),
+    (
+      "export",
+      vec![Sequence(vec![Keyword("export"), Rule("assignment")])],
+    ),
+    (
+      "import",
+      vec![Sequence(vec![
+        Keyword("import"),
+        Rule("constant"),
+        Rule("eol"),
+      ])],
+    ),
+    (
+      "module",
+      vec![Sequence(vec![Keyword("mod"), Token(Name), Rule("eol")])],
+    ),
+    (
+      "setting",
+      vec![
+        Sequence(vec![
+          Keyword("set"),
+          Token(Name),
+          opt(Sequence(vec![Token(ColonEquals), Token(Name)])),
+          Rule("eol"),
+        ]),
+        Sequence(vec![
+          Keyword("set"),
+          keywords(&[
+            "dotenv-filename",
+            "dotenv-path",
+            "export-prefix",
+            "verbosity",
+          ]),
+          Token(ColonEquals),
+          Rule("constant"),
+          Rule("eol"),
+        ]),
+        Sequence(
+          vec![
+            Keyword("set"),
+            keywords(&["shell", "windows-shell"]),
+            Token(ColonEquals),
+            Token(BracketL),
+          ]
+          .into_iter()
+          .chain(constant_list())
+          .chain(vec![Token(BracketR), Rule("eol")])
+          .collect(),
+        ),
+      ],
+    ),
+    (
+      "export_attribute",
+      vec![
+        attribute(vec![
+          Keyword("export"),
+          opt(Sequence(vec![Token(Colon), Rule("constant")])),
+        ]),
+        attribute(vec![
+          Keyword("export-path"),
+          Token(Colon),
+          keywords(&["append", "prepend"]),
+          Rule("constant"),
+        ]),
+      ],
+    ),
+    (
+      "attribute",
+      vec![
+        attribute(vec![
+          Keyword("matrix"),
+          Token(Colon),
+          Rule("axis"),
+          rep(Sequence(vec![Token(Comma), Rule("axis")])),
+        ]),
+        attribute(vec![keywords(&invocation_contexts)]),
+        attribute(vec![Keyword("confirm"), opt(Rule("argument"))]),
+        attribute(vec![keywords(&[
+          "interactive",
+          "no-interpolation",
+          "no-network",
+          "private",
+        ])]),
+        attribute(vec![keywords(OS_ATTRIBUTES)]),
+        attribute(vec![Rule("process_option"), Token(Colon), Rule("constant")]),
+        attribute(vec![Keyword("group"), Rule("argument")]),
+        attribute(vec![Keyword("skip-if"), Token(Colon), Rule("constant")]),
+        attribute(vec![
+          Keyword("weight"),
+          Token(Colon),
+          Choice(vec![Rule("constant"), Token(Number)]),
+        ]),
+        attribute(vec![
+          Keyword("meta"),
+          Token(Colon),
+          Rule("metadata"),
+          rep(Sequence(vec![Token(Comma), Rule("metadata")])),
+        ]),
+      ],
+    ),
+    (
+      "argument",
+      vec![
+        Sequence(vec![Token(Colon), Rule("constant")]),
+        Sequence(vec![Token(ParenL), Rule("constant"), Token(ParenR)]),
+      ],
+    ),
+    (
+      "metadata",
+      vec![Sequence(vec![Token(Name), Token(Equals), Rule("constant")])],
+    ),
+    ("process_option", vec![keywords(ProcessOptions::ATTRIBUTES)]),
+    (
+      "cfg_attribute",
+      vec![attribute(vec![
+        Keyword("cfg"),
+        Token(Colon),
+        Rule("predicate"),
+      ])],
+    ),
+    (
+      "predicate",
+      vec![
+        Sequence(vec![
+          keywords(&["all", "any"]),
+          Token(ParenL),
+          Rule("predicate"),
+          rep(Sequence(vec![Token(Comma), Rule("predicate")])),
+          opt(Token(Comma)),
+          Token(ParenR),
+        ]),
+        Sequence(vec![
+          Keyword("not"),
+          Token(ParenL),
+          Rule("predicate"),
+          Token(ParenR),
+        ]),
+        Sequence(vec![
+          keywords(&["define", "env", "family", "os"]),
+          Token(Equals),
+          Rule("constant"),
+        ]),
+      ],
+    ),
+    (
+      "constant",
+      vec![Token(StringCooked), Token(StringRaw), Token(Name)],
+    ),
+    (
+      "axis",
+      vec![Sequence(vec![
+        Token(Name),
+        Token(Equals),
+        Token(ParenL),
+        Choice(vec![Token(StringCooked), Token(StringRaw)]),
+        rep(Sequence(vec![
+          Token(Bar),
+          Choice(vec![Token(StringCooked), Token(StringRaw)]),
+        ])),
+        Token(ParenR),
+      ])],
+    ),
+    (
+      "expression",
+      vec![
+        Sequence(vec![
+          Keyword("if"),
+          Rule("condition"),
+          Token(BraceL),
+          Rule("expression"),
+          Token(BraceR),
+          Keyword("else"),
+          Token(BraceL),
+          Rule("expression"),
+          Token(BraceR),
+        ]),
+        Sequence(vec![Rule("value"), Token(Plus), Rule("expression")]),
+        Sequence(vec![Rule("value"), Token(Slash), Rule("expression")]),
+        Rule("value"),
+      ],
+    ),
+    (
+      "condition",
+      vec![Sequence(vec![
+        Rule("expression"),
+        Token(EqualsEquals),
+        Rule("expression"),
+      ])],
+    ),
+    (
+      "value",
+      vec![
+        Sequence(vec![
+          Token(Name),
+          Token(ParenL),
+          opt(Rule("sequence")),
+          Token(ParenR),
+        ]),
+        Token(StringCooked),
+        Token(StringRaw),
+        Token(Backtick),
+        Token(Name),
+        Sequence(vec![Token(ParenL), Rule("expression"), Token(ParenR)]),
+      ],
+    ),
+    (
+      "sequence",
+      vec![
+        Sequence(vec![Rule("expression"), Token(Comma), Rule("sequence")]),
+        Sequence(vec![Rule("expression"), opt(Token(Comma))]),
+      ],
+    ),
+    (
+      "recipe",
+      vec![Sequence(vec![
+        opt(Token(At)),
+        Token(Name),
+        rep(Rule("parameter")),
+        opt(Sequence(vec![
+          Choice(vec![Token(Plus), Token(Asterisk)]),
+          Rule("parameter"),
+        ])),
+        Token(Colon),
+        opt(Rule("dependencies")),
+        opt(Rule("subsequents")),
+        Rule("eol"),
+        opt(Rule("body")),
+      ])],
+    ),
+    (
+      "parameter",
+      vec![
+        Sequence(vec![opt(Token(Dollar)), Token(Name)]),
+        Sequence(vec![
+          opt(Token(Dollar)),
+          Token(Name),
+          Token(Equals),
+          Rule("value"),
+        ]),
+      ],
+    ),
+    (
+      "dependencies",
+      vec![rep1(Choice(vec![Rule("dependency"), Rule("group")]))],
+    ),
+    (
+      "subsequents",
+      vec![Sequence(vec![
+        Token(AmpersandAmpersand),
+        rep1(Choice(vec![Token(Name), Rule("group")])),
+      ])],
+    ),
+    (
+      "group",
+      vec![Sequence(vec![
+        Token(BracketL),
+        Token(Name),
+        rep(Sequence(vec![Token(Comma), Token(Name)])),
+        opt(Token(Comma)),
+        Token(BracketR),
+      ])],
+    ),
+    (
+      "dependency",
+      vec![
+        Token(Name),
+        Sequence(vec![Token(Path), Token(Colon), Token(Colon), Token(Name)]),
+      ],
+    ),
+    (
+      "body",
+      vec![Sequence(vec![
+        Token(Indent),
+        rep1(Rule("line")),
+        Token(Dedent),
+      ])],
+    ),
+    (
+      "line",
+      vec![
+        Sequence(vec![
+          Token(Line),
+          rep(Choice(vec![Token(Text), Rule("interpolation")])),
+          opt(Token(Eol)),
+        ]),
+        Token(Eol),
+      ],
+    ),
+    (
+      "interpolation",
+      vec![Sequence(vec![
+        Token(InterpolationStart),
+        Rule("expression"),
+        Token(InterpolationEnd),
+      ])],
+    ),
+  ]
+}
+
+fn terminal(kind: TokenKind) -> &'static (TokenKind, &'static str, Pattern) {
+  TERMINALS
+    .iter()
+    .find(|(terminal, _, _)| *terminal == kind)
+    .unwrap_or_else(|| panic!("No terminal for token kind {:?}", kind))
+}
+
+/// The tokens, followed by the rules, as they appear in `GRAMMAR.md`
+pub(crate) fn ebnf() -> String {
+  format!("{}\n{}", ebnf_tokens(), ebnf_rules())
+}
+
+fn ebnf_tokens() -> String {
+  let width = TERMINALS
+    .iter()
+    .map(|(_, name, _)| name.len())
+    .max()
+    .unwrap_or(0);
+
+  let mut tokens = String::new();
+  for (_, name, pattern) in TERMINALS {
+    let pattern = match pattern {
+      Regex(regex) => regex,
+      Context(description) => description,
+    };
+    tokens.push_str(&format!("{:width$} = {}\n", name, pattern, width = width));
+  }
+  tokens
+}
+
+fn ebnf_rules() -> String {
+  let rules = rules()
+    .iter()
+    .map(|(name, alternatives)| {
+      let width = cmp::max(name.len(), 13);
+      let alternatives = alternatives
+        .iter()
+        .map(|alternative| ebnf_symbol(alternative, 0))
+        .collect::<Vec<String>>();
+      format!(
+        "{:width$} : {}\n",
+        name,
+        alternatives.join(&format!("\n{:width$} | ", "", width = width)),
+        width = width,
+      )
+    })
+    .collect::<Vec<String>>();
+  rules.join("\n")
+}
+
+/// Render `symbol` in EBNF, parenthesizing it if it binds less tightly than
+/// `precedence`: 0 for alternatives, 1 for sequences, and 2 for repetitions
+fn ebnf_symbol(symbol: &Symbol, precedence: usize) -> String {
+  let group = |text: String, own| {
+    if own < precedence {
+      format!("({})", text)
+    } else {
+      text
+    }
+  };
+
+  let items = |symbols: &[Symbol], precedence| {
+    symbols
+      .iter()
+      .map(|symbol| ebnf_symbol(symbol, precedence))
+      .collect::<Vec<String>>()
+  };
+
+  match symbol {
+    Token(kind) => match kind.fixed_lexeme() {
+      Some(lexeme) => format!("'{}'", lexeme),
+      None => terminal(*kind).1.to_owned(),
+    },
+    Keyword(keyword) => format!("'{}'", keyword),
+    Rule(name) => (*name).to_owned(),
+    Sequence(symbols) => group(items(symbols, 1).join(" "), 1),
+    Choice(symbols) => group(items(symbols, 0).join(" | "), 0),
+    Optional(symbol) => format!("{}?", ebnf_symbol(symbol, 2)),
+    Repeat(symbol) => format!("{}*", ebnf_symbol(symbol, 2)),
+    Repeat1(symbol) => format!("{}+", ebnf_symbol(symbol, 2)),
+  }
+}
+
+/// A `grammar.js` for tree-sitter. The context-sensitive tokens are declared
+/// as externals, which must be provided by a hand-written scanner.
+pub(crate) fn tree_sitter() -> String {
+  let mut grammar = String::new();
+
+  grammar.push_str("module.exports = grammar({\n");
+  grammar.push_str("  name: 'just',\n\n");
+
+  grammar.push_str("  externals: $ => [\n");
+  for (kind, name, pattern) in TERMINALS {
+    if let (Context(_), true) = (pattern, *kind != Eof) {
+      grammar.push_str(&format!("    $.{},\n", name.to_lowercase()));
+    }
+  }
+  grammar.push_str("  ],\n\n");
+
+  grammar.push_str("  extras: $ => [/[ \\t]/, /\\\\\\r?\\n/],\n\n");
+  grammar.push_str("  word: $ => $.name,\n\n");
+
+  grammar.push_str("  rules: {\n");
+
+  for (name, alternatives) in rules() {
+    let alternatives = alternatives
+      .iter()
+      .map(tree_sitter_symbol)
+      .collect::<Vec<String>>();
+
+    if alternatives.len() == 1 {
+      grammar.push_str(&format!("    {}: $ => {},\n\n", name, alternatives[0]));
+    } else {
+      grammar.push_str(&format!("    {}: $ => choice(\n", name));
+      for alternative in alternatives {
+        grammar.push_str(&format!("      {},\n", alternative));
+      }
+      grammar.push_str("    ),\n\n");
+    }
+  }
+
+  for (_, name, pattern) in TERMINALS {
+    if let Regex(regex) = pattern {
+      grammar.push_str(&format!(
+        "    {}: $ => /{}/,\n\n",
+        name.to_lowercase(),
+        regex.replace('/', "\\/"),
+      ));
+    }
+  }
+
+  grammar.truncate(grammar.len() - 1);
+  grammar.push_str("  },\n");
+  grammar.push_str("});\n");
+
+  grammar
+}
+
+/// Render `symbol` as a tree-sitter rule. The end of the file is implicit in
+/// tree-sitter, so `EOF` is left out.
+fn tree_sitter_symbol(symbol: &Symbol) -> String {
+  let call = |function: &str, symbols: &[Symbol]| {
+    let arguments = symbols
+      .iter()
+      .filter(|symbol| !matches!(symbol, Token(Eof)))
+      .map(tree_sitter_symbol)
+      .collect::<Vec<String>>();
+    if arguments.len() == 1 {
+      arguments.into_iter().next().unwrap()
+    } else {
+      format!("{}({})", function, arguments.join(", "))
+    }
+  };
+
+  match symbol {
+    Token(kind) => match kind.fixed_lexeme() {
+      Some(lexeme) => format!("'{}'", lexeme),
+      None => format!("$.{}", terminal(*kind).1.to_lowercase()),
+    },
+    Keyword(keyword) => format!("'{}'", keyword),
+    Rule(name) => format!("$.{}", name),
+    Sequence(symbols) => call("seq", symbols),
+    Choice(symbols) => call("choice", symbols),
+    Optional(symbol) => format!("optional({})", tree_sitter_symbol(symbol)),
+    Repeat(symbol) => format!("repeat({})", tree_sitter_symbol(symbol)),
+    Repeat1(symbol) => format!("repeat1({})", tree_sitter_symbol(symbol)),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Every token the parser sees must either have fixed text, or appear in
+  /// the table of terminals
+  #[test]
+  fn terminals() {
+    fn visit(symbol: &Symbol) {
+      match symbol {
+        Token(kind) => {
+          if kind.fixed_lexeme().is_none() {
+            terminal(*kind);
+          }
+        }
+        Keyword(_) | Rule(_) => {}
+        Sequence(symbols) | Choice(symbols) => symbols.iter().for_each(visit),
+        Optional(symbol) | Repeat(symbol) | Repeat1(symbol) => visit(symbol),
+      }
+    }
+
+    for (_, alternatives) in rules() {
+      alternatives.iter().for_each(visit);
+    }
+  }
+
+  #[test]
+  fn rules_defined() {
+    let rules = rules();
+
+    fn visit(symbol: &Symbol, names: &[&str]) {
+      match symbol {
+        Rule(name) => assert!(names.contains(name), "Undefined rule `{}`", name),
+        Token(_) | Keyword(_) => {}
+        Sequence(symbols) | Choice(symbols) => {
+          symbols.iter().for_each(|symbol| visit(symbol, names))
+        }
+        Optional(symbol) | Repeat(symbol) | Repeat1(symbol) => visit(symbol, names),
+      }
+    }
+
+    let names = rules.iter().map(|(name, _)| *name).collect::<Vec<&str>>();
+
+    for (_, alternatives) in &rules {
+      for alternative in alternatives {
+        visit(alternative, &names);
+      }
+    }
+  }
+
+  #[test]
+  fn grammar_md_up_to_date() {
+    let grammar = fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/GRAMMAR.md")).unwrap();
+
+    assert!(
+      grammar.contains(&format!("```\n{}```", ebnf_tokens())),
+      "GRAMMAR.md tokens are out of date, update them from `just --grammar ebnf`",
+    );
+
+    assert!(
+      grammar.contains(&format!("```\n{}```", ebnf_rules())),
+      "GRAMMAR.md grammar is out of date, update it from `just --grammar ebnf`",
+    );
+  }
+
+  #[test]
+  fn ebnf_precedence() {
+    assert_eq!(
+      ebnf_symbol(
+        &Sequence(vec![
+          opt(Choice(vec![Token(Plus), Token(Asterisk)])),
+          rep(Sequence(vec![Token(Comma), Token(Name)])),
+          Choice(vec![Token(Name), Token(Path)]),
+        ]),
+        0,
+      ),
+      "('+' | '*')? (',' NAME)* (NAME | PATH)",
+    );
+  }
+
+  #[test]
+  fn tree_sitter_rules() {
+    let grammar = tree_sitter();
+    assert!(grammar.contains("    justfile: $ => repeat($.item),\n"));
+    assert!(grammar.contains("    name: $ => /[a-zA-Z_][a-zA-Z0-9_-]*/,\n"));
+    assert!(grammar.contains("    $.path,\n"));
+    assert!(grammar.contains("    $.indent,\n"));
+  }
+
+  /// The justfiles in the README, and our own. Some of those in the README
+  /// can't be compiled on their own, but all of them can be lexed.
+  fn samples() -> Vec<String> {
+    let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR"));
+    let readme = fs::read_to_string(root.join("README.adoc")).unwrap();
+
+    let mut samples = vec![fs::read_to_string(root.join("justfile")).unwrap()];
+    for block in readme.split("```make\n").skip(1) {
+      samples.push(block[..block.find("```").unwrap()].to_owned());
+    }
+
+    samples
+  }
+
+  /// Every sample must be accepted by the exported grammar alone
+  #[test]
+  fn samples_match_grammar() {
+    let rules = rules();
+    let rules = rules
+      .iter()
+      .map(|(name, alternatives)| (*name, alternatives.as_slice()))
+      .collect::<BTreeMap<&str, &[Symbol]>>();
+
+    let samples = samples();
+    assert!(samples.len() > 50);
+
+    for sample in samples {
+      let tokens = tokens(&sample);
+      assert!(
+        ends(&rules, &Rule("justfile"), &tokens, 0).contains(&tokens.len()),
+        "sample not matched by the grammar:\n{}",
+        sample,
+      );
+    }
+  }
+
+  /// The text of every token in the samples must match its terminal's
+  /// regular expression
+  #[test]
+  fn samples_match_terminals() {
+    for (kind, name, pattern) in TERMINALS {
+      let regex = match pattern {
+        Regex(regex) => regex::Regex::new(&format!("^(?:{})$", regex)).unwrap(),
+        Context(_) => continue,
+      };
+
+      let mut seen = false;
+      for sample in samples() {
+        for token in tokens(&sample) {
+          if token.kind == *kind && !token.lexeme().is_empty() {
+            seen = true;
+            assert!(
+              regex.is_match(token.lexeme()),
+              "{} doesn't match {:?}",
+              name,
+              token.lexeme(),
+            );
+          }
+        }
+      }
+      assert!(seen, "no {} in the samples", name);
+    }
+  }
+
+  /// The tokens of `text` that the grammar sees, which is all but whitespace
+  fn tokens(text: &str) -> Vec<crate::token::Token<'_>> {
+    Lexer::lex(text)
+      .unwrap_or_else(|error| panic!("{}\n{}", error, text))
+      .into_iter()
+      .filter(|token| token.kind != Whitespace)
+      .collect()
+  }
+
+  /// The positions in `tokens` where a match of `symbol` starting at `start`
+  /// can end
+  fn ends(
+    rules: &BTreeMap<&str, &[Symbol]>,
+    symbol: &Symbol,
+    tokens: &[crate::token::Token<'_>],
+    start: usize,
+  ) -> BTreeSet<usize> {
+    let mut ends = BTreeSet::new();
+
+    match symbol {
+      Token(kind) => {
+        if tokens.get(start).map(|token| token.kind) == Some(*kind) {
+          ends.insert(start + 1);
+        }
+      }
+      Keyword(keyword) => {
+        if let Some(token) = tokens.get(start) {
+          if token.kind == Name && token.lexeme() == *keyword {
+            ends.insert(start + 1);
+          }
+        }
+      }
+      Rule(name) => {
+        for alternative in rules[name] {
+          ends.extend(self::ends(rules, alternative, tokens, start));
+        }
+      }
+      Sequence(symbols) => {
+        ends.insert(start);
+        for symbol in symbols {
+          ends = ends
+            .iter()
+            .flat_map(|&start| self::ends(rules, symbol, tokens, start))
+            .collect();
+        }
+      }
+      Choice(symbols) => {
+        for symbol in symbols {
+          ends.extend(self::ends(rules, symbol, tokens, start));
+        }
+      }
+      Optional(symbol) => {
+        ends.insert(start);
+        ends.extend(self::ends(rules, symbol, tokens, start));
+      }
+      Repeat(repeated) | Repeat1(repeated) => {
+        if let Repeat(_) = symbol {
+          ends.insert(start);
+        }
+        let mut frontier = self::ends(rules, repeated, tokens, start);
+        while !frontier.is_empty() {
+          ends.extend(frontier.iter().cloned());
+          frontier = frontier
+            .iter()
+            .flat_map(|&start| self::ends(rules, repeated, tokens, start))
+            .filter(|end| !ends.contains(end))
+            .collect();
+        }
+      }
+    }
+
+    ends
+  }
+}
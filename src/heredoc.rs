@@ -0,0 +1,125 @@
+use crate::common::*;
+
+/// A heredoc opened in a recipe line by a `<<DELIMITER` redirection, whose
+/// body continues until a line containing only the delimiter
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) struct Heredoc<'a> {
+  pub(crate) delimiter: &'a str,
+  /// Whether the heredoc was opened with `<<-`, so leading tabs are
+  /// stripped from the closing delimiter line
+  pub(crate) strip_tabs: bool,
+}
+
+impl<'a> Heredoc<'a> {
+  /// The heredoc opened by the first `<<` redirection in `text`, if any.
+  /// Here-strings (`<<<`) and shifts by numbers (`1 << 2`) are not
+  /// heredocs.
+  pub(crate) fn open(text: &'a str) -> Option<Heredoc<'a>> {
+    let mut rest = text;
+
+    while let Some(start) = rest.find("<<") {
+      let after = &rest[start + 2..];
+      let preceded = rest[..start].ends_with('<');
+      rest = after.trim_start_matches('<');
+
+      if preceded || after.starts_with('<') {
+        continue;
+      }
+
+      let strip_tabs = after.starts_with('-');
+      let word = after
+        .trim_start_matches('-')
+        .trim_start_matches([' ', '\t']);
+
+      let delimiter = match word.chars().next() {
+        Some(quote @ '\'') | Some(quote @ '"') => word[1..].split(quote).next().unwrap_or(""),
+        _ => {
+          let end = word
+            .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-'))
+            .unwrap_or(word.len());
+          &word[..end]
+        }
+      };
+
+      match delimiter.chars().next() {
+        Some(c) if c.is_alphabetic() || c == '_' => {
+          return Some(Heredoc {
+            delimiter,
+            strip_tabs,
+          })
+        }
+        _ => continue,
+      }
+    }
+
+    None
+  }
+
+  /// Whether `line` closes the heredoc
+  pub(crate) fn closes(self, line: &str) -> bool {
+    let line = if self.strip_tabs {
+      line.trim_start_matches('\t')
+    } else {
+      line
+    };
+
+    line.trim_end_matches('\r') == self.delimiter
+  }
+
+  /// The heredoc that is open after the recipe line `line`, given the
+  /// heredoc that was open before it
+  pub(crate) fn after_line(
+    heredoc: Option<Heredoc<'a>>,
+    line: &[Fragment<'a>],
+  ) -> Option<Heredoc<'a>> {
+    match heredoc {
+      Some(heredoc) => match line {
+        [Fragment::Text { text }] if heredoc.closes(text.lexeme()) => None,
+        _ => Some(heredoc),
+      },
+      None => line.iter().find_map(|fragment| match fragment {
+        Fragment::Text { text } => Heredoc::open(text.lexeme()),
+        Fragment::Expression { .. } => None,
+      }),
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::Heredoc;
+
+  #[test]
+  fn open() {
+    fn check(text: &str, expected: Option<(&str, bool)>) {
+      assert_eq!(
+        Heredoc::open(text).map(|heredoc| (heredoc.delimiter, heredoc.strip_tabs)),
+        expected,
+      );
+    }
+
+    check("cat <<EOF", Some(("EOF", false)));
+    check("cat << EOF > out.txt", Some(("EOF", false)));
+    check("cat <<-EOF", Some(("EOF", true)));
+    check("cat <<'END_TEXT'", Some(("END_TEXT", false)));
+    check("cat <<\"EOF\" | sort", Some(("EOF", false)));
+    check("python3 - <<PY; echo done", Some(("PY", false)));
+    check("cat <<<EOF", None);
+    check("cat <<< \"$x\"", None);
+    check("echo $((1 << 2))", None);
+    check("echo hello", None);
+  }
+
+  #[test]
+  fn closes() {
+    let heredoc = Heredoc::open("cat <<EOF").unwrap();
+    assert!(heredoc.closes("EOF"));
+    assert!(heredoc.closes("EOF\r"));
+    assert!(!heredoc.closes("\tEOF"));
+    assert!(!heredoc.closes("EOF "));
+
+    let heredoc = Heredoc::open("cat <<-EOF").unwrap();
+    assert!(heredoc.closes("\t\tEOF"));
+    assert!(!heredoc.closes("  EOF"));
+  }
+}
@@ -0,0 +1,297 @@
+use crate::common::*;
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The number of invocations kept in each history file
+const LIMIT: usize = 100;
+
+/// A recorded invocation of `just`, with the arguments it was given. Overrides
+/// aren't recorded, since their values may be secret.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Invocation {
+  /// When the invocation started, in seconds since the unix epoch
+  pub(crate) time: u64,
+  pub(crate) exit_code: i32,
+  pub(crate) duration: Duration,
+  pub(crate) arguments: Vec<String>,
+}
+
+impl Invocation {
+  pub(crate) fn new(start: SystemTime, exit_code: i32, arguments: Vec<String>) -> Invocation {
+    Invocation {
+      time: start
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_secs())
+        .unwrap_or(0),
+      exit_code,
+      duration: start.elapsed().unwrap_or_default(),
+      arguments,
+    }
+  }
+
+  /// Parse a line of a history file, as written by `Display`
+  fn parse(line: &str) -> Option<Invocation> {
+    let mut fields = line.split('\t');
+
+    Some(Invocation {
+      time: fields.next()?.parse().ok()?,
+      exit_code: fields.next()?.parse().ok()?,
+      duration: Duration::from_millis(fields.next()?.parse().ok()?),
+      arguments: fields.map(unescape).collect(),
+    })
+  }
+
+  /// Describe the invocation, relative to `now`, for `--history`
+  pub(crate) fn summary(&self, now: SystemTime) -> String {
+    let now = now
+      .duration_since(UNIX_EPOCH)
+      .map(|now| now.as_secs())
+      .unwrap_or(0);
+
+    let ago = match now.saturating_sub(self.time) {
+      seconds if seconds < 60 => format!("{}s ago", seconds),
+      seconds if seconds < 60 * 60 => format!("{}m ago", seconds / 60),
+      seconds if seconds < 60 * 60 * 24 => format!("{}h ago", seconds / 60 / 60),
+      seconds => format!("{}d ago", seconds / 60 / 60 / 24),
+    };
+
+    let status = if self.exit_code == 0 {
+      "ok".to_owned()
+    } else {
+      format!("exit {}", self.exit_code)
+    };
+
+    format!(
+      "{:<8} {:<8} {:>8}  {}",
+      ago,
+      status,
+      format!("{:.3}s", self.duration.as_secs_f64()),
+      self.arguments.join(" ")
+    )
+  }
+}
+
+impl Display for Invocation {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    write!(
+      f,
+      "{}\t{}\t{}",
+      self.time,
+      self.exit_code,
+      self.duration.as_millis()
+    )?;
+
+    for argument in &self.arguments {
+      write!(f, "\t{}", escape(argument))?;
+    }
+
+    Ok(())
+  }
+}
+
+pub(crate) fn escape(argument: &str) -> String {
+  argument
+    .replace('\\', "\\\\")
+    .replace('\t', "\\t")
+    .replace('\n', "\\n")
+}
+
+pub(crate) fn unescape(argument: &str) -> String {
+  let mut unescaped = String::new();
+  let mut chars = argument.chars();
+  while let Some(c) = chars.next() {
+    if c == '\\' {
+      match chars.next() {
+        Some('t') => unescaped.push('\t'),
+        Some('n') => unescaped.push('\n'),
+        Some(c) => unescaped.push(c),
+        None => unescaped.push('\\'),
+      }
+    } else {
+      unescaped.push(c);
+    }
+  }
+  unescaped
+}
+
+/// How long to wait for another `just` to finish updating a history file,
+/// before assuming that it exited without removing its lock
+const LOCK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Whether invocations are recorded, which they are unless `JUST_NO_HISTORY`
+/// is set
+pub(crate) fn enabled() -> bool {
+  env::var_os("JUST_NO_HISTORY")
+    .filter(|value| !value.is_empty())
+    .is_none()
+}
+
+/// The path of the history file for the justfile at `justfile`
+pub(crate) fn path(justfile: &Path) -> Option<PathBuf> {
+  state_path("history", justfile)
+}
+
+/// The path of a file in `directory`, under `$XDG_STATE_HOME/just`, or
+/// `~/.local/state/just`, named after the path of the justfile at `justfile`,
+/// or `None` if neither variable is set
+pub(crate) fn state_path(directory: &str, justfile: &Path) -> Option<PathBuf> {
+  let state = match env::var_os("XDG_STATE_HOME").filter(|state| !state.is_empty()) {
+    Some(state) => PathBuf::from(state),
+    None => Path::new(&env::var_os("HOME").filter(|home| !home.is_empty())?).join(".local/state"),
+  };
+
+  let justfile = fs::canonicalize(justfile).unwrap_or_else(|_| justfile.to_owned());
+
+  Some(
+    state
+      .join("just")
+      .join(directory)
+      .join(file_name(&justfile)),
+  )
+}
+
+/// The name of the state files of the justfile at `justfile`, which is its
+/// path, percent-encoded so that different paths have different names
+fn file_name(justfile: &Path) -> String {
+  justfile
+    .to_string_lossy()
+    .replace('%', "%25")
+    .replace('/', "%2F")
+    .replace('\\', "%5C")
+    .replace(':', "%3A")
+}
+
+/// The invocations recorded in the history file at `path`, oldest first
+pub(crate) fn load(path: &Path) -> Vec<Invocation> {
+  fs::read_to_string(path)
+    .map(|history| history.lines().filter_map(Invocation::parse).collect())
+    .unwrap_or_default()
+}
+
+/// Append `invocation` to the history file at `path`, dropping the oldest
+/// invocations if there are too many
+pub(crate) fn record(path: &Path, invocation: Invocation) -> io::Result<()> {
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent)?;
+  }
+
+  let _lock = Lock::acquire(path)?;
+
+  let mut history = load(path);
+  history.push(invocation);
+
+  let mut contents = String::new();
+  for invocation in &history[history.len().saturating_sub(LIMIT)..] {
+    contents += &invocation.to_string();
+    contents += "\n";
+  }
+
+  fs::write(path, contents)
+}
+
+/// A lock on a history file, held while it's read and written, so that
+/// invocations that finish at the same time are all recorded. The lock is a
+/// file next to the history file, which is removed when it's released.
+struct Lock {
+  path: PathBuf,
+}
+
+impl Lock {
+  fn acquire(history: &Path) -> io::Result<Lock> {
+    let mut path = history.as_os_str().to_owned();
+    path.push(".lock");
+    let path = PathBuf::from(path);
+
+    let start = SystemTime::now();
+
+    loop {
+      match fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&path)
+      {
+        Ok(_) => return Ok(Lock { path }),
+        Err(io_error) if io_error.kind() == io::ErrorKind::AlreadyExists => {
+          if start.elapsed().unwrap_or_default() > LOCK_TIMEOUT {
+            return Ok(Lock { path });
+          }
+          thread::sleep(Duration::from_millis(10));
+        }
+        Err(io_error) => return Err(io_error),
+      }
+    }
+  }
+}
+
+impl Drop for Lock {
+  fn drop(&mut self) {
+    fs::remove_file(&self.path).ok();
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn round_trip() {
+    let invocation = Invocation {
+      time: 1_600_000_000,
+      exit_code: 2,
+      duration: Duration::from_millis(1500),
+      arguments: vec![
+        "mode=release".to_owned(),
+        "build".to_owned(),
+        "a\tb\nc\\d".to_owned(),
+      ],
+    };
+
+    let line = invocation.to_string();
+    assert_eq!(
+      line,
+      "1600000000\t2\t1500\tmode=release\tbuild\ta\\tb\\nc\\\\d"
+    );
+    assert_eq!(Invocation::parse(&line), Some(invocation));
+  }
+
+  #[test]
+  fn summary() {
+    let invocation = Invocation {
+      time: 1_600_000_000,
+      exit_code: 0,
+      duration: Duration::from_millis(1500),
+      arguments: vec!["build".to_owned(), "release".to_owned()],
+    };
+
+    assert_eq!(
+      invocation.summary(UNIX_EPOCH + Duration::from_secs(1_600_000_090)),
+      "1m ago   ok         1.500s  build release",
+    );
+  }
+
+  #[test]
+  fn file_names_are_distinct() {
+    assert_eq!(file_name(Path::new("/a/b")), "%2Fa%2Fb");
+    assert_eq!(file_name(Path::new("/a%2Fb")), "%2Fa%252Fb");
+    assert_eq!(file_name(Path::new("C:\\a")), "C%3A%5Ca");
+  }
+
+  #[test]
+  fn lock() {
+    let tmp = testing::tempdir();
+    let history = tmp.path().join("history");
+
+    let lock = Lock::acquire(&history).unwrap();
+    assert!(tmp.path().join("history.lock").exists());
+    drop(lock);
+    assert!(!tmp.path().join("history.lock").exists());
+
+    record(
+      &history,
+      Invocation::new(SystemTime::now(), 0, vec!["a".into()]),
+    )
+    .unwrap();
+    assert_eq!(load(&history).len(), 1);
+    assert!(!tmp.path().join("history.lock").exists());
+  }
+}
@@ -3,9 +3,14 @@ use crate::common::*;
 pub(crate) struct InterruptHandler {
   blocks: u32,
   interrupted: bool,
+  /// The process IDs of running commands, which are killed on interrupt,
+  /// since commands run in parallel with `--jobs` don't all exit when one
+  /// is interrupted
+  children: BTreeSet<u32>,
 }
 
 impl InterruptHandler {
+  #[cfg(not(target_arch = "wasm32"))]
   pub(crate) fn install() -> Result<(), ctrlc::Error> {
     ctrlc::set_handler(|| InterruptHandler::instance().interrupt())
   }
@@ -30,6 +35,7 @@ impl InterruptHandler {
     InterruptHandler {
       blocks: 0,
       interrupted: false,
+      children: BTreeSet::new(),
     }
   }
 
@@ -37,6 +43,9 @@ impl InterruptHandler {
     self.interrupted = true;
 
     if self.blocks > 0 {
+      for child in &self.children {
+        Platform::kill(*child);
+      }
       return;
     }
 
@@ -69,6 +78,14 @@ impl InterruptHandler {
     }
   }
 
+  /// Wait for `child` to exit, killing it if just is interrupted
+  pub(crate) fn wait(child: &mut process::Child) -> io::Result<process::ExitStatus> {
+    Self::instance().children.insert(child.id());
+    let status = child.wait();
+    Self::instance().children.remove(&child.id());
+    status
+  }
+
   pub(crate) fn guard<T, F: FnOnce() -> T>(function: F) -> T {
     let _guard = InterruptGuard::new();
     function()
@@ -0,0 +1,44 @@
+use crate::common::*;
+
+/// The context a recipe is restricted to by a `[ci-only]` or `[local-only]`
+/// attribute. Just considers itself to be running in CI if the `CI`
+/// environment variable is set, as it is by most CI services.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub(crate) enum InvocationContext {
+  Ci,
+  Local,
+}
+
+impl InvocationContext {
+  pub(crate) fn from_attribute(attribute: &str) -> Option<InvocationContext> {
+    match attribute {
+      "ci-only" => Some(InvocationContext::Ci),
+      "local-only" => Some(InvocationContext::Local),
+      _ => None,
+    }
+  }
+
+  pub(crate) fn attribute(self) -> &'static str {
+    match self {
+      InvocationContext::Ci => "ci-only",
+      InvocationContext::Local => "local-only",
+    }
+  }
+
+  pub(crate) fn current() -> InvocationContext {
+    if env::var_os("CI").is_some() {
+      InvocationContext::Ci
+    } else {
+      InvocationContext::Local
+    }
+  }
+}
+
+impl Display for InvocationContext {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    match self {
+      InvocationContext::Ci => write!(f, "in CI"),
+      InvocationContext::Local => write!(f, "locally"),
+    }
+  }
+}
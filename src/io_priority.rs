@@ -0,0 +1,32 @@
+use crate::common::*;
+
+/// The I/O scheduling priority set by an `[ionice: ...]` attribute, either
+/// `idle` or a best-effort level from 0, the highest, to 7, the lowest
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub(crate) enum IoPriority {
+  Idle,
+  BestEffort(u8),
+}
+
+impl FromStr for IoPriority {
+  type Err = ();
+
+  fn from_str(text: &str) -> Result<IoPriority, ()> {
+    match text {
+      "idle" => Ok(IoPriority::Idle),
+      _ => match text.parse::<u8>() {
+        Ok(level) if level <= 7 => Ok(IoPriority::BestEffort(level)),
+        _ => Err(()),
+      },
+    }
+  }
+}
+
+impl Display for IoPriority {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    match self {
+      IoPriority::Idle => write!(f, "idle"),
+      IoPriority::BestEffort(level) => write!(f, "{}", level),
+    }
+  }
+}
@@ -0,0 +1,9 @@
+/// A named item in a justfile, recorded in the order they appear in the
+/// source, so that `--dump --order source` can preserve it
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub(crate) enum Item<'a> {
+  Alias(&'a str),
+  Assignment(&'a str),
+  Module(&'a str),
+  Recipe(&'a str),
+}
@@ -0,0 +1,76 @@
+use crate::common::*;
+
+/// The `--jobs` slots shared by recipes run in parallel, of which each
+/// recipe takes as many as its weight
+pub(crate) struct JobSlots {
+  available: Mutex<usize>,
+  released: Condvar,
+  total: usize,
+}
+
+impl JobSlots {
+  pub(crate) fn new(total: usize) -> JobSlots {
+    JobSlots {
+      available: Mutex::new(total),
+      released: Condvar::new(),
+      total,
+    }
+  }
+
+  /// Wait until `weight` slots are free, or all of them if there are fewer
+  /// than `weight`, and take them until the returned guard is dropped
+  pub(crate) fn acquire(&self, weight: usize) -> JobSlotsGuard<'_> {
+    let taken = cmp::min(weight, self.total);
+
+    let mut available = self.available.lock().unwrap();
+    while *available < taken {
+      available = self.released.wait(available).unwrap();
+    }
+    *available -= taken;
+
+    JobSlotsGuard { slots: self, taken }
+  }
+}
+
+pub(crate) struct JobSlotsGuard<'a> {
+  slots: &'a JobSlots,
+  taken: usize,
+}
+
+impl<'a> Drop for JobSlotsGuard<'a> {
+  fn drop(&mut self) {
+    *self.slots.available.lock().unwrap() += self.taken;
+    self.slots.released.notify_all();
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::sync::atomic::AtomicBool;
+
+  #[test]
+  fn heavy_recipe_waits_for_slots() {
+    let slots = JobSlots::new(4);
+
+    let light = slots.acquire(1);
+    assert_eq!(*slots.available.lock().unwrap(), 3);
+
+    let released = AtomicBool::new(false);
+
+    thread::scope(|threads| {
+      threads.spawn(|| {
+        let _heavy = slots.acquire(8);
+        assert!(released.load(Ordering::SeqCst));
+        assert_eq!(*slots.available.lock().unwrap(), 0);
+      });
+
+      thread::sleep(std::time::Duration::from_millis(100));
+      released.store(true, Ordering::SeqCst);
+      drop(light);
+    });
+
+    assert_eq!(*slots.available.lock().unwrap(), 4);
+  }
+}
@@ -1,38 +1,120 @@
 use crate::common::*;
 
+use std::time::Instant;
+
 #[derive(Debug)]
 pub(crate) struct Justfile<'a> {
-  pub(crate) recipes: BTreeMap<&'a str, Recipe<'a>>,
-  pub(crate) assignments: BTreeMap<&'a str, Expression<'a>>,
-  pub(crate) exports: BTreeSet<&'a str>,
+  pub(crate) recipes: OrderedMap<&'a str, Recipe<'a>>,
+  /// Recipes that are only available on other platforms, mapped to the OS
+  /// attributes that restrict them, such as `linux`
+  pub(crate) unavailable: BTreeMap<&'a str, Vec<&'a str>>,
+  pub(crate) assignments: OrderedMap<&'a str, Expression<'a>>,
+  /// Modules, variables, aliases, and recipes in the order they appear in the
+  /// source, with those from imported justfiles where they are imported
+  pub(crate) items: Vec<Item<'a>>,
+  /// Exported variables, mapped to how they are exported
+  pub(crate) exports: BTreeMap<&'a str, Export>,
   pub(crate) aliases: BTreeMap<&'a str, Alias<'a>>,
+  /// Modules declared with `mod`, each parsed from its own source file
+  pub(crate) modules: BTreeMap<&'a str, Justfile<'a>>,
+  pub(crate) settings: Settings,
   pub(crate) warnings: Vec<Warning<'a>>,
 }
 
 impl<'a> Justfile<'a> {
+  /// The first recipe in the justfile, which is run by default. Recipes
+  /// from imported justfiles come after those in the justfile itself.
   pub(crate) fn first(&self) -> Option<&Recipe> {
-    let mut first: Option<&Recipe> = None;
-    for recipe in self.recipes.values() {
-      if let Some(first_recipe) = first {
-        if recipe.line_number < first_recipe.line_number {
-          first = Some(recipe)
-        }
-      } else {
-        first = Some(recipe);
-      }
-    }
-    first
+    self
+      .recipes
+      .values()
+      .find(|recipe| !recipe.imported)
+      .or_else(|| self.recipes.values().next())
   }
 
   pub(crate) fn count(&self) -> usize {
     self.recipes.len()
   }
 
-  pub(crate) fn suggest(&self, name: &str) -> Option<&'a str> {
-    let mut suggestions = self
+  /// The names of the public recipes in the justfile, followed by those of
+  /// the public recipes in its modules, prefixed by the module name and `::`
+  pub(crate) fn public_recipe_paths(&self) -> Vec<String> {
+    let mut paths = self
       .recipes
-      .keys()
-      .map(|suggestion| (edit_distance(suggestion, name), suggestion))
+      .sorted_values()
+      .filter(|recipe| !recipe.private)
+      .map(|recipe| recipe.name.to_owned())
+      .collect::<Vec<String>>();
+
+    for (name, module) in &self.modules {
+      for path in module.public_recipe_paths() {
+        paths.push(format!("{}::{}", name, path));
+      }
+    }
+
+    paths
+  }
+
+  /// The module and recipe named by `path`, which is the name of a recipe
+  /// or alias, prefixed by the names of the modules containing it, each
+  /// followed by `::`
+  pub(crate) fn get_recipe_path(&self, path: &str) -> Option<(&Justfile<'a>, &Recipe<'a>)> {
+    let mut modules = path.split("::").collect::<Vec<&str>>();
+    let name = modules.pop()?;
+
+    let mut justfile = self;
+    for module in modules {
+      justfile = justfile.modules.get(module)?;
+    }
+
+    justfile.get_recipe(name).map(|recipe| (justfile, recipe))
+  }
+
+  /// The error for `path`, which doesn't name a recipe, if it names one that
+  /// is only available on other platforms. The recipes with the same base
+  /// name, the part before the first `-` or `_`, are suggested instead.
+  fn unavailable(&self, path: &'a str) -> Option<RuntimeError<'a>> {
+    let mut modules = path.split("::").collect::<Vec<&str>>();
+    let name = modules.pop()?;
+
+    let mut justfile = self;
+    for module in &modules {
+      justfile = justfile.modules.get(module)?;
+    }
+
+    let platforms = justfile.unavailable.get(name)?;
+
+    let base = |name: &'a str| name.split(&['-', '_'][..]).next().unwrap_or(name);
+    let prefix = &path[..path.len() - name.len()];
+
+    let alternatives = justfile
+      .recipes
+      .values()
+      .filter(|recipe| !recipe.private && base(recipe.name) == base(name))
+      .map(|recipe| format!("{}{}", prefix, recipe.name))
+      .collect();
+
+    Some(RuntimeError::UnavailableRecipe {
+      recipe: path,
+      platforms: platforms.clone(),
+      alternatives,
+    })
+  }
+
+  pub(crate) fn suggest(&self, name: &str) -> Option<&'a str> {
+    Self::closest(self.recipes.sorted_keys(), name)
+  }
+
+  pub(crate) fn suggest_variable(&self, name: &str) -> Option<&'a str> {
+    Self::closest(self.assignments.sorted_keys(), name)
+  }
+
+  fn closest<'b>(candidates: impl Iterator<Item = &'b &'a str>, name: &str) -> Option<&'a str>
+  where
+    'a: 'b,
+  {
+    let mut suggestions = candidates
+      .map(|suggestion| (edit_distance(suggestion, name), *suggestion))
       .collect::<Vec<_>>();
     suggestions.sort();
     if let Some(&(distance, suggestion)) = suggestions.first() {
@@ -44,6 +126,17 @@ impl<'a> Justfile<'a> {
   }
 
   pub(crate) fn run(&'a self, arguments: &[&'a str], config: &'a Config<'a>) -> RunResult<'a, ()> {
+    self.run_from(arguments, config, &mut Progress::default())
+  }
+
+  /// Run the recipes given by `arguments`, skipping those that `progress`
+  /// records as having finished, and recording those that finish in it
+  pub(crate) fn run_from(
+    &'a self,
+    arguments: &[&'a str],
+    config: &'a Config<'a>,
+    progress: &mut Progress,
+  ) -> RunResult<'a, ()> {
     let unknown_overrides = config
       .overrides
       .keys()
@@ -52,22 +145,20 @@ impl<'a> Justfile<'a> {
       .collect::<Vec<_>>();
 
     if !unknown_overrides.is_empty() {
+      let suggestion = if unknown_overrides.len() == 1 {
+        self.suggest_variable(unknown_overrides[0])
+      } else {
+        None
+      };
       return Err(RuntimeError::UnknownOverrides {
         overrides: unknown_overrides,
+        suggestion,
       });
     }
 
-    let dotenv = load_dotenv()?;
+    let mut dotenv = self.environment(config)?;
 
-    let scope = AssignmentEvaluator::evaluate_assignments(
-      &self.assignments,
-      &config.invocation_directory,
-      &dotenv,
-      &config.overrides,
-      config.quiet,
-      config.shell,
-      config.dry_run,
-    )?;
+    let scope = self.evaluate_scope(config, &dotenv)?;
 
     if config.evaluate {
       let mut width = 0;
@@ -81,14 +172,58 @@ impl<'a> Justfile<'a> {
       return Ok(());
     }
 
+    // Recipe lines are passed to the shell, which would run any backticks
+    // they contain, so they may only be printed
+    if config.no_execute_backticks && !config.dry_run {
+      return Err(RuntimeError::NoExecuteBackticks);
+    }
+
+    // Kept until all recipes have run, since the env file is deleted with it
+    let _env_file_directory = if self.settings.env_file {
+      let (directory, path) = Self::write_env_file(&scope)?;
+      dotenv.insert(
+        "JUST_ENV_FILE".to_owned(),
+        path.to_string_lossy().into_owned(),
+      );
+      Some(directory)
+    } else {
+      None
+    };
+
+    if config.each {
+      let (name, items) = arguments
+        .split_first()
+        .ok_or_else(|| RuntimeError::Internal {
+          message: "attempted to run `--each` without a recipe".to_string(),
+        })?;
+
+      let (module, recipe) = self.get_recipe_path(name).ok_or_else(|| {
+        self
+          .unavailable(name)
+          .unwrap_or_else(|| RuntimeError::UnknownRecipes {
+            recipes: vec![name],
+            suggestion: self.suggest(name),
+          })
+      })?;
+
+      let scope = if std::ptr::eq(module, self) {
+        scope
+      } else {
+        module.evaluate_scope(config, &dotenv)?
+      };
+
+      return module.run_each(config, scope, &dotenv, recipe, items);
+    }
+
     let mut missing = vec![];
     let mut grouped = vec![];
     let mut rest = arguments;
 
     while let Some((argument, mut tail)) = rest.split_first() {
-      if let Some(recipe) = self.get_recipe(argument) {
-        if recipe.parameters.is_empty() {
-          grouped.push((recipe, &tail[0..0]));
+      if let Some((module, recipe)) = self.get_recipe_path(argument) {
+        let path = &argument[..argument.rfind("::").unwrap_or(0)];
+        if recipe.parameters.is_empty() || (config.all && !recipe.matrix.is_empty()) {
+          grouped.push((path, module, recipe, &tail[0..0]));
         } else {
           let argument_range = recipe.argument_range();
           let argument_count = cmp::min(tail.len(), recipe.max_arguments());
@@ -101,7 +236,7 @@ impl<'a> Justfile<'a> {
               max: recipe.max_arguments(),
             });
           }
-          grouped.push((recipe, &tail[0..argument_count]));
+          grouped.push((path, module, recipe, &tail[0..argument_count]));
           tail = &tail[argument_count..];
         }
       } else {
@@ -110,6 +245,10 @@ impl<'a> Justfile<'a> {
       rest = tail;
     }
 
+    if let Some(error) = missing.iter().find_map(|name| self.unavailable(name)) {
+      return Err(error);
+    }
+
     if !missing.is_empty() {
       let suggestion = if missing.len() == 1 {
         self.suggest(missing.first().unwrap())
@@ -122,16 +261,340 @@ impl<'a> Justfile<'a> {
       });
     }
 
-    let context = RecipeContext { config, scope };
+    // Refuse before running anything, rather than after earlier recipes
+    for (_, _, recipe, _) in &grouped {
+      recipe.check_invocation_context()?;
+    }
+
+    // Each module has its own variables, which are only evaluated if one of
+    // its recipes is run
+    let mut contexts = BTreeMap::new();
+    contexts.insert(
+      "",
+      RecipeContext {
+        ran: RefCell::new(self.finished("", progress)),
+        running: RefCell::new(Vec::new()),
+        output: None,
+        justfile: self,
+        config,
+        scope,
+      },
+    );
+
+    let result = Self::run_groups(config, grouped, &mut contexts, &dotenv, progress);
+
+    for (path, context) in &contexts {
+      for name in context.ran.borrow().iter() {
+        progress.ran.insert(if path.is_empty() {
+          (*name).to_owned()
+        } else {
+          format!("{}::{}", path, name)
+        });
+      }
+    }
+
+    result
+  }
+
+  /// Evaluate `expression`, given with `--expr`, in the scope of the
+  /// justfile's variables
+  pub(crate) fn evaluate_expression(
+    &'a self,
+    expression: &Expression<'a>,
+    config: &'a Config<'a>,
+  ) -> RunResult<'a, String> {
+    let dotenv = self.environment(config)?;
+    AssignmentEvaluator::evaluate_standalone(expression, &self.assignments, config, &dotenv)
+  }
+
+  /// The value of each of the justfile's variables, as printed by `--evaluate`
+  pub(crate) fn evaluate_variables(
+    &'a self,
+    config: &'a Config<'a>,
+  ) -> RunResult<'a, BTreeMap<&'a str, String>> {
+    let dotenv = self.environment(config)?;
+    self.evaluate_scope(config, &dotenv)
+  }
+
+  /// The environment variables that commands are run with, in addition to
+  /// just's own, from env files and `--env`
+  fn environment(&self, config: &Config) -> RunResult<'a, BTreeMap<String, String>> {
+    let directory = config
+      .justfile_directory
+      .clone()
+      .or_else(|| env::current_dir().ok())
+      .unwrap_or_default();
+
+    let mut dotenv = load_dotenv(&directory, &self.settings)?;
+
+    for path in &config.env_files {
+      dotenv.extend(load_env_file(path)?);
+    }
+
+    // Ask child processes to use color even though their output may not be a
+    // terminal, when just's own output is colored
+    if self.settings.force_color && config.color.stdout().active() {
+      dotenv.insert("FORCE_COLOR".to_owned(), "1".to_owned());
+      dotenv.insert("CLICOLOR_FORCE".to_owned(), "1".to_owned());
+    }
+
+    // Variables passed with `--env` take precedence over those loaded from `.env`
+    for (key, value) in &config.environment {
+      dotenv.insert((*key).to_owned(), (*value).to_owned());
+    }
+
+    Ok(dotenv)
+  }
+
+  /// Run each group of a recipe and its arguments in turn, after those that
+  /// `progress` records as having finished. With `--report`, the rest are
+  /// run even if one fails, and a report of their results is written.
+  fn run_groups(
+    config: &'a Config<'a>,
+    grouped: Vec<(&'a str, &'a Justfile<'a>, &'a Recipe<'a>, &[&'a str])>,
+    contexts: &mut BTreeMap<&'a str, RecipeContext<'a>>,
+    dotenv: &BTreeMap<String, String>,
+    progress: &mut Progress,
+  ) -> RunResult<'a, ()> {
+    let mut report = Report::default();
+
+    for (i, (path, module, recipe, arguments)) in grouped.into_iter().enumerate() {
+      if i < progress.finished {
+        continue;
+      }
+
+      if !contexts.contains_key(path) {
+        let scope = module.evaluate_scope(config, dotenv)?;
+        contexts.insert(
+          path,
+          RecipeContext {
+            ran: RefCell::new(module.finished(path, progress)),
+            running: RefCell::new(Vec::new()),
+            output: None,
+            justfile: module,
+            config,
+            scope,
+          },
+        );
+      }
+
+      let context = &contexts[path];
+
+      let start = Instant::now();
+
+      let result = if config.all && !recipe.matrix.is_empty() {
+        recipe
+          .matrix_arguments()
+          .iter()
+          .try_for_each(|arguments| module.run_recipe(context, recipe, arguments, dotenv))
+      } else {
+        module.run_recipe(context, recipe, arguments, dotenv)
+      };
+
+      if config.report.is_none() {
+        result?;
+      } else {
+        let name = iter::once(recipe.name)
+          .chain(arguments.iter().cloned())
+          .collect::<Vec<&str>>()
+          .join(" ");
+
+        let name = if path.is_empty() {
+          name
+        } else {
+          format!("{}::{}", path, name)
+        };
 
-    let mut ran = empty();
-    for (recipe, arguments) in grouped {
-      self.run_recipe(&context, recipe, arguments, &dotenv, &mut ran)?
+        let failure = result.err().map(|error| {
+          if config.color.stderr().active() {
+            eprintln!("{:#}", error);
+          } else {
+            eprintln!("{}", error);
+          }
+          error.to_string()
+        });
+
+        report.record(name, start.elapsed(), failure);
+      }
+
+      // Later recipes can only be skipped by `--resume` if none before them
+      // failed
+      if report.failed().is_empty() {
+        progress.finished = i + 1;
+      }
+    }
+
+    if let Some(path) = config.report {
+      fs::write(path, report.render(config.report_format))
+        .map_err(|io_error| RuntimeError::ReportWrite { path, io_error })?;
+
+      let failed = report.failed();
+      if !failed.is_empty() {
+        return Err(RuntimeError::Report {
+          failed,
+          total: report.total(),
+        });
+      }
     }
 
     Ok(())
   }
 
+  /// The names of the recipes of this justfile, the module at `path`, that
+  /// `progress` records as having finished
+  fn finished(&'a self, path: &str, progress: &Progress) -> BTreeSet<&'a str> {
+    self
+      .recipes
+      .keys()
+      .cloned()
+      .filter(|name| {
+        progress.ran.contains(&if path.is_empty() {
+          (*name).to_owned()
+        } else {
+          format!("{}::{}", path, name)
+        })
+      })
+      .collect()
+  }
+
+  /// Evaluate the justfile's variables
+  fn evaluate_scope(
+    &'a self,
+    config: &'a Config<'a>,
+    dotenv: &BTreeMap<String, String>,
+  ) -> RunResult<'a, BTreeMap<&'a str, String>> {
+    AssignmentEvaluator::evaluate_assignments(&self.assignments, config, dotenv)
+  }
+
+  /// Run `recipe` once for each of `items`, on up to `config.jobs` threads,
+  /// fewer if the recipe's weight is more than one. Every run is attempted
+  /// even if some fail, and failures are reported together.
+  fn run_each(
+    &'a self,
+    config: &'a Config<'a>,
+    scope: BTreeMap<&'a str, String>,
+    dotenv: &BTreeMap<String, String>,
+    recipe: &'a Recipe<'a>,
+    items: &[&'a str],
+  ) -> RunResult<'a, ()> {
+    if !recipe.argument_range().range_contains(&1) {
+      return Err(RuntimeError::ArgumentCountMismatch {
+        recipe: recipe.name,
+        parameters: recipe.parameters.iter().collect(),
+        found: 1,
+        min: recipe.min_arguments(),
+        max: recipe.max_arguments(),
+      });
+    }
+
+    let context = RecipeContext {
+      ran: RefCell::new(empty()),
+      running: RefCell::new(Vec::new()),
+      output: None,
+      justfile: self,
+      config,
+      scope,
+    };
+
+    recipe.check_invocation_context()?;
+
+    if recipe.skip(&context, dotenv, &self.exports)? {
+      return Ok(());
+    }
+
+    // Dependencies only need to run once, before or after all of the runs
+    for dependency in &recipe.external_dependencies {
+      dependency.run(config)?;
+    }
+    for dependency_name in &recipe.dependencies[..recipe.priors] {
+      if !context.ran.borrow().contains(dependency_name) {
+        self.run_recipe(&context, &self.recipes[dependency_name], &[], dotenv)?;
+      }
+    }
+
+    let ran = context.ran.into_inner();
+    let scope = context.scope;
+
+    let next = AtomicUsize::new(0);
+    let failed = Mutex::new(Vec::new());
+
+    thread::scope(|threads| {
+      let runs = cmp::max(config.jobs / recipe.weight, 1);
+      for _ in 0..cmp::min(runs, items.len()) {
+        threads.spawn(|| {
+          let context = RecipeContext {
+            ran: RefCell::new(ran.clone()),
+            running: RefCell::new(vec![recipe.name]),
+            output: None,
+            justfile: self,
+            scope: scope.clone(),
+            config,
+          };
+
+          while let Some(&item) = items.get(next.fetch_add(1, Ordering::SeqCst)) {
+            if let Err(error) = recipe.run(&context, &[item], dotenv, &self.exports) {
+              if config.color.stderr().active() {
+                eprintln!("{:#}", error);
+              } else {
+                eprintln!("{}", error);
+              }
+              failed.lock().unwrap().push(item);
+            }
+          }
+        });
+      }
+    });
+
+    let mut failed = failed.into_inner().unwrap();
+
+    if failed.is_empty() {
+      let context = RecipeContext {
+        ran: RefCell::new(ran),
+        running: RefCell::new(Vec::new()),
+        output: None,
+        justfile: self,
+        config,
+        scope,
+      };
+      for dependency_name in &recipe.dependencies[recipe.priors..] {
+        if !context.ran.borrow().contains(dependency_name) {
+          self.run_recipe(&context, &self.recipes[dependency_name], &[], dotenv)?;
+        }
+      }
+      Ok(())
+    } else {
+      // Report failures in the order the arguments were given
+      failed.sort_by_key(|item| items.iter().position(|other| other == item));
+      Err(RuntimeError::Each {
+        recipe: recipe.name,
+        total: items.len(),
+        failed,
+      })
+    }
+  }
+
+  /// Write evaluated assignments as `NAME=VALUE` lines to a file in a new
+  /// temporary directory, for tools that read env files
+  fn write_env_file(
+    scope: &BTreeMap<&'a str, String>,
+  ) -> RunResult<'a, (tempfile::TempDir, PathBuf)> {
+    let directory = tempfile::Builder::new()
+      .prefix("just")
+      .tempdir()
+      .map_err(|io_error| RuntimeError::EnvFileWrite { io_error })?;
+
+    let path = directory.path().join("env");
+
+    let mut text = String::new();
+    for (name, value) in scope {
+      text += &format!("{}={}\n", name, value);
+    }
+
+    fs::write(&path, text).map_err(|io_error| RuntimeError::EnvFileWrite { io_error })?;
+
+    Ok((directory, path))
+  }
+
   pub(crate) fn get_alias(&self, name: &str) -> Option<&Alias> {
     self.aliases.get(name)
   }
@@ -146,56 +609,330 @@ impl<'a> Justfile<'a> {
     }
   }
 
-  fn run_recipe<'b>(
+  /// Every chain of dependencies by which running `recipe` runs
+  /// `dependency`, each starting with `recipe` and ending with `dependency`
+  pub(crate) fn dependency_paths(
+    &self,
+    recipe: &Recipe<'a>,
+    dependency: &str,
+  ) -> Vec<Vec<&'a str>> {
+    let mut paths = Vec::new();
+    for name in &recipe.dependencies {
+      if *name == dependency {
+        paths.push(vec![recipe.name, *name]);
+      } else if let Some(inner) = self.recipes.get(name) {
+        for mut path in self.dependency_paths(inner, dependency) {
+          path.insert(0, recipe.name);
+          paths.push(path);
+        }
+      }
+    }
+    paths
+  }
+
+  pub(crate) fn run_recipe<'b>(
     &self,
     context: &'b RecipeContext<'a>,
     recipe: &Recipe<'a>,
-    arguments: &[&'a str],
+    arguments: &[&str],
     dotenv: &BTreeMap<String, String>,
-    ran: &mut BTreeSet<&'a str>,
-  ) -> RunResult<()> {
-    for dependency_name in &recipe.dependencies {
-      if !ran.contains(dependency_name) {
-        self.run_recipe(context, &self.recipes[dependency_name], &[], dotenv, ran)?;
+  ) -> RunResult<'a, ()> {
+    if context.running.borrow().contains(&recipe.name) {
+      return Err(RuntimeError::RecursiveInvocation {
+        recipe: recipe.name,
+      });
+    }
+    recipe.check_invocation_context()?;
+    if recipe.skip(context, dotenv, &self.exports)? {
+      context.ran.borrow_mut().insert(recipe.name);
+      return Ok(());
+    }
+    context.running.borrow_mut().push(recipe.name);
+    for dependency in &recipe.external_dependencies {
+      if !context.ran.borrow().contains(dependency.key) {
+        dependency.run(context.config)?;
+        context.ran.borrow_mut().insert(dependency.key);
       }
     }
+    self.run_dependencies(context, recipe, 0..recipe.priors, dotenv)?;
     recipe.run(context, arguments, dotenv, &self.exports)?;
-    ran.insert(recipe.name);
+    context.running.borrow_mut().pop();
+    context.ran.borrow_mut().insert(recipe.name);
+    self.run_dependencies(
+      context,
+      recipe,
+      recipe.priors..recipe.dependencies.len(),
+      dotenv,
+    )
+  }
+
+  /// Run the dependencies of `recipe` in `range`, which haven't already run,
+  /// with the groups written in brackets run in parallel with `--jobs`
+  fn run_dependencies(
+    &self,
+    context: &RecipeContext<'a>,
+    recipe: &Recipe<'a>,
+    range: Range<usize>,
+    dotenv: &BTreeMap<String, String>,
+  ) -> RunResult<'a, ()> {
+    for i in range {
+      if context.config.jobs > 1 {
+        if let Some(group) = recipe
+          .parallel_dependencies
+          .iter()
+          .find(|group| group.start == i)
+        {
+          self.run_parallel(context, &recipe.dependencies[group.clone()], dotenv)?;
+        }
+      }
+      let name = recipe.dependencies[i];
+      if !context.ran.borrow().contains(name) {
+        self.run_recipe(context, &self.recipes[name], &[], dotenv)?;
+      }
+    }
     Ok(())
   }
-}
 
-impl<'a> Display for Justfile<'a> {
-  fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
-    let mut items = self.recipes.len() + self.assignments.len() + self.aliases.len();
-    for (name, expression) in &self.assignments {
-      if self.exports.contains(name) {
-        write!(f, "export ")?;
-      }
-      write!(f, "{} := {}", name, expression)?;
-      items -= 1;
-      if items != 0 {
-        write!(f, "\n\n")?;
+  /// Run the recipes named `names`, a group of dependencies written in
+  /// brackets, along with the recipes they depend on, using up to
+  /// `config.jobs` job slots, of which each recipe takes as many as its
+  /// weight. Each recipe starts once its dependencies, and the dependencies
+  /// written before it outside of its brackets, have finished. Dependencies
+  /// on other justfiles, which change the current directory, are run first,
+  /// one at a time. The output of each recipe is printed when it finishes.
+  /// If any fail, no more are started, and the failure of the first that
+  /// would have run one at a time is returned.
+  fn run_parallel(
+    &self,
+    context: &RecipeContext<'a>,
+    names: &[&'a str],
+    dotenv: &BTreeMap<String, String>,
+  ) -> RunResult<'a, ()> {
+    let mut closure = BTreeSet::new();
+    let mut external = Vec::new();
+    for name in names {
+      self.dependency_closure(name, &mut closure, &mut external);
+    }
+
+    for dependency in external {
+      if !context.ran.borrow().contains(dependency.key) {
+        dependency.run(context.config)?;
+        context.ran.borrow_mut().insert(dependency.key);
+      }
+    }
+
+    let mut order = Vec::new();
+    for name in names {
+      self.serial_order(name, &context.ran.borrow(), &mut order);
+    }
+
+    let mut scheduler = Scheduler::new(order.clone());
+    for name in &order {
+      let recipe = &self.recipes[name];
+      for (i, dependency) in recipe.dependencies.iter().enumerate() {
+        if i < recipe.priors {
+          scheduler.order(dependency, name);
+        } else {
+          scheduler.order(name, dependency);
+        }
+
+        let group = recipe
+          .parallel_dependencies
+          .iter()
+          .find(|group| group.contains(&i));
+        let first = if i < recipe.priors { 0 } else { recipe.priors };
+        for (j, earlier) in recipe.dependencies.iter().enumerate().take(i).skip(first) {
+          if !group.map(|group| group.contains(&j)).unwrap_or(false) {
+            scheduler.order(earlier, dependency);
+          }
+        }
+      }
+    }
+
+    let config = context.config;
+    let justfile = context.justfile;
+    let scope = &context.scope;
+    let running = context.running.borrow().clone();
+    let ran = Mutex::new(context.ran.borrow().clone());
+
+    let result = scheduler.run(
+      config.jobs,
+      |name| self.recipes[name].weight,
+      |name| {
+        let output = RecipeOutput::new().map_err(|io_error| RuntimeError::TmpdirIoError {
+          recipe: name,
+          io_error,
+        })?;
+
+        let context = RecipeContext {
+          ran: RefCell::new(ran.lock().unwrap().clone()),
+          running: RefCell::new(running.clone()),
+          output: Some(output),
+          scope: scope.clone(),
+          justfile,
+          config,
+        };
+
+        let result = self.run_scheduled(&context, &self.recipes[name], dotenv);
+
+        ran.lock().unwrap().extend(context.ran.into_inner());
+
+        let printed = context.output.unwrap().print();
+        result?;
+        printed.map_err(|io_error| RuntimeError::IoError {
+          recipe: name,
+          io_error,
+        })
+      },
+    );
+
+    context.ran.borrow_mut().extend(ran.into_inner().unwrap());
+
+    result
+  }
+
+  /// Run `recipe`, but not its dependencies, which the scheduler has already
+  /// run. Its `[skip-if]` command is only run once they have finished.
+  fn run_scheduled(
+    &self,
+    context: &RecipeContext<'a>,
+    recipe: &Recipe<'a>,
+    dotenv: &BTreeMap<String, String>,
+  ) -> RunResult<'a, ()> {
+    if context.running.borrow().contains(&recipe.name) {
+      return Err(RuntimeError::RecursiveInvocation {
+        recipe: recipe.name,
+      });
+    }
+    if !recipe.skip(context, dotenv, &self.exports)? {
+      context.running.borrow_mut().push(recipe.name);
+      recipe.run(context, &[], dotenv, &self.exports)?;
+    }
+    context.ran.borrow_mut().insert(recipe.name);
+    Ok(())
+  }
+
+  /// Add `name` and the recipes it depends on that haven't already run, in
+  /// the order `run_recipe` would run them one at a time, to `order`
+  fn serial_order(&self, name: &'a str, ran: &BTreeSet<&'a str>, order: &mut Vec<&'a str>) {
+    if ran.contains(name) || order.contains(&name) {
+      return;
+    }
+
+    let recipe = &self.recipes[name];
+
+    for dependency in &recipe.dependencies[..recipe.priors] {
+      self.serial_order(dependency, ran, order);
+    }
+
+    order.push(name);
+
+    for dependency in &recipe.dependencies[recipe.priors..] {
+      self.serial_order(dependency, ran, order);
+    }
+  }
+
+  /// Add `name` and the recipes it depends on, directly or indirectly, to
+  /// `closure`, and their dependencies on other justfiles to `external`
+  fn dependency_closure<'b>(
+    &'b self,
+    name: &'a str,
+    closure: &mut BTreeSet<&'a str>,
+    external: &mut Vec<&'b ExternalDependency<'a>>,
+  ) {
+    if !closure.insert(name) {
+      return;
+    }
+
+    let recipe = &self.recipes[name];
+
+    for dependency in &recipe.external_dependencies {
+      if !external.iter().any(|other| other.key == dependency.key) {
+        external.push(dependency);
       }
     }
-    for alias in self.aliases.values() {
-      write!(f, "{}", alias)?;
-      items -= 1;
-      if items != 0 {
+
+    for dependency in &recipe.dependencies {
+      self.dependency_closure(dependency, closure, external);
+    }
+  }
+}
+
+impl<'a> Justfile<'a> {
+  /// The justfile as printed by `--dump`, with its items in `order`
+  pub(crate) fn dump(&self, order: DumpOrder) -> String {
+    let items = match order {
+      DumpOrder::Alphabetical => self.alphabetical_items(),
+      DumpOrder::Source => self.items.clone(),
+    };
+
+    let mut dump = String::new();
+    // Writing to a string can't fail
+    self.write_items(&mut dump, &items).unwrap();
+    dump
+  }
+
+  fn alphabetical_items(&self) -> Vec<Item<'a>> {
+    self
+      .modules
+      .keys()
+      .cloned()
+      .map(Item::Module)
+      .chain(
+        self
+          .assignments
+          .sorted_keys()
+          .cloned()
+          .map(Item::Assignment),
+      )
+      .chain(self.aliases.keys().cloned().map(Item::Alias))
+      .chain(self.recipes.sorted_keys().cloned().map(Item::Recipe))
+      .collect()
+  }
+
+  /// Write the settings, followed by `items`, separated by blank lines
+  fn write_items(&self, f: &mut dyn fmt::Write, items: &[Item<'a>]) -> fmt::Result {
+    if !self.settings.is_default() {
+      write!(f, "{}", self.settings)?;
+      if !items.is_empty() {
         write!(f, "\n\n")?;
       }
     }
-    for recipe in self.recipes.values() {
-      write!(f, "{}", recipe)?;
-      items -= 1;
-      if items != 0 {
+
+    for (i, item) in items.iter().enumerate() {
+      if i > 0 {
         write!(f, "\n\n")?;
       }
+
+      match *item {
+        Item::Module(name) => write!(f, "mod {}", name)?,
+        Item::Assignment(name) => {
+          if let Some(export) = self.exports.get(name) {
+            if let Some(path) = export.path {
+              writeln!(f, "[export-path: {} {}]", path, Quoted(&export.variable))?;
+            } else if export.variable == format!("{}{}", self.settings.export_prefix, name) {
+              write!(f, "export ")?;
+            } else {
+              writeln!(f, "[export: {}]", Quoted(&export.variable))?;
+            }
+          }
+          write!(f, "{} := {}", name, self.assignments[name])?;
+        }
+        Item::Alias(name) => write!(f, "{}", self.aliases[name])?,
+        Item::Recipe(name) => write!(f, "{}", self.recipes[name])?,
+      }
     }
+
     Ok(())
   }
 }
 
+impl<'a> Display for Justfile<'a> {
+  fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+    self.write_items(f, &self.alphabetical_items())
+  }
+}
+
 #[cfg(test)]
 mod test {
   use super::*;
@@ -243,6 +980,7 @@ a:
         recipe,
         line_number,
         code,
+        ..
       } => {
         assert_eq!(recipe, "a");
         assert_eq!(code, 200);
@@ -262,15 +1000,58 @@ a:
         recipe,
         line_number,
         code,
+        command,
       } => {
         assert_eq!(recipe, "fail");
         assert_eq!(code, 100);
         assert_eq!(line_number, Some(2));
+        assert_eq!(command.as_deref(), Some("exit 100"));
       }
       other => panic!("expected a code run error, but got: {}", other),
     }
   }
 
+  #[test]
+  fn environment_snapshot() {
+    let config = Config {
+      environment_snapshot: vec![("JUST_SNAPSHOT_CODE".into(), "7".into())]
+        .into_iter()
+        .collect(),
+      ..Default::default()
+    };
+
+    let text = "
+code := env_var('JUST_SNAPSHOT_CODE')
+
+fail:
+ @exit {{code}}{{env_var_or_default('JUST_SNAPSHOT_CODE', '0')}}";
+
+    match parse(text).run(&["fail"], &config).unwrap_err() {
+      Code { code, .. } => assert_eq!(code, 77),
+      other => panic!("expected a code run error, but got: {}", other),
+    }
+  }
+
+  #[test]
+  #[cfg(unix)]
+  fn export_path_environment_snapshot() {
+    let config = Config {
+      environment_snapshot: vec![("JUST_SNAPSHOT_PATH".into(), "/a".into())]
+        .into_iter()
+        .collect(),
+      ..Default::default()
+    };
+
+    let text = "
+[export-path: prepend 'JUST_SNAPSHOT_PATH']
+path := '/b'
+
+check:
+ @[ \"$JUST_SNAPSHOT_PATH\" = /b:/a ] || exit 1";
+
+    parse(text).run(&["check"], &config).unwrap();
+  }
+
   #[test]
   fn run_args() {
     let text = r#"
@@ -285,6 +1066,7 @@ a return code:
         recipe,
         line_number,
         code,
+        ..
       } => {
         assert_eq!(recipe, "a");
         assert_eq!(code, 150);
@@ -423,8 +1205,12 @@ a return code:
       .run(&["a"], &config)
       .unwrap_err()
     {
-      UnknownOverrides { overrides } => {
+      UnknownOverrides {
+        overrides,
+        suggestion,
+      } => {
         assert_eq!(overrides, &["baz", "foo"]);
+        assert_eq!(suggestion, None);
       }
       other => panic!("expected a code run error, but got: {}", other),
     }
@@ -452,6 +1238,7 @@ wut:
         code: _,
         line_number,
         recipe,
+        ..
       } => {
         assert_eq!(recipe, "wut");
         assert_eq!(line_number, Some(8));
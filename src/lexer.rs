@@ -23,6 +23,12 @@ pub(crate) struct Lexer<'a> {
   token_end: Position,
   /// Next character
   next: Option<char>,
+  /// Whether the recipe body being lexed has the `[no-interpolation]`
+  /// attribute, so `{{` is text
+  raw: bool,
+  /// The heredoc open in the recipe body being lexed, and the text token
+  /// that opened it
+  heredoc: Option<(Heredoc<'a>, Token<'a>)>,
 }
 
 impl<'a> Lexer<'a> {
@@ -50,6 +56,8 @@ impl<'a> Lexer<'a> {
       chars,
       next,
       text,
+      raw: false,
+      heredoc: None,
     }
   }
 
@@ -163,6 +171,7 @@ impl<'a> Lexer<'a> {
       line: self.token_end.line,
       column: self.token_end.column,
       width: 0,
+      secondary: None,
       kind: CompilationErrorKind::Internal {
         message: message.into(),
       },
@@ -189,6 +198,7 @@ impl<'a> Lexer<'a> {
       line: self.token_start.line,
       column: self.token_start.column,
       width,
+      secondary: None,
       kind,
     }
   }
@@ -203,6 +213,7 @@ impl<'a> Lexer<'a> {
       line: interpolation_start.line,
       column: interpolation_start.column,
       width: 2,
+      secondary: None,
       kind: UnterminatedInterpolation,
     }
   }
@@ -210,7 +221,8 @@ impl<'a> Lexer<'a> {
   /// Consume the text and produce a series of tokens
   fn tokenize(mut self) -> CompilationResult<'a, Vec<Token<'a>>> {
     loop {
-      if self.token_start.column == 0 {
+      // Lines in a heredoc may start text without any indentation
+      if self.token_start.column == 0 && self.state()? != State::Text {
         self.lex_line_start()?;
       }
 
@@ -234,6 +246,12 @@ impl<'a> Lexer<'a> {
       return Err(self.unterminated_interpolation_error(interpolation_start));
     }
 
+    if let Some((heredoc, text)) = &self.heredoc {
+      return Err(text.error(UnterminatedHeredoc {
+        delimiter: heredoc.delimiter,
+      }));
+    }
+
     if let State::Indented { .. } | State::Text = self.state()? {
       self.token(Dedent);
     }
@@ -269,6 +287,26 @@ impl<'a> Lexer<'a> {
       return Ok(());
     }
 
+    // Handle lines in a heredoc, which are part of the recipe body whatever
+    // their indentation
+    if let (State::Indented { indentation }, Some((heredoc, _))) = (self.state()?, &self.heredoc) {
+      let heredoc = *heredoc;
+
+      if self.rest_starts_with(indentation) {
+        for _ in indentation.chars() {
+          self.advance()?;
+        }
+
+        self.token(Whitespace);
+      }
+
+      if heredoc.closes(self.rest().lines().next().unwrap_or("")) {
+        self.heredoc = None;
+      }
+
+      return Ok(());
+    }
+
     // Handle nonblank lines with no leading whitespace
     if !self.next_is_whitespace() {
       if let State::Indented { .. } = self.state()? {
@@ -335,20 +373,76 @@ impl<'a> Lexer<'a> {
 
     self.state.push(State::Indented { indentation });
 
+    self.raw = self.no_interpolation();
+
     self.token(Indent);
 
     Ok(())
   }
 
+  /// Whether the recipe whose body is about to be lexed has the
+  /// `[no-interpolation]` attribute
+  fn no_interpolation(&self) -> bool {
+    // Skip the empty line after the final newline and the recipe header, and
+    // look through the attributes and comments above the header
+    self
+      .tokens
+      .split(|token| token.kind == Eol)
+      .rev()
+      .skip(2)
+      .map(|line| {
+        line
+          .iter()
+          .filter(|token| token.kind != Whitespace && token.kind != Dedent)
+          .collect::<Vec<&Token>>()
+      })
+      .take_while(|line| {
+        line
+          .first()
+          .map(|token| token.kind == BracketL || token.kind == Comment)
+          .unwrap_or(false)
+      })
+      .any(|line| match line.as_slice() {
+        [bracket_l, name, bracket_r] => {
+          bracket_l.kind == BracketL
+            && name.lexeme() == "no-interpolation"
+            && bracket_r.kind == BracketR
+        }
+        _ => false,
+      })
+  }
+
+  /// Whether the recipe body being lexed is a shebang recipe, whose lines
+  /// are not shell commands
+  fn shebang(&self) -> bool {
+    let indent = match self.tokens.iter().rposition(|token| token.kind == Indent) {
+      Some(indent) => indent,
+      None => return false,
+    };
+
+    match &self.tokens[indent + 1..] {
+      [line, text, ..] => line.kind == Line && text.kind == Text && text.lexeme().starts_with("#!"),
+      _ => false,
+    }
+  }
+
   /// Lex token beginning with `start` in normal state
   fn lex_normal(&mut self, start: char) -> CompilationResult<'a, ()> {
     match start {
+      '&' => self.lex_ampersand(),
+      '*' => self.lex_single(Asterisk),
       '@' => self.lex_single(At),
-      '=' => self.lex_single(Equals),
+      '$' if self.dollar_is_sigil() => self.lex_single(Dollar),
+      '=' => self.lex_equals(),
       ',' => self.lex_single(Comma),
       ':' => self.lex_colon(),
       '(' => self.lex_single(ParenL),
       ')' => self.lex_single(ParenR),
+      '[' => self.lex_single(BracketL),
+      ']' => self.lex_single(BracketR),
+      '|' => self.lex_single(Bar),
+      '.' | '/' if self.in_dependencies() => self.lex_path(),
+      '/' => self.lex_single(Slash),
       '{' => self.lex_brace_l(),
       '}' => self.lex_brace_r(),
       '+' => self.lex_single(Plus),
@@ -360,6 +454,7 @@ impl<'a> Lexer<'a> {
       '\'' => self.lex_raw_string(),
       '"' => self.lex_cooked_string(),
       'a'..='z' | 'A'..='Z' | '_' => self.lex_name(),
+      '0'..='9' => self.lex_number(),
       _ => {
         self.advance()?;
         Err(self.error(UnknownStartOfToken))
@@ -408,7 +503,15 @@ impl<'a> Lexer<'a> {
         break NewlineCarriageReturn;
       }
 
-      if self.rest_starts_with("{{") {
+      // `{{{{` is an escaped `{{`, and part of the text
+      if self.rest_starts_with("{{{{") && !self.raw {
+        for _ in 0..4 {
+          self.advance()?;
+        }
+        continue;
+      }
+
+      if self.rest_starts_with("{{") && !self.raw {
         break Interpolation;
       }
 
@@ -422,6 +525,11 @@ impl<'a> Lexer<'a> {
     // emit text token containing text so far
     if self.current_token_length() > 0 {
       self.token(Text);
+
+      if self.heredoc.is_none() && !self.shebang() {
+        let text = self.tokens[self.tokens.len() - 1].clone();
+        self.heredoc = Heredoc::open(text.lexeme()).map(|heredoc| (heredoc, text));
+      }
     }
 
     match terminator {
@@ -465,6 +573,52 @@ impl<'a> Lexer<'a> {
     Ok(())
   }
 
+  /// Whether the recipe header on the current line has reached its
+  /// dependencies, after the colon, the only place where `.` and `/` start a
+  /// path to another justfile, rather than being an unknown token and the path
+  /// joining operator
+  fn in_dependencies(&self) -> bool {
+    let line = self
+      .tokens
+      .rsplit(|token| token.kind == Eol)
+      .next()
+      .unwrap_or(&[]);
+
+    let header = line
+      .iter()
+      .find(|token| token.kind != Whitespace && token.kind != Dedent)
+      .map(|token| token.column == 0 && (token.kind == Name || token.kind == At))
+      .unwrap_or(false);
+
+    header && line.iter().any(|token| token.kind == Colon)
+  }
+
+  /// Whether the `$` in `self.next` marks an exported parameter, which it
+  /// does only when directly followed by a name, so that make-style `$(...)`
+  /// is still reported as an unknown start of token.
+  fn dollar_is_sigil(&self) -> bool {
+    self.rest()[1..]
+      .chars()
+      .next()
+      .map(|c| c.is_ascii_alphabetic() || c == '_')
+      .unwrap_or(false)
+  }
+
+  /// Lex a path to another justfile, which ends at whitespace or a colon
+  fn lex_path(&mut self) -> CompilationResult<'a, ()> {
+    while self
+      .next
+      .map(|c| !c.is_whitespace() && c != ':')
+      .unwrap_or(false)
+    {
+      self.advance()?;
+    }
+
+    self.token(Path);
+
+    Ok(())
+  }
+
   /// Lex a token starting with ':'
   fn lex_colon(&mut self) -> CompilationResult<'a, ()> {
     self.advance()?;
@@ -479,26 +633,46 @@ impl<'a> Lexer<'a> {
     Ok(())
   }
 
-  /// Lex a token starting with '{'
-  fn lex_brace_l(&mut self) -> CompilationResult<'a, ()> {
-    if !self.rest_starts_with("{{") {
+  /// Lex a token starting with '&', of which only `&&` is valid
+  fn lex_ampersand(&mut self) -> CompilationResult<'a, ()> {
+    if self.rest_starts_with("&&") {
+      self.lex_double(AmpersandAmpersand)
+    } else {
       self.advance()?;
+      Err(self.error(UnknownStartOfToken))
+    }
+  }
+
+  /// Lex a token starting with '='
+  fn lex_equals(&mut self) -> CompilationResult<'a, ()> {
+    self.advance()?;
 
-      return Err(self.error(UnknownStartOfToken));
+    if self.next_is('=') {
+      self.advance()?;
+      self.token(EqualsEquals);
+    } else {
+      self.token(Equals);
     }
 
-    self.lex_double(InterpolationStart)
+    Ok(())
+  }
+
+  /// Lex a token starting with '{'
+  fn lex_brace_l(&mut self) -> CompilationResult<'a, ()> {
+    if self.rest_starts_with("{{") {
+      self.lex_double(InterpolationStart)
+    } else {
+      self.lex_single(BraceL)
+    }
   }
 
   /// Lex a token starting with '}'
   fn lex_brace_r(&mut self) -> CompilationResult<'a, ()> {
-    if !self.rest_starts_with("}}") {
-      self.advance()?;
-
-      return Err(self.error(UnknownStartOfToken));
+    if self.rest_starts_with("}}") {
+      self.lex_double(InterpolationEnd)
+    } else {
+      self.lex_single(BraceR)
     }
-
-    self.lex_double(InterpolationEnd)
   }
 
   /// Lex a carriage return and line feed
@@ -528,6 +702,17 @@ impl<'a> Lexer<'a> {
     Ok(())
   }
 
+  /// Lex number: [0-9]+
+  fn lex_number(&mut self) -> CompilationResult<'a, ()> {
+    while self.next.map(|c| c.is_ascii_digit()).unwrap_or(false) {
+      self.advance()?;
+    }
+
+    self.token(Number);
+
+    Ok(())
+  }
+
   /// Lex comment: #[^\r\n]
   fn lex_comment(&mut self) -> CompilationResult<'a, ()> {
     // advance over #
@@ -632,24 +817,36 @@ mod tests {
     tokens
       .iter()
       .map(|t| match t.kind {
+        AmpersandAmpersand => "&&",
+        Asterisk => "*",
         At => "@",
         Backtick => "`",
+        Bar => "|",
+        BraceL => "B",
+        BraceR => "b",
+        BracketL => "[",
+        BracketR => "]",
         Colon => ":",
         ColonEquals => ":=",
         Comma => ",",
         Comment => "#",
         Dedent => "<",
+        Dollar => "D",
         Eof => ".",
         Eol => "$",
         Equals => "=",
+        EqualsEquals => "==",
         Indent => ">",
         InterpolationEnd => "}",
         InterpolationStart => "{",
         Line => "^",
         Name => "N",
+        Number => "0",
         ParenL => "(",
         ParenR => ")",
+        Path => "P",
         Plus => "+",
+        Slash => "/",
         StringRaw => "'",
         StringCooked => "\"",
         Text => "_",
@@ -688,6 +885,66 @@ mod tests {
     "N.",
   }
 
+  lex_test! {
+    number,
+    "[weight: 42]",
+    "[N: 0].",
+  }
+
+  lex_test! {
+    path,
+    "a: ../lib/justfile::b",
+    "N: P::N.",
+  }
+
+  lex_test! {
+    slash_operator,
+    "a := b / c + d/'e' + (f)/`g`",
+    "N := N / N + N/' + (N)/`.",
+  }
+
+  lex_test! {
+    slash_operator_before_string,
+    "a := 'b' /'c' + d /\"e\"",
+    "N := ' /' + N /\".",
+  }
+
+  lex_test! {
+    slash_path,
+    "a: b /c::d",
+    "N: N P::N.",
+  }
+
+  lex_test! {
+    slash_outside_dependencies,
+    "[a: 'b']\nc d=('e' /'f'):",
+    "[N: ']$N N=(' /'):.",
+  }
+
+  lex_test! {
+    exported_parameter,
+    "a $b *$c:",
+    "N DN *DN:.",
+  }
+
+  lex_test! {
+    subsequent_dependencies,
+    "a: b && c",
+    "N: N && N.",
+  }
+
+  lex_test! {
+    conditional,
+    "a := if b == 'c' { 'd' } else { 'e' }",
+    "N := N N == ' B ' b N B ' b.",
+  }
+
+  lex_test! {
+    conditional_in_interpolation,
+    "a:\n echo {{ if b == c { d } else { e } }}",
+    "N:$>^_{ N N == N B N b N B N b }<.",
+  }
+
   lex_test! {
     comment,
     "# hello",
@@ -793,6 +1050,30 @@ c: b
     "$N: N$>^_$^$<N:$>^_$ ^_$^$<N: N$>^_$^$<N: N$>^_<.",
   }
 
+  lex_test! {
+    interpolation_escape,
+    "a:\n echo ${{{{ b }} {{{{{{c}}",
+    "N:$>^_{N}<.",
+  }
+
+  lex_test! {
+    no_interpolation,
+    "[no-interpolation]\na:\n echo {{ b }}\n\n# c\nd:\n echo {{ e }}",
+    "[N]$N:$>^_$^$<#$N:$>^_{ N }<.",
+  }
+
+  lex_test! {
+    heredoc,
+    "a:\n cat <<EOF\nb\n   c\n EOF\n echo\nd:",
+    "N:$>^_$^_$ ^_$ ^_$ ^_$<N:.",
+  }
+
+  lex_test! {
+    heredoc_shebang,
+    "a:\n #!/usr/bin/env python3\n print(1 << b)\nc:",
+    "N:$>^_$ ^_$<N:.",
+  }
+
   lex_test! {
     interpolation_empty,
     "hello:\n echo {{}}",
@@ -1016,6 +1297,16 @@ c: b
     kind:   InconsistentLeadingWhitespace{expected: "\t\t", found: "\t "},
   }
 
+  error_test! {
+    name:  unterminated_heredoc,
+    input: "a:\n cat <<EOF\n b\nc:\n",
+    offset: 4,
+    line:   1,
+    column: 1,
+    width:  9,
+    kind:   UnterminatedHeredoc{delimiter: "EOF"},
+  }
+
   error_test! {
     name:   tokenize_unknown,
     input:  "~",
@@ -6,11 +6,14 @@ extern crate lazy_static;
 mod testing;
 
 #[cfg(fuzzing)]
-pub(crate) mod fuzzing;
+pub mod fuzzing;
 
 #[macro_use]
 mod die;
 
+#[macro_use]
+mod catalog;
+
 mod alias;
 mod alias_resolver;
 mod assignment_evaluator;
@@ -20,42 +23,81 @@ mod command_ext;
 mod common;
 mod compilation_error;
 mod compilation_error_kind;
+#[cfg(any(feature = "ffi", feature = "wasm"))]
+mod compilation_report;
+#[cfg(any(feature = "ffi", feature = "wasm"))]
+mod compiler;
+mod complete;
 mod config;
 mod config_error;
 mod count;
+mod date_time;
 mod default;
+mod dump_order;
 mod empty;
 mod enclosure;
+mod exit_code;
+mod export;
 mod expression;
+mod external_dependency;
 mod fragment;
 mod function;
 mod function_context;
 mod functions;
+mod github;
+mod grammar;
+mod heredoc;
+mod history;
 mod interrupt_guard;
 mod interrupt_handler;
+mod invocation_context;
+mod io_priority;
+mod item;
+mod job_slots;
 mod justfile;
 mod lexer;
+mod lint;
 mod list;
 mod load_dotenv;
+mod loader;
+mod matrix_axis;
+mod merge;
+mod ordered_map;
 mod ordinal;
 mod output;
 mod output_error;
+mod output_format;
 mod parameter;
+mod parameter_kind;
 mod parser;
+mod path_export;
 mod platform;
 mod platform_interface;
 mod position;
+mod process_options;
+mod progress;
+mod quoted;
 mod range_ext;
 mod recipe;
 mod recipe_context;
+mod recipe_output;
 mod recipe_resolver;
+mod rename;
+#[cfg(not(target_arch = "wasm32"))]
+mod repl;
+mod report;
+mod report_format;
+mod request;
 mod run;
 mod runtime_error;
+mod scheduler;
 mod search;
 mod search_error;
+mod settings;
 mod shebang;
 mod show_whitespace;
 mod state;
+mod string_fragment;
 mod string_literal;
 mod subcommand;
 mod token;
@@ -68,5 +110,11 @@ mod write_message_context;
 
 pub use crate::run::run;
 
+#[cfg(feature = "ffi")]
+pub mod ffi;
+
 #[cfg(feature = "summary")]
 pub mod summary;
+
+#[cfg(feature = "wasm")]
+pub mod wasm;
@@ -0,0 +1,212 @@
+use crate::common::*;
+
+use std::process::Stdio;
+
+use crate::config::{DEFAULT_SHELL, DEFAULT_SHELL_ARGUMENTS};
+
+/// Interpolations are replaced with this before recipe bodies are passed to
+/// the linter, since evaluating them might have side effects
+const PLACEHOLDER: &str = "just_interpolation";
+
+/// Shells whose scripts are linted. Shebang recipes run by anything else are
+/// skipped.
+const SHELLS: &[&str] = &["bash", "dash", "ksh", "sh"];
+
+/// A recipe body as a shell script, along with where each of its lines came
+/// from in the justfile
+struct Script {
+  text: String,
+  /// For each line of `text`, where it came from, or `None` for lines that
+  /// were added
+  lines: Vec<Option<Line>>,
+}
+
+struct Line {
+  /// The 1-based justfile line number
+  number: usize,
+  /// The script and justfile columns each text fragment starts at
+  segments: Vec<(usize, usize)>,
+}
+
+impl Script {
+  fn new(recipe: &Recipe, shell: &str) -> Option<Script> {
+    if recipe.lines.is_empty() {
+      return None;
+    }
+
+    let mut script = Script {
+      text: String::new(),
+      lines: Vec::new(),
+    };
+
+    if recipe.shebang {
+      let shebang = Self::render(&recipe.lines[0]).0;
+      if !SHELLS.contains(&Self::program(&shebang)?) {
+        return None;
+      }
+    } else {
+      if !SHELLS.contains(&Path::new(shell).file_name()?.to_str()?) {
+        return None;
+      }
+      script.text += &format!("#!/usr/bin/env {}\n", shell);
+      script.lines.push(None);
+    }
+
+    for (i, line) in recipe.lines.iter().enumerate() {
+      let (mut text, mut segments) = Self::render(line);
+
+      // The `@` and `-` prefixes are interpreted by just, not the shell
+      if !recipe.shebang {
+        let prefix = if text.starts_with("@-") || text.starts_with("-@") {
+          2
+        } else if text.starts_with('@') || text.starts_with('-') {
+          1
+        } else {
+          0
+        };
+        text.drain(..prefix);
+        for (start, _) in &mut segments {
+          *start = start.saturating_sub(prefix);
+        }
+        if let Some((_, column)) = segments.first_mut() {
+          *column += prefix;
+        }
+      }
+
+      script.text += &text;
+      script.text += "\n";
+      script.lines.push(Some(Line {
+        number: recipe.line_number + 2 + i,
+        segments,
+      }));
+    }
+
+    Some(script)
+  }
+
+  /// Render a line with interpolations replaced, returning it along with the
+  /// script and justfile columns each text fragment starts at
+  fn render(line: &[Fragment]) -> (String, Vec<(usize, usize)>) {
+    let mut text = String::new();
+    let mut segments = Vec::new();
+    for fragment in line {
+      match fragment {
+        Fragment::Text { text: token } => {
+          segments.push((text.chars().count(), token.column));
+          text += &Fragment::unescape(token);
+        }
+        Fragment::Expression { .. } => text += PLACEHOLDER,
+      }
+    }
+    (text, segments)
+  }
+
+  /// The name of the program a shebang line runs, looking through `env`
+  fn program(shebang: &str) -> Option<&str> {
+    let shebang = Shebang::new(shebang)?;
+    let interpreter = Path::new(shebang.interpreter).file_name()?.to_str()?;
+    let program = if interpreter == "env" {
+      shebang.arguments.first()?.split_whitespace().next()?
+    } else {
+      interpreter
+    };
+    Path::new(program).file_name()?.to_str()
+  }
+
+  /// Rewrite a diagnostic in the `-:LINE:COLUMN: MESSAGE` format to point
+  /// into the justfile at `path`. Lines in other formats are returned as-is.
+  fn locate(&self, diagnostic: &str, path: &Path) -> Option<String> {
+    let mut pieces = diagnostic.splitn(4, ':');
+    pieces.next()?;
+    let line = pieces.next()?.parse::<usize>().ok()?;
+    let column = pieces.next()?.parse::<usize>().ok()?;
+    let message = pieces.next()?;
+
+    let line = match self.lines.get(line.checked_sub(1)?)? {
+      Some(line) => line,
+      None => return Some(format!("{}:{}", path.display(), message)),
+    };
+
+    // Columns are 1-based. Those within interpolations are approximate.
+    let column = match line
+      .segments
+      .iter()
+      .rev()
+      .find(|(start, _)| *start < column)
+    {
+      Some((start, justfile)) => justfile + (column - start),
+      None => column,
+    };
+
+    Some(format!(
+      "{}:{}:{}:{}",
+      path.display(),
+      line.number,
+      column,
+      message
+    ))
+  }
+}
+
+/// Pass the body of each recipe written in a shell to `linter`, replacing
+/// interpolations with a placeholder, and print its diagnostics with the
+/// justfile lines they refer to. `linter` reads a script from standard input
+/// and prints diagnostics in the `-:LINE:COLUMN: MESSAGE` format. It's run
+/// with the default shell, since the shell recipes are run with may not be
+/// able to run it.
+pub(crate) fn lint(
+  justfile: &Justfile,
+  path: &Path,
+  config: &Config,
+  linter: &str,
+) -> Result<(), i32> {
+  let mut clean = true;
+
+  let shell = config.shell;
+
+  for recipe in justfile.recipes.sorted_values() {
+    let script = match Script::new(recipe, shell) {
+      Some(script) => script,
+      None => continue,
+    };
+
+    let mut command = Platform::make_shell_command(DEFAULT_SHELL, DEFAULT_SHELL_ARGUMENTS, linter);
+    if let Some(run_directory) = &config.run_directory {
+      command.current_dir(run_directory);
+    }
+    command.stdin(Stdio::piped()).stdout(Stdio::piped());
+
+    let output = command.spawn().and_then(|mut child| {
+      let mut stdin = child.stdin.take().unwrap();
+      let text = script.text.clone();
+      let writer = thread::spawn(move || stdin.write_all(text.as_bytes()));
+      let output = child.wait_with_output()?;
+      // The linter may exit without reading all of its input
+      let _ = writer.join();
+      Ok(output)
+    });
+
+    let output = match output {
+      Ok(output) => output,
+      Err(io_error) => {
+        eprintln!("Failed to run linter `{}`: {}", linter, io_error);
+        return Err(EXIT_FAILURE);
+      }
+    };
+
+    for diagnostic in String::from_utf8_lossy(&output.stdout).lines() {
+      let located = script.locate(diagnostic, path);
+      println!("{}", located.as_deref().unwrap_or(diagnostic));
+    }
+
+    if !output.status.success() {
+      clean = false;
+    }
+  }
+
+  if clean {
+    Ok(())
+  } else {
+    Err(EXIT_FAILURE)
+  }
+}
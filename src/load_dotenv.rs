@@ -1,17 +1,38 @@
 use crate::common::*;
 
-pub(crate) fn load_dotenv() -> RunResult<'static, BTreeMap<String, String>> {
-  match dotenv::dotenv_iter() {
-    Ok(iter) => {
-      let result: dotenv::Result<BTreeMap<String, String>> = iter.collect();
-      result.map_err(|dotenv_error| RuntimeError::Dotenv { dotenv_error })
-    }
-    Err(dotenv_error) => {
-      if dotenv_error.not_found() {
-        Ok(BTreeMap::new())
-      } else {
-        Err(RuntimeError::Dotenv { dotenv_error })
-      }
+/// Load the variables in the file at `set dotenv-path`, relative to
+/// `directory`, or else in the file named `.env`, or `set dotenv-filename`,
+/// in `directory` or the nearest of its ancestors that has one. Nothing is
+/// loaded unless one of the dotenv settings is given.
+pub(crate) fn load_dotenv(
+  directory: &Path,
+  settings: &Settings,
+) -> RunResult<'static, BTreeMap<String, String>> {
+  if let Some(path) = &settings.dotenv_path {
+    return load_env_file(&directory.join(path));
+  }
+
+  if !settings.dotenv_load && settings.dotenv_filename.is_none() {
+    return Ok(BTreeMap::new());
+  }
+
+  let filename = settings.dotenv_filename.as_deref().unwrap_or(".env");
+
+  for directory in directory.ancestors() {
+    let path = directory.join(filename);
+    if path.is_file() {
+      return load_env_file(&path);
     }
   }
+
+  Ok(BTreeMap::new())
+}
+
+pub(crate) fn load_env_file(path: &Path) -> RunResult<'static, BTreeMap<String, String>> {
+  dotenv::from_path_iter(path)
+    .and_then(Iterator::collect)
+    .map_err(|dotenv_error| RuntimeError::EnvFileLoad {
+      path: path.to_owned(),
+      dotenv_error,
+    })
 }
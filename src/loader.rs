@@ -0,0 +1,173 @@
+use crate::common::*;
+
+use std::path::Path;
+
+use TokenKind::*;
+
+/// A justfile and the justfiles it imports or declares as modules, directly
+/// or indirectly. These justfiles are read before parsing, so that they live
+/// as long as the justfile parsed from them.
+pub(crate) struct Loader {
+  /// The path and contents of each justfile, starting with the root
+  /// justfile, or an error message if it couldn't be read
+  files: Vec<(PathBuf, Result<String, String>)>,
+}
+
+impl Loader {
+  /// Load the justfiles imported or declared as modules by the justfile at
+  /// `path`, which contains `text`, and by those justfiles in turn
+  pub(crate) fn load(path: &Path, text: String, defines: &BTreeMap<&str, &str>) -> Loader {
+    let mut loader = Loader {
+      files: vec![(path.to_owned(), Ok(text))],
+    };
+
+    let mut i = 0;
+    while i < loader.files.len() {
+      let (imports, modules) = match &loader.files[i].1 {
+        Ok(text) => Self::items(text, defines),
+        Err(_) => (Vec::new(), Vec::new()),
+      };
+
+      let importer = &loader.files[i].0;
+
+      // Modules may be in either of two places, and only those that exist
+      // are loaded
+      let paths = imports
+        .iter()
+        .map(|import| Self::resolve(importer, import))
+        .chain(
+          modules
+            .iter()
+            .flat_map(|module| Self::module_paths(importer, module).to_vec())
+            .filter(|path| path.is_file()),
+        )
+        .collect::<Vec<PathBuf>>();
+
+      for path in paths {
+        if !loader.files.iter().any(|(loaded, _)| *loaded == path) {
+          let text = fs::read_to_string(&path).map_err(|error| error.to_string());
+          loader.files.push((path, text));
+        }
+      }
+
+      i += 1;
+    }
+
+    loader
+  }
+
+  /// The same justfiles, with the root justfile's contents replaced by `text`
+  pub(crate) fn with_root(&self, text: String) -> Loader {
+    let mut files = self.files.clone();
+    files[0].1 = Ok(text);
+    Loader { files }
+  }
+
+  /// The contents of the root justfile
+  pub(crate) fn root(&self) -> &str {
+    match &self.files[0].1 {
+      Ok(text) => text,
+      Err(_) => "",
+    }
+  }
+
+  /// The path of the justfile imported as `import` by `importer`
+  pub(crate) fn resolve(importer: &Path, import: &str) -> PathBuf {
+    importer
+      .parent()
+      .unwrap_or_else(|| Path::new(""))
+      .join(import)
+  }
+
+  /// The paths that the source of the module `name`, declared by the
+  /// justfile at `importer`, may be found at, in order of preference
+  pub(crate) fn module_paths(importer: &Path, name: &str) -> [PathBuf; 2] {
+    let directory = importer.parent().unwrap_or_else(|| Path::new(""));
+    [
+      directory.join(name).join(search::FILENAME),
+      directory.join(format!("{}.just", name)),
+    ]
+  }
+
+  /// The path and contents of the source of the module `name`, declared by
+  /// the justfile at `importer`, or why it couldn't be read, if it was found
+  pub(crate) fn module(&self, importer: &Path, name: &str) -> Option<(&Path, Result<&str, &str>)> {
+    Self::module_paths(importer, name)
+      .iter()
+      .find_map(|path| self.files.iter().find(|(loaded, _)| loaded == path))
+      .map(|(path, text)| (path.as_path(), text.as_deref().map_err(String::as_str)))
+  }
+
+  /// The contents of the justfile at `path`, or why it couldn't be read
+  pub(crate) fn text(&self, path: &Path) -> Result<&str, &str> {
+    match self.files.iter().find(|(loaded, _)| loaded == path) {
+      Some((_, Ok(text))) => Ok(text),
+      Some((_, Err(message))) => Err(message),
+      None => Err("justfile was not loaded"),
+    }
+  }
+
+  /// The path of the justfile that `text`, which must be a slice of one of
+  /// the loaded justfiles, is from
+  pub(crate) fn path(&self, text: &str) -> Option<&Path> {
+    let start = text.as_ptr() as usize;
+    self
+      .files
+      .iter()
+      .find(|(_, contents)| match contents {
+        Ok(contents) => {
+          let contents_start = contents.as_ptr() as usize;
+          start >= contents_start && start <= contents_start + contents.len()
+        }
+        Err(_) => false,
+      })
+      .map(|(path, _)| path.as_path())
+  }
+
+  /// The paths of the justfiles imported by `import` items in `text`, as
+  /// written, and the names of the modules declared by `mod` items. Invalid
+  /// justfiles and items are skipped here, and reported by the parser.
+  fn items(text: &str, defines: &BTreeMap<&str, &str>) -> (Vec<String>, Vec<String>) {
+    let tokens = match Lexer::lex(text) {
+      Ok(tokens) => tokens,
+      Err(_) => return (Vec::new(), Vec::new()),
+    };
+
+    let tokens = tokens
+      .into_iter()
+      .filter(|token| token.kind != Whitespace)
+      .collect::<Vec<Token>>();
+
+    let mut imports = Vec::new();
+    let mut modules = Vec::new();
+
+    for (i, item) in tokens.windows(3).enumerate() {
+      let line_start = i == 0 || tokens[i - 1].kind == Eol || tokens[i - 1].kind == Dedent;
+      let (keyword, path, end) = (&item[0], &item[1], &item[2]);
+
+      if !line_start
+        || keyword.kind != Name
+        || (end.kind != Eol && end.kind != Eof && end.kind != Comment)
+      {
+        continue;
+      }
+
+      match (keyword.lexeme(), path.kind) {
+        ("mod", Name) => modules.push(path.lexeme().to_owned()),
+        ("import", StringRaw) | ("import", StringCooked) => {
+          if let Ok(string) = StringLiteral::new(path) {
+            imports.push(string.cooked.into_owned());
+          }
+        }
+        ("import", Name) => {
+          if let Some(value) = defines.get(path.lexeme()) {
+            imports.push((*value).to_owned());
+          }
+        }
+        _ => {}
+      }
+    }
+
+    (imports, modules)
+  }
+}
@@ -0,0 +1,23 @@
+use crate::common::*;
+
+/// One parameter of a recipe's `[matrix: ...]` attribute, along with the
+/// values it takes
+#[derive(PartialEq, Debug)]
+pub(crate) struct MatrixAxis<'a> {
+  pub(crate) name: &'a str,
+  pub(crate) token: Token<'a>,
+  pub(crate) values: Vec<StringLiteral<'a>>,
+}
+
+impl<'a> Display for MatrixAxis<'a> {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    write!(f, "{}=(", self.name)?;
+    for (i, value) in self.values.iter().enumerate() {
+      if i > 0 {
+        write!(f, "|")?;
+      }
+      write!(f, "{}", value)?;
+    }
+    write!(f, ")")
+  }
+}
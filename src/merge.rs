@@ -0,0 +1,255 @@
+use crate::common::*;
+
+/// Combines justfiles, remembering which justfile each item came from so
+/// that conflicts can be reported
+struct Merger<'a> {
+  justfile: Justfile<'a>,
+  /// The justfile that each recipe, alias, variable, and setting was first
+  /// defined in, keyed by kind and name
+  sources: BTreeMap<(&'static str, &'a str), &'a Path>,
+  conflicts: Vec<String>,
+}
+
+impl<'a> Merger<'a> {
+  fn new() -> Merger<'a> {
+    Merger {
+      justfile: Justfile {
+        recipes: OrderedMap::new(),
+        unavailable: BTreeMap::new(),
+        assignments: OrderedMap::new(),
+        items: Vec::new(),
+        exports: BTreeMap::new(),
+        aliases: BTreeMap::new(),
+        modules: BTreeMap::new(),
+        settings: Settings::default(),
+        warnings: Vec::new(),
+      },
+      sources: BTreeMap::new(),
+      conflicts: Vec::new(),
+    }
+  }
+
+  /// Record that `name` was defined in `path`, and report a conflict if it
+  /// was already defined differently somewhere else. Returns true if the
+  /// item is new.
+  fn define(&mut self, kind: &'static str, name: &'a str, path: &'a Path, same: bool) -> bool {
+    match self.sources.get(&(kind, name)) {
+      None => {
+        self.sources.insert((kind, name), path);
+        true
+      }
+      Some(_) if same => false,
+      Some(original) => {
+        self.conflicts.push(format!(
+          "{} `{}` is defined differently in `{}` and `{}`",
+          kind,
+          name,
+          original.display(),
+          path.display()
+        ));
+        false
+      }
+    }
+  }
+
+  fn add(&mut self, path: &'a Path, justfile: Justfile<'a>) {
+    let Justfile {
+      recipes,
+      unavailable,
+      assignments,
+      mut exports,
+      aliases,
+      modules,
+      settings,
+      ..
+    } = justfile;
+
+    self.settings(path, settings);
+
+    for (name, expression) in assignments {
+      let export = exports.remove(name);
+      let same = self.justfile.assignments.get(name).map(ToString::to_string)
+        == Some(expression.to_string())
+        && self.justfile.exports.get(name) == export.as_ref();
+      if self.define("Variable", name, path, same) {
+        self.justfile.items.push(Item::Assignment(name));
+        self.justfile.assignments.insert(name, expression);
+        if let Some(export) = export {
+          self.justfile.exports.insert(name, export);
+        }
+      }
+    }
+
+    for (name, alias) in aliases {
+      let same = self.justfile.aliases.get(name).map(|alias| alias.target) == Some(alias.target);
+      if self.define("Alias", name, path, same) {
+        self.justfile.aliases.insert(name, alias);
+        self.justfile.items.push(Item::Alias(name));
+      }
+    }
+
+    // Modules are declared by name, and only their contents can be compared
+    for (name, module) in modules {
+      let same =
+        self.justfile.modules.get(name).map(ToString::to_string) == Some(module.to_string());
+      if self.define("Module", name, path, same) {
+        self.justfile.modules.insert(name, module);
+        self.justfile.items.push(Item::Module(name));
+      }
+    }
+
+    for (name, recipe) in recipes {
+      let same =
+        self.justfile.recipes.get(name).map(ToString::to_string) == Some(recipe.to_string());
+      if self.define("Recipe", name, path, same) {
+        self.justfile.recipes.insert(name, recipe);
+        self.justfile.items.push(Item::Recipe(name));
+      }
+    }
+
+    for (name, platforms) in unavailable {
+      self.justfile.unavailable.entry(name).or_insert(platforms);
+    }
+  }
+
+  fn settings(&mut self, path: &'a Path, settings: Settings) {
+    let merged = &mut self.justfile.settings;
+    merged.dotenv_load |= settings.dotenv_load;
+    merged.env_file |= settings.env_file;
+    merged.follow_symlinks |= settings.follow_symlinks;
+    merged.force_color |= settings.force_color;
+    merged.one_shell |= settings.one_shell;
+    merged.positional_arguments |= settings.positional_arguments;
+    merged.shebang_error_line |= settings.shebang_error_line;
+    merged.windows_powershell |= settings.windows_powershell;
+
+    if !settings.export_prefix.is_empty() {
+      let same = merged.export_prefix == settings.export_prefix;
+      if self.define("Setting", "export-prefix", path, same) {
+        self.justfile.settings.export_prefix = settings.export_prefix;
+      }
+    }
+
+    if let Some(filename) = settings.dotenv_filename {
+      let same = self.justfile.settings.dotenv_filename.as_ref() == Some(&filename);
+      if self.define("Setting", "dotenv-filename", path, same) {
+        self.justfile.settings.dotenv_filename = Some(filename);
+      }
+    }
+
+    if let Some(dotenv_path) = settings.dotenv_path {
+      let same = self.justfile.settings.dotenv_path.as_ref() == Some(&dotenv_path);
+      if self.define("Setting", "dotenv-path", path, same) {
+        self.justfile.settings.dotenv_path = Some(dotenv_path);
+      }
+    }
+
+    if let Some(shell) = settings.shell {
+      let same = self.justfile.settings.shell.as_ref() == Some(&shell);
+      if self.define("Setting", "shell", path, same) {
+        self.justfile.settings.shell = Some(shell);
+      }
+    }
+
+    if let Some(windows_shell) = settings.windows_shell {
+      let same = self.justfile.settings.windows_shell.as_ref() == Some(&windows_shell);
+      if self.define("Setting", "windows-shell", path, same) {
+        self.justfile.settings.windows_shell = Some(windows_shell);
+      }
+    }
+
+    if let Some(verbosity) = settings.verbosity {
+      let same = self.justfile.settings.verbosity == Some(verbosity);
+      if self.define("Setting", "verbosity", path, same) {
+        self.justfile.settings.verbosity = Some(verbosity);
+      }
+    }
+  }
+
+  /// Report aliases that have the same name as a recipe from another
+  /// justfile
+  fn check_aliases(&mut self) {
+    for name in self.justfile.aliases.keys() {
+      if self.justfile.recipes.contains_key(name) {
+        self.conflicts.push(format!(
+          "Alias `{}` from `{}` has the same name as recipe `{}` from `{}`",
+          name,
+          self.sources[&("Alias", *name)].display(),
+          name,
+          self.sources[&("Recipe", *name)].display()
+        ));
+      }
+    }
+  }
+}
+
+/// Combine the justfiles at `paths` into one, and write it to `output`, or
+/// to standard output if no output path is given. Recipes, aliases, and
+/// variables defined in more than one justfile must be defined identically,
+/// and settings may not be given different values. Constants in `defines`
+/// are substituted into the result.
+pub(crate) fn merge(
+  paths: &[&Path],
+  output: Option<&Path>,
+  defines: &BTreeMap<&str, &str>,
+) -> Result<(), i32> {
+  let mut loaders = Vec::new();
+  for path in paths {
+    match fs::read_to_string(path) {
+      Ok(text) => loaders.push(Loader::load(path, text, defines)),
+      Err(error) => {
+        eprintln!("Error reading justfile `{}`: {}", path.display(), error);
+        return Err(EXIT_FAILURE);
+      }
+    }
+  }
+
+  let mut merger = Merger::new();
+
+  for (path, loader) in paths.iter().zip(&loaders) {
+    match Parser::parse_with_loader(loader, defines) {
+      Ok(justfile) => merger.add(path, justfile),
+      Err(error) => {
+        eprintln!("Error parsing justfile `{}`:", path.display());
+        eprintln!("{}", error);
+        return Err(error.exit_code());
+      }
+    }
+  }
+
+  merger.check_aliases();
+
+  if !merger.conflicts.is_empty() {
+    for conflict in &merger.conflicts {
+      eprintln!("error: {}", conflict);
+    }
+    return Err(EXIT_FAILURE);
+  }
+
+  let merged = merger.justfile.to_string();
+
+  // Items that are valid on their own may not be valid together, for
+  // example if a parameter shadows a variable from another justfile
+  if let Err(error) = Parser::parse(&merged) {
+    eprintln!("Merged justfile is invalid:");
+    eprintln!("{}", error);
+    return Err(EXIT_FAILURE);
+  }
+
+  match output {
+    Some(output) => {
+      if let Err(error) = fs::write(output, merged + "\n") {
+        eprintln!(
+          "Failed to write justfile to `{}`: {}",
+          output.display(),
+          error
+        );
+        return Err(EXIT_FAILURE);
+      }
+      eprintln!("Wrote justfile to `{}`", output.display());
+    }
+    None => println!("{}", merged),
+  }
+
+  Ok(())
+}
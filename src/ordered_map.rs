@@ -0,0 +1,154 @@
+use crate::common::*;
+
+use std::{borrow::Borrow, iter::FromIterator, ops::Index};
+
+/// A map that iterates over its entries in the order they were first
+/// inserted, used for the recipes and variables of a justfile so that they
+/// keep the order they appear in the source. An index from each key to its
+/// position gives fast lookup, and iteration in sorted order, for listings
+/// that are alphabetical.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct OrderedMap<K: Ord + Copy, V> {
+  entries: Vec<(K, V)>,
+  indices: BTreeMap<K, usize>,
+}
+
+impl<K: Ord + Copy, V> OrderedMap<K, V> {
+  pub(crate) fn new() -> OrderedMap<K, V> {
+    OrderedMap {
+      entries: Vec::new(),
+      indices: BTreeMap::new(),
+    }
+  }
+
+  pub(crate) fn len(&self) -> usize {
+    self.entries.len()
+  }
+
+  pub(crate) fn contains_key<Q: Ord + ?Sized>(&self, key: &Q) -> bool
+  where
+    K: Borrow<Q>,
+  {
+    self.indices.contains_key(key)
+  }
+
+  pub(crate) fn get<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&V>
+  where
+    K: Borrow<Q>,
+  {
+    self.indices.get(key).map(|&index| &self.entries[index].1)
+  }
+
+  /// Insert `value` under `key`. A key that is already present keeps its
+  /// position, and its old value is returned.
+  pub(crate) fn insert(&mut self, key: K, value: V) -> Option<V> {
+    match self.indices.get(&key) {
+      Some(&index) => Some(mem::replace(&mut self.entries[index].1, value)),
+      None => {
+        self.indices.insert(key, self.entries.len());
+        self.entries.push((key, value));
+        None
+      }
+    }
+  }
+
+  pub(crate) fn keys(&self) -> impl Iterator<Item = &K> + Clone {
+    self.entries.iter().map(|(key, _)| key)
+  }
+
+  pub(crate) fn values(&self) -> impl Iterator<Item = &V> + Clone {
+    self.entries.iter().map(|(_, value)| value)
+  }
+
+  /// The entries, sorted by key
+  pub(crate) fn sorted(&self) -> impl Iterator<Item = (&K, &V)> + Clone {
+    self
+      .indices
+      .iter()
+      .map(move |(key, &index)| (key, &self.entries[index].1))
+  }
+
+  pub(crate) fn sorted_keys(&self) -> impl Iterator<Item = &K> + Clone {
+    self.indices.keys()
+  }
+
+  pub(crate) fn sorted_values(&self) -> impl Iterator<Item = &V> + Clone {
+    self.sorted().map(|(_, value)| value)
+  }
+}
+
+impl<K: Ord + Copy, V> Default for OrderedMap<K, V> {
+  fn default() -> OrderedMap<K, V> {
+    OrderedMap::new()
+  }
+}
+
+impl<K: Ord + Copy + Borrow<Q>, Q: Ord + ?Sized, V> Index<&Q> for OrderedMap<K, V> {
+  type Output = V;
+
+  fn index(&self, key: &Q) -> &V {
+    self.get(key).expect("no entry found for key")
+  }
+}
+
+impl<K: Ord + Copy, V> FromIterator<(K, V)> for OrderedMap<K, V> {
+  fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> OrderedMap<K, V> {
+    let mut map = OrderedMap::new();
+    for (key, value) in iter {
+      map.insert(key, value);
+    }
+    map
+  }
+}
+
+impl<K: Ord + Copy, V> IntoIterator for OrderedMap<K, V> {
+  type Item = (K, V);
+  type IntoIter = vec::IntoIter<(K, V)>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.entries.into_iter()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn insertion_order() {
+    let mut map = OrderedMap::new();
+    map.insert("c", 1);
+    map.insert("a", 2);
+    map.insert("b", 3);
+    assert_eq!(map.insert("a", 4), Some(2));
+
+    assert_eq!(map.keys().cloned().collect::<Vec<&str>>(), &["c", "a", "b"]);
+    assert_eq!(map.values().cloned().collect::<Vec<i32>>(), &[1, 4, 3]);
+    assert_eq!(
+      map.sorted_keys().cloned().collect::<Vec<&str>>(),
+      &["a", "b", "c"]
+    );
+    assert_eq!(
+      map.sorted_values().cloned().collect::<Vec<i32>>(),
+      &[4, 3, 1]
+    );
+    assert_eq!(map["b"], 3);
+    assert_eq!(map.len(), 3);
+  }
+
+  #[test]
+  fn from_iter() {
+    let map = vec![("b", 1), ("c", 2), ("a", 3), ("c", 4)]
+      .into_iter()
+      .collect::<OrderedMap<&str, i32>>();
+
+    assert_eq!(map.get("c"), Some(&4));
+    assert_eq!(map.get("d"), None);
+    assert!(map.contains_key("a"));
+
+    assert_eq!(
+      map.into_iter().collect::<Vec<(&str, i32)>>(),
+      &[("b", 1), ("c", 4), ("a", 3)]
+    );
+  }
+}
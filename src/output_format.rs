@@ -0,0 +1,9 @@
+/// The format of the output that just itself prints, from `--output-format`
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub(crate) enum OutputFormat {
+  /// Plain output, for reading in a terminal
+  Human,
+  /// Plain output, along with GitHub Actions workflow commands that group
+  /// the output of each recipe and annotate errors with their location
+  Github,
+}
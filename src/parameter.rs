@@ -3,16 +3,23 @@ use crate::common::*;
 #[derive(PartialEq, Debug)]
 pub(crate) struct Parameter<'a> {
   pub(crate) default: Option<Expression<'a>>,
+  pub(crate) doc: Option<&'a str>,
+  /// Whether the parameter was written with `$`, so that its value is
+  /// exported to the recipe's commands as an environment variable
+  pub(crate) export: bool,
+  pub(crate) kind: ParameterKind,
   pub(crate) name: &'a str,
   pub(crate) token: Token<'a>,
-  pub(crate) variadic: bool,
 }
 
 impl<'a> Display for Parameter<'a> {
   fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
     let color = Color::fmt(f);
-    if self.variadic {
-      write!(f, "{}", color.annotation().paint("+"))?;
+    if self.kind.is_variadic() {
+      write!(f, "{}", color.annotation().paint(self.kind.prefix()))?;
+    }
+    if self.export {
+      write!(f, "$")?;
     }
     write!(f, "{}", color.parameter().paint(self.name))?;
     if let Some(ref default) = self.default {
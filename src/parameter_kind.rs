@@ -0,0 +1,25 @@
+/// Whether a parameter takes a single argument, or the rest of them
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) enum ParameterKind {
+  /// `+NAME`, which takes one or more arguments
+  Plus,
+  /// `NAME`, which takes one argument
+  Singular,
+  /// `*NAME`, which takes zero or more arguments
+  Star,
+}
+
+impl ParameterKind {
+  pub(crate) fn is_variadic(self) -> bool {
+    self != ParameterKind::Singular
+  }
+
+  /// The sigil written before the parameter's name
+  pub(crate) fn prefix(self) -> &'static str {
+    match self {
+      ParameterKind::Plus => "+",
+      ParameterKind::Singular => "",
+      ParameterKind::Star => "*",
+    }
+  }
+}
@@ -3,45 +3,233 @@ use crate::common::*;
 use CompilationErrorKind::*;
 use TokenKind::*;
 
+/// Attributes that limit a recipe to the platforms they name
+pub(crate) const OS_ATTRIBUTES: &[&str] = &["linux", "macos", "unix", "windows"];
+
 pub(crate) struct Parser<'a> {
   text: &'a str,
   tokens: itertools::PutBackN<vec::IntoIter<Token<'a>>>,
-  recipes: BTreeMap<&'a str, Recipe<'a>>,
-  assignments: BTreeMap<&'a str, Expression<'a>>,
+  recipes: OrderedMap<&'a str, Recipe<'a>>,
+  assignments: OrderedMap<&'a str, Expression<'a>>,
   assignment_tokens: BTreeMap<&'a str, Token<'a>>,
-  exports: BTreeSet<&'a str>,
+  items: Vec<Item<'a>>,
+  exports: BTreeMap<&'a str, Option<Export>>,
   aliases: BTreeMap<&'a str, Alias<'a>>,
   alias_tokens: BTreeMap<&'a str, Token<'a>>,
+  settings: Settings,
+  setting_names: BTreeSet<&'a str>,
+  attribute: Option<Token<'a>>,
+  export: Option<(Token<'a>, Option<Export>)>,
+  interactive: bool,
+  /// The pending OS attributes, such as `[linux]`, of which the current
+  /// platform must match at least one
+  os: Vec<Token<'a>>,
+  /// Recipes left out because of their OS attributes, mapped to those
+  /// attributes
+  unavailable: BTreeMap<&'a str, Vec<&'a str>>,
+  private: bool,
+  no_interpolation: bool,
+  process_options: ProcessOptions,
+  invocation_context: Option<(Token<'a>, InvocationContext)>,
+  matrix: Option<Vec<MatrixAxis<'a>>>,
+  /// The number of job slots the next recipe takes, from `[weight: N]`
+  weight: Option<usize>,
+  /// The command that decides whether to skip the next recipe, from
+  /// `[skip-if: 'COMMAND']`
+  skip_if: Option<String>,
+  /// Whether to ask before running the next recipe, from `[confirm]`, and
+  /// the question to ask, if one was given
+  confirm: Option<Option<String>>,
+  /// Metadata for the next recipe, from `[meta: KEY='VALUE', ...]`
+  meta: BTreeMap<&'a str, String>,
+  /// The groups the next recipe is listed under, from `[group: 'NAME']`
+  groups: BTreeSet<String>,
+  /// The pending `[cfg]` attribute, and whether its condition holds
+  cfg: Option<(Token<'a>, bool)>,
   warnings: Vec<Warning<'a>>,
+  /// Constants given with `--define`, which may be used in place of strings
+  /// in attributes and settings
+  defines: BTreeMap<&'a str, &'a str>,
+  /// The justfiles that may be imported, if the justfile was read from a file
+  loader: Option<&'a Loader>,
+  /// The justfiles that have already been imported
+  imported: BTreeSet<PathBuf>,
+  modules: BTreeMap<&'a str, Justfile<'a>>,
+  module_tokens: BTreeMap<&'a str, Token<'a>>,
+  /// The justfile being parsed and the justfiles of the modules that
+  /// contain it, used to detect modules that contain themselves
+  ancestors: Vec<PathBuf>,
 }
 
 impl<'a> Parser<'a> {
   pub(crate) fn parse(text: &'a str) -> CompilationResult<'a, Justfile> {
+    Self::parse_with_defines(text, &empty())
+  }
+
+  pub(crate) fn parse_with_defines(
+    text: &'a str,
+    defines: &BTreeMap<&'a str, &'a str>,
+  ) -> CompilationResult<'a, Justfile<'a>> {
+    let mut tokens = Lexer::lex(text)?;
+    tokens.retain(|token| token.kind != Whitespace);
+    let mut parser = Parser::new(text, tokens);
+    parser.defines = defines.clone();
+    parser.justfile()
+  }
+
+  /// Parse the root justfile of `loader`, along with the justfiles it
+  /// imports
+  pub(crate) fn parse_with_loader(
+    loader: &'a Loader,
+    defines: &BTreeMap<&'a str, &'a str>,
+  ) -> CompilationResult<'a, Justfile<'a>> {
+    let text = loader.root();
+    let path = loader
+      .path(text)
+      .map(|path| path.to_owned())
+      .unwrap_or_default();
+    Self::parse_loaded(loader, text, path, defines, Vec::new())
+  }
+
+  /// Parse `text`, the contents of the justfile at `path` in `loader`,
+  /// which is the source of a module declared by each of `ancestors`
+  fn parse_loaded(
+    loader: &'a Loader,
+    text: &'a str,
+    path: PathBuf,
+    defines: &BTreeMap<&'a str, &'a str>,
+    mut ancestors: Vec<PathBuf>,
+  ) -> CompilationResult<'a, Justfile<'a>> {
     let mut tokens = Lexer::lex(text)?;
     tokens.retain(|token| token.kind != Whitespace);
-    let parser = Parser::new(text, tokens);
+    let mut parser = Parser::new(text, tokens);
+    parser.defines = defines.clone();
+    parser.loader = Some(loader);
+    parser.imported.insert(path.clone());
+    ancestors.push(path);
+    parser.ancestors = ancestors;
     parser.justfile()
   }
 
+  /// Parse `text`, an expression given on the command line, which may use
+  /// the variables of `justfile`
+  pub(crate) fn parse_expression(
+    text: &'a str,
+    justfile: &Justfile,
+  ) -> CompilationResult<'a, Expression<'a>> {
+    let text = text.trim();
+    let mut tokens = Lexer::lex(text)?;
+    tokens.retain(|token| token.kind != Whitespace);
+    let mut parser = Parser::new(text, tokens);
+
+    let expression = parser.expression()?;
+    if let Some(token) = parser.expect_eol() {
+      return Err(parser.unexpected_token(&token, &[Plus, Slash, Eof]));
+    }
+
+    for variable in expression.variables() {
+      let name = variable.lexeme();
+      if !justfile.assignments.contains_key(name) {
+        return Err(variable.error(UndefinedVariable { variable: name }));
+      }
+    }
+
+    for (function, argc) in expression.functions() {
+      Function::resolve(function, argc)?;
+    }
+
+    Ok(expression)
+  }
+
   pub(crate) fn new(text: &'a str, tokens: Vec<Token<'a>>) -> Parser<'a> {
     Parser {
       tokens: itertools::put_back_n(tokens),
       recipes: empty(),
       assignments: empty(),
       assignment_tokens: empty(),
+      items: Vec::new(),
       exports: empty(),
       aliases: empty(),
       alias_tokens: empty(),
+      settings: Settings::default(),
+      setting_names: empty(),
+      attribute: None,
+      export: None,
+      interactive: false,
+      os: Vec::new(),
+      unavailable: BTreeMap::new(),
+      private: false,
+      no_interpolation: false,
+      process_options: ProcessOptions::default(),
+      invocation_context: None,
+      matrix: None,
+      weight: None,
+      skip_if: None,
+      confirm: None,
+      meta: empty(),
+      groups: empty(),
+      cfg: None,
       warnings: Vec::new(),
+      defines: empty(),
+      loader: None,
+      imported: empty(),
+      modules: empty(),
+      module_tokens: empty(),
+      ancestors: Vec::new(),
       text,
     }
   }
 
+  fn next(&mut self) -> CompilationResult<'a, Token<'a>> {
+    self
+      .tokens
+      .next()
+      .ok_or_else(|| self.unexpected_end_of_token_stream())
+  }
+
   fn peek(&mut self, kind: TokenKind) -> bool {
-    let next = self.tokens.next().unwrap();
-    let result = next.kind == kind;
-    self.tokens.put_back(next);
-    result
+    if let Some(next) = self.tokens.next() {
+      let result = next.kind == kind;
+      self.tokens.put_back(next);
+      result
+    } else {
+      false
+    }
+  }
+
+  fn peek_keyword(&mut self, keyword: &str) -> bool {
+    if let Some(next) = self.tokens.next() {
+      let result = next.kind == Name && next.lexeme() == keyword;
+      self.tokens.put_back(next);
+      result
+    } else {
+      false
+    }
+  }
+
+  /// Whether the tokens after an `import` keyword are the path of the
+  /// justfile to import and the end of the line
+  fn peek_import(&mut self) -> bool {
+    if let Some(next) = self.tokens.next() {
+      let result = (next.kind == StringRaw || next.kind == StringCooked || next.kind == Name)
+        && (self.peek(Eol) || self.peek(Eof) || self.peek(Comment));
+      self.tokens.put_back(next);
+      result
+    } else {
+      false
+    }
+  }
+
+  /// Whether the tokens after a `mod` keyword are the name of the module
+  /// and the end of the line
+  fn peek_module(&mut self) -> bool {
+    if let Some(next) = self.tokens.next() {
+      let result = next.kind == Name && (self.peek(Eol) || self.peek(Eof) || self.peek(Comment));
+      self.tokens.put_back(next);
+      result
+    } else {
+      false
+    }
   }
 
   fn accept(&mut self, kind: TokenKind) -> Option<Token<'a>> {
@@ -77,6 +265,20 @@ impl<'a> Parser<'a> {
     }
   }
 
+  fn unexpected_end_of_token_stream(&self) -> CompilationError<'a> {
+    CompilationError {
+      text: self.text,
+      offset: 0,
+      line: 0,
+      column: 0,
+      width: 0,
+      secondary: None,
+      kind: Internal {
+        message: "unexpected end of token stream".to_string(),
+      },
+    }
+  }
+
   fn unexpected_token(&self, found: &Token<'a>, expected: &[TokenKind]) -> CompilationError<'a> {
     found.error(UnexpectedToken {
       expected: expected.to_vec(),
@@ -87,10 +289,40 @@ impl<'a> Parser<'a> {
   fn recipe(
     &mut self,
     name: &Token<'a>,
-    doc: Option<Token<'a>>,
+    comments: &[Token<'a>],
     quiet: bool,
   ) -> CompilationResult<'a, ()> {
-    if let Some(recipe) = self.recipes.get(name.lexeme()) {
+    self.check_no_pending_export()?;
+    self.attribute = None;
+    let os = mem::take(&mut self.os);
+    let cfg_included = self
+      .cfg
+      .take()
+      .map(|(_, included)| included)
+      .unwrap_or(true);
+    let os_included = os.is_empty() || os.iter().any(|os| Self::current_os(os.lexeme()));
+    let included = cfg_included && os_included;
+    let interactive = self.interactive;
+    self.interactive = false;
+    let private = self.private || name.lexeme().starts_with('_');
+    self.private = false;
+    let no_interpolation = self.no_interpolation;
+    self.no_interpolation = false;
+    let process_options = self.process_options;
+    self.process_options = ProcessOptions::default();
+    let invocation_context = self
+      .invocation_context
+      .take()
+      .map(|(_, invocation_context)| invocation_context);
+    let matrix = self.matrix.take();
+    let weight = self.weight.take().unwrap_or(1);
+    let skip_if = self.skip_if.take();
+    let confirm = self.confirm.take();
+    let meta = mem::take(&mut self.meta);
+    let groups = mem::take(&mut self.groups);
+
+    if let Some(recipe) = self.recipes.get(name.lexeme()).filter(|_| included) {
+      self.check_import_conflict("Recipe", &recipe.name_token, name)?;
       return Err(name.error(DuplicateRecipe {
         recipe: recipe.name,
         first: recipe.line_number,
@@ -101,20 +333,26 @@ impl<'a> Parser<'a> {
     let mut parsed_variadic_parameter = false;
     let mut parameters: Vec<Parameter> = vec![];
     loop {
-      let plus = self.accept(Plus);
+      let sigil = self.accept(Plus).or_else(|| self.accept(Asterisk));
+
+      let dollar = self.accept(Dollar);
 
       let parameter = match self.accept(Name) {
         Some(parameter) => parameter,
         None => {
-          if let Some(plus) = plus {
-            return Err(self.unexpected_token(&plus, &[Name]));
+          if let Some(sigil) = dollar.or(sigil) {
+            return Err(self.unexpected_token(&sigil, &[Name]));
           } else {
             break;
           }
         }
       };
 
-      let variadic = plus.is_some();
+      let kind = match sigil.map(|sigil| sigil.kind) {
+        Some(Plus) => ParameterKind::Plus,
+        Some(_) => ParameterKind::Star,
+        None => ParameterKind::Singular,
+      };
 
       if parsed_variadic_parameter {
         return Err(parameter.error(ParameterFollowsVariadicParameter {
@@ -136,52 +374,143 @@ impl<'a> Parser<'a> {
         default = None
       }
 
-      if parsed_parameter_with_default && default.is_none() {
+      if parsed_parameter_with_default && default.is_none() && kind != ParameterKind::Star {
         return Err(parameter.error(RequiredParameterFollowsDefaultParameter {
           parameter: parameter.lexeme(),
         }));
       }
 
       parsed_parameter_with_default |= default.is_some();
-      parsed_variadic_parameter = variadic;
+      parsed_variadic_parameter = kind.is_variadic();
 
       parameters.push(Parameter {
         name: parameter.lexeme(),
         token: parameter,
+        doc: None,
+        export: dollar.is_some(),
         default,
-        variadic,
+        kind,
       });
     }
 
+    // Comments of the form `# PARAMETER: DESCRIPTION` directly above the
+    // recipe document its parameters, and the first other comment above
+    // those is the recipe's doc comment
+    let mut doc = None;
+    for comment in comments.iter().rev() {
+      let text = comment.lexeme()[1..].trim();
+      if let Some(i) = text.find(':') {
+        if let Some(parameter) = parameters
+          .iter_mut()
+          .find(|parameter| parameter.name == &text[..i] && parameter.doc.is_none())
+        {
+          parameter.doc = Some(text[i + 1..].trim());
+          continue;
+        }
+      }
+      doc = Some(text);
+      break;
+    }
+
     if let Some(token) = self.expect(Colon) {
       // if we haven't accepted any parameters, a :=
       // would have been fine as part of an assignment
       if parameters.is_empty() {
-        return Err(self.unexpected_token(&token, &[Name, Plus, Colon, ColonEquals]));
+        return Err(
+          self.unexpected_token(&token, &[Name, Plus, Asterisk, Dollar, Colon, ColonEquals]),
+        );
       } else {
-        return Err(self.unexpected_token(&token, &[Name, Plus, Colon]));
+        return Err(self.unexpected_token(&token, &[Name, Plus, Asterisk, Dollar, Colon]));
       }
     }
 
+    let matrix = match matrix {
+      Some(axes) => {
+        Self::check_matrix(name, &parameters, &axes)?;
+        axes
+      }
+      None => Vec::new(),
+    };
+
     let mut dependencies = vec![];
     let mut dependency_tokens = vec![];
-    while let Some(dependency) = self.accept(Name) {
-      if dependencies.contains(&dependency.lexeme()) {
-        return Err(dependency.error(DuplicateDependency {
-          recipe: name.lexeme(),
-          dependency: dependency.lexeme(),
-        }));
+    let mut parallel_dependencies = vec![];
+    let mut external_dependencies: Vec<ExternalDependency> = vec![];
+    // Dependencies after `&&` run after the recipe instead of before it
+    let mut priors = None;
+    loop {
+      if priors.is_none() && self.accepted(AmpersandAmpersand) {
+        priors = Some(dependencies.len());
+      } else if let Some(dependency) = self.accept(Name) {
+        if dependencies.contains(&dependency.lexeme()) {
+          return Err(dependency.error(DuplicateDependency {
+            recipe: name.lexeme(),
+            dependency: dependency.lexeme(),
+          }));
+        }
+        dependencies.push(dependency.lexeme());
+        dependency_tokens.push(dependency);
+      } else if self.accepted(BracketL) {
+        // Dependencies in brackets may run in parallel with each other
+        let start = dependencies.len();
+        loop {
+          let dependency = match self.accept(Name) {
+            Some(dependency) => dependency,
+            None => {
+              let token = self.next()?;
+              return Err(self.unexpected_token(&token, &[Name]));
+            }
+          };
+          if dependencies.contains(&dependency.lexeme()) {
+            return Err(dependency.error(DuplicateDependency {
+              recipe: name.lexeme(),
+              dependency: dependency.lexeme(),
+            }));
+          }
+          dependencies.push(dependency.lexeme());
+          dependency_tokens.push(dependency);
+          if !self.accepted(Comma) || self.peek(BracketR) {
+            break;
+          }
+        }
+        if let Some(token) = self.expect(BracketR) {
+          return Err(self.unexpected_token(&token, &[Comma, BracketR]));
+        }
+        parallel_dependencies.push(start..dependencies.len());
+      } else if priors.is_none() && self.peek(Path) {
+        let path = self.next()?;
+        let dependency = self.external_dependency(path)?;
+        if external_dependencies
+          .iter()
+          .any(|other| other.key == dependency.key)
+        {
+          return Err(dependency.token.error(DuplicateDependency {
+            recipe: name.lexeme(),
+            dependency: dependency.key,
+          }));
+        }
+        external_dependencies.push(dependency);
+      } else {
+        break;
       }
-      dependencies.push(dependency.lexeme());
-      dependency_tokens.push(dependency);
     }
 
     if let Some(token) = self.expect_eol() {
-      return Err(self.unexpected_token(&token, &[Name, Eol, Eof]));
+      return Err(if priors.is_some() {
+        self.unexpected_token(&token, &[Name, BracketL, Eol, Eof])
+      } else {
+        self.unexpected_token(
+          &token,
+          &[AmpersandAmpersand, Name, BracketL, Path, Eol, Eof],
+        )
+      });
     }
 
+    let priors = priors.unwrap_or(dependencies.len());
+
     let mut lines: Vec<Vec<Fragment>> = vec![];
     let mut shebang = false;
+    let mut heredoc = None;
 
     if self.accepted(Indent) {
       while !self.accepted(Dedent) {
@@ -204,6 +533,7 @@ impl<'a> Parser<'a> {
                   shebang = true;
                 }
               } else if !shebang
+                && heredoc.is_none()
                 && !lines
                   .last()
                   .and_then(|line| line.last())
@@ -223,11 +553,15 @@ impl<'a> Parser<'a> {
             });
 
             if let Some(token) = self.expect(InterpolationEnd) {
-              return Err(self.unexpected_token(&token, &[Plus, InterpolationEnd]));
+              return Err(self.unexpected_token(&token, &[Plus, Slash, InterpolationEnd]));
             }
           }
         }
 
+        if !shebang {
+          heredoc = Heredoc::after_line(heredoc, &fragments);
+        }
+
         lines.push(fragments);
       }
     }
@@ -236,27 +570,157 @@ impl<'a> Parser<'a> {
       lines.pop();
     }
 
+    if shebang {
+      if let Some(line) = lines.first() {
+        Self::check_shebang(name, line)?;
+      }
+    }
+
+    // Recipes excluded by `[cfg]` or OS attributes are parsed, so that they
+    // must still be valid, and then discarded. Those excluded by OS
+    // attributes are remembered, so that trying to run them can be reported.
+    if !included {
+      if cfg_included {
+        self
+          .unavailable
+          .entry(name.lexeme())
+          .or_insert_with(|| os.iter().map(Token::lexeme).collect());
+      }
+      return Ok(());
+    }
+
     self.recipes.insert(
       name.lexeme(),
       Recipe {
         line_number: name.line,
         name: name.lexeme(),
-        doc: doc.map(|t| t.lexeme()[1..].trim()),
-        private: &name.lexeme()[0..1] == "_",
+        name_token: name.clone(),
+        doc,
+        private,
         dependencies,
         dependency_tokens,
+        external_dependencies,
+        groups,
+        imported: !std::ptr::eq(name.text, self.text),
         lines,
+        confirm,
+        interactive,
+        invocation_context,
+        matrix,
+        meta,
+        no_interpolation,
+        parallel_dependencies,
+        priors,
         parameters,
+        process_options,
         quiet,
         shebang,
+        skip_if,
+        weight,
       },
     );
+    self.items.push(Item::Recipe(name.lexeme()));
+
+    Ok(())
+  }
+
+  /// Check that every parameter in a recipe's matrix exists, and that those
+  /// it includes come before those it doesn't, which must have defaults, so
+  /// that every combination of values is a valid set of arguments
+  fn check_matrix(
+    name: &Token<'a>,
+    parameters: &[Parameter<'a>],
+    matrix: &[MatrixAxis<'a>],
+  ) -> CompilationResult<'a, ()> {
+    for axis in matrix {
+      if !parameters
+        .iter()
+        .any(|parameter| parameter.name == axis.name)
+      {
+        return Err(axis.token.error(UnknownMatrixParameter {
+          recipe: name.lexeme(),
+          parameter: axis.name,
+        }));
+      }
+    }
+
+    let included = |parameter: &Parameter| matrix.iter().any(|axis| axis.name == parameter.name);
+
+    for (i, parameter) in parameters.iter().enumerate() {
+      if !included(parameter)
+        && (parameter.default.is_none() || parameters[i + 1..].iter().any(&included))
+      {
+        return Err(parameter.token.error(MatrixMissingParameter {
+          recipe: name.lexeme(),
+          parameter: parameter.name,
+        }));
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Parse the `::recipe` that follows the path of an external dependency
+  fn external_dependency(
+    &mut self,
+    path: Token<'a>,
+  ) -> CompilationResult<'a, ExternalDependency<'a>> {
+    for _ in 0..2 {
+      if let Some(token) = self.expect(Colon) {
+        return Err(self.unexpected_token(&token, &[Colon]));
+      }
+    }
+
+    let recipe = match self.accept(Name) {
+      Some(recipe) => recipe,
+      None => {
+        let token = self.next()?;
+        return Err(self.unexpected_token(&token, &[Name]));
+      }
+    };
+
+    Ok(ExternalDependency {
+      key: &path.text[path.offset..recipe.offset + recipe.length],
+      path: path.lexeme(),
+      recipe: recipe.lexeme(),
+      importer: self.loader.and_then(|loader| loader.path(path.text)),
+      token: path,
+    })
+  }
+
+  /// Check the shebang line of a recipe, if it contains interpolations whose
+  /// values are known at compile time
+  fn check_shebang(name: &Token<'a>, line: &[Fragment<'a>]) -> CompilationResult<'a, ()> {
+    let mut evaluated = String::new();
+    let mut interpolated = false;
+
+    for fragment in line {
+      match fragment {
+        Fragment::Text { text } => evaluated += &Fragment::unescape(text),
+        Fragment::Expression { expression } => match expression.constant() {
+          Some(value) => {
+            evaluated += &value;
+            interpolated = true;
+          }
+          None => return Ok(()),
+        },
+      }
+    }
+
+    if interpolated && Shebang::new(&evaluated).is_none() {
+      if let Some(Fragment::Text { text }) = line.first() {
+        return Err(text.error(InvalidShebang {
+          recipe: name.lexeme(),
+          line: evaluated,
+        }));
+      }
+    }
 
     Ok(())
   }
 
   fn value(&mut self) -> CompilationResult<'a, Expression<'a>> {
-    let first = self.tokens.next().unwrap();
+    let first = self.next()?;
 
     match first.kind {
       Name => {
@@ -284,6 +748,7 @@ impl<'a> Parser<'a> {
         raw: &first.lexeme()[1..first.lexeme().len() - 1],
         token: first,
       }),
+      StringCooked if first.lexeme().contains("{{") => self.interpolated_string(&first),
       StringRaw | StringCooked => Ok(Expression::String {
         cooked_string: StringLiteral::new(&first)?,
       }),
@@ -303,6 +768,10 @@ impl<'a> Parser<'a> {
   }
 
   fn expression(&mut self) -> CompilationResult<'a, Expression<'a>> {
+    if self.peek_keyword("if") {
+      return self.conditional();
+    }
+
     let lhs = self.value()?;
 
     if self.accepted(Plus) {
@@ -312,11 +781,168 @@ impl<'a> Parser<'a> {
         lhs: Box::new(lhs),
         rhs: Box::new(rhs),
       })
+    } else if self.accepted(Slash) {
+      let rhs = self.expression()?;
+
+      Ok(Expression::Join {
+        lhs: Box::new(lhs),
+        rhs: Box::new(rhs),
+      })
     } else {
       Ok(lhs)
     }
   }
 
+  /// Parse a cooked string containing `{{EXPRESSION}}` interpolations
+  fn interpolated_string(&self, token: &Token<'a>) -> CompilationResult<'a, Expression<'a>> {
+    let raw = &token.lexeme()[1..token.lexeme().len() - 1];
+
+    let mut fragments = Vec::new();
+    let mut text = String::new();
+    let mut rest = 0;
+
+    while let Some(start) = raw[rest..].find("{{").map(|start| rest + start) {
+      text += &raw[rest..start];
+
+      if raw[start..].starts_with("{{{{") {
+        text += "{{";
+        rest = start + 4;
+        continue;
+      }
+
+      // A `{{` without a matching `}}` is literal text, so that `"{{"` can be
+      // used to escape `{{` in recipe bodies
+      let end = match raw[start + 2..].find("}}") {
+        Some(end) => start + 2 + end,
+        None => {
+          rest = start;
+          break;
+        }
+      };
+
+      if !text.is_empty() {
+        fragments.push(StringFragment::Text {
+          cooked: StringLiteral::cook(token, &text)?,
+        });
+        text.clear();
+      }
+
+      fragments.push(StringFragment::Expression {
+        expression: self.interpolation(token, start + 2, end)?,
+      });
+
+      rest = end + 2;
+    }
+
+    text += &raw[rest..];
+    if !text.is_empty() {
+      fragments.push(StringFragment::Text {
+        cooked: StringLiteral::cook(token, &text)?,
+      });
+    }
+
+    Ok(Expression::InterpolatedString { raw, fragments })
+  }
+
+  /// Parse the expression between `start` and `end` in the contents of the
+  /// cooked string `token`
+  fn interpolation(
+    &self,
+    token: &Token<'a>,
+    start: usize,
+    end: usize,
+  ) -> CompilationResult<'a, Expression<'a>> {
+    let raw = &token.lexeme()[1..token.lexeme().len() - 1];
+    let inner = &raw[start..end];
+    let start = start + inner.len() - inner.trim_start().len();
+    let offset = token.offset + 1 + start;
+    let column = token.column + 1 + raw[..start].chars().count();
+
+    // Positions of tokens and errors are relative to the interpolation, so
+    // make them relative to the justfile
+    let tokens = Lexer::lex(&raw[start..end]).map_err(|error| CompilationError {
+      text: token.text,
+      offset: offset + error.offset,
+      line: token.line,
+      column: column + error.column,
+      ..error
+    })?;
+
+    let tokens = tokens
+      .into_iter()
+      .filter(|token| token.kind != Whitespace)
+      .map(|inner| Token {
+        text: token.text,
+        offset: offset + inner.offset,
+        line: token.line,
+        column: column + inner.column,
+        length: if inner.kind == Eof { 2 } else { inner.length },
+        kind: if inner.kind == Eof {
+          InterpolationEnd
+        } else {
+          inner.kind
+        },
+      })
+      .collect();
+
+    let mut parser = Parser::new(token.text, tokens);
+    parser.defines = self.defines.clone();
+
+    let expression = parser.expression()?;
+    if let Some(token) = parser.expect(InterpolationEnd) {
+      return Err(parser.unexpected_token(&token, &[Plus, Slash, InterpolationEnd]));
+    }
+
+    Ok(expression)
+  }
+
+  /// Parse `if LHS == RHS { THEN } else { OTHERWISE }`
+  fn conditional(&mut self) -> CompilationResult<'a, Expression<'a>> {
+    self.next()?;
+
+    let lhs = self.expression()?;
+    if let Some(token) = self.expect(EqualsEquals) {
+      return Err(self.unexpected_token(&token, &[Plus, Slash, EqualsEquals]));
+    }
+
+    let rhs = self.expression()?;
+    if let Some(token) = self.expect(BraceL) {
+      return Err(self.unexpected_token(&token, &[Plus, Slash, BraceL]));
+    }
+
+    let then = self.expression()?;
+    if let Some(token) = self.expect(BraceR) {
+      return Err(self.unexpected_token(&token, &[Plus, Slash, BraceR]));
+    }
+
+    let keyword = self.next()?;
+    if keyword.kind != Name {
+      return Err(self.unexpected_token(&keyword, &[Name]));
+    }
+    if keyword.lexeme() != "else" {
+      return Err(keyword.error(ExpectedKeyword {
+        expected: "else",
+        found: keyword.lexeme(),
+      }));
+    }
+
+    if let Some(token) = self.expect(BraceL) {
+      return Err(self.unexpected_token(&token, &[BraceL]));
+    }
+
+    let otherwise = self.expression()?;
+    if let Some(token) = self.expect(BraceR) {
+      return Err(self.unexpected_token(&token, &[Plus, Slash, BraceR]));
+    }
+
+    Ok(Expression::Conditional {
+      lhs: Box::new(lhs),
+      rhs: Box::new(rhs),
+      then: Box::new(then),
+      otherwise: Box::new(otherwise),
+    })
+  }
+
   fn arguments(&mut self) -> CompilationResult<'a, Vec<Expression<'a>>> {
     let mut arguments = Vec::new();
 
@@ -326,7 +952,7 @@ impl<'a> Parser<'a> {
         if self.peek(ParenR) {
           break;
         } else {
-          let next = self.tokens.next().unwrap();
+          let next = self.next()?;
           return Err(self.unexpected_token(&next, &[Comma, ParenR]));
         }
       }
@@ -336,28 +962,47 @@ impl<'a> Parser<'a> {
   }
 
   fn assignment(&mut self, name: Token<'a>, export: bool) -> CompilationResult<'a, ()> {
-    if self.assignments.contains_key(name.lexeme()) {
+    let attribute = self.export.take();
+    let included = self
+      .cfg
+      .take()
+      .map(|(_, included)| included)
+      .unwrap_or(true);
+    self.check_no_pending_attributes()?;
+
+    let expression = self.expression()?;
+    if let Some(token) = self.expect_eol() {
+      return Err(self.unexpected_token(&token, &[Plus, Slash, Eol]));
+    }
+
+    if !included {
+      return Ok(());
+    }
+
+    if let Some(first) = self.assignment_tokens.get(name.lexeme()) {
+      self.check_import_conflict("Variable", first, &name)?;
       return Err(name.error(DuplicateVariable {
         variable: name.lexeme(),
       }));
     }
-    if export {
-      self.exports.insert(name.lexeme());
-    }
-
-    let expression = self.expression()?;
-    if let Some(token) = self.expect_eol() {
-      return Err(self.unexpected_token(&token, &[Plus, Eol]));
+    if let Some((_, export)) = attribute {
+      self.exports.insert(name.lexeme(), export);
+    } else if export {
+      self.exports.insert(name.lexeme(), None);
     }
 
     self.assignments.insert(name.lexeme(), expression);
+    self.items.push(Item::Assignment(name.lexeme()));
     self.assignment_tokens.insert(name.lexeme(), name);
     Ok(())
   }
 
   fn alias(&mut self, name: Token<'a>) -> CompilationResult<'a, ()> {
+    self.check_no_pending_attributes()?;
+
     // Make sure alias doesn't already exist
     if let Some(alias) = self.aliases.get(name.lexeme()) {
+      self.check_import_conflict("Alias", &self.alias_tokens[alias.name], &name)?;
       return Err(name.error(DuplicateAlias {
         alias: alias.name,
         first: alias.line_number,
@@ -368,7 +1013,7 @@ impl<'a> Parser<'a> {
     let target = if let Some(next) = self.accept(Name) {
       next.lexeme()
     } else {
-      let unexpected = self.tokens.next().unwrap();
+      let unexpected = self.next()?;
       return Err(self.unexpected_token(&unexpected, &[Name]));
     };
 
@@ -386,513 +1031,2361 @@ impl<'a> Parser<'a> {
         target,
       },
     );
+    self.items.push(Item::Alias(name.lexeme()));
     self.alias_tokens.insert(name.lexeme(), name);
 
     Ok(())
   }
 
-  pub(crate) fn justfile(mut self) -> CompilationResult<'a, Justfile<'a>> {
-    let mut doc = None;
-    loop {
-      match self.tokens.next() {
-        Some(token) => match token.kind {
-          Eof => break,
-          Eol => {
-            doc = None;
-            continue;
-          }
-          Comment => {
-            if let Some(token) = self.expect_eol() {
-              return Err(token.error(Internal {
-                message: format!("found comment followed by {}", token.kind),
-              }));
-            }
-            doc = Some(token);
-          }
-          At => {
-            if let Some(name) = self.accept(Name) {
-              self.recipe(&name, doc, true)?;
-              doc = None;
-            } else {
-              let unexpected = &self.tokens.next().unwrap();
-              return Err(self.unexpected_token(unexpected, &[Name]));
-            }
-          }
-          Name => {
-            if token.lexeme() == "export" {
-              let next = self.tokens.next().unwrap();
-              if next.kind == Name && self.peek(Equals) {
-                self.warnings.push(Warning::DeprecatedEquals {
-                  equals: self.tokens.next().unwrap(),
-                });
-                self.assignment(next, true)?;
-                doc = None;
-              } else if next.kind == Name && self.accepted(ColonEquals) {
-                self.assignment(next, true)?;
-                doc = None;
-              } else {
-                self.tokens.put_back(next);
-                self.recipe(&token, doc, false)?;
-                doc = None;
-              }
-            } else if token.lexeme() == "alias" {
-              let next = self.tokens.next().unwrap();
-              if next.kind == Name && self.peek(Equals) {
-                self.warnings.push(Warning::DeprecatedEquals {
-                  equals: self.tokens.next().unwrap(),
-                });
-                self.alias(next)?;
-                doc = None;
-              } else if next.kind == Name && self.accepted(ColonEquals) {
-                self.alias(next)?;
-                doc = None;
-              } else {
-                self.tokens.put_back(next);
-                self.recipe(&token, doc, false)?;
-                doc = None;
-              }
-            } else if self.peek(Equals) {
-              self.warnings.push(Warning::DeprecatedEquals {
-                equals: self.tokens.next().unwrap(),
-              });
-              self.assignment(token, false)?;
-              doc = None;
-            } else if self.accepted(ColonEquals) {
-              self.assignment(token, false)?;
-              doc = None;
-            } else {
-              self.recipe(&token, doc, false)?;
-              doc = None;
-            }
-          }
-          _ => return Err(self.unexpected_token(&token, &[Name, At])),
-        },
-        None => {
-          return Err(CompilationError {
-            text: self.text,
-            offset: 0,
-            line: 0,
-            column: 0,
-            width: 0,
-            kind: Internal {
-              message: "unexpected end of token stream".to_string(),
-            },
-          });
-        }
-      }
-    }
+  fn attribute(&mut self, bracket: Token<'a>) -> CompilationResult<'a, ()> {
+    let name = if let Some(name) = self.accept(Name) {
+      name
+    } else {
+      let unexpected = self.next()?;
+      return Err(self.unexpected_token(&unexpected, &[Name]));
+    };
 
-    if let Some(token) = self.tokens.next() {
-      return Err(token.error(Internal {
-        message: format!(
-          "unexpected token remaining after parsing completed: {:?}",
-          token.kind
-        ),
-      }));
-    }
+    if name.lexeme() == "matrix" {
+      if self.matrix.is_some() {
+        return Err(name.error(DuplicateAttribute {
+          attribute: name.lexeme(),
+        }));
+      }
+      self.matrix = Some(self.matrix()?);
+    } else if name.lexeme() == "interactive" {
+      if self.interactive {
+        return Err(name.error(DuplicateAttribute {
+          attribute: name.lexeme(),
+        }));
+      }
+      self.interactive = true;
 
-    AssignmentResolver::resolve_assignments(&self.assignments, &self.assignment_tokens)?;
+      if let Some(token) = self.expect(BracketR) {
+        return Err(self.unexpected_token(&token, &[BracketR]));
+      }
+    } else if name.lexeme() == "private" {
+      if self.private {
+        return Err(name.error(DuplicateAttribute {
+          attribute: name.lexeme(),
+        }));
+      }
+      self.private = true;
 
-    RecipeResolver::resolve_recipes(&self.recipes, &self.assignments, self.text)?;
+      if let Some(token) = self.expect(BracketR) {
+        return Err(self.unexpected_token(&token, &[BracketR]));
+      }
+    } else if OS_ATTRIBUTES.contains(&name.lexeme()) {
+      if self.os.iter().any(|os| os.lexeme() == name.lexeme()) {
+        return Err(name.error(DuplicateAttribute {
+          attribute: name.lexeme(),
+        }));
+      }
+      self.os.push(name);
 
-    for recipe in self.recipes.values() {
-      for parameter in &recipe.parameters {
-        if self.assignments.contains_key(parameter.token.lexeme()) {
-          return Err(parameter.token.error(ParameterShadowsVariable {
-            parameter: parameter.token.lexeme(),
+      if let Some(token) = self.expect(BracketR) {
+        return Err(self.unexpected_token(&token, &[BracketR]));
+      }
+    } else if name.lexeme() == "no-interpolation" {
+      if self.no_interpolation {
+        return Err(name.error(DuplicateAttribute {
+          attribute: name.lexeme(),
+        }));
+      }
+      self.no_interpolation = true;
+
+      if let Some(token) = self.expect(BracketR) {
+        return Err(self.unexpected_token(&token, &[BracketR]));
+      }
+    } else if name.lexeme() == "no-network" {
+      if self.process_options.no_network {
+        return Err(name.error(DuplicateAttribute {
+          attribute: name.lexeme(),
+        }));
+      }
+      self.process_options.no_network = true;
+
+      if let Some(token) = self.expect(BracketR) {
+        return Err(self.unexpected_token(&token, &[BracketR]));
+      }
+    } else if ProcessOptions::ATTRIBUTES.contains(&name.lexeme()) {
+      if self.process_options.has(name.lexeme()) {
+        return Err(name.error(DuplicateAttribute {
+          attribute: name.lexeme(),
+        }));
+      }
+
+      if let Some(token) = self.expect(Colon) {
+        return Err(self.unexpected_token(&token, &[Colon]));
+      }
+
+      let (token, value) = self.constant()?;
+      if !self.process_options.set(name.lexeme(), &value) {
+        return Err(token.error(InvalidAttributeValue {
+          attribute: name.lexeme(),
+          value,
+        }));
+      }
+
+      if let Some(token) = self.expect(BracketR) {
+        return Err(self.unexpected_token(&token, &[BracketR]));
+      }
+    } else if name.lexeme() == "skip-if" {
+      if self.skip_if.is_some() {
+        return Err(name.error(DuplicateAttribute {
+          attribute: name.lexeme(),
+        }));
+      }
+
+      if let Some(token) = self.expect(Colon) {
+        return Err(self.unexpected_token(&token, &[Colon]));
+      }
+
+      self.skip_if = Some(self.attribute_string()?);
+
+      if let Some(token) = self.expect(BracketR) {
+        return Err(self.unexpected_token(&token, &[BracketR]));
+      }
+    } else if name.lexeme() == "confirm" {
+      if self.confirm.is_some() {
+        return Err(name.error(DuplicateAttribute {
+          attribute: name.lexeme(),
+        }));
+      }
+
+      self.confirm = Some(self.attribute_argument()?);
+
+      if let Some(token) = self.expect(BracketR) {
+        return Err(self.unexpected_token(&token, &[Colon, ParenL, BracketR]));
+      }
+    } else if name.lexeme() == "group" {
+      let group = match self.attribute_argument()? {
+        Some(group) => group,
+        None => {
+          let token = self.next()?;
+          return Err(self.unexpected_token(&token, &[Colon, ParenL]));
+        }
+      };
+      self.groups.insert(group);
+
+      if let Some(token) = self.expect(BracketR) {
+        return Err(self.unexpected_token(&token, &[BracketR]));
+      }
+    } else if name.lexeme() == "meta" {
+      if let Some(token) = self.expect(Colon) {
+        return Err(self.unexpected_token(&token, &[Colon]));
+      }
+
+      loop {
+        let key = match self.accept(Name) {
+          Some(key) => key,
+          None => {
+            let unexpected = self.next()?;
+            return Err(self.unexpected_token(&unexpected, &[Name]));
+          }
+        };
+
+        if self.meta.contains_key(key.lexeme()) {
+          return Err(key.error(DuplicateMetadataKey { key: key.lexeme() }));
+        }
+
+        if let Some(token) = self.expect(Equals) {
+          return Err(self.unexpected_token(&token, &[Equals]));
+        }
+
+        let value = self.attribute_string()?;
+        self.meta.insert(key.lexeme(), value);
+
+        if !self.accepted(Comma) {
+          break;
+        }
+      }
+
+      if let Some(token) = self.expect(BracketR) {
+        return Err(self.unexpected_token(&token, &[Comma, BracketR]));
+      }
+    } else if name.lexeme() == "weight" {
+      if self.weight.is_some() {
+        return Err(name.error(DuplicateAttribute {
+          attribute: name.lexeme(),
+        }));
+      }
+
+      if let Some(token) = self.expect(Colon) {
+        return Err(self.unexpected_token(&token, &[Colon]));
+      }
+
+      let (token, value) = match self.accept(Number) {
+        Some(token) => {
+          let value = token.lexeme().to_owned();
+          (token, value)
+        }
+        None => self.constant()?,
+      };
+      match value.parse::<usize>() {
+        Ok(weight) if weight > 0 => self.weight = Some(weight),
+        _ => {
+          return Err(token.error(InvalidAttributeValue {
+            attribute: name.lexeme(),
+            value,
+          }))
+        }
+      }
+
+      if let Some(token) = self.expect(BracketR) {
+        return Err(self.unexpected_token(&token, &[BracketR]));
+      }
+    } else if name.lexeme() == "export" || name.lexeme() == "export-path" {
+      if let Some((first, _)) = &self.export {
+        if first.lexeme() == name.lexeme() {
+          return Err(name.error(DuplicateAttribute {
+            attribute: name.lexeme(),
+          }));
+        } else {
+          return Err(name.error(ConflictingAttributes {
+            first: first.lexeme(),
+            second: name.lexeme(),
           }));
         }
       }
 
-      for dependency in &recipe.dependency_tokens {
-        if !self.recipes[dependency.lexeme()].parameters.is_empty() {
-          return Err(dependency.error(DependencyHasParameters {
-            recipe: recipe.name,
-            dependency: dependency.lexeme(),
+      let export = if name.lexeme() == "export-path" {
+        if let Some(token) = self.expect(Colon) {
+          return Err(self.unexpected_token(&token, &[Colon]));
+        }
+
+        let path = self.next()?;
+        let path = match path.lexeme().parse::<PathExport>() {
+          Ok(export) if path.kind == Name => export,
+          _ => {
+            return Err(path.error(InvalidAttributeValue {
+              attribute: name.lexeme(),
+              value: path.lexeme().to_owned(),
+            }))
+          }
+        };
+
+        Some(Export {
+          variable: self.attribute_string()?,
+          path: Some(path),
+        })
+      } else if self.accepted(Colon) {
+        Some(Export {
+          variable: self.attribute_string()?,
+          path: None,
+        })
+      } else {
+        None
+      };
+
+      if let Some(token) = self.expect(BracketR) {
+        return Err(self.unexpected_token(&token, &[BracketR]));
+      }
+
+      if let Some(token) = self.expect_eol() {
+        return Err(self.unexpected_token(&token, &[Eol, Eof]));
+      }
+
+      // Unlike the other attributes, `[export]` and `[export-path]` apply to
+      // an assignment
+      self.export = Some((name, export));
+      return Ok(());
+    } else if name.lexeme() == "cfg" {
+      if self.cfg.is_some() {
+        return Err(name.error(DuplicateAttribute {
+          attribute: name.lexeme(),
+        }));
+      }
+
+      if let Some(token) = self.expect(Colon) {
+        return Err(self.unexpected_token(&token, &[Colon]));
+      }
+
+      let included = self.cfg_predicate()?;
+
+      if let Some(token) = self.expect(BracketR) {
+        return Err(self.unexpected_token(&token, &[BracketR]));
+      }
+
+      if let Some(token) = self.expect_eol() {
+        return Err(self.unexpected_token(&token, &[Eol, Eof]));
+      }
+
+      // Like `[export]`, `[cfg]` may apply to an assignment as well as a
+      // recipe
+      self.cfg = Some((name, included));
+      return Ok(());
+    } else if let Some(invocation_context) = InvocationContext::from_attribute(name.lexeme()) {
+      if let Some((first, _)) = &self.invocation_context {
+        if first.lexeme() == name.lexeme() {
+          return Err(name.error(DuplicateAttribute {
+            attribute: name.lexeme(),
+          }));
+        } else {
+          return Err(name.error(ConflictingAttributes {
+            first: first.lexeme(),
+            second: name.lexeme(),
           }));
         }
       }
+      self.invocation_context = Some((name, invocation_context));
+
+      if let Some(token) = self.expect(BracketR) {
+        return Err(self.unexpected_token(&token, &[BracketR]));
+      }
+    } else {
+      return Err(name.error(UnknownAttribute {
+        attribute: name.lexeme(),
+      }));
     }
 
-    AliasResolver::resolve_aliases(&self.aliases, &self.recipes, &self.alias_tokens)?;
+    if let Some(token) = self.expect_eol() {
+      return Err(self.unexpected_token(&token, &[Eol, Eof]));
+    }
 
-    Ok(Justfile {
-      recipes: self.recipes,
-      assignments: self.assignments,
-      exports: self.exports,
-      aliases: self.aliases,
-      warnings: self.warnings,
-    })
+    if self.attribute.is_none() {
+      self.attribute = Some(bracket);
+    }
+
+    Ok(())
   }
-}
 
-#[cfg(test)]
-mod test {
-  use super::*;
-  use crate::testing::parse;
+  /// Whether the current platform is the one named by the OS attribute `os`
+  fn current_os(os: &str) -> bool {
+    match os {
+      "unix" => env::consts::FAMILY == "unix",
+      _ => env::consts::OS == os,
+    }
+  }
 
-  macro_rules! parse_test {
-    ($name:ident, $input:expr, $expected:expr $(,)*) => {
-      #[test]
-      fn $name() {
-        let input = $input;
-        let expected = $expected;
-        let justfile = parse(input);
-        let actual = format!("{:#}", justfile);
-        use pretty_assertions::assert_eq;
-        assert_eq!(actual, expected);
-        println!("Re-parsing...");
-        let reparsed = parse(&actual);
-        let redumped = format!("{:#}", reparsed);
-        assert_eq!(redumped, actual);
+  /// Parse a string literal attribute argument
+  fn attribute_string(&mut self) -> CompilationResult<'a, String> {
+    Ok(self.constant()?.1)
+  }
+
+  /// Parse the argument of an attribute, if it has one, given after a colon,
+  /// as in `[confirm: 'QUESTION']`, or in parentheses, as in
+  /// `[confirm('QUESTION')]`
+  fn attribute_argument(&mut self) -> CompilationResult<'a, Option<String>> {
+    if self.accepted(Colon) {
+      return Ok(Some(self.attribute_string()?));
+    }
+
+    if !self.accepted(ParenL) {
+      return Ok(None);
+    }
+
+    let argument = self.attribute_string()?;
+
+    if let Some(token) = self.expect(ParenR) {
+      return Err(self.unexpected_token(&token, &[ParenR]));
+    }
+
+    Ok(Some(argument))
+  }
+
+  /// Parse a string literal, or the name of a constant given with
+  /// `--define`, returning its token and value
+  fn constant(&mut self) -> CompilationResult<'a, (Token<'a>, String)> {
+    let token = self.next()?;
+    let value = match token.kind {
+      StringRaw | StringCooked => StringLiteral::new(&token)?.cooked.into_owned(),
+      Name => match self.defines.get(token.lexeme()) {
+        Some(value) => (*value).to_owned(),
+        None => {
+          return Err(token.error(UndefinedConstant {
+            constant: token.lexeme(),
+          }))
+        }
+      },
+      _ => return Err(self.unexpected_token(&token, &[StringRaw, StringCooked, Name])),
+    };
+    Ok((token, value))
+  }
+
+  /// Parse and evaluate the condition of a `[cfg: ...]` attribute
+  fn cfg_predicate(&mut self) -> CompilationResult<'a, bool> {
+    let name = match self.accept(Name) {
+      Some(name) => name,
+      None => {
+        let unexpected = self.next()?;
+        return Err(self.unexpected_token(&unexpected, &[Name]));
+      }
+    };
+
+    match name.lexeme() {
+      "all" | "any" | "not" => {
+        if let Some(token) = self.expect(ParenL) {
+          return Err(self.unexpected_token(&token, &[ParenL]));
+        }
+
+        let mut values = vec![self.cfg_predicate()?];
+        if name.lexeme() != "not" {
+          while self.accepted(Comma) && !self.peek(ParenR) {
+            values.push(self.cfg_predicate()?);
+          }
+        }
+
+        if let Some(token) = self.expect(ParenR) {
+          return Err(self.unexpected_token(&token, &[ParenR]));
+        }
+
+        Ok(match name.lexeme() {
+          "all" => values.iter().all(|value| *value),
+          "any" => values.iter().any(|value| *value),
+          _ => !values[0],
+        })
+      }
+      "define" | "env" | "family" | "os" => {
+        if let Some(token) = self.expect(Equals) {
+          return Err(self.unexpected_token(&token, &[Equals]));
+        }
+
+        let expected = self.constant()?.1;
+
+        Ok(match name.lexeme() {
+          "family" => expected == env::consts::FAMILY,
+          "os" => expected == env::consts::OS,
+          variable => {
+            // `NAME` checks that `NAME` is set, `NAME=VALUE` that it has
+            // the value `VALUE`
+            let (key, value) = match expected.find('=') {
+              Some(i) => (&expected[..i], Some(&expected[i + 1..])),
+              None => (expected.as_str(), None),
+            };
+
+            let actual = if variable == "define" {
+              self.defines.get(key).map(|value| value.to_string())
+            } else {
+              env::var(key).ok()
+            };
+
+            match value {
+              Some(value) => actual.as_deref() == Some(value),
+              None => actual.is_some(),
+            }
+          }
+        })
+      }
+      _ => Err(name.error(InvalidAttributeValue {
+        attribute: "cfg",
+        value: name.lexeme().to_owned(),
+      })),
+    }
+  }
+
+  /// Parse the axes of a `[matrix: ...]` attribute, after its name
+  fn matrix(&mut self) -> CompilationResult<'a, Vec<MatrixAxis<'a>>> {
+    if let Some(token) = self.expect(Colon) {
+      return Err(self.unexpected_token(&token, &[Colon]));
+    }
+
+    let mut axes: Vec<MatrixAxis> = Vec::new();
+    loop {
+      let axis = if let Some(axis) = self.accept(Name) {
+        axis
+      } else {
+        let unexpected = self.next()?;
+        return Err(self.unexpected_token(&unexpected, &[Name]));
+      };
+
+      if axes.iter().any(|other| other.name == axis.lexeme()) {
+        return Err(axis.error(DuplicateMatrixParameter {
+          parameter: axis.lexeme(),
+        }));
+      }
+
+      if let Some(token) = self.expect(Equals) {
+        return Err(self.unexpected_token(&token, &[Equals]));
+      }
+
+      if let Some(token) = self.expect(ParenL) {
+        return Err(self.unexpected_token(&token, &[ParenL]));
+      }
+
+      let mut values = Vec::new();
+      loop {
+        let value = self.next()?;
+        if value.kind != StringRaw && value.kind != StringCooked {
+          return Err(self.unexpected_token(&value, &[StringRaw, StringCooked]));
+        }
+        values.push(StringLiteral::new(&value)?);
+        if !self.accepted(Bar) {
+          break;
+        }
+      }
+
+      if let Some(token) = self.expect(ParenR) {
+        return Err(self.unexpected_token(&token, &[Bar, ParenR]));
+      }
+
+      axes.push(MatrixAxis {
+        name: axis.lexeme(),
+        token: axis,
+        values,
+      });
+
+      if !self.accepted(Comma) {
+        break;
+      }
+    }
+
+    if let Some(token) = self.expect(BracketR) {
+      return Err(self.unexpected_token(&token, &[Comma, BracketR]));
+    }
+
+    Ok(axes)
+  }
+
+  /// Attributes apply to the recipe that follows them, so one followed by
+  /// anything else is an error
+  fn check_no_pending_attributes(&mut self) -> CompilationResult<'a, ()> {
+    self.check_no_pending_export()?;
+
+    if let Some((name, _)) = self.cfg.take() {
+      return Err(name.error(AttributeWithoutItem {
+        attribute: name.lexeme(),
+      }));
+    }
+
+    if let Some(bracket) = self.attribute.take() {
+      return Err(bracket.error(AttributeWithoutRecipe));
+    }
+
+    Ok(())
+  }
+
+  /// Parse an `import` item, and insert the tokens of the imported justfile
+  /// in its place, unless it has already been imported
+  fn import(&mut self) -> CompilationResult<'a, ()> {
+    self.check_no_pending_attributes()?;
+
+    let (token, import) = self.constant()?;
+
+    if let Some(token) = self.expect_eol() {
+      return Err(self.unexpected_token(&token, &[Eol]));
+    }
+
+    let loader = match self.loader {
+      Some(loader) => loader,
+      None => {
+        return Err(token.error(UnreadableImport {
+          path: import,
+          message: "justfiles may only be imported by a justfile read from a file".to_owned(),
+        }))
+      }
+    };
+
+    let path = match loader.path(token.text) {
+      Some(importer) => Loader::resolve(importer, &import),
+      None => {
+        return Err(token.error(Internal {
+          message: format!("import `{}` is not from a loaded justfile", import),
+        }))
       }
     };
+
+    if !self.imported.insert(path.clone()) {
+      return Ok(());
+    }
+
+    let text = loader.text(&path).map_err(|message| {
+      token.error(UnreadableImport {
+        path: path.display().to_string(),
+        message: message.to_owned(),
+      })
+    })?;
+
+    // The end of the imported justfile ends its last item, so it becomes the
+    // end of a line
+    let tokens = Lexer::lex(text)?;
+    for mut token in tokens.into_iter().rev() {
+      if token.kind == Eof {
+        token.kind = Eol;
+      }
+      if token.kind != Whitespace {
+        self.tokens.put_back(token);
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Parse the source of the module named by the next token as a justfile of
+  /// its own
+  fn module(&mut self) -> CompilationResult<'a, ()> {
+    self.check_no_pending_attributes()?;
+
+    let name = self.next()?;
+
+    if let Some(token) = self.expect_eol() {
+      return Err(self.unexpected_token(&token, &[Eol]));
+    }
+
+    if let Some(first) = self.module_tokens.get(name.lexeme()) {
+      return Err(name.error(DuplicateModule {
+        module: name.lexeme(),
+        first: first.line,
+      }));
+    }
+
+    let loader = match self.loader {
+      Some(loader) => loader,
+      None => {
+        return Err(name.error(UnreadableModule {
+          module: name.lexeme(),
+          path: format!("{}.just", name.lexeme()),
+          message: "modules may only be declared by a justfile read from a file".to_owned(),
+        }))
+      }
+    };
+
+    let importer = loader.path(name.text).ok_or_else(|| {
+      name.error(Internal {
+        message: format!("module `{}` is not from a loaded justfile", name.lexeme()),
+      })
+    })?;
+
+    let (path, text) = loader.module(importer, name.lexeme()).ok_or_else(|| {
+      name.error(MissingModule {
+        module: name.lexeme(),
+      })
+    })?;
+
+    let text = text.map_err(|message| {
+      name.error(UnreadableModule {
+        module: name.lexeme(),
+        path: path.display().to_string(),
+        message: message.to_owned(),
+      })
+    })?;
+
+    if self.ancestors.iter().any(|ancestor| ancestor == path) {
+      return Err(name.error(CircularModule {
+        module: name.lexeme(),
+      }));
+    }
+
+    let module = Self::parse_loaded(
+      loader,
+      text,
+      path.to_owned(),
+      &self.defines,
+      self.ancestors.clone(),
+    )?;
+
+    self.modules.insert(name.lexeme(), module);
+    self.items.push(Item::Module(name.lexeme()));
+    self.module_tokens.insert(name.lexeme(), name);
+
+    Ok(())
+  }
+
+  /// Return an error if the item named by `second` has the same name as the
+  /// item named by `first`, which was defined in a different justfile
+  fn check_import_conflict(
+    &self,
+    kind: &'static str,
+    first: &Token<'a>,
+    second: &Token<'a>,
+  ) -> CompilationResult<'a, ()> {
+    let loader = match self.loader {
+      Some(loader) => loader,
+      None => return Ok(()),
+    };
+
+    match (loader.path(first.lexeme()), loader.path(second.lexeme())) {
+      (Some(first_path), Some(second_path)) if first_path != second_path => {
+        let mut error = second.error(ConflictingImport {
+          kind,
+          first: first_path,
+          second: second_path,
+        });
+        error.secondary = Some(Box::new(first.clone()));
+        Err(error)
+      }
+      _ => Ok(()),
+    }
+  }
+
+  /// The `[export]` and `[export-path]` attributes apply to the assignment
+  /// that follows them, so one followed by anything else is an error
+  fn check_no_pending_export(&mut self) -> CompilationResult<'a, ()> {
+    if let Some((name, _)) = self.export.take() {
+      return Err(name.error(AttributeWithoutAssignment {
+        attribute: name.lexeme(),
+      }));
+    }
+
+    Ok(())
+  }
+
+  fn setting(&mut self, name: Token<'a>) -> CompilationResult<'a, ()> {
+    self.check_no_pending_attributes()?;
+
+    if !self.setting_names.insert(name.lexeme()) {
+      return Err(name.error(DuplicateSetting {
+        setting: name.lexeme(),
+      }));
+    }
+
+    if name.lexeme() == "dotenv-filename" {
+      self.settings.dotenv_filename = Some(self.string_setting()?.1);
+      return Ok(());
+    }
+
+    if name.lexeme() == "dotenv-path" {
+      self.settings.dotenv_path = Some(self.string_setting()?.1);
+      return Ok(());
+    }
+
+    if name.lexeme() == "export-prefix" {
+      self.settings.export_prefix = self.string_setting()?.1;
+      return Ok(());
+    }
+
+    if name.lexeme() == "shell" {
+      self.settings.shell = Some(self.shell_setting()?);
+      return Ok(());
+    }
+
+    if name.lexeme() == "windows-shell" {
+      self.settings.windows_shell = Some(self.shell_setting()?);
+      return Ok(());
+    }
+
+    if name.lexeme() == "verbosity" {
+      let (token, value) = self.string_setting()?;
+      match value.parse() {
+        Ok(verbosity) => self.settings.verbosity = Some(verbosity),
+        Err(()) => return Err(token.error(InvalidVerbosity { value })),
+      }
+      return Ok(());
+    }
+
+    // A bare `set NAME` is shorthand for `set NAME := true`
+    let value = if self.accepted(ColonEquals) {
+      if let Some(value) = self.accept(Name) {
+        match value.lexeme() {
+          "true" => true,
+          "false" => false,
+          _ => {
+            return Err(value.error(InvalidSettingValue {
+              setting: name.lexeme(),
+              value: value.lexeme(),
+            }))
+          }
+        }
+      } else {
+        let unexpected = self.next()?;
+        return Err(self.unexpected_token(&unexpected, &[Name]));
+      }
+    } else {
+      true
+    };
+
+    if let Some(token) = self.expect_eol() {
+      return Err(self.unexpected_token(&token, &[Eol, Eof]));
+    }
+
+    match name.lexeme() {
+      "dotenv-load" => self.settings.dotenv_load = value,
+      "env-file" => self.settings.env_file = value,
+      "follow-symlinks" => self.settings.follow_symlinks = value,
+      "force-color" => self.settings.force_color = value,
+      "one-shell" => self.settings.one_shell = value,
+      "positional-arguments" => self.settings.positional_arguments = value,
+      "shebang-error-line" => self.settings.shebang_error_line = value,
+      "windows-powershell" => self.settings.windows_powershell = value,
+      _ => {
+        return Err(name.error(UnknownSetting {
+          setting: name.lexeme(),
+        }))
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Parse the rest of a setting whose value is a string, returning the
+  /// string's token and value
+  fn string_setting(&mut self) -> CompilationResult<'a, (Token<'a>, String)> {
+    if let Some(token) = self.expect(ColonEquals) {
+      return Err(self.unexpected_token(&token, &[ColonEquals]));
+    }
+
+    let (token, value) = self.constant()?;
+
+    if let Some(token) = self.expect_eol() {
+      return Err(self.unexpected_token(&token, &[Eol, Eof]));
+    }
+
+    Ok((token, value))
+  }
+
+  /// Parse the value of the `shell` setting, a list of strings holding the
+  /// shell and the arguments it's invoked with before each command
+  fn shell_setting(&mut self) -> CompilationResult<'a, Vec<String>> {
+    if let Some(token) = self.expect(ColonEquals) {
+      return Err(self.unexpected_token(&token, &[ColonEquals]));
+    }
+
+    let open = self.next()?;
+    if open.kind != BracketL {
+      return Err(self.unexpected_token(&open, &[BracketL]));
+    }
+
+    let mut shell = Vec::new();
+    while !self.accepted(BracketR) {
+      shell.push(self.constant()?.1);
+
+      if !self.accepted(Comma) {
+        if let Some(token) = self.expect(BracketR) {
+          return Err(self.unexpected_token(&token, &[Comma, BracketR]));
+        }
+        break;
+      }
+    }
+
+    if shell.is_empty() {
+      return Err(open.error(EmptyShell));
+    }
+
+    if let Some(token) = self.expect_eol() {
+      return Err(self.unexpected_token(&token, &[Eol, Eof]));
+    }
+
+    Ok(shell)
+  }
+
+  pub(crate) fn justfile(mut self) -> CompilationResult<'a, Justfile<'a>> {
+    let mut comments = Vec::new();
+    loop {
+      match self.tokens.next() {
+        Some(token) => match token.kind {
+          Eof => {
+            self.check_no_pending_attributes()?;
+            break;
+          }
+          Eol => {
+            comments.clear();
+            continue;
+          }
+          Comment => {
+            if let Some(token) = self.expect_eol() {
+              return Err(token.error(Internal {
+                message: format!("found comment followed by {}", token.kind),
+              }));
+            }
+            comments.push(token);
+          }
+          At => {
+            if let Some(name) = self.accept(Name) {
+              self.recipe(&name, &comments, true)?;
+              comments.clear();
+            } else {
+              let unexpected = &self.next()?;
+              return Err(self.unexpected_token(unexpected, &[Name]));
+            }
+          }
+          Name => {
+            if token.lexeme() == "export" {
+              let next = self.next()?;
+              if next.kind == Name && self.peek(Equals) {
+                let equals = self.next()?;
+                self.warnings.push(Warning::DeprecatedEquals { equals });
+                self.assignment(next, true)?;
+                comments.clear();
+              } else if next.kind == Name && self.accepted(ColonEquals) {
+                self.assignment(next, true)?;
+                comments.clear();
+              } else {
+                self.tokens.put_back(next);
+                self.recipe(&token, &comments, false)?;
+                comments.clear();
+              }
+            } else if token.lexeme() == "alias" {
+              let next = self.next()?;
+              if next.kind == Name && self.peek(Equals) {
+                let equals = self.next()?;
+                self.warnings.push(Warning::DeprecatedEquals { equals });
+                self.alias(next)?;
+                comments.clear();
+              } else if next.kind == Name && self.accepted(ColonEquals) {
+                self.alias(next)?;
+                comments.clear();
+              } else {
+                self.tokens.put_back(next);
+                self.recipe(&token, &comments, false)?;
+                comments.clear();
+              }
+            } else if token.lexeme() == "set" {
+              let next = self.next()?;
+              if next.kind == Name
+                && (self.peek(ColonEquals)
+                  || self.peek(Eol)
+                  || self.peek(Eof)
+                  || self.peek(Comment))
+              {
+                self.setting(next)?;
+                comments.clear();
+              } else {
+                self.tokens.put_back(next);
+                self.recipe(&token, &comments, false)?;
+                comments.clear();
+              }
+            } else if token.lexeme() == "import" && self.peek_import() {
+              self.import()?;
+              comments.clear();
+            } else if token.lexeme() == "mod" && self.peek_module() {
+              self.module()?;
+              comments.clear();
+            } else if self.peek(Equals) {
+              let equals = self.next()?;
+              self.warnings.push(Warning::DeprecatedEquals { equals });
+              self.assignment(token, false)?;
+              comments.clear();
+            } else if self.accepted(ColonEquals) {
+              self.assignment(token, false)?;
+              comments.clear();
+            } else {
+              self.recipe(&token, &comments, false)?;
+              comments.clear();
+            }
+          }
+          BracketL => self.attribute(token)?,
+          _ => return Err(self.unexpected_token(&token, &[Name, At, BracketL])),
+        },
+        None => return Err(self.unexpected_end_of_token_stream()),
+      }
+    }
+
+    if let Some(token) = self.tokens.next() {
+      return Err(token.error(Internal {
+        message: format!(
+          "unexpected token remaining after parsing completed: {:?}",
+          token.kind
+        ),
+      }));
+    }
+
+    AssignmentResolver::resolve_assignments(&self.assignments, &self.assignment_tokens)?;
+
+    RecipeResolver::resolve_recipes(&self.recipes, &self.assignments)?;
+
+    for recipe in self.recipes.values() {
+      for parameter in &recipe.parameters {
+        if self.assignments.contains_key(parameter.token.lexeme()) {
+          return Err(parameter.token.error(ParameterShadowsVariable {
+            parameter: parameter.token.lexeme(),
+          }));
+        }
+      }
+
+      for dependency in &recipe.dependency_tokens {
+        if !self.recipes[dependency.lexeme()].parameters.is_empty() {
+          return Err(dependency.error(DependencyHasParameters {
+            recipe: recipe.name,
+            dependency: dependency.lexeme(),
+          }));
+        }
+      }
+    }
+
+    AliasResolver::resolve_aliases(&self.aliases, &self.recipes, &self.alias_tokens)?;
+
+    if !self.settings.one_shell {
+      for recipe in self.recipes.values() {
+        if let Some(cd) = recipe.lost_directory_change() {
+          self.warnings.push(Warning::LostDirectoryChange {
+            recipe: recipe.name,
+            cd: cd.clone(),
+          });
+        }
+      }
+    }
+
+    // Variables exported with an explicit environment variable name are
+    // exported as exactly that name, and the rest are exported with the
+    // `export-prefix` prepended
+    let prefix = &self.settings.export_prefix;
+    let exports = self
+      .exports
+      .into_iter()
+      .map(|(name, export)| {
+        let export = export.unwrap_or_else(|| Export {
+          variable: format!("{}{}", prefix, name),
+          path: None,
+        });
+        (name, export)
+      })
+      .collect();
+
+    let recipes = &self.recipes;
+    self
+      .unavailable
+      .retain(|name, _| !recipes.contains_key(name));
+
+    Ok(Justfile {
+      recipes: self.recipes,
+      unavailable: self.unavailable,
+      assignments: self.assignments,
+      items: self.items,
+      exports,
+      aliases: self.aliases,
+      modules: self.modules,
+      settings: self.settings,
+      warnings: self.warnings,
+    })
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::testing::parse;
+
+  macro_rules! parse_test {
+    ($name:ident, $input:expr, $expected:expr $(,)*) => {
+      #[test]
+      fn $name() {
+        let input = $input;
+        let expected = $expected;
+        let justfile = parse(input);
+        let actual = format!("{:#}", justfile);
+        use pretty_assertions::assert_eq;
+        assert_eq!(actual, expected);
+        println!("Re-parsing...");
+        let reparsed = parse(&actual);
+        let redumped = format!("{:#}", reparsed);
+        assert_eq!(redumped, actual);
+      }
+    };
+  }
+
+  parse_test! {
+    parse_empty,
+    "
+
+# hello
+
+
+    ",
+    "",
+  }
+
+  parse_test! {
+    parse_external_dependencies,
+    "a: b ../lib/justfile::c /tmp/justfile::d\nb:",
+    "a: b ../lib/justfile::c /tmp/justfile::d\n\nb:",
+  }
+
+  parse_test! {
+    parse_parallel_dependencies,
+    "a: [b,c ,] d [e]\nb:\nc:\nd:\ne:",
+    "a: [b, c] d [e]\n\nb:\n\nc:\n\nd:\n\ne:",
+  }
+
+  parse_test! {
+    parse_subsequent_dependencies,
+    "a: b ../lib/justfile::c&&[d] e\nb:\nd:\ne:\nf: && b",
+    "a: b ../lib/justfile::c && [d] e\n\nb:\n\nd:\n\ne:\n\nf: && b",
+  }
+
+  parse_test! {
+    parse_string_default,
+    r#"
+
+foo a="b\t":
+
+
+  "#,
+    r#"foo a="b\t":"#,
+  }
+
+  parse_test! {
+  parse_multiple,
+    r#"
+a:
+b:
+"#,
+    r#"a:
+
+b:"#,
+  }
+
+  parse_test! {
+    parse_variadic,
+    r#"
+
+foo +a:
+
+
+  "#,
+    r#"foo +a:"#,
+  }
+
+  parse_test! {
+    parse_star_variadic,
+    r#"
+
+foo a='x' *b:
+
+
+  "#,
+    r#"foo a='x' *b:"#,
+  }
+
+  parse_test! {
+    parse_exported_parameters,
+    r#"
+foo $a b='x' *$c:
+  echo $a $c
+  "#,
+    r#"foo $a b='x' *$c:
+    echo $a $c"#,
+  }
+
+  parse_test! {
+    parse_variadic_string_default,
+    r#"
+
+foo +a="Hello":
+
+
+  "#,
+    r#"foo +a="Hello":"#,
+  }
+
+  parse_test! {
+    parse_raw_string_default,
+    r#"
+
+foo a='b\t':
+
+
+  "#,
+    r#"foo a='b\t':"#,
+  }
+
+  parse_test! {
+    parse_export,
+    r#"
+export a := "hello"
+
+  "#,
+    r#"export a := "hello""#,
+  }
+
+  parse_test! {
+    parse_export_attribute,
+    r#"
+[export]
+a := "hello"
+
+[export: 'B']
+b := "goodbye"
+  "#,
+    r#"export a := "hello"
+
+[export: 'B']
+b := "goodbye""#,
+  }
+
+  parse_test! {
+    parse_export_path,
+    r#"
+[export-path: prepend 'PATH']
+bin := "./bin"
+
+[export-path: append "MANPATH"]
+man := "./man"
+  "#,
+    r#"[export-path: prepend 'PATH']
+bin := "./bin"
+
+[export-path: append 'MANPATH']
+man := "./man""#,
+  }
+
+  parse_test! {
+    parse_export_prefix,
+    r#"
+set export-prefix := 'APP_'
+
+export a := "hello"
+
+[export: 'B']
+b := "goodbye"
+  "#,
+    r#"set export-prefix := 'APP_'
+
+export a := "hello"
+
+[export: 'B']
+b := "goodbye""#,
+  }
+
+  parse_test! {
+    parse_setting,
+    r#"
+set follow-symlinks := true
+
+foo:
+  echo a
+"#,
+    r#"set follow-symlinks := true
+
+foo:
+    echo a"#,
+  }
+
+  parse_test! {
+    parse_verbosity_setting,
+    r#"
+set verbosity := "2"
+"#,
+    r#"set verbosity := 'grandiloquent'"#,
+  }
+
+  parse_test! {
+    parse_shell_setting,
+    r#"
+set shell := ["bash", '-euo', "pipefail", "-c",]
+"#,
+    r#"set shell := ['bash', '-euo', 'pipefail', '-c']"#,
+  }
+
+  parse_test! {
+    parse_windows_shell_settings,
+    r#"
+set windows-shell := ["pwsh.exe", "-NoProfile", "-Command"]
+set windows-powershell
+set shell := ["bash", "-c"]
+"#,
+    r#"set shell := ['bash', '-c']
+set windows-powershell := true
+set windows-shell := ['pwsh.exe', '-NoProfile', '-Command']"#,
+  }
+
+  parse_test! {
+    parse_cfg,
+    r#"
+[cfg: os = 'plan9']
+build:
+  echo plan9
+
+[cfg: not(os = "plan9")]
+build:
+  echo other
+
+[cfg: all(family = 'plan9', os = 'plan9',)]
+x := 'plan9'
+
+[cfg: any(family = 'plan9', define = 'X')]
+y := 'other'
+"#,
+    r#"build:
+    echo other"#,
+  }
+
+  parse_test! {
+    parse_skip_if,
+    r#"
+[skip-if: "test -d node_modules"]
+install:
+  npm install
+"#,
+    r#"[skip-if: 'test -d node_modules']
+install:
+    npm install"#,
+  }
+
+  parse_test! {
+    parse_attributes_with_quotes,
+    r#"
+[skip-if: "grep -q 'x' log"]
+[confirm: "Don't you \"want\" to?"]
+[group: "it's"]
+[meta: owner="o'brien\n"]
+install:
+"#,
+    r#"[skip-if: "grep -q 'x' log"]
+[confirm: "Don't you \"want\" to?"]
+[group: "it's"]
+[meta: owner="o'brien\n"]
+install:"#,
+  }
+
+  parse_test! {
+    parse_confirm,
+    r#"
+[confirm]
+clean:
+  rm -rf target
+
+[confirm: "Deploy to production?"]
+deploy:
+  ./deploy
+
+[confirm("Release?")]
+release:
+  ./release
+"#,
+    r#"[confirm]
+clean:
+    rm -rf target
+
+[confirm: 'Deploy to production?']
+deploy:
+    ./deploy
+
+[confirm: 'Release?']
+release:
+    ./release"#,
+  }
+
+  error_test! {
+    name: confirm_unclosed_parenthesis,
+    input: "[confirm('Release?']\nrelease:",
+    offset: 19,
+    line: 0,
+    column: 19,
+    width: 1,
+    kind: UnexpectedToken { expected: vec![ParenR], found: BracketR },
+  }
+
+  parse_test! {
+    parse_weight,
+    r#"
+[weight: "4"]
+foo:
+
+[weight: '1']
+bar:
+
+[weight: 2]
+baz:
+"#,
+    r#"bar:
+
+[weight: 2]
+baz:
+
+[weight: 4]
+foo:"#,
+  }
+
+  parse_test! {
+    parse_group,
+    r#"
+[group: 'test']
+[group: "lint"]
+[group('ci')]
+[group: 'test']
+foo:
+"#,
+    r#"[group: 'ci']
+[group: 'lint']
+[group: 'test']
+foo:"#,
+  }
+
+  error_test! {
+    name: group_without_name,
+    input: "[group]\nfoo:",
+    offset: 6,
+    line: 0,
+    column: 6,
+    width: 1,
+    kind: UnexpectedToken { expected: vec![Colon, ParenL], found: BracketR },
+  }
+
+  parse_test! {
+    parse_meta,
+    r#"
+[meta: sla="p1", owner='infra']
+[meta: team = 'build']
+foo:
+"#,
+    "[meta: owner='infra', sla='p1', team='build']\nfoo:",
+  }
+
+  parse_test! {
+    parse_private,
+    r#"
+[private]
+foo:
+
+[private]
+_bar:
+"#,
+    r#"_bar:
+
+[private]
+foo:"#,
+  }
+
+  parse_test! {
+    parse_os_attributes,
+    r#"
+[linux]
+[macos]
+[unix]
+[windows]
+build:
+  echo any
+"#,
+    r#"build:
+    echo any"#,
+  }
+
+  #[cfg(unix)]
+  parse_test! {
+    parse_os_attribute_excluded,
+    r#"
+[windows]
+build:
+  echo windows
+
+[unix]
+build:
+  echo unix
+"#,
+    r#"build:
+    echo unix"#,
+  }
+
+  parse_test! {
+    parse_matrix,
+    r#"
+# build the project
+[matrix: os=('linux'|"mac"), mode=('debug'|'release')]
+build mode os target='x86':
+  echo {{os}} {{mode}} {{target}}
+"#,
+    r#"# build the project
+[matrix: os=('linux'|"mac"), mode=('debug'|'release')]
+build mode os target='x86':
+    echo {{os}} {{mode}} {{target}}"#,
+  }
+
+  parse_test! {
+    parse_invocation_context,
+    r#"
+[matrix: os=('linux')]
+[local-only]
+deploy os:
+
+[ci-only]
+[interactive]
+release:
+"#,
+    r#"[local-only]
+[matrix: os=('linux')]
+deploy os:
+
+[interactive]
+[ci-only]
+release:"#,
+  }
+
+  parse_test! {
+    parse_process_options,
+    r#"
+[umask: "22"]
+[ionice: '7']
+[max-memory: '2048M']
+[max-cpu-time: '90']
+[nice: '10']
+[no-network]
+build:
+"#,
+    r#"[ionice: '7']
+[max-cpu-time: '90s']
+[max-memory: '2G']
+[nice: '10']
+[no-network]
+[umask: '022']
+build:"#,
+  }
+
+  parse_test! {
+    parse_no_interpolation,
+    r#"
+# Render the template
+[no-interpolation]
+render:
+  echo '{{ name }} {{{{ }}' > template.j2
+
+other:
+  echo {{ "{{" }}
+"#,
+    r#"other:
+    echo {{"{{"}}
+
+# Render the template
+[no-interpolation]
+render:
+    echo '{{ name }} {{{{ }}' > template.j2"#,
+  }
+
+  parse_test! {
+    parse_import_name,
+    "import := 'a'\nimport-b := import",
+    "import := 'a'\n\nimport-b := import",
+  }
+
+  parse_test! {
+    parse_mod_name,
+    "mod:\n  echo mod\n\nmod-b := 'b'",
+    "mod-b := 'b'\n\nmod:\n    echo mod",
+  }
+
+  parse_test! {
+    parse_settings_with_quotes,
+    r#"
+set shell := ["sh", "-c", "echo 'hi'; \"$0\""]
+set export-prefix := "it's_"
+set dotenv-path := '"quoted".env'
+"#,
+    r#"set dotenv-path := '"quoted".env'
+set export-prefix := "it's_"
+set shell := ['sh', '-c', "echo 'hi'; \"$0\""]"#,
+  }
+
+  parse_test! {
+    parse_settings,
+    r#"
+set follow-symlinks
+set env-file
+set force-color := true
+set positional-arguments
+set dotenv-path := '.env.test'
+set dotenv-load
+set dotenv-filename := ".env.local"
+set shebang-error-line
+"#,
+    r#"set dotenv-filename := '.env.local'
+set dotenv-load := true
+set dotenv-path := '.env.test'
+set env-file := true
+set follow-symlinks := true
+set force-color := true
+set positional-arguments := true
+set shebang-error-line := true"#,
+  }
+
+  parse_test! {
+    parse_setting_shorthand,
+    r#"
+set follow-symlinks # comment
+"#,
+    r#"set follow-symlinks := true"#,
+  }
+
+  parse_test! {
+    parse_setting_false,
+    r#"
+set follow-symlinks := false
+"#,
+    r#""#,
+  }
+
+  parse_test! {
+    parse_recipe_named_set,
+    r#"
+set a:
+  echo {{a}}
+"#,
+    r#"set a:
+    echo {{a}}"#,
+  }
+
+  parse_test! {
+  parse_alias_after_target,
+    r#"
+foo:
+  echo a
+alias f := foo
+"#,
+r#"alias f := foo
+
+foo:
+    echo a"#
+  }
+
+  parse_test! {
+  parse_alias_before_target,
+    r#"
+alias f := foo
+foo:
+  echo a
+"#,
+r#"alias f := foo
+
+foo:
+    echo a"#
+  }
+
+  parse_test! {
+    parse_parameter_doc,
+    "
+# build the project
+# target: the triple to build for
+# mode: build mode
+build target mode='debug':
+  echo {{target}} {{mode}}
+",
+    "# build the project
+# target: the triple to build for
+# mode: build mode
+build target mode='debug':
+    echo {{target}} {{mode}}",
+  }
+
+  parse_test! {
+    parse_parameter_doc_without_recipe_doc,
+    "
+# comment
+
+# target: the triple to build for
+build target:
+",
+    "# target: the triple to build for\nbuild target:",
+  }
+
+  parse_test! {
+  parse_alias_with_comment,
+    r#"
+alias f := foo #comment
+foo:
+  echo a
+"#,
+r#"alias f := foo
+
+foo:
+    echo a"#
+  }
+
+  parse_test! {
+  parse_complex,
+    "
+x:
+y:
+z:
+foo := \"xx\"
+bar := foo
+goodbye := \"y\"
+hello a b    c   : x y    z #hello
+  #! blah
+  #blarg
+  {{ foo + bar}}abc{{ goodbye\t  + \"x\" }}xyz
+  1
+  2
+  3
+",
+    "bar := foo
+
+foo := \"xx\"
+
+goodbye := \"y\"
+
+hello a b c: x y z
+    #! blah
+    #blarg
+    {{foo + bar}}abc{{goodbye + \"x\"}}xyz
+    1
+    2
+    3
+
+x:
+
+y:
+
+z:"
+  }
+
+  parse_test! {
+  parse_shebang,
+    "
+practicum := 'hello'
+install:
+\t#!/bin/sh
+\tif [[ -f {{practicum}} ]]; then
+\t\treturn
+\tfi
+",
+    "practicum := 'hello'
+
+install:
+    #!/bin/sh
+    if [[ -f {{practicum}} ]]; then
+    \treturn
+    fi",
+  }
+
+  parse_test! {
+    parse_simple_shebang,
+    "a:\n #!\n  print(1)",
+    "a:\n    #!\n     print(1)",
+  }
+
+  parse_test! {
+  parse_assignments,
+    r#"a := "0"
+c := a + b + a + b
+b := "1"
+"#,
+    r#"a := "0"
+
+b := "1"
+
+c := a + b + a + b"#,
+  }
+
+  parse_test! {
+  parse_assignment_backticks,
+    "a := `echo hello`
+c := a + b + a + b
+b := `echo goodbye`",
+    "a := `echo hello`
+
+b := `echo goodbye`
+
+c := a + b + a + b",
+  }
+
+  parse_test! {
+  parse_interpolation_backticks,
+    r#"a:
+  echo {{  `echo hello` + "blarg"   }} {{   `echo bob`   }}"#,
+    r#"a:
+    echo {{`echo hello` + "blarg"}} {{`echo bob`}}"#,
+  }
+
+  parse_test! {
+    parse_join,
+    "a := b / 'c' / `d`+e\nb := 'b'\ne := 'e'",
+    "a := b / 'c' / `d` + e\n\nb := 'b'\n\ne := 'e'",
+  }
+
+  parse_test! {
+    parse_conditional,
+    "a := if os() == 'linux' { 'x' + b } else { `uname` }\nb := 'b'",
+    "a := if os() == 'linux' { 'x' + b } else { `uname` }\n\nb := 'b'",
+  }
+
+  parse_test! {
+    parse_conditional_nested,
+    "a := if b + c == if c == 'd' { b } else { c } { 'e' } else { 'f' }\nb := 'b'\nc := 'c'",
+    "a := if b + c == if c == 'd' { b } else { c } { 'e' } else { 'f' }\n\nb := 'b'\n\nc := 'c'",
+  }
+
+  parse_test! {
+    parse_conditional_interpolation,
+    "a x:\n echo {{ if x == 'y' { 'z' } else { x } }}",
+    "a x:\n    echo {{ if x == 'y' { 'z' } else { x } }}",
+  }
+
+  parse_test! {
+    parse_interpolated_string_escape,
+    r#"a := "{{" + "x{{"
+b:
+  echo {{ "{{" }}"#,
+    r#"a := "{{" + "x{{"
+
+b:
+    echo {{"{{"}}"#,
+  }
+
+  parse_test! {
+    parse_interpolation_escape,
+    r#"a := "${{{{ b }} {{{{{{c}}"
+c := 'c'
+b:
+  echo ${{{{ b }} {{{{{{c}}"#,
+    r#"a := "${{{{ b }} {{{{{{c}}"
+
+c := 'c'
+
+b:
+    echo ${{{{ b }} {{{{{{c}}"#,
+  }
+
+  parse_test! {
+    parse_interpolated_string,
+    r#"version := "1.0"
+tag := "v{{version}}-{{ os() + '\t' }}\n"
+build target="{{tag}}/bin":
+  echo {{ "{{target}}!" }}"#,
+    r#"tag := "v{{version}}-{{ os() + '\t' }}\n"
+
+version := "1.0"
+
+build target="{{tag}}/bin":
+    echo {{"{{target}}!"}}"#,
+  }
+
+  parse_test! {
+    eof_test,
+    "x:\ny:\nz:\na b c: x y z",
+    "a b c: x y z\n\nx:\n\ny:\n\nz:",
+  }
+
+  parse_test! {
+    string_quote_escape,
+    r#"a := "hello\"""#,
+    r#"a := "hello\"""#,
   }
 
   parse_test! {
-    parse_empty,
-    "
-
-# hello
-
-
-    ",
-    "",
+    string_escapes,
+    r#"a := "\n\t\r\"\\""#,
+    r#"a := "\n\t\r\"\\""#,
   }
 
   parse_test! {
-    parse_string_default,
-    r#"
+  parameters,
+    "a b c:
+  {{b}} {{c}}",
+    "a b c:
+    {{b}} {{c}}",
+  }
 
-foo a="b\t":
+  parse_test! {
+  unary_functions,
+    "
+x := arch()
 
+a:
+  {{os()}} {{os_family()}}",
+    "x := arch()
 
-  "#,
-    r#"foo a="b\t":"#,
+a:
+    {{os()}} {{os_family()}}",
   }
 
   parse_test! {
-  parse_multiple,
+  env_functions,
     r#"
+x := env_var('foo',)
+
 a:
-b:
-"#,
-    r#"a:
+  {{env_var_or_default('foo' + 'bar', 'baz',)}} {{env_var(env_var("baz"))}}"#,
+    r#"x := env_var('foo')
 
-b:"#,
+a:
+    {{env_var_or_default('foo' + 'bar', 'baz')}} {{env_var(env_var("baz"))}}"#,
   }
 
   parse_test! {
-    parse_variadic,
+    parameter_default_string,
     r#"
-
-foo +a:
-
-
-  "#,
-    r#"foo +a:"#,
+f x="abc":
+"#,
+    r#"f x="abc":"#,
   }
 
   parse_test! {
-    parse_variadic_string_default,
+    parameter_default_raw_string,
     r#"
-
-foo +a="Hello":
-
-
-  "#,
-    r#"foo +a="Hello":"#,
+f x='abc':
+"#,
+    r#"f x='abc':"#,
   }
 
   parse_test! {
-    parse_raw_string_default,
+    parameter_default_backtick,
     r#"
-
-foo a='b\t':
-
-
-  "#,
-    r#"foo a='b\t':"#,
+f x=`echo hello`:
+"#,
+    r#"f x=`echo hello`:"#,
   }
 
   parse_test! {
-    parse_export,
+    parameter_default_concatination_string,
     r#"
-export a := "hello"
-
-  "#,
-    r#"export a := "hello""#,
+f x=(`echo hello` + "foo"):
+"#,
+    r#"f x=(`echo hello` + "foo"):"#,
   }
 
   parse_test! {
-  parse_alias_after_target,
+    parameter_default_concatination_variable,
     r#"
-foo:
-  echo a
-alias f := foo
+x := "10"
+f y=(`echo hello` + x) +z="foo":
 "#,
-r#"alias f := foo
+    r#"x := "10"
 
-foo:
-    echo a"#
+f y=(`echo hello` + x) +z="foo":"#,
   }
 
   parse_test! {
-  parse_alias_before_target,
+    parameter_default_multiple,
     r#"
-alias f := foo
-foo:
-  echo a
+x := "10"
+f y=(`echo hello` + x) +z=("foo" + "bar"):
 "#,
-r#"alias f := foo
+    r#"x := "10"
 
-foo:
-    echo a"#
+f y=(`echo hello` + x) +z=("foo" + "bar"):"#,
   }
 
   parse_test! {
-  parse_alias_with_comment,
-    r#"
-alias f := foo #comment
-foo:
-  echo a
-"#,
-r#"alias f := foo
+    concatination_in_group,
+    "x := ('0' + '1')",
+    "x := ('0' + '1')",
+  }
 
-foo:
-    echo a"#
+  parse_test! {
+    string_in_group,
+    "x := ('0'   )",
+    "x := ('0')",
   }
 
   parse_test! {
-  parse_complex,
-    "
-x:
-y:
-z:
-foo := \"xx\"
-bar := foo
-goodbye := \"y\"
-hello a b    c   : x y    z #hello
-  #! blah
-  #blarg
-  {{ foo + bar}}abc{{ goodbye\t  + \"x\" }}xyz
-  1
-  2
-  3
+    ignored_error_prefix,
+    "a:\n -exit 1\n @-exit 2\n -@exit 3",
+    "a:\n    -exit 1\n    @-exit 2\n    -@exit 3",
+  }
+
+  #[rustfmt::skip]
+  parse_test! {
+    escaped_dos_newlines,
+    "@spam:\r
+\t{ \\\r
+\t\tfiglet test; \\\r
+\t\tcargo build --color always 2>&1; \\\r
+\t\tcargo test  --color always -- --color always 2>&1; \\\r
+\t} | less\r
 ",
-    "bar := foo
+"@spam:
+    { \\
+    \tfiglet test; \\
+    \tcargo build --color always 2>&1; \\
+    \tcargo test  --color always -- --color always 2>&1; \\
+    } | less",
+  }
 
-foo := \"xx\"
+  error_test! {
+    name: conditional_missing_equals,
+    input: "a := if 'b' { 'c' } else { 'd' }",
+    offset: 12,
+    line: 0,
+    column: 12,
+    width: 1,
+    kind: UnexpectedToken { expected: vec![Plus, Slash, EqualsEquals], found: BraceL },
+  }
 
-goodbye := \"y\"
+  error_test! {
+    name: conditional_missing_else,
+    input: "a := if 'b' == 'c' { 'd' } elif { 'e' }",
+    offset: 27,
+    line: 0,
+    column: 27,
+    width: 4,
+    kind: ExpectedKeyword { expected: "else", found: "elif" },
+  }
 
-hello a b c: x y z
-    #! blah
-    #blarg
-    {{foo + bar}}abc{{goodbye + \"x\"}}xyz
-    1
-    2
-    3
+  error_test! {
+    name: conditional_undefined_variable,
+    input: "a := if b == 'c' { 'd' } else { 'e' }",
+    offset: 8,
+    line: 0,
+    column: 8,
+    width: 1,
+    kind: UndefinedVariable { variable: "b" },
+  }
 
-x:
+  error_test! {
+    name: interpolated_string_undefined_variable,
+    input: r#"a := "x{{ b }}""#,
+    offset: 10,
+    line: 0,
+    column: 10,
+    width: 1,
+    kind: UndefinedVariable { variable: "b" },
+  }
 
-y:
+  error_test! {
+    name: interpolated_string_unexpected_token,
+    input: r#"a := "x{{ 'b' 'c' }}""#,
+    offset: 14,
+    line: 0,
+    column: 14,
+    width: 3,
+    kind: UnexpectedToken { expected: vec![Plus, Slash, InterpolationEnd], found: StringRaw },
+  }
 
-z:"
+  error_test! {
+    name: interpolated_string_unknown_start_of_token,
+    input: r#"a := "x{{ ~ }}""#,
+    offset: 10,
+    line: 0,
+    column: 10,
+    width: 1,
+    kind: UnknownStartOfToken,
+  }
+
+  error_test! {
+    name: invalid_shebang_interpolation,
+    input: "a:\n #!{{' ' + ''}}\n echo a",
+    offset: 4,
+    line: 1,
+    column: 1,
+    width: 2,
+    kind: InvalidShebang { recipe: "a", line: "#! ".to_string() },
   }
 
   parse_test! {
-  parse_shebang,
-    "
-practicum := 'hello'
-install:
-\t#!/bin/sh
-\tif [[ -f {{practicum}} ]]; then
-\t\treturn
-\tfi
-",
-    "practicum := 'hello'
+    parse_shebang_constant_interpolation,
+    "a:\n #!{{'/bin/' + 'sh'}}\n echo a",
+    "a:\n    #!{{'/bin/' + 'sh'}}\n    echo a",
+  }
 
-install:
-    #!/bin/sh
-    if [[ -f {{practicum}} ]]; then
-    \treturn
-    fi",
+  error_test! {
+    name: matrix_unknown_parameter,
+    input: "[matrix: os=('linux')]\nbuild:",
+    offset: 9,
+    line: 0,
+    column: 9,
+    width: 2,
+    kind: UnknownMatrixParameter { recipe: "build", parameter: "os" },
   }
 
-  parse_test! {
-    parse_simple_shebang,
-    "a:\n #!\n  print(1)",
-    "a:\n    #!\n     print(1)",
+  error_test! {
+    name: matrix_missing_parameter,
+    input: "[matrix: os=('linux')]\nbuild mode os:",
+    offset: 29,
+    line: 1,
+    column: 6,
+    width: 4,
+    kind: MatrixMissingParameter { recipe: "build", parameter: "mode" },
   }
 
-  parse_test! {
-  parse_assignments,
-    r#"a := "0"
-c := a + b + a + b
-b := "1"
-"#,
-    r#"a := "0"
+  error_test! {
+    name: matrix_duplicate_parameter,
+    input: "[matrix: os=('linux'), os=('mac')]\nbuild os:",
+    offset: 23,
+    line: 0,
+    column: 23,
+    width: 2,
+    kind: DuplicateMatrixParameter { parameter: "os" },
+  }
 
-b := "1"
+  error_test! {
+    name: attribute_without_recipe,
+    input: "[matrix: os=('linux')]\nfoo := 'bar'",
+    offset: 0,
+    line: 0,
+    column: 0,
+    width: 1,
+    kind: AttributeWithoutRecipe,
+  }
 
-c := a + b + a + b"#,
+  error_test! {
+    name: export_attribute_without_assignment,
+    input: "[export]\nfoo:",
+    offset: 1,
+    line: 0,
+    column: 1,
+    width: 6,
+    kind: AttributeWithoutAssignment { attribute: "export" },
+  }
+
+  error_test! {
+    name: export_attribute_before_alias,
+    input: "[export]\nalias foo := bar",
+    offset: 1,
+    line: 0,
+    column: 1,
+    width: 6,
+    kind: AttributeWithoutAssignment { attribute: "export" },
   }
 
-  parse_test! {
-  parse_assignment_backticks,
-    "a := `echo hello`
-c := a + b + a + b
-b := `echo goodbye`",
-    "a := `echo hello`
+  error_test! {
+    name: export_path_invalid_position,
+    input: "[export-path: middle 'PATH']\nbin := 'bin'",
+    offset: 14,
+    line: 0,
+    column: 14,
+    width: 6,
+    kind: InvalidAttributeValue {
+      attribute: "export-path",
+      value: "middle".to_owned(),
+    },
+  }
 
-b := `echo goodbye`
+  error_test! {
+    name: export_and_export_path,
+    input: "[export]\n[export-path: append 'PATH']\nbin := 'bin'",
+    offset: 10,
+    line: 1,
+    column: 1,
+    width: 11,
+    kind: ConflictingAttributes {
+      first: "export",
+      second: "export-path",
+    },
+  }
 
-c := a + b + a + b",
+  error_test! {
+    name: empty_shell,
+    input: "set shell := []",
+    offset: 13,
+    line: 0,
+    column: 13,
+    width: 1,
+    kind: EmptyShell,
   }
 
-  parse_test! {
-  parse_interpolation_backticks,
-    r#"a:
-  echo {{  `echo hello` + "blarg"   }} {{   `echo bob`   }}"#,
-    r#"a:
-    echo {{`echo hello` + "blarg"}} {{`echo bob`}}"#,
+  error_test! {
+    name: shell_missing_comma,
+    input: "set shell := ['bash' '-c']",
+    offset: 21,
+    line: 0,
+    column: 21,
+    width: 4,
+    kind: UnexpectedToken {
+      expected: vec![Comma, BracketR],
+      found: StringRaw,
+    },
   }
 
-  parse_test! {
-    eof_test,
-    "x:\ny:\nz:\na b c: x y z",
-    "a b c: x y z\n\nx:\n\ny:\n\nz:",
+  error_test! {
+    name: cfg_without_item,
+    input: "[cfg: os = 'linux']\nalias a := b",
+    offset: 1,
+    line: 0,
+    column: 1,
+    width: 3,
+    kind: AttributeWithoutItem {
+      attribute: "cfg",
+    },
   }
 
-  parse_test! {
-    string_quote_escape,
-    r#"a := "hello\"""#,
-    r#"a := "hello\"""#,
+  error_test! {
+    name: cfg_unknown_predicate,
+    input: "[cfg: arch = 'x86']\nfoo:",
+    offset: 6,
+    line: 0,
+    column: 6,
+    width: 4,
+    kind: InvalidAttributeValue {
+      attribute: "cfg",
+      value: "arch".to_owned(),
+    },
   }
 
-  parse_test! {
-    string_escapes,
-    r#"a := "\n\t\r\"\\""#,
-    r#"a := "\n\t\r\"\\""#,
+  error_test! {
+    name: cfg_not_multiple,
+    input: "[cfg: not(os = 'linux', os = 'macos')]\nfoo:",
+    offset: 22,
+    line: 0,
+    column: 22,
+    width: 1,
+    kind: UnexpectedToken {
+      expected: vec![ParenR],
+      found: Comma,
+    },
   }
 
-  parse_test! {
-  parameters,
-    "a b c:
-  {{b}} {{c}}",
-    "a b c:
-    {{b}} {{c}}",
+  error_test! {
+    name: cfg_excluded_recipe_must_parse,
+    input: "[cfg: os = 'plan9']\nfoo: (",
+    offset: 25,
+    line: 1,
+    column: 5,
+    width: 1,
+    kind: UnexpectedToken {
+      expected: vec![AmpersandAmpersand, Name, BracketL, Path, Eol, Eof],
+      found: ParenL,
+    },
   }
 
-  parse_test! {
-  unary_functions,
-    "
-x := arch()
+  error_test! {
+    name: invalid_verbosity,
+    input: "set verbosity := 'loud'",
+    offset: 17,
+    line: 0,
+    column: 17,
+    width: 6,
+    kind: InvalidVerbosity {
+      value: "loud".to_owned(),
+    },
+  }
 
-a:
-  {{os()}} {{os_family()}}",
-    "x := arch()
+  error_test! {
+    name: export_prefix_without_string,
+    input: "set export-prefix := (",
+    offset: 21,
+    line: 0,
+    column: 21,
+    width: 1,
+    kind: UnexpectedToken {
+      expected: vec![StringRaw, StringCooked, Name],
+      found: ParenL,
+    },
+  }
 
-a:
-    {{os()}} {{os_family()}}",
+  error_test! {
+    name: undefined_constant,
+    input: "set export-prefix := true",
+    offset: 21,
+    line: 0,
+    column: 21,
+    width: 4,
+    kind: UndefinedConstant {
+      constant: "true",
+    },
   }
 
-  parse_test! {
-  env_functions,
-    r#"
-x := env_var('foo',)
+  error_test! {
+    name: conflicting_attributes,
+    input: "[ci-only]\n[local-only]\nbuild:",
+    offset: 11,
+    line: 1,
+    column: 1,
+    width: 10,
+    kind: ConflictingAttributes { first: "ci-only", second: "local-only" },
+  }
 
-a:
-  {{env_var_or_default('foo' + 'bar', 'baz',)}} {{env_var(env_var("baz"))}}"#,
-    r#"x := env_var('foo')
+  error_test! {
+    name: duplicate_attribute,
+    input: "[matrix: os=('linux')]\n[matrix: os=('mac')]\nbuild os:",
+    offset: 24,
+    line: 1,
+    column: 1,
+    width: 6,
+    kind: DuplicateAttribute { attribute: "matrix" },
+  }
 
-a:
-    {{env_var_or_default('foo' + 'bar', 'baz')}} {{env_var(env_var("baz"))}}"#,
+  error_test! {
+    name: duplicate_no_interpolation,
+    input: "[no-interpolation]\n[no-interpolation]\nbuild:",
+    offset: 20,
+    line: 1,
+    column: 1,
+    width: 16,
+    kind: DuplicateAttribute { attribute: "no-interpolation" },
   }
 
-  parse_test! {
-    parameter_default_string,
-    r#"
-f x="abc":
-"#,
-    r#"f x="abc":"#,
+  error_test! {
+    name: duplicate_os_attribute,
+    input: "[linux]\n[linux]\nbuild:",
+    offset: 9,
+    line: 1,
+    column: 1,
+    width: 5,
+    kind: DuplicateAttribute { attribute: "linux" },
   }
 
-  parse_test! {
-    parameter_default_raw_string,
-    r#"
-f x='abc':
-"#,
-    r#"f x='abc':"#,
+  error_test! {
+    name: import_without_loader,
+    input: "import 'a.just'",
+    offset: 7,
+    line: 0,
+    column: 7,
+    width: 8,
+    kind: UnreadableImport {
+      path: "a.just".to_owned(),
+      message: "justfiles may only be imported by a justfile read from a file".to_owned(),
+    },
   }
 
-  parse_test! {
-    parameter_default_backtick,
-    r#"
-f x=`echo hello`:
-"#,
-    r#"f x=`echo hello`:"#,
+  error_test! {
+    name: module_without_loader,
+    input: "mod foo",
+    offset: 4,
+    line: 0,
+    column: 4,
+    width: 3,
+    kind: UnreadableModule {
+      module: "foo",
+      path: "foo.just".to_owned(),
+      message: "modules may only be declared by a justfile read from a file".to_owned(),
+    },
   }
 
-  parse_test! {
-    parameter_default_concatination_string,
-    r#"
-f x=(`echo hello` + "foo"):
-"#,
-    r#"f x=(`echo hello` + "foo"):"#,
+  error_test! {
+    name: duplicate_metadata_key,
+    input: "[meta: owner='a']\n[meta: owner='b']\nbuild:",
+    offset: 25,
+    line: 1,
+    column: 7,
+    width: 5,
+    kind: DuplicateMetadataKey { key: "owner" },
   }
 
-  parse_test! {
-    parameter_default_concatination_variable,
-    r#"
-x := "10"
-f y=(`echo hello` + x) +z="foo":
-"#,
-    r#"x := "10"
+  error_test! {
+    name: invalid_weight,
+    input: "[weight: '0']\nbuild:",
+    offset: 9,
+    line: 0,
+    column: 9,
+    width: 3,
+    kind: InvalidAttributeValue { attribute: "weight", value: "0".to_string() },
+  }
 
-f y=(`echo hello` + x) +z="foo":"#,
+  error_test! {
+    name: invalid_attribute_value,
+    input: "[umask: '999']\nbuild:",
+    offset: 8,
+    line: 0,
+    column: 8,
+    width: 5,
+    kind: InvalidAttributeValue { attribute: "umask", value: "999".to_string() },
   }
 
-  parse_test! {
-    parameter_default_multiple,
-    r#"
-x := "10"
-f y=(`echo hello` + x) +z=("foo" + "bar"):
-"#,
-    r#"x := "10"
+  error_test! {
+    name: invalid_max_memory,
+    input: "[max-memory: '2X']\nbuild:",
+    offset: 13,
+    line: 0,
+    column: 13,
+    width: 4,
+    kind: InvalidAttributeValue { attribute: "max-memory", value: "2X".to_string() },
+  }
 
-f y=(`echo hello` + x) +z=("foo" + "bar"):"#,
+  error_test! {
+    name: unknown_attribute,
+    input: "[foo]\nbuild:",
+    offset: 1,
+    line: 0,
+    column: 1,
+    width: 3,
+    kind: UnknownAttribute { attribute: "foo" },
   }
 
-  parse_test! {
-    concatination_in_group,
-    "x := ('0' + '1')",
-    "x := ('0' + '1')",
+  error_test! {
+    name: duplicate_setting,
+    input: "set follow-symlinks\nset follow-symlinks := false",
+    offset: 24,
+    line: 1,
+    column: 4,
+    width: 15,
+    kind: DuplicateSetting { setting: "follow-symlinks" },
   }
 
-  parse_test! {
-    string_in_group,
-    "x := ('0'   )",
-    "x := ('0')",
+  error_test! {
+    name: unknown_setting,
+    input: "set foo := true",
+    offset: 4,
+    line: 0,
+    column: 4,
+    width: 3,
+    kind: UnknownSetting { setting: "foo" },
   }
 
-  #[rustfmt::skip]
-  parse_test! {
-    escaped_dos_newlines,
-    "@spam:\r
-\t{ \\\r
-\t\tfiglet test; \\\r
-\t\tcargo build --color always 2>&1; \\\r
-\t\tcargo test  --color always -- --color always 2>&1; \\\r
-\t} | less\r
-",
-"@spam:
-    { \\
-    \tfiglet test; \\
-    \tcargo build --color always 2>&1; \\
-    \tcargo test  --color always -- --color always 2>&1; \\
-    } | less",
+  error_test! {
+    name: invalid_setting_value,
+    input: "set follow-symlinks := yes",
+    offset: 23,
+    line: 0,
+    column: 23,
+    width: 3,
+    kind: InvalidSettingValue { setting: "follow-symlinks", value: "yes" },
   }
 
   error_test! {
@@ -962,7 +3455,7 @@ f y=(`echo hello` + x) +z=("foo" + "bar"):"#,
     line:   0,
     column: 5,
     width:  1,
-    kind:   UnexpectedToken{expected: vec![Name, Plus, Colon], found: Eol},
+    kind:   UnexpectedToken{expected: vec![Name, Plus, Asterisk, Dollar, Colon], found: Eol},
   }
 
   error_test! {
@@ -1012,7 +3505,17 @@ f y=(`echo hello` + x) +z=("foo" + "bar"):"#,
     line:   0,
     column: 9,
     width:  1,
-    kind:   UnexpectedToken{expected: vec![Name, Eol, Eof], found: Equals},
+    kind:   UnexpectedToken{expected: vec![AmpersandAmpersand, Name, BracketL, Path, Eol, Eof], found: Equals},
+  }
+
+  error_test! {
+    name:   external_dependency_missing_recipe,
+    input:  "a: ../justfile::",
+    offset:  16,
+    line:   0,
+    column: 16,
+    width:  0,
+    kind:   UnexpectedToken{expected: vec![Name], found: Eof},
   }
 
   error_test! {
@@ -1055,6 +3558,56 @@ f y=(`echo hello` + x) +z=("foo" + "bar"):"#,
     kind:   DuplicateDependency{recipe: "a", dependency: "z"},
   }
 
+  error_test! {
+    name:   duplicate_parallel_dependency,
+    input:  "a: b [c, b]\nb:\nc:",
+    offset:  9,
+    line:   0,
+    column: 9,
+    width:  1,
+    kind:   DuplicateDependency{recipe: "a", dependency: "b"},
+  }
+
+  error_test! {
+    name:   empty_parallel_dependencies,
+    input:  "a: []\n",
+    offset:  4,
+    line:   0,
+    column: 4,
+    width:  1,
+    kind:   UnexpectedToken{expected: vec![Name], found: BracketR},
+  }
+
+  error_test! {
+    name:   unclosed_parallel_dependencies,
+    input:  "a: [b c]\nb:\nc:",
+    offset:  6,
+    line:   0,
+    column: 6,
+    width:  1,
+    kind:   UnexpectedToken{expected: vec![Comma, BracketR], found: Name},
+  }
+
+  error_test! {
+    name:   duplicate_subsequent_dependency,
+    input:  "a: b && b\nb:",
+    offset:  8,
+    line:   0,
+    column: 8,
+    width:  1,
+    kind:   DuplicateDependency{recipe: "a", dependency: "b"},
+  }
+
+  error_test! {
+    name:   external_subsequent_dependency,
+    input:  "a: && ../justfile::b",
+    offset:  6,
+    line:   0,
+    column: 6,
+    width:  11,
+    kind:   UnexpectedToken{expected: vec![Name, BracketL, Eol, Eof], found: Path},
+  }
+
   error_test! {
     name:   duplicate_recipe,
     input:  "a:\nb:\na:",
@@ -1075,6 +3628,12 @@ f y=(`echo hello` + x) +z=("foo" + "bar"):"#,
     kind:   DuplicateVariable{variable: "a"},
   }
 
+  parse_test! {
+    heredoc_leading_whitespace,
+    "a:\n cat <<EOF\n   b\nc\n EOF",
+    "a:\n    cat <<EOF\n      b\n    c\n    EOF",
+  }
+
   error_test! {
     name:   extra_whitespace,
     input:  "a:\n blah\n  blarg",
@@ -1092,7 +3651,7 @@ f y=(`echo hello` + x) +z=("foo" + "bar"):"#,
     line:   0,
     column: 0,
     width:  2,
-    kind:   UnexpectedToken{expected: vec![Name, At], found: InterpolationStart},
+    kind:   UnexpectedToken{expected: vec![Name, At, BracketL], found: InterpolationStart},
   }
 
   error_test! {
@@ -1132,7 +3691,7 @@ f y=(`echo hello` + x) +z=("foo" + "bar"):"#,
     line:   0,
     column: 8,
     width:  0,
-    kind:   UnexpectedToken{expected: vec![Name, Plus, Colon], found: Eof},
+    kind:   UnexpectedToken{expected: vec![Name, Plus, Asterisk, Dollar, Colon], found: Eof},
   }
 
   #[test]
@@ -1155,8 +3714,22 @@ f y=(`echo hello` + x) +z=("foo" + "bar"):"#,
       }
     }
 
+    // Constants used by examples, as if given with `--define`
+    let defines = vec![("NICENESS", "10")].into_iter().collect();
+
     for justfile in justfiles {
-      parse(&justfile);
+      // Examples with imports and modules depend on justfiles that aren't in
+      // the README
+      if justfile
+        .lines()
+        .any(|line| line.starts_with("import ") || line.starts_with("mod "))
+      {
+        continue;
+      }
+
+      if let Err(error) = Parser::parse_with_defines(&justfile, &defines) {
+        panic!("README example failed to parse: {}\n{}", error, justfile);
+      }
     }
   }
 
@@ -1188,4 +3761,22 @@ b:
 
     assert_eq!(justfile.recipes["a"].lines.len(), 1);
   }
+
+  #[test]
+  fn truncated_token_stream() {
+    let text = "foo := 'bar'";
+    let mut tokens = Lexer::lex(text).unwrap();
+    tokens.retain(|token| token.kind != Whitespace && token.kind != Eof);
+
+    match Parser::new(text, tokens).justfile() {
+      Err(CompilationError {
+        kind: Internal { message },
+        ..
+      }) => assert_eq!(message, "unexpected end of token stream"),
+      other => panic!(
+        "expected an internal error, but got: {:?}",
+        other.map(|_| ())
+      ),
+    }
+  }
 }
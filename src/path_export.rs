@@ -0,0 +1,80 @@
+use crate::common::*;
+
+/// Where an `[export-path]` variable's value goes relative to the inherited
+/// value of the environment variable it's exported as
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub(crate) enum PathExport {
+  Append,
+  Prepend,
+}
+
+impl PathExport {
+  const SEPARATOR: &'static str = if cfg!(windows) { ";" } else { ":" };
+
+  pub(crate) fn join(self, inherited: Option<OsString>, value: &str) -> OsString {
+    let inherited = match inherited {
+      Some(inherited) if !inherited.is_empty() => inherited,
+      _ => return value.into(),
+    };
+
+    let mut joined = OsString::new();
+    match self {
+      PathExport::Append => {
+        joined.push(inherited);
+        joined.push(Self::SEPARATOR);
+        joined.push(value);
+      }
+      PathExport::Prepend => {
+        joined.push(value);
+        joined.push(Self::SEPARATOR);
+        joined.push(inherited);
+      }
+    }
+    joined
+  }
+}
+
+impl FromStr for PathExport {
+  type Err = ();
+
+  fn from_str(s: &str) -> Result<PathExport, ()> {
+    match s {
+      "append" => Ok(PathExport::Append),
+      "prepend" => Ok(PathExport::Prepend),
+      _ => Err(()),
+    }
+  }
+}
+
+impl Display for PathExport {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    match self {
+      PathExport::Append => write!(f, "append"),
+      PathExport::Prepend => write!(f, "prepend"),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn join() {
+    let separator = PathExport::SEPARATOR;
+
+    assert_eq!(
+      PathExport::Append.join(Some("a".into()), "b"),
+      OsString::from(format!("a{}b", separator))
+    );
+    assert_eq!(
+      PathExport::Prepend.join(Some("a".into()), "b"),
+      OsString::from(format!("b{}a", separator))
+    );
+    assert_eq!(PathExport::Prepend.join(None, "b"), OsString::from("b"));
+    assert_eq!(
+      PathExport::Append.join(Some("".into()), "b"),
+      OsString::from("b")
+    );
+  }
+}
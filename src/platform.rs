@@ -2,17 +2,251 @@ use crate::common::*;
 
 pub(crate) struct Platform;
 
+/// The shell used with `set windows-powershell`
+#[cfg(windows)]
+const POWERSHELL: &[&str] = &["powershell.exe", "-NoLogo", "-Command"];
+
 #[cfg(unix)]
 impl PlatformInterface for Platform {
   fn make_shebang_command(
     path: &Path,
     _command: &str,
-    _argument: Option<&str>,
+    _arguments: &[&str],
   ) -> Result<Command, OutputError> {
     // shebang scripts can be executed directly on unix
     Ok(Command::new(path))
   }
 
+  fn make_shell_command(shell: &str, arguments: &[&str], command: &str) -> Command {
+    let mut cmd = Command::new(shell);
+    cmd.args(arguments).arg(command);
+    cmd
+  }
+
+  fn shell_setting(settings: &Settings) -> Option<Vec<&str>> {
+    settings
+      .shell
+      .as_ref()
+      .map(|shell| shell.iter().map(String::as_str).collect())
+  }
+
+  fn status_with_pty(command: &mut Command, interactive: bool) -> io::Result<process::ExitStatus> {
+    use std::{
+      fs::File,
+      io::{Read, Write},
+      mem,
+      os::unix::{
+        io::{AsRawFd, FromRawFd},
+        process::CommandExt,
+      },
+      ptr,
+    };
+
+    // Give the terminal the same size as ours, if we have one
+    let mut size: libc::winsize = unsafe { mem::zeroed() };
+    let size = if unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) } == 0 {
+      &mut size as *mut libc::winsize
+    } else {
+      ptr::null_mut()
+    };
+
+    let mut master = 0;
+    let mut slave = 0;
+    if unsafe {
+      libc::openpty(
+        &mut master,
+        &mut slave,
+        ptr::null_mut(),
+        ptr::null_mut(),
+        size,
+      )
+    } != 0
+    {
+      return Err(io::Error::last_os_error());
+    }
+    let mut master = unsafe { File::from_raw_fd(master) };
+    let slave = unsafe { File::from_raw_fd(slave) };
+
+    if interactive {
+      command.stdin(slave.try_clone()?);
+      command.stderr(slave.try_clone()?);
+      unsafe {
+        // Make the terminal the child's controlling terminal, so that
+        // job control and reading from `/dev/tty` work
+        command.pre_exec(|| {
+          if libc::setsid() == -1 || libc::ioctl(0, libc::TIOCSCTTY as _, 0) == -1 {
+            return Err(io::Error::last_os_error());
+          }
+          Ok(())
+        });
+      }
+    } else {
+      // Pass output through unchanged, instead of translating `\n` to `\r\n`
+      unsafe {
+        let mut termios = mem::zeroed();
+        if libc::tcgetattr(slave.as_raw_fd(), &mut termios) != 0 {
+          return Err(io::Error::last_os_error());
+        }
+        termios.c_oflag &= !libc::OPOST;
+        if libc::tcsetattr(slave.as_raw_fd(), libc::TCSANOW, &termios) != 0 {
+          return Err(io::Error::last_os_error());
+        }
+      }
+    }
+
+    command.stdout(slave);
+    let spawned = command.spawn();
+    // Close our other copies of the slave, so that reading from the master
+    // ends when the child exits
+    command
+      .stdin(process::Stdio::inherit())
+      .stdout(process::Stdio::inherit())
+      .stderr(process::Stdio::inherit());
+    let mut child = spawned?;
+
+    // Pass keystrokes through as they are typed, if stdin is a terminal
+    let mut original = None;
+    let mut forwarder = None;
+    if interactive {
+      unsafe {
+        let mut termios = mem::zeroed();
+        if libc::isatty(libc::STDIN_FILENO) == 1
+          && libc::tcgetattr(libc::STDIN_FILENO, &mut termios) == 0
+        {
+          let mut raw = termios;
+          libc::cfmakeraw(&mut raw);
+          libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &raw);
+          original = Some(termios);
+        }
+      }
+
+      // Writing to this pipe stops the forwarding thread once the child has
+      // exited, so that it doesn't take input meant for later commands
+      let mut fds = [0; 2];
+      if unsafe { libc::pipe(fds.as_mut_ptr()) } != 0 {
+        return Err(io::Error::last_os_error());
+      }
+      let stop = unsafe { File::from_raw_fd(fds[0]) };
+      let stopper = unsafe { File::from_raw_fd(fds[1]) };
+
+      let input = master.try_clone()?;
+      forwarder = Some((
+        stopper,
+        thread::spawn(move || Self::forward_input(input, &stop)),
+      ));
+    }
+
+    let result = (|| {
+      let stdout = io::stdout();
+      let mut stdout = stdout.lock();
+      let mut buffer = [0; 4096];
+      loop {
+        match master.read(&mut buffer) {
+          Ok(0) => break,
+          Ok(n) => {
+            stdout.write_all(&buffer[..n])?;
+            stdout.flush()?;
+          }
+          // Linux reports EIO once the slave has been closed
+          Err(ref error) if error.raw_os_error() == Some(libc::EIO) => break,
+          Err(ref error) if error.kind() == io::ErrorKind::Interrupted => continue,
+          Err(error) => return Err(error),
+        }
+      }
+      child.wait()
+    })();
+
+    if let Some((mut stopper, forwarder)) = forwarder {
+      stopper.write_all(&[0]).ok();
+      forwarder.join().ok();
+    }
+
+    if let Some(termios) = original {
+      unsafe {
+        libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &termios);
+      }
+    }
+
+    result
+  }
+
+  fn set_process_options(command: &mut Command, options: ProcessOptions) {
+    use std::os::unix::process::CommandExt;
+
+    if options.is_default() {
+      return;
+    }
+
+    // Prepared here, since memory must not be allocated after forking
+    #[cfg(target_os = "linux")]
+    let id_maps = if options.no_network {
+      let uid = unsafe { libc::getuid() };
+      let gid = unsafe { libc::getgid() };
+      Some((
+        format!("{0} {0} 1", uid).into_bytes(),
+        format!("{0} {0} 1", gid).into_bytes(),
+      ))
+    } else {
+      None
+    };
+
+    unsafe {
+      command.pre_exec(move || {
+        #[cfg(target_os = "linux")]
+        {
+          if let Some((uid_map, gid_map)) = &id_maps {
+            Platform::unshare_network(uid_map, gid_map)?;
+          }
+        }
+
+        if let Some(umask) = options.umask {
+          libc::umask(umask as libc::mode_t);
+        }
+
+        if let Some(nice) = options.nice {
+          if libc::setpriority(libc::PRIO_PROCESS as _, 0, nice) == -1 {
+            return Err(io::Error::last_os_error());
+          }
+        }
+
+        if let Some(ionice) = options.ionice {
+          Platform::set_io_priority(ionice)?;
+        }
+
+        // Lower the soft limit, leaving the hard limit alone so it isn't
+        // an error to ask for more than it allows
+        let limit = |resource, value: u64| {
+          let mut limit = libc::rlimit {
+            rlim_cur: 0,
+            rlim_max: 0,
+          };
+          if libc::getrlimit(resource, &mut limit) == -1 {
+            return Err(io::Error::last_os_error());
+          }
+          limit.rlim_cur = cmp::min(value as libc::rlim_t, limit.rlim_max);
+          if libc::setrlimit(resource, &limit) == -1 {
+            return Err(io::Error::last_os_error());
+          }
+          Ok(())
+        };
+
+        if let Some(max_memory) = options.max_memory {
+          limit(libc::RLIMIT_AS, max_memory)?;
+        }
+
+        if let Some(max_cpu_time) = options.max_cpu_time {
+          limit(libc::RLIMIT_CPU, max_cpu_time)?;
+        }
+
+        Ok(())
+      });
+    }
+  }
+
+  fn network_isolation_supported() -> bool {
+    cfg!(target_os = "linux")
+  }
+
   fn set_execute_permission(path: &Path) -> Result<(), io::Error> {
     use std::os::unix::fs::PermissionsExt;
 
@@ -32,12 +266,91 @@ impl PlatformInterface for Platform {
     exit_status.signal()
   }
 
+  fn kill(pid: u32) {
+    unsafe {
+      libc::kill(pid as libc::pid_t, libc::SIGTERM);
+    }
+  }
+
   fn to_shell_path(path: &Path) -> Result<String, String> {
     path
       .to_str()
       .map(str::to_string)
       .ok_or_else(|| String::from("Error getting current directory: unicode decode error"))
   }
+
+  fn local_offset(timestamp: i64) -> Option<i64> {
+    let timestamp = timestamp as libc::time_t;
+    let mut tm: libc::tm = unsafe { mem::zeroed() };
+    if unsafe { libc::localtime_r(&timestamp, &mut tm) }.is_null() {
+      return None;
+    }
+    Some(tm.tm_gmtoff as i64)
+  }
+}
+
+#[cfg(unix)]
+impl Platform {
+  /// Set the I/O scheduling priority of the current process
+  #[cfg(target_os = "linux")]
+  fn set_io_priority(priority: IoPriority) -> io::Result<()> {
+    const IOPRIO_WHO_PROCESS: libc::c_long = 1;
+    const IOPRIO_CLASS_SHIFT: libc::c_long = 13;
+
+    let value = match priority {
+      IoPriority::Idle => 3 << IOPRIO_CLASS_SHIFT,
+      IoPriority::BestEffort(level) => (2 << IOPRIO_CLASS_SHIFT) | libc::c_long::from(level),
+    };
+
+    if unsafe { libc::syscall(libc::SYS_ioprio_set, IOPRIO_WHO_PROCESS, 0, value) } == -1 {
+      return Err(io::Error::last_os_error());
+    }
+
+    Ok(())
+  }
+
+  /// Move the current process into new user and network namespaces, which
+  /// leaves it with only a loopback interface, that is down. `uid_map` and
+  /// `gid_map` map the process's user and group IDs to themselves, so files
+  /// it creates have the usual owners. This runs after forking, so it must
+  /// not allocate.
+  #[cfg(target_os = "linux")]
+  fn unshare_network(uid_map: &[u8], gid_map: &[u8]) -> io::Result<()> {
+    fn write(path: &[u8], contents: &[u8]) -> io::Result<()> {
+      unsafe {
+        let fd = libc::open(path.as_ptr() as *const libc::c_char, libc::O_WRONLY);
+        if fd == -1 {
+          return Err(io::Error::last_os_error());
+        }
+        let written = libc::write(fd, contents.as_ptr() as *const libc::c_void, contents.len());
+        libc::close(fd);
+        if written == -1 {
+          return Err(io::Error::last_os_error());
+        }
+      }
+      Ok(())
+    }
+
+    if unsafe { libc::unshare(libc::CLONE_NEWUSER | libc::CLONE_NEWNET) } == -1 {
+      return Err(io::Error::last_os_error());
+    }
+
+    // Unprivileged processes must give up `setgroups` before mapping groups
+    if let Err(error) = write(b"/proc/self/setgroups\0", b"deny") {
+      if error.kind() != io::ErrorKind::NotFound {
+        return Err(error);
+      }
+    }
+
+    write(b"/proc/self/uid_map\0", uid_map)?;
+    write(b"/proc/self/gid_map\0", gid_map)
+  }
+
+  /// I/O scheduling priorities are only supported on Linux
+  #[cfg(not(target_os = "linux"))]
+  fn set_io_priority(_priority: IoPriority) -> io::Result<()> {
+    Ok(())
+  }
 }
 
 #[cfg(windows)]
@@ -45,7 +358,7 @@ impl PlatformInterface for Platform {
   fn make_shebang_command(
     path: &Path,
     command: &str,
-    argument: Option<&str>,
+    arguments: &[&str],
   ) -> Result<Command, OutputError> {
     // Translate path to the interpreter from unix style to windows style
     let mut cygpath = Command::new("cygpath");
@@ -53,13 +366,73 @@ impl PlatformInterface for Platform {
     cygpath.arg(command);
 
     let mut cmd = Command::new(output(cygpath)?);
-    if let Some(argument) = argument {
-      cmd.arg(argument);
-    }
+    cmd.args(arguments);
     cmd.arg(path);
     Ok(cmd)
   }
 
+  fn make_shell_command(shell: &str, arguments: &[&str], command: &str) -> Command {
+    use crate::config::DEFAULT_SHELL;
+    use std::sync::Once;
+
+    static WARN: Once = Once::new();
+
+    // Fall back to `cmd.exe` if the default shell isn't installed, so that
+    // simple justfiles work on machines without Git for Windows or Cygwin
+    if shell == DEFAULT_SHELL && !Platform::sh_installed() {
+      WARN.call_once(|| {
+        eprintln!(
+          "warning: `{}` could not be found in PATH, falling back to `cmd /C`",
+          DEFAULT_SHELL
+        );
+      });
+      let mut cmd = Command::new("cmd");
+      cmd.arg("/C").arg(command);
+      return cmd;
+    }
+
+    let mut cmd = Command::new(shell);
+    cmd.args(arguments).arg(command);
+    cmd
+  }
+
+  /// `set windows-shell` takes precedence over `set windows-powershell`,
+  /// which takes precedence over `set shell`
+  fn shell_setting(settings: &Settings) -> Option<Vec<&str>> {
+    if let Some(shell) = &settings.windows_shell {
+      Some(shell.iter().map(String::as_str).collect())
+    } else if settings.windows_powershell {
+      Some(POWERSHELL.to_vec())
+    } else {
+      settings
+        .shell
+        .as_ref()
+        .map(|shell| shell.iter().map(String::as_str).collect())
+    }
+  }
+
+  fn status_with_pty(command: &mut Command, _interactive: bool) -> io::Result<process::ExitStatus> {
+    // Windows has no pseudo-terminals that child processes could detect, so
+    // the command is run attached to just's own console
+    command.status()
+  }
+
+  fn set_process_options(_command: &mut Command, options: ProcessOptions) {
+    use std::sync::Once;
+
+    static WARN: Once = Once::new();
+
+    if !options.is_default() {
+      WARN.call_once(|| {
+        eprintln!("warning: Resource limit, priority, and umask attributes are ignored on Windows");
+      });
+    }
+  }
+
+  fn network_isolation_supported() -> bool {
+    false
+  }
+
   fn set_execute_permission(_path: &Path) -> Result<(), io::Error> {
     // it is not necessary to set an execute permission on a script on windows,
     // so this is a nop
@@ -72,6 +445,15 @@ impl PlatformInterface for Platform {
     None
   }
 
+  fn kill(pid: u32) {
+    Command::new("taskkill")
+      .args(["/F", "/T", "/PID", &pid.to_string()])
+      .stdout(process::Stdio::null())
+      .stderr(process::Stdio::null())
+      .status()
+      .ok();
+  }
+
   fn to_shell_path(path: &Path) -> Result<String, String> {
     // Translate path from windows style to unix style
     let mut cygpath = Command::new("cygpath");
@@ -79,4 +461,148 @@ impl PlatformInterface for Platform {
     cygpath.arg(path);
     output(cygpath).map_err(|e| format!("Error converting shell path: {}", e))
   }
+
+  fn local_offset(timestamp: i64) -> Option<i64> {
+    let timestamp = timestamp as libc::time_t;
+    let mut local: libc::tm = unsafe { mem::zeroed() };
+    let mut utc: libc::tm = unsafe { mem::zeroed() };
+    if unsafe { libc::localtime_s(&mut local, &timestamp) } != 0
+      || unsafe { libc::gmtime_s(&mut utc, &timestamp) } != 0
+    {
+      return None;
+    }
+
+    // The offset is the difference between the local and UTC times, read
+    // as if they were both in UTC
+    let seconds = |tm: &libc::tm| {
+      let days = DateTime::days_from_civil(
+        i64::from(tm.tm_year) + 1900,
+        i64::from(tm.tm_mon) + 1,
+        i64::from(tm.tm_mday),
+      );
+      days * 86400 + i64::from(tm.tm_hour) * 3600 + i64::from(tm.tm_min) * 60 + i64::from(tm.tm_sec)
+    };
+
+    Some(seconds(&local) - seconds(&utc))
+  }
+}
+
+/// Platforms without processes, such as WebAssembly, where justfiles can
+/// be compiled and formatted, but recipes can't be run
+#[cfg(not(any(unix, windows)))]
+impl PlatformInterface for Platform {
+  fn make_shebang_command(
+    path: &Path,
+    _command: &str,
+    _arguments: &[&str],
+  ) -> Result<Command, OutputError> {
+    Ok(Command::new(path))
+  }
+
+  fn make_shell_command(shell: &str, arguments: &[&str], command: &str) -> Command {
+    let mut cmd = Command::new(shell);
+    cmd.args(arguments).arg(command);
+    cmd
+  }
+
+  fn shell_setting(settings: &Settings) -> Option<Vec<&str>> {
+    settings
+      .shell
+      .as_ref()
+      .map(|shell| shell.iter().map(String::as_str).collect())
+  }
+
+  fn status_with_pty(command: &mut Command, _interactive: bool) -> io::Result<process::ExitStatus> {
+    // Fails, since processes can't be spawned
+    command.status()
+  }
+
+  fn set_process_options(_command: &mut Command, _options: ProcessOptions) {}
+
+  fn network_isolation_supported() -> bool {
+    false
+  }
+
+  fn set_execute_permission(_path: &Path) -> Result<(), io::Error> {
+    Ok(())
+  }
+
+  fn signal_from_exit_status(_exit_status: process::ExitStatus) -> Option<i32> {
+    None
+  }
+
+  fn kill(_pid: u32) {}
+
+  fn to_shell_path(path: &Path) -> Result<String, String> {
+    path
+      .to_str()
+      .map(str::to_string)
+      .ok_or_else(|| String::from("Error getting current directory: unicode decode error"))
+  }
+
+  fn local_offset(_timestamp: i64) -> Option<i64> {
+    None
+  }
+}
+
+#[cfg(unix)]
+impl Platform {
+  /// Forward stdin to `input`, the master side of a terminal, until `stop`
+  /// becomes readable. Input the child doesn't read before it exits is left
+  /// in the terminal, and discarded with it.
+  fn forward_input(mut input: fs::File, stop: &fs::File) {
+    use std::{io::Write, os::unix::io::AsRawFd};
+
+    let mut buffer = [0u8; 1024];
+
+    loop {
+      let mut pollfds = [
+        libc::pollfd {
+          fd: libc::STDIN_FILENO,
+          events: libc::POLLIN,
+          revents: 0,
+        },
+        libc::pollfd {
+          fd: stop.as_raw_fd(),
+          events: libc::POLLIN,
+          revents: 0,
+        },
+      ];
+
+      if unsafe { libc::poll(pollfds.as_mut_ptr(), 2, -1) } == -1 {
+        if io::Error::last_os_error().kind() == io::ErrorKind::Interrupted {
+          continue;
+        }
+        return;
+      }
+
+      if pollfds[1].revents != 0 {
+        return;
+      }
+
+      let n = unsafe { libc::read(libc::STDIN_FILENO, buffer.as_mut_ptr().cast(), buffer.len()) };
+
+      // Send end-of-file as the terminal's EOF character, control-D
+      let bytes = match n {
+        0 => &[4][..],
+        n if n > 0 => &buffer[..n as usize],
+        _ if io::Error::last_os_error().kind() == io::ErrorKind::Interrupted => continue,
+        _ => return,
+      };
+
+      if input.write_all(bytes).is_err() || n == 0 {
+        return;
+      }
+    }
+  }
+}
+
+#[cfg(windows)]
+impl Platform {
+  /// Check whether `sh` can be found in any directory in PATH
+  fn sh_installed() -> bool {
+    env::var_os("PATH")
+      .map(|paths| env::split_paths(&paths).any(|directory| directory.join("sh.exe").is_file()))
+      .unwrap_or(false)
+  }
 }
@@ -6,15 +6,44 @@ pub(crate) trait PlatformInterface {
   fn make_shebang_command(
     path: &Path,
     command: &str,
-    argument: Option<&str>,
+    arguments: &[&str],
   ) -> Result<Command, OutputError>;
 
+  /// Construct a command that runs `command` with `shell`, passing
+  /// `arguments` before it
+  fn make_shell_command(shell: &str, arguments: &[&str], command: &str) -> Command;
+
+  /// The shell, followed by its arguments, that the justfile's `settings`
+  /// ask for on this platform, if any
+  fn shell_setting(settings: &Settings) -> Option<Vec<&str>>;
+
+  /// Run `command` with its stdout attached to a pseudo-terminal, copying
+  /// its output to stdout, so that it behaves as if writing to a terminal.
+  /// If `interactive`, stdin and stderr are attached too, and input is
+  /// forwarded to it. Falls back to running it normally where this is
+  /// unsupported.
+  fn status_with_pty(command: &mut Command, interactive: bool) -> io::Result<process::ExitStatus>;
+
+  /// Apply `options` to the process that `command` will spawn
+  fn set_process_options(command: &mut Command, options: ProcessOptions);
+
+  /// Whether commands can be run without network access
+  fn network_isolation_supported() -> bool;
+
   /// Set the execute permission on the file pointed to by `path`
   fn set_execute_permission(path: &Path) -> Result<(), io::Error>;
 
   /// Extract the signal from a process exit status, if it was terminated by a signal
   fn signal_from_exit_status(exit_status: process::ExitStatus) -> Option<i32>;
 
+  /// Terminate the process with ID `pid`, along with, on windows, its
+  /// children
+  fn kill(pid: u32);
+
   /// Translate a path from a "native" path to a path the interpreter expects
   fn to_shell_path(path: &Path) -> Result<String, String>;
+
+  /// The offset of the local time zone from UTC in seconds, at `timestamp`
+  /// seconds after the Unix epoch, if it can be determined
+  fn local_offset(timestamp: i64) -> Option<i64>;
 }
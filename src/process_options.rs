@@ -0,0 +1,126 @@
+use crate::common::*;
+
+/// Settings applied to the processes a recipe spawns, from attributes like
+/// `[umask: '077']`
+#[derive(PartialEq, Debug, Default, Copy, Clone)]
+pub(crate) struct ProcessOptions {
+  pub(crate) ionice: Option<IoPriority>,
+  /// Limit on CPU time, in seconds
+  pub(crate) max_cpu_time: Option<u64>,
+  /// Limit on address space, in bytes
+  pub(crate) max_memory: Option<u64>,
+  pub(crate) nice: Option<i32>,
+  pub(crate) no_network: bool,
+  pub(crate) umask: Option<u32>,
+}
+
+impl ProcessOptions {
+  pub(crate) const ATTRIBUTES: &'static [&'static str] =
+    &["ionice", "max-cpu-time", "max-memory", "nice", "umask"];
+
+  pub(crate) fn is_default(&self) -> bool {
+    *self == Self::default()
+  }
+
+  /// Whether `attribute` has already been set
+  pub(crate) fn has(&self, attribute: &str) -> bool {
+    match attribute {
+      "ionice" => self.ionice.is_some(),
+      "max-cpu-time" => self.max_cpu_time.is_some(),
+      "max-memory" => self.max_memory.is_some(),
+      "nice" => self.nice.is_some(),
+      "umask" => self.umask.is_some(),
+      _ => false,
+    }
+  }
+
+  /// Set `attribute` to `value`, returning false if `value` is invalid
+  pub(crate) fn set(&mut self, attribute: &str, value: &str) -> bool {
+    match attribute {
+      "ionice" => self.ionice = value.parse().ok(),
+      "max-cpu-time" => self.max_cpu_time = Self::parse_quantity(value, &TIME_UNITS),
+      "max-memory" => self.max_memory = Self::parse_quantity(value, &MEMORY_UNITS),
+      "nice" => {
+        self.nice = value
+          .parse()
+          .ok()
+          .filter(|nice| (-20..=19).range_contains(nice))
+      }
+      "umask" => {
+        self.umask = u32::from_str_radix(value, 8)
+          .ok()
+          .filter(|umask| *umask <= 0o777)
+      }
+      _ => return false,
+    }
+    self.has(attribute)
+  }
+
+  /// Parse a positive number with an optional unit suffix, like `2G`
+  fn parse_quantity(text: &str, units: &[(&str, u64)]) -> Option<u64> {
+    let (number, multiplier) = units
+      .iter()
+      .find(|(suffix, _)| text.ends_with(suffix))
+      .map(|(suffix, multiplier)| (&text[..text.len() - suffix.len()], *multiplier))
+      .unwrap_or((text, 1));
+
+    number
+      .parse::<u64>()
+      .ok()
+      .filter(|number| *number > 0)
+      .and_then(|number| number.checked_mul(multiplier))
+  }
+
+  /// Format a quantity using the largest unit that divides it evenly
+  fn format_quantity(quantity: u64, units: &[(&str, u64)]) -> String {
+    for (suffix, multiplier) in units {
+      if quantity / multiplier * multiplier == quantity {
+        return format!("{}{}", quantity / multiplier, suffix);
+      }
+    }
+    quantity.to_string()
+  }
+}
+
+/// Units for `max-memory`, largest first
+const MEMORY_UNITS: [(&str, u64); 4] = [
+  ("T", 1 << 40),
+  ("G", 1 << 30),
+  ("M", 1 << 20),
+  ("K", 1 << 10),
+];
+
+/// Units for `max-cpu-time`, largest first
+const TIME_UNITS: [(&str, u64); 3] = [("h", 60 * 60), ("m", 60), ("s", 1)];
+
+impl Display for ProcessOptions {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    if let Some(ionice) = self.ionice {
+      writeln!(f, "[ionice: '{}']", ionice)?;
+    }
+    if let Some(max_cpu_time) = self.max_cpu_time {
+      writeln!(
+        f,
+        "[max-cpu-time: '{}']",
+        Self::format_quantity(max_cpu_time, &TIME_UNITS)
+      )?;
+    }
+    if let Some(max_memory) = self.max_memory {
+      writeln!(
+        f,
+        "[max-memory: '{}']",
+        Self::format_quantity(max_memory, &MEMORY_UNITS)
+      )?;
+    }
+    if let Some(nice) = self.nice {
+      writeln!(f, "[nice: '{}']", nice)?;
+    }
+    if self.no_network {
+      writeln!(f, "[no-network]")?;
+    }
+    if let Some(umask) = self.umask {
+      writeln!(f, "[umask: '{:03o}']", umask)?;
+    }
+    Ok(())
+  }
+}
@@ -0,0 +1,104 @@
+use crate::common::*;
+
+use history::{escape, unescape};
+
+/// How far a run of recipes got before failing, so that `--resume` can
+/// continue from the recipe that failed
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct Progress {
+  /// The arguments of the run, without overrides, whose values may be secret
+  pub(crate) arguments: Vec<String>,
+  /// How many of the recipes given on the command line finished
+  pub(crate) finished: usize,
+  /// The recipes that finished, including dependencies, prefixed by the path
+  /// of their module and `::`
+  pub(crate) ran: BTreeSet<String>,
+}
+
+impl Progress {
+  /// Parse the contents of a progress file, as written by `Display`
+  fn parse(text: &str) -> Option<Progress> {
+    let mut progress = Progress::default();
+
+    for line in text.lines() {
+      let (key, value) = line.split_at(line.find('\t')?);
+      let value = unescape(&value[1..]);
+      match key {
+        "argument" => progress.arguments.push(value),
+        "finished" => progress.finished = value.parse().ok()?,
+        "ran" => {
+          progress.ran.insert(value);
+        }
+        _ => return None,
+      }
+    }
+
+    Some(progress)
+  }
+}
+
+impl Display for Progress {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    writeln!(f, "finished\t{}", self.finished)?;
+
+    for recipe in &self.ran {
+      writeln!(f, "ran\t{}", escape(recipe))?;
+    }
+
+    for argument in &self.arguments {
+      writeln!(f, "argument\t{}", escape(argument))?;
+    }
+
+    Ok(())
+  }
+}
+
+/// The path of the progress file for the justfile at `justfile`
+pub(crate) fn path(justfile: &Path) -> Option<PathBuf> {
+  history::state_path("progress", justfile)
+}
+
+/// The progress of the last failed run, recorded in the file at `path`
+pub(crate) fn load(path: &Path) -> Option<Progress> {
+  Progress::parse(&fs::read_to_string(path).ok()?)
+}
+
+/// Record `progress` in the file at `path`, replacing that of any earlier run
+pub(crate) fn save(path: &Path, progress: &Progress) -> io::Result<()> {
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent)?;
+  }
+
+  fs::write(path, progress.to_string())
+}
+
+/// Remove the progress file at `path`, after a successful run
+pub(crate) fn clear(path: &Path) -> io::Result<()> {
+  match fs::remove_file(path) {
+    Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(()),
+    result => result,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn round_trip() {
+    let progress = Progress {
+      arguments: vec!["mode=release".to_owned(), "build\tx".to_owned()],
+      finished: 1,
+      ran: vec!["build".to_owned(), "foo::test".to_owned()]
+        .into_iter()
+        .collect(),
+    };
+
+    let text = progress.to_string();
+    assert_eq!(
+      text,
+      "finished\t1\nran\tbuild\nran\tfoo::test\nargument\tmode=release\nargument\tbuild\\tx\n"
+    );
+    assert_eq!(Progress::parse(&text), Some(progress));
+  }
+}
@@ -0,0 +1,45 @@
+use crate::common::*;
+
+/// String wrapper that displays a string as a string literal that parses
+/// back to it: a raw string if possible, and otherwise a cooked string with
+/// escape sequences
+pub struct Quoted<'str>(pub &'str str);
+
+impl<'str> Display for Quoted<'str> {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    if !self.0.contains(&['\'', '\n', '\r'][..]) {
+      return write!(f, "'{}'", self.0);
+    }
+
+    write!(f, "\"")?;
+    for c in self.0.chars() {
+      match c {
+        '\n' => write!(f, "\\n")?,
+        '\r' => write!(f, "\\r")?,
+        '\t' => write!(f, "\\t")?,
+        '\\' => write!(f, "\\\\")?,
+        '"' => write!(f, "\\\"")?,
+        _ => write!(f, "{}", c)?,
+      }
+    }
+    write!(f, "\"")
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn raw() {
+    assert_eq!(Quoted("a \"b\" \\c").to_string(), "'a \"b\" \\c'");
+  }
+
+  #[test]
+  fn cooked() {
+    assert_eq!(
+      Quoted("it's \"a\"\\\n").to_string(),
+      "\"it's \\\"a\\\"\\\\\\n\""
+    );
+  }
+}
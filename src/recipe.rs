@@ -1,6 +1,10 @@
 use crate::common::*;
 
-use std::process::{Command, ExitStatus, Stdio};
+use std::{
+  process::{ExitStatus, Stdio},
+  sync::mpsc,
+  time::Duration,
+};
 
 /// Return a `RuntimeError::Signal` if the process was terminated by a signal,
 /// otherwise return an `RuntimeError::UnknownFailure`
@@ -22,18 +26,176 @@ fn error_from_signal(
   }
 }
 
+/// Words which, when they appear in the name of a variable or parameter,
+/// mark its value as secret
+const SECRET_WORDS: &[&str] = &["key", "passwd", "password", "secret", "token"];
+
+/// Replace the values of variables and parameters that look like they hold
+/// secrets, like `api_key` or `GITHUB_TOKEN`, with `***`
+fn mask_secrets(
+  command: &str,
+  scope: &BTreeMap<&str, String>,
+  arguments: &BTreeMap<&str, Cow<str>>,
+) -> String {
+  let secret = |name: &str| {
+    name
+      .to_lowercase()
+      .split(&['_', '-'][..])
+      .any(|word| SECRET_WORDS.contains(&word.trim_end_matches('s')))
+  };
+
+  let values = scope
+    .iter()
+    .map(|(name, value)| (*name, value.as_str()))
+    .chain(
+      arguments
+        .iter()
+        .map(|(name, value)| (*name, value.as_ref())),
+    );
+
+  let mut masked = command.to_owned();
+  for (name, value) in values {
+    if !value.is_empty() && secret(name) {
+      masked = masked.replace(value, "***");
+    }
+  }
+  masked
+}
+
+/// Make a command that runs `command` in the shell, followed by `--` and
+/// `positional`, the recipe's arguments, with `set positional-arguments`
+fn shell_command(context: &RecipeContext, command: &str, positional: &[String]) -> Command {
+  let config = &context.config;
+
+  let mut cmd = Platform::make_shell_command(config.shell, &config.shell_arguments, command);
+
+  if let Some(run_directory) = &config.run_directory {
+    cmd.current_dir(run_directory);
+  }
+
+  if context.justfile.settings.positional_arguments {
+    cmd.arg("--");
+    cmd.args(positional);
+  }
+
+  cmd
+}
+
+/// Find the line number in an interpreter's error message about the script
+/// at `path`, for example `/tmp/justXXX/foo: line 4: ...` from bash, or
+/// `File "/tmp/justXXX/foo", line 4` from Python
+fn reported_line(message: &str, path: &str) -> Option<usize> {
+  let (_, rest) = message.rsplit_once(path)?;
+  let rest = rest.trim_start_matches(&['"', ':', ',', ' '][..]);
+  let rest = rest.strip_prefix("line").unwrap_or(rest).trim_start();
+  let end = rest
+    .find(|c: char| !c.is_ascii_digit())
+    .unwrap_or(rest.len());
+  rest[..end].parse().ok()
+}
+
+/// Run a shebang script, forwarding its standard error unless `quiet`, to
+/// `output` if given, while looking for the last line of the script
+/// mentioned there, which is usually where it failed. Since the script's
+/// standard error is then a pipe, this is only done with
+/// `set shebang-error-line`.
+fn status_with_reported_line(
+  command: &mut Command,
+  path: &Path,
+  quiet: bool,
+  output: Option<fs::File>,
+) -> io::Result<(ExitStatus, Option<usize>)> {
+  command.stderr(Stdio::piped());
+  let mut child = command.spawn()?;
+  let mut stderr = child.stderr.take().unwrap();
+  let path = path.to_string_lossy().into_owned();
+
+  let mut sink: Box<dyn Write + Send> = match output {
+    Some(output) => Box::new(output),
+    None => Box::new(io::stderr()),
+  };
+
+  let (sender, receiver) = mpsc::channel();
+  thread::spawn(move || {
+    let mut reported = None;
+    let mut line = Vec::new();
+    let mut buffer = [0; 1024];
+    loop {
+      let read = match stderr.read(&mut buffer) {
+        Ok(0) | Err(_) => break,
+        Ok(read) => read,
+      };
+      if !quiet {
+        sink.write_all(&buffer[..read]).ok();
+      }
+      for &byte in &buffer[..read] {
+        if byte == b'\n' {
+          reported = reported_line(&String::from_utf8_lossy(&line), &path).or(reported);
+          line.clear();
+        } else {
+          line.push(byte);
+        }
+      }
+    }
+    reported = reported_line(&String::from_utf8_lossy(&line), &path).or(reported);
+    sender.send(reported).ok();
+  });
+
+  let status = InterruptHandler::wait(&mut child)?;
+
+  // Background processes started by the script may keep its standard error
+  // open, so don't wait long for the rest of it
+  let reported = receiver
+    .recv_timeout(Duration::from_millis(100))
+    .ok()
+    .flatten();
+
+  Ok((status, reported))
+}
+
 #[derive(PartialEq, Debug)]
 pub(crate) struct Recipe<'a> {
+  /// Whether to ask before running the recipe, from `[confirm]`, and the
+  /// question to ask, if one was given
+  pub(crate) confirm: Option<Option<String>>,
   pub(crate) dependencies: Vec<&'a str>,
   pub(crate) dependency_tokens: Vec<Token<'a>>,
   pub(crate) doc: Option<&'a str>,
+  pub(crate) external_dependencies: Vec<ExternalDependency<'a>>,
+  /// The groups the recipe is listed under by `--list`, from
+  /// `[group: 'NAME']`
+  pub(crate) groups: BTreeSet<String>,
+  /// Whether the recipe was defined in an imported justfile
+  pub(crate) imported: bool,
+  pub(crate) interactive: bool,
+  pub(crate) invocation_context: Option<InvocationContext>,
   pub(crate) line_number: usize,
   pub(crate) lines: Vec<Vec<Fragment<'a>>>,
+  pub(crate) matrix: Vec<MatrixAxis<'a>>,
+  /// Metadata for tools that use the recipe, from
+  /// `[meta: KEY='VALUE', ...]`
+  pub(crate) meta: BTreeMap<&'a str, String>,
   pub(crate) name: &'a str,
+  /// The recipe's name where it's defined, from which the file and position
+  /// of the definition can be found
+  pub(crate) name_token: Token<'a>,
+  pub(crate) no_interpolation: bool,
+  /// The ranges of `dependencies` written in brackets, whose recipes may run
+  /// in parallel with each other with `--jobs`
+  pub(crate) parallel_dependencies: Vec<Range<usize>>,
+  /// The number of `dependencies` that run before the recipe. The rest were
+  /// written after `&&`, and run after it.
+  pub(crate) priors: usize,
   pub(crate) parameters: Vec<Parameter<'a>>,
   pub(crate) private: bool,
+  pub(crate) process_options: ProcessOptions,
   pub(crate) quiet: bool,
   pub(crate) shebang: bool,
+  /// A command that, if it succeeds, means the recipe doesn't need to run,
+  /// from `[skip-if: 'COMMAND']`
+  pub(crate) skip_if: Option<String>,
+  /// How many of the `--jobs` slots the recipe takes when run in parallel
+  pub(crate) weight: usize,
 }
 
 impl<'a> Recipe<'a> {
@@ -41,31 +203,293 @@ impl<'a> Recipe<'a> {
     self.min_arguments()..=self.max_arguments()
   }
 
+  /// Refuse to run a recipe restricted by `[ci-only]` or `[local-only]`
+  /// outside of that context
+  pub(crate) fn check_invocation_context(&self) -> RunResult<'a, ()> {
+    match self.invocation_context {
+      Some(invocation_context) if invocation_context != InvocationContext::current() => {
+        Err(RuntimeError::InvocationContext {
+          recipe: self.name,
+          invocation_context,
+        })
+      }
+      _ => Ok(()),
+    }
+  }
+
   pub(crate) fn min_arguments(&self) -> usize {
     self
       .parameters
       .iter()
-      .filter(|p| p.default.is_none())
+      .filter(|p| p.default.is_none() && p.kind != ParameterKind::Star)
       .count()
   }
 
   pub(crate) fn max_arguments(&self) -> usize {
-    if self.parameters.iter().any(|p| p.variadic) {
+    if self.parameters.iter().any(|p| p.kind.is_variadic()) {
       usize::MAX - 1
     } else {
       self.parameters.len()
     }
   }
 
+  /// The arguments for every combination of the values in the recipe's
+  /// matrix, with the values for the first parameter in the matrix varying
+  /// slowest
+  pub(crate) fn matrix_arguments(&self) -> Vec<Vec<&str>> {
+    let mut combinations: Vec<Vec<&str>> = vec![Vec::new()];
+
+    for axis in &self.matrix {
+      combinations = combinations
+        .into_iter()
+        .flat_map(|combination| {
+          axis.values.iter().map(move |value| {
+            let mut combination = combination.clone();
+            combination.push(value.cooked.as_ref());
+            combination
+          })
+        })
+        .collect();
+    }
+
+    // Reorder each combination from matrix order to parameter order
+    combinations
+      .into_iter()
+      .map(|combination| {
+        self
+          .parameters
+          .iter()
+          .filter_map(|parameter| {
+            self
+              .matrix
+              .iter()
+              .position(|axis| axis.name == parameter.name)
+              .map(|i| combination[i])
+          })
+          .collect()
+      })
+      .collect()
+  }
+
+  /// Print a shebang recipe's script with its line numbers, leaving out the
+  /// blank lines that pad it so those numbers match the justfile's
+  fn show_script(&self, script: &str) {
+    let padding = 1..self.line_number + 2;
+    let width = (self.line_number + 1 + self.lines.len()).to_string().len();
+    eprintln!("{}", message!("runtime.show-script", recipe = self.name));
+    for (i, line) in script.lines().enumerate() {
+      if !padding.contains(&i) {
+        eprintln!("{:>width$} | {}", i + 1, line, width = width);
+      }
+    }
+  }
+
+  /// The text of the first line that does nothing but change directory with
+  /// `cd`, if a later line depends on it. Since each line is run by a new
+  /// shell, the later line will run in the original directory.
+  pub(crate) fn lost_directory_change(&self) -> Option<&Token<'a>> {
+    if self.shebang {
+      return None;
+    }
+
+    let mut cd = None;
+    let mut heredoc = None;
+    let mut continued = false;
+
+    for line in &self.lines {
+      if heredoc.is_none() && !continued && !line.is_empty() {
+        if cd.is_some() {
+          return cd;
+        }
+
+        cd = Self::lone_cd(line);
+      }
+
+      heredoc = Heredoc::after_line(heredoc, line);
+      continued = line.last().map(Fragment::continuation).unwrap_or(false);
+    }
+
+    None
+  }
+
+  /// The first token of `line`, if it's a command that does nothing but
+  /// change directory
+  fn lone_cd<'b>(line: &'b [Fragment<'a>]) -> Option<&'b Token<'a>> {
+    let first = match line.first() {
+      Some(Fragment::Text { text }) => text,
+      _ => return None,
+    };
+
+    let command = first.lexeme().trim_start_matches(&['@', '-'][..]);
+    if command != "cd" && !command.starts_with("cd ") {
+      return None;
+    }
+
+    // Commands joined to the `cd` with `&&`, `;`, `|`, or a continued line
+    // run in the new directory
+    let joined = line.iter().any(|fragment| match fragment {
+      Fragment::Text { text } => text.lexeme().contains(['&', ';', '|']),
+      Fragment::Expression { .. } => false,
+    });
+
+    if joined || line.last().map(Fragment::continuation).unwrap_or(false) {
+      return None;
+    }
+
+    Some(first)
+  }
+
+  /// Evaluate `line`, which in a `[no-interpolation]` recipe is only text,
+  /// and is used as written
+  fn evaluate_line(
+    &self,
+    evaluator: &mut AssignmentEvaluator<'a, '_>,
+    line: &[Fragment<'a>],
+    arguments: &BTreeMap<&str, Cow<str>>,
+  ) -> RunResult<'a, String> {
+    if self.no_interpolation {
+      Ok(
+        line
+          .iter()
+          .map(|fragment| match fragment {
+            Fragment::Text { text } => text.lexeme(),
+            Fragment::Expression { .. } => "",
+          })
+          .collect(),
+      )
+    } else {
+      evaluator.evaluate_line(line, arguments)
+    }
+  }
+
+  /// Whether the recipe's `[skip-if]` command succeeds, in which case the
+  /// recipe, and its dependencies, aren't run. The command isn't run with
+  /// `--dry-run`.
+  pub(crate) fn skip(
+    &self,
+    context: &RecipeContext<'a>,
+    dotenv: &BTreeMap<String, String>,
+    exports: &BTreeMap<&'a str, Export>,
+  ) -> RunResult<'a, bool> {
+    let config = &context.config;
+
+    let command = match &self.skip_if {
+      Some(command) if !config.dry_run => command,
+      _ => return Ok(false),
+    };
+
+    let mut cmd = Platform::make_shell_command(config.shell, &config.shell_arguments, command);
+
+    if let Some(run_directory) = &config.run_directory {
+      cmd.current_dir(run_directory);
+    }
+
+    cmd.stdout(Stdio::null());
+
+    if config.quiet {
+      cmd.stderr(Stdio::null());
+    }
+
+    cmd.export_environment_variables(
+      &context.scope,
+      dotenv,
+      exports,
+      &context.config.environment_snapshot,
+    )?;
+
+    let skip = InterruptHandler::guard(|| cmd.status())
+      .map_err(|io_error| RuntimeError::IoError {
+        recipe: self.name,
+        io_error,
+      })?
+      .success();
+
+    if skip && !config.quiet {
+      let color = config.color.stderr().banner();
+      eprintln!(
+        "{}===> Skipping recipe `{}`, since `{}` succeeded{}",
+        color.prefix(),
+        self.name,
+        command,
+        color.suffix()
+      );
+    }
+
+    Ok(skip)
+  }
+
+  /// Whether the user answers yes when asked if the recipe should run, if it
+  /// has the `[confirm]` attribute. They aren't asked with `--yes` or
+  /// `--dry-run`.
+  fn confirm(&self, config: &Config) -> RunResult<'a, bool> {
+    lazy_static! {
+      /// Held while asking, so that recipes run in parallel ask one at a time
+      static ref ASKING: Mutex<()> = Mutex::new(());
+    }
+
+    let message = match &self.confirm {
+      Some(message) if !(config.yes || config.dry_run) => message,
+      _ => return Ok(true),
+    };
+
+    let _asking = ASKING.lock().unwrap();
+
+    let color = config.color.stderr().banner();
+    match message {
+      Some(message) => eprint!("{}{} [y/N] {}", color.prefix(), message, color.suffix()),
+      None => eprint!(
+        "{}Run recipe `{}`? [y/N] {}",
+        color.prefix(),
+        self.name,
+        color.suffix()
+      ),
+    }
+
+    let mut answer = String::new();
+    io::stdin()
+      .read_line(&mut answer)
+      .map_err(|io_error| RuntimeError::IoError {
+        recipe: self.name,
+        io_error,
+      })?;
+
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+  }
+
   pub(crate) fn run(
     &self,
     context: &RecipeContext<'a>,
-    arguments: &[&'a str],
+    arguments: &[&str],
     dotenv: &BTreeMap<String, String>,
-    exports: &BTreeSet<&'a str>,
+    exports: &BTreeMap<&'a str, Export>,
+  ) -> RunResult<'a, ()> {
+    if context.config.output_format != OutputFormat::Github {
+      return self.execute(context, arguments, dotenv, exports);
+    }
+
+    context.eprintln(github::group(self.name));
+    let result = self.execute(context, arguments, dotenv, exports);
+    context.eprintln(github::END_GROUP);
+    result
+  }
+
+  fn execute(
+    &self,
+    context: &RecipeContext<'a>,
+    arguments: &[&str],
+    dotenv: &BTreeMap<String, String>,
+    exports: &BTreeMap<&'a str, Export>,
   ) -> RunResult<'a, ()> {
     let config = &context.config;
 
+    if self.process_options.no_network && !Platform::network_isolation_supported() {
+      return Err(RuntimeError::NetworkIsolationUnsupported { recipe: self.name });
+    }
+
+    if !self.confirm(config)? {
+      return Err(RuntimeError::NotConfirmed { recipe: self.name });
+    }
+
     if config.verbosity.loquacious() {
       let color = config.color.stderr().banner();
       eprintln!(
@@ -78,15 +502,25 @@ impl<'a> Recipe<'a> {
 
     let mut argument_map = BTreeMap::new();
 
+    // With `set positional-arguments`, the arguments are also passed to the
+    // shell, so that commands can quote them
+    let mut positional = Vec::new();
+
     let mut evaluator = AssignmentEvaluator {
       assignments: &empty(),
       dry_run: config.dry_run,
+      environment: &config.environment_snapshot,
       evaluated: empty(),
       invocation_directory: &config.invocation_directory,
+      justfile: config.justfile_path.as_deref(),
+      justfile_directory: config.justfile_directory.as_deref(),
+      run_directory: config.run_directory.as_deref(),
       overrides: &empty(),
       quiet: config.quiet,
+      recipe_context: Some(context),
       scope: &context.scope,
       shell: config.shell,
+      shell_arguments: &config.shell_arguments,
       dotenv,
       exports,
     };
@@ -95,18 +529,25 @@ impl<'a> Recipe<'a> {
     for parameter in &self.parameters {
       let value = if rest.is_empty() {
         match parameter.default {
-          Some(ref default) => Cow::Owned(evaluator.evaluate_expression(default, &empty())?),
+          Some(ref default) => {
+            let value = evaluator.evaluate_expression(default, &empty())?;
+            positional.push(value.clone());
+            Cow::Owned(value)
+          }
+          None if parameter.kind == ParameterKind::Star => Cow::Borrowed(""),
           None => {
             return Err(RuntimeError::Internal {
               message: "missing parameter without default".to_string(),
             });
           }
         }
-      } else if parameter.variadic {
+      } else if parameter.kind.is_variadic() {
+        positional.extend(rest.iter().map(|argument| (*argument).to_owned()));
         let value = Cow::Owned(rest.to_vec().join(" "));
         rest = &[];
         value
       } else {
+        positional.push(rest[0].to_owned());
         let value = Cow::Borrowed(rest[0]);
         rest = &rest[1..];
         value
@@ -117,12 +558,12 @@ impl<'a> Recipe<'a> {
     if self.shebang {
       let mut evaluated_lines = vec![];
       for line in &self.lines {
-        evaluated_lines.push(evaluator.evaluate_line(line, &argument_map)?);
+        evaluated_lines.push(self.evaluate_line(&mut evaluator, line, &argument_map)?);
       }
 
       if config.dry_run || self.quiet {
         for line in &evaluated_lines {
-          eprintln!("{}", line);
+          context.eprintln(line);
         }
       }
 
@@ -130,6 +571,20 @@ impl<'a> Recipe<'a> {
         return Ok(());
       }
 
+      let shebang_line = evaluated_lines
+        .first()
+        .ok_or_else(|| RuntimeError::Internal {
+          message: "evaluated_lines was empty".to_string(),
+        })?;
+
+      let Shebang {
+        interpreter,
+        arguments,
+      } = Shebang::new(shebang_line).ok_or_else(|| RuntimeError::InvalidShebang {
+        recipe: self.name,
+        line: shebang_line.to_string(),
+      })?;
+
       let tmp = tempfile::Builder::new()
         .prefix("just")
         .tempdir()
@@ -163,6 +618,10 @@ impl<'a> Recipe<'a> {
           eprintln!("{}", config.color.doc().stderr().paint(&text));
         }
 
+        if config.show_script {
+          self.show_script(&text);
+        }
+
         f.write_all(text.as_bytes())
           .map_err(|error| RuntimeError::TmpdirIoError {
             recipe: self.name,
@@ -176,55 +635,95 @@ impl<'a> Recipe<'a> {
         io_error: error,
       })?;
 
-      let shebang_line = evaluated_lines
-        .first()
-        .ok_or_else(|| RuntimeError::Internal {
-          message: "evaluated_lines was empty".to_string(),
-        })?;
-
-      let Shebang {
-        interpreter,
-        argument,
-      } = Shebang::new(shebang_line).ok_or_else(|| RuntimeError::Internal {
-        message: format!("bad shebang line: {}", shebang_line),
-      })?;
-
       // create a command to run the script
       let mut command =
-        Platform::make_shebang_command(&path, interpreter, argument).map_err(|output_error| {
+        Platform::make_shebang_command(&path, interpreter, &arguments).map_err(|output_error| {
           RuntimeError::Cygpath {
             recipe: self.name,
             output_error,
           }
         })?;
 
-      command.export_environment_variables(&context.scope, dotenv, exports)?;
+      if let Some(run_directory) = &config.run_directory {
+        command.current_dir(run_directory);
+      }
+
+      if context.justfile.settings.positional_arguments {
+        command.args(&positional);
+      }
+
+      if config.quiet {
+        command.stdout(Stdio::null());
+      }
+
+      let output = self.collect_output(context, &mut command)?;
+
+      command.export_environment_variables(
+        &context.scope,
+        dotenv,
+        exports,
+        &context.config.environment_snapshot,
+      )?;
+      self.export_parameters(&mut command, &argument_map);
+
+      Platform::set_process_options(&mut command, self.process_options);
 
       // run it!
-      match InterruptHandler::guard(|| command.status()) {
-        Ok(exit_status) => {
+      match InterruptHandler::guard(|| {
+        if self.pty(context) {
+          Platform::status_with_pty(&mut command, self.interactive)
+            .map(|exit_status| (exit_status, None))
+        } else if context.justfile.settings.shebang_error_line {
+          status_with_reported_line(&mut command, &path, config.quiet, output)
+        } else {
+          if config.quiet {
+            command.stderr(Stdio::null());
+          } else if let Some(output) = output {
+            command.stderr(output);
+          }
+          command
+            .spawn()
+            .and_then(|mut child| InterruptHandler::wait(&mut child))
+            .map(|exit_status| (exit_status, None))
+        }
+      }) {
+        Ok((exit_status, reported_line)) => {
+          // The script is padded so that its lines have the same numbers as
+          // the justfile's, so a line the interpreter reported in the body
+          // of the recipe is also the justfile line
+          let body = self.line_number + 3..=self.line_number + 1 + self.lines.len();
+          let line_number = reported_line.filter(|line| body.contains(line));
+
           if let Some(code) = exit_status.code() {
             if code != 0 {
               return Err(RuntimeError::Code {
                 recipe: self.name,
-                line_number: None,
+                line_number,
                 code,
+                command: None,
               });
             }
           } else {
-            return Err(error_from_signal(self.name, None, exit_status));
+            return Err(error_from_signal(self.name, line_number, exit_status));
           }
         }
         Err(io_error) => {
           return Err(RuntimeError::Shebang {
             recipe: self.name,
             command: interpreter.to_string(),
-            argument: argument.map(String::from),
+            arguments: arguments
+              .iter()
+              .map(|argument| argument.to_string())
+              .collect(),
             io_error,
           });
         }
       };
     } else {
+      let one_shell = context.justfile.settings.one_shell;
+      let mut script = String::new();
+      let mut script_echoed = true;
+      let mut script_ignores_error = false;
       let mut lines = self.lines.iter().peekable();
       let mut line_number = self.line_number + 1;
       loop {
@@ -232,22 +731,37 @@ impl<'a> Recipe<'a> {
           break;
         }
         let mut evaluated = String::new();
+        let mut heredoc = None;
         loop {
           if lines.peek().is_none() {
             break;
           }
           let line = lines.next().unwrap();
           line_number += 1;
-          evaluated += &evaluator.evaluate_line(line, &argument_map)?;
-          if line.last().map(Fragment::continuation).unwrap_or(false) {
+          evaluated += &self.evaluate_line(&mut evaluator, line, &argument_map)?;
+          // A heredoc's lines are passed to the shell with the line that opens it
+          heredoc = Heredoc::after_line(heredoc, line);
+          if heredoc.is_some() {
+            evaluated.push('\n');
+          } else if line.last().map(Fragment::continuation).unwrap_or(false) {
             evaluated.pop();
           } else {
             break;
           }
         }
+        // Commands may start with `@`, to not be echoed, and `-`, to not
+        // fail the recipe if they fail, in either order
         let mut command = evaluated.as_str();
-        let quiet_command = command.starts_with('@');
-        if quiet_command {
+        let mut quiet_command = false;
+        let mut ignore_error = false;
+        loop {
+          if !quiet_command && command.starts_with('@') {
+            quiet_command = true;
+          } else if !ignore_error && command.starts_with('-') {
+            ignore_error = true;
+          } else {
+            break;
+          }
           command = &command[1..];
         }
 
@@ -255,58 +769,214 @@ impl<'a> Recipe<'a> {
           continue;
         }
 
-        if config.dry_run
+        let echoed = config.dry_run
           || config.verbosity.loquacious()
-          || !((quiet_command ^ self.quiet) || config.quiet)
-        {
+          || !((quiet_command ^ self.quiet) || config.quiet);
+
+        if echoed {
           let color = if config.highlight {
             config.color.command()
           } else {
             config.color
           };
-          eprintln!("{}", color.stderr().paint(command));
+          context.eprintln(format!(
+            "{}{}",
+            config.timestamp(),
+            color.stderr().paint(command)
+          ));
         }
 
         if config.dry_run {
           continue;
         }
 
-        let mut cmd = Command::new(config.shell);
+        if one_shell {
+          if !script.is_empty() {
+            script.push('\n');
+          }
+          script.push_str(command);
+          script_echoed &= echoed;
+          script_ignores_error = ignore_error;
+          continue;
+        }
+
+        let masked = if echoed {
+          None
+        } else {
+          Some(mask_secrets(command, &context.scope, &argument_map))
+        };
 
-        cmd.arg("-cu").arg(command);
+        let mut command = shell_command(context, command, &positional);
+        command.export_environment_variables(
+          &context.scope,
+          dotenv,
+          exports,
+          &context.config.environment_snapshot,
+        )?;
+        self.export_parameters(&mut command, &argument_map);
+        let result = self.run_command(context, command, Some(line_number), masked);
+        self.ignore_error(config, result, ignore_error)?;
+      }
 
-        if config.quiet {
-          cmd.stderr(Stdio::null());
-          cmd.stdout(Stdio::null());
-        }
+      // With `set one-shell`, the lines are run by a single shell, which only
+      // reports the status of the last one
+      if !script.is_empty() {
+        let masked = if script_echoed {
+          None
+        } else {
+          Some(mask_secrets(&script, &context.scope, &argument_map))
+        };
 
-        cmd.export_environment_variables(&context.scope, dotenv, exports)?;
-
-        match InterruptHandler::guard(|| cmd.status()) {
-          Ok(exit_status) => {
-            if let Some(code) = exit_status.code() {
-              if code != 0 {
-                return Err(RuntimeError::Code {
-                  recipe: self.name,
-                  line_number: Some(line_number),
-                  code,
-                });
-              }
-            } else {
-              return Err(error_from_signal(self.name, Some(line_number), exit_status));
-            }
+        let mut command = shell_command(context, &script, &positional);
+        command.export_environment_variables(
+          &context.scope,
+          dotenv,
+          exports,
+          &context.config.environment_snapshot,
+        )?;
+        self.export_parameters(&mut command, &argument_map);
+        let result = self.run_command(context, command, None, masked);
+        self.ignore_error(config, result, script_ignores_error)?;
+      }
+    }
+    Ok(())
+  }
+
+  /// Report a command that failed with a nonzero exit code, instead of
+  /// returning the error, if it was prefixed with `-`, in `ignore`
+  fn ignore_error(
+    &self,
+    config: &Config,
+    result: RunResult<'a, ()>,
+    ignore: bool,
+  ) -> RunResult<'a, ()> {
+    match result {
+      Err(RuntimeError::Code {
+        line_number, code, ..
+      }) if ignore => {
+        if !config.quiet {
+          let warning = config.color.stderr().warning();
+          match line_number {
+            Some(line_number) => eprintln!(
+              "{} {}",
+              warning.paint(&message!("label.warning")),
+              message!(
+                "warning.ignored-failure-on-line",
+                recipe = self.name,
+                line = line_number,
+                code = code
+              ),
+            ),
+            None => eprintln!(
+              "{} {}",
+              warning.paint(&message!("label.warning")),
+              message!("warning.ignored-failure", recipe = self.name, code = code),
+            ),
           }
-          Err(io_error) => {
-            return Err(RuntimeError::IoError {
+        }
+        Ok(())
+      }
+      result => result,
+    }
+  }
+
+  /// Run `cmd`, a command in the shell, failing with an error that names
+  /// `line_number`, and shows `masked`, the command with secrets masked, if
+  /// the command wasn't echoed
+  fn run_command(
+    &self,
+    context: &RecipeContext<'a>,
+    mut cmd: Command,
+    line_number: Option<usize>,
+    masked: Option<String>,
+  ) -> RunResult<'a, ()> {
+    let config = &context.config;
+
+    if config.quiet {
+      cmd.stderr(Stdio::null());
+      cmd.stdout(Stdio::null());
+    } else if let Some(stderr) = self.collect_output(context, &mut cmd)? {
+      cmd.stderr(stderr);
+    }
+
+    Platform::set_process_options(&mut cmd, self.process_options);
+
+    match InterruptHandler::guard(|| {
+      if self.pty(context) {
+        Platform::status_with_pty(&mut cmd, self.interactive)
+      } else {
+        cmd
+          .spawn()
+          .and_then(|mut child| InterruptHandler::wait(&mut child))
+      }
+    }) {
+      Ok(exit_status) => {
+        if let Some(code) = exit_status.code() {
+          if code != 0 {
+            return Err(RuntimeError::Code {
               recipe: self.name,
-              io_error,
+              line_number,
+              code,
+              command: masked,
             });
           }
-        };
+        } else {
+          return Err(error_from_signal(self.name, line_number, exit_status));
+        }
       }
-    }
+      Err(io_error) => {
+        return Err(RuntimeError::IoError {
+          recipe: self.name,
+          io_error,
+        });
+      }
+    };
+
     Ok(())
   }
+
+  /// Export the values of the parameters written with `$` to `command`,
+  /// taking precedence over exported variables of the same name
+  fn export_parameters(&self, command: &mut Command, arguments: &BTreeMap<&'a str, Cow<str>>) {
+    for parameter in self.parameters.iter().filter(|parameter| parameter.export) {
+      command.env(parameter.name, arguments[parameter.name].as_ref());
+    }
+  }
+
+  /// Send the standard output of `command` to the collected output, if it is
+  /// being collected, and it isn't suppressed or run attached to a terminal,
+  /// returning a handle for its standard error to be sent there as well
+  fn collect_output(
+    &self,
+    context: &RecipeContext<'a>,
+    command: &mut Command,
+  ) -> RunResult<'a, Option<fs::File>> {
+    let output = match &context.output {
+      Some(output) if !context.config.quiet && !self.pty(context) => output,
+      _ => return Ok(None),
+    };
+
+    let io_error = |io_error| RuntimeError::IoError {
+      recipe: self.name,
+      io_error,
+    };
+
+    command.stdout(output.stdout().map_err(io_error)?);
+
+    output.stderr().map(Some).map_err(io_error)
+  }
+
+  /// Whether commands are run attached to a terminal. Interactive recipes
+  /// always are. With `set force-color`, commands are given a terminal to
+  /// write to in builds with the `pty` feature.
+  fn pty(&self, context: &RecipeContext<'a>) -> bool {
+    let config = &context.config;
+    self.interactive
+      || (cfg!(feature = "pty")
+        && context.justfile.settings.force_color
+        && config.color.stdout().active()
+        && !config.quiet)
+  }
 }
 
 impl<'a> Display for Recipe<'a> {
@@ -315,6 +985,70 @@ impl<'a> Display for Recipe<'a> {
       writeln!(f, "# {}", doc)?;
     }
 
+    for parameter in &self.parameters {
+      if let Some(doc) = parameter.doc {
+        writeln!(f, "# {}: {}", parameter.name, doc)?;
+      }
+    }
+
+    if self.private && !self.name.starts_with('_') {
+      writeln!(f, "[private]")?;
+    }
+
+    if self.interactive {
+      writeln!(f, "[interactive]")?;
+    }
+
+    if self.no_interpolation {
+      writeln!(f, "[no-interpolation]")?;
+    }
+
+    write!(f, "{}", self.process_options)?;
+
+    if let Some(invocation_context) = self.invocation_context {
+      writeln!(f, "[{}]", invocation_context.attribute())?;
+    }
+
+    if let Some(command) = &self.skip_if {
+      writeln!(f, "[skip-if: {}]", Quoted(command))?;
+    }
+
+    match &self.confirm {
+      Some(Some(message)) => writeln!(f, "[confirm: {}]", Quoted(message))?,
+      Some(None) => writeln!(f, "[confirm]")?,
+      None => {}
+    }
+
+    if self.weight != 1 {
+      writeln!(f, "[weight: {}]", self.weight)?;
+    }
+
+    for group in &self.groups {
+      writeln!(f, "[group: {}]", Quoted(group))?;
+    }
+
+    if !self.meta.is_empty() {
+      write!(f, "[meta: ")?;
+      for (i, (key, value)) in self.meta.iter().enumerate() {
+        if i > 0 {
+          write!(f, ", ")?;
+        }
+        write!(f, "{}={}", key, Quoted(value))?;
+      }
+      writeln!(f, "]")?;
+    }
+
+    if !self.matrix.is_empty() {
+      write!(f, "[matrix: ")?;
+      for (i, axis) in self.matrix.iter().enumerate() {
+        if i > 0 {
+          write!(f, ", ")?;
+        }
+        write!(f, "{}", axis)?;
+      }
+      writeln!(f, "]")?;
+    }
+
     if self.quiet {
       write!(f, "@{}", self.name)?;
     } else {
@@ -325,8 +1059,30 @@ impl<'a> Display for Recipe<'a> {
       write!(f, " {}", parameter)?;
     }
     write!(f, ":")?;
-    for dependency in &self.dependencies {
-      write!(f, " {}", dependency)?;
+    for (i, dependency) in self.dependencies.iter().enumerate() {
+      if i == self.priors {
+        for dependency in &self.external_dependencies {
+          write!(f, " {}", dependency)?;
+        }
+        write!(f, " &&")?;
+      }
+      let group = self
+        .parallel_dependencies
+        .iter()
+        .find(|group| group.contains(&i));
+      match group {
+        Some(group) if group.start == i => write!(f, " [{}", dependency)?,
+        Some(_) => write!(f, ", {}", dependency)?,
+        None => write!(f, " {}", dependency)?,
+      }
+      if group.map(|group| group.end == i + 1).unwrap_or(false) {
+        write!(f, "]")?;
+      }
+    }
+    if self.priors == self.dependencies.len() {
+      for dependency in &self.external_dependencies {
+        write!(f, " {}", dependency)?;
+      }
     }
 
     for (i, pieces) in self.lines.iter().enumerate() {
@@ -339,6 +1095,11 @@ impl<'a> Display for Recipe<'a> {
         }
         match *piece {
           Fragment::Text { ref text } => write!(f, "{}", text.lexeme())?,
+          // The closing brace of a conditional can't touch the closing `}}`
+          Fragment::Expression {
+            expression: ref expression @ Expression::Conditional { .. },
+            ..
+          } => write!(f, "{{{{ {} }}}}", expression)?,
           Fragment::Expression { ref expression, .. } => write!(f, "{{{{{}}}}}", expression)?,
         }
       }
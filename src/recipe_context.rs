@@ -3,4 +3,23 @@ use crate::common::*;
 pub(crate) struct RecipeContext<'a> {
   pub(crate) config: &'a Config<'a>,
   pub(crate) scope: BTreeMap<&'a str, String>,
+  pub(crate) justfile: &'a Justfile<'a>,
+  /// Recipes that have run to completion
+  pub(crate) ran: RefCell<BTreeSet<&'a str>>,
+  /// Recipes that have started but not yet finished running, used to detect
+  /// recipes that invoke themselves, directly or indirectly
+  pub(crate) running: RefCell<Vec<&'a str>>,
+  /// Where the output of commands is collected, when running alongside
+  /// other recipes with `--jobs`
+  pub(crate) output: Option<RecipeOutput>,
+}
+
+impl<'a> RecipeContext<'a> {
+  /// Print `message` to standard error, or add it to the collected output
+  pub(crate) fn eprintln(&self, message: impl Display) {
+    match &self.output {
+      Some(output) => output.eprintln(message),
+      None => eprintln!("{}", message),
+    }
+  }
 }
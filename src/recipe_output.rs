@@ -0,0 +1,111 @@
+use crate::common::*;
+
+lazy_static! {
+  /// Held while printing collected output, so that the output of recipes
+  /// that finish at the same time isn't interleaved
+  static ref PRINTING: Mutex<()> = Mutex::new(());
+}
+
+/// The output of a recipe run alongside others with `--jobs`, collected in
+/// temporary files and printed all at once when the recipe finishes
+pub(crate) struct RecipeOutput {
+  stdout: fs::File,
+  stderr: fs::File,
+  /// The order that the output was written in
+  spans: Mutex<Spans>,
+}
+
+/// The output collected so far, as spans of the two files, in the order that
+/// they were written in
+#[derive(Default)]
+struct Spans {
+  spans: Vec<(Stream, Range<u64>)>,
+  stdout: u64,
+  stderr: u64,
+}
+
+#[derive(Copy, Clone)]
+enum Stream {
+  Stdout,
+  Stderr,
+}
+
+impl RecipeOutput {
+  pub(crate) fn new() -> io::Result<RecipeOutput> {
+    Ok(RecipeOutput {
+      stdout: tempfile::tempfile()?,
+      stderr: tempfile::tempfile()?,
+      spans: Mutex::new(Spans::default()),
+    })
+  }
+
+  /// A handle to the file collecting standard output, for a child process
+  pub(crate) fn stdout(&self) -> io::Result<fs::File> {
+    self.stdout.try_clone()
+  }
+
+  /// A handle to the file collecting standard error, for a child process
+  pub(crate) fn stderr(&self) -> io::Result<fs::File> {
+    self.stderr.try_clone()
+  }
+
+  /// Add a line to the collected standard error, after the output of the
+  /// commands run so far
+  pub(crate) fn eprintln(&self, message: impl Display) {
+    let mut spans = self.spans.lock().unwrap();
+    self.record(&mut spans).ok();
+    writeln!(&self.stderr, "{}", message).ok();
+    self.record(&mut spans).ok();
+  }
+
+  /// Record the output written to either file since the last time, with
+  /// standard output first, since the order within a single command's output
+  /// isn't known
+  fn record(&self, spans: &mut Spans) -> io::Result<()> {
+    let stdout = self.stdout.metadata()?.len();
+    if stdout > spans.stdout {
+      spans.spans.push((Stream::Stdout, spans.stdout..stdout));
+      spans.stdout = stdout;
+    }
+
+    let stderr = self.stderr.metadata()?.len();
+    if stderr > spans.stderr {
+      spans.spans.push((Stream::Stderr, spans.stderr..stderr));
+      spans.stderr = stderr;
+    }
+
+    Ok(())
+  }
+
+  /// Print the collected output to standard output and standard error, in
+  /// the order it was written in
+  pub(crate) fn print(mut self) -> io::Result<()> {
+    let _printing = PRINTING.lock().unwrap();
+
+    let mut spans = mem::take(self.spans.get_mut().unwrap());
+    self.record(&mut spans)?;
+
+    for (stream, span) in spans.spans {
+      let file = match stream {
+        Stream::Stdout => &mut self.stdout,
+        Stream::Stderr => &mut self.stderr,
+      };
+      file.seek(io::SeekFrom::Start(span.start))?;
+      let mut span = Read::take(&*file, span.end - span.start);
+
+      match stream {
+        Stream::Stdout => {
+          let stdout = io::stdout();
+          let mut stdout = stdout.lock();
+          io::copy(&mut span, &mut stdout)?;
+          stdout.flush()?;
+        }
+        Stream::Stderr => {
+          io::copy(&mut span, &mut io::stderr().lock())?;
+        }
+      }
+    }
+
+    Ok(())
+  }
+}
@@ -2,37 +2,24 @@ use crate::common::*;
 
 use CompilationErrorKind::*;
 
-// There are borrow issues here that seems too difficult to solve.
-// The errors derived from the variable token has too short a lifetime,
-// so we create a new error from its contents, which do live long
-// enough.
-//
-// I suspect the solution here is to give recipes, pieces, and expressions
-// two lifetime parameters instead of one, with one being the lifetime
-// of the struct, and the second being the lifetime of the tokens
-// that it contains.
-
 pub(crate) struct RecipeResolver<'a: 'b, 'b> {
   stack: Vec<&'a str>,
   seen: BTreeSet<&'a str>,
   resolved: BTreeSet<&'a str>,
-  recipes: &'b BTreeMap<&'a str, Recipe<'a>>,
-  assignments: &'b BTreeMap<&'a str, Expression<'a>>,
-  text: &'a str,
+  recipes: &'b OrderedMap<&'a str, Recipe<'a>>,
+  assignments: &'b OrderedMap<&'a str, Expression<'a>>,
 }
 
 impl<'a, 'b> RecipeResolver<'a, 'b> {
   pub(crate) fn resolve_recipes(
-    recipes: &BTreeMap<&'a str, Recipe<'a>>,
-    assignments: &BTreeMap<&'a str, Expression<'a>>,
-    text: &'a str,
+    recipes: &OrderedMap<&'a str, Recipe<'a>>,
+    assignments: &OrderedMap<&'a str, Expression<'a>>,
   ) -> CompilationResult<'a, ()> {
     let mut resolver = RecipeResolver {
       seen: empty(),
       stack: empty(),
       resolved: empty(),
       assignments,
-      text,
       recipes,
     };
 
@@ -70,39 +57,25 @@ impl<'a, 'b> RecipeResolver<'a, 'b> {
     Ok(())
   }
 
-  fn resolve_function(&self, function: &Token, argc: usize) -> CompilationResult<'a, ()> {
+  fn resolve_function(&self, function: &Token<'a>, argc: usize) -> CompilationResult<'a, ()> {
     Function::resolve(function, argc).map_err(|error| CompilationError {
-      offset: error.offset,
-      line: error.line,
-      column: error.column,
-      width: error.width,
       kind: UnknownFunction {
-        function: &self.text[error.offset..error.offset + error.width],
+        function: function.lexeme(),
       },
-      text: self.text,
+      ..error
     })
   }
 
   fn resolve_variable(
     &self,
-    variable: &Token,
+    variable: &Token<'a>,
     parameters: &[Parameter],
   ) -> CompilationResult<'a, ()> {
     let name = variable.lexeme();
     let undefined =
       !self.assignments.contains_key(name) && !parameters.iter().any(|p| p.name == name);
     if undefined {
-      let error = variable.error(UndefinedVariable { variable: name });
-      return Err(CompilationError {
-        offset: error.offset,
-        line: error.line,
-        column: error.column,
-        width: error.width,
-        kind: UndefinedVariable {
-          variable: &self.text[error.offset..error.offset + error.width],
-        },
-        text: self.text,
-      });
+      return Err(variable.error(UndefinedVariable { variable: name }));
     }
 
     Ok(())
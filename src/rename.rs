@@ -0,0 +1,284 @@
+use crate::common::*;
+
+use std::path::Path;
+
+use TokenKind::*;
+
+#[derive(Copy, Clone, PartialEq)]
+enum Kind {
+  Recipe,
+  Variable,
+}
+
+impl Display for Kind {
+  fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+    match self {
+      Kind::Recipe => write!(f, "recipe"),
+      Kind::Variable => write!(f, "variable"),
+    }
+  }
+}
+
+/// Finds the offsets of the definition of and references to a recipe or
+/// variable in a token stream
+struct Renamer<'a> {
+  kind: Kind,
+  old: &'a str,
+  offsets: Vec<usize>,
+}
+
+impl<'a> Renamer<'a> {
+  fn new(kind: Kind, old: &'a str) -> Renamer<'a> {
+    Renamer {
+      kind,
+      old,
+      offsets: Vec::new(),
+    }
+  }
+
+  fn tokens(&mut self, tokens: &[Token<'a>]) {
+    let tokens = tokens
+      .iter()
+      .filter(|token| token.kind != Whitespace)
+      .collect::<Vec<&Token>>();
+
+    let mut line = Vec::new();
+    let mut body = false;
+    let mut interpolation = false;
+
+    for (i, token) in tokens.iter().cloned().enumerate() {
+      let next = tokens.get(i + 1).map(|token| token.kind);
+      match token.kind {
+        Comment => self.comment(token),
+        StringCooked => {
+          self.string(token);
+          if !body {
+            line.push(token);
+          }
+        }
+        Indent => body = true,
+        Dedent => body = false,
+        InterpolationStart => interpolation = true,
+        InterpolationEnd => interpolation = false,
+        Name if body && interpolation && next != Some(ParenL) => {
+          self.reference(token, Kind::Variable)
+        }
+        Eol | Eof if !body => {
+          self.item(&line);
+          line.clear();
+        }
+        _ if !body => line.push(token),
+        _ => {}
+      }
+    }
+  }
+
+  /// Record `token` if it names the recipe or variable being renamed
+  fn reference(&mut self, token: &Token<'a>, kind: Kind) {
+    if kind == self.kind && token.lexeme() == self.old {
+      self.offsets.push(token.offset);
+    }
+  }
+
+  /// Record references to a recipe written as `` `NAME` `` in a comment
+  fn comment(&mut self, token: &Token<'a>) {
+    if self.kind != Kind::Recipe {
+      return;
+    }
+
+    let quoted = format!("`{}`", self.old);
+    for (index, _) in token.lexeme().match_indices(&quoted) {
+      self.offsets.push(token.offset + index + 1);
+    }
+  }
+
+  /// Record references to a variable in `{{…}}` interpolations in a cooked
+  /// string
+  fn string(&mut self, token: &Token<'a>) {
+    if self.kind != Kind::Variable {
+      return;
+    }
+
+    let lexeme = token.lexeme();
+    let mut rest = 0;
+    while let Some(start) = lexeme[rest..].find("{{").map(|start| rest + start + 2) {
+      if lexeme[start..].starts_with("{{") {
+        rest = start + 2;
+        continue;
+      }
+
+      let end = match lexeme[start..].find("}}") {
+        Some(end) => start + end,
+        None => return,
+      };
+
+      if let Ok(tokens) = Lexer::lex(lexeme[start..end].trim_start()) {
+        let start = end - lexeme[start..end].trim_start().len();
+        for (i, inner) in tokens.iter().enumerate() {
+          let next = tokens[i + 1..]
+            .iter()
+            .find(|token| token.kind != Whitespace)
+            .map(|token| token.kind);
+          if inner.kind == Name && next != Some(ParenL) && inner.lexeme() == self.old {
+            self.offsets.push(token.offset + start + inner.offset);
+          }
+        }
+      }
+
+      rest = end + 2;
+    }
+  }
+
+  /// Handle a top-level line, other than comments
+  fn item(&mut self, line: &[&Token<'a>]) {
+    let kinds = line
+      .iter()
+      .map(|token| token.kind)
+      .collect::<Vec<TokenKind>>();
+    let keyword = line.first().map(|token| token.lexeme());
+
+    match (keyword, kinds.as_slice()) {
+      (_, []) | (_, [BracketL, ..]) => {}
+      (Some("set"), [Name, Name]) | (Some("set"), [Name, Name, ColonEquals, ..]) => {}
+      (Some("alias"), [Name, Name, ColonEquals, Name])
+      | (Some("alias"), [Name, Name, Equals, Name]) => self.reference(line[3], Kind::Recipe),
+      (Some("export"), [Name, Name, ColonEquals, ..])
+      | (Some("export"), [Name, Name, Equals, ..]) => self.assignment(&line[1..]),
+      (_, [Name, ColonEquals, ..]) | (_, [Name, Equals, ..]) => self.assignment(line),
+      (_, [At, ..]) => self.recipe(&line[1..]),
+      _ => self.recipe(line),
+    }
+  }
+
+  fn assignment(&mut self, line: &[&Token<'a>]) {
+    self.reference(line[0], Kind::Variable);
+    self.expression(&line[2..]);
+  }
+
+  fn expression(&mut self, tokens: &[&Token<'a>]) {
+    for (i, token) in tokens.iter().cloned().enumerate() {
+      let next = tokens.get(i + 1).map(|token| token.kind);
+      if token.kind == Name && next != Some(ParenL) {
+        self.reference(token, Kind::Variable);
+      }
+    }
+  }
+
+  fn recipe(&mut self, line: &[&Token<'a>]) {
+    let name = match line.first() {
+      Some(name) if name.kind == Name => name,
+      _ => return,
+    };
+
+    self.reference(name, Kind::Recipe);
+
+    let mut depth = 0;
+    let mut dependencies = false;
+
+    for i in 1..line.len() {
+      let token = line[i];
+      let previous = line[i - 1].kind;
+      let next = line.get(i + 1).map(|token| token.kind);
+      match token.kind {
+        ParenL => depth += 1,
+        ParenR => depth -= 1,
+        Colon if depth == 0 => dependencies = true,
+        // Dependencies on recipes in other justfiles are written `PATH::NAME`
+        Name if dependencies && (previous != Colon || line[i - 2].kind != Colon) => {
+          self.reference(token, Kind::Recipe)
+        }
+        Name if dependencies => {}
+        Name if next == Some(ParenL) => {}
+        Name if depth > 0 || previous == Equals => self.reference(token, Kind::Variable),
+        _ => {}
+      }
+    }
+  }
+}
+
+/// Rename the recipe or variable `old` in the justfile at `path` to `new`,
+/// along with references to it in aliases, dependencies, expressions, and
+/// comments, and write the result back to `path`
+pub(crate) fn rename(
+  justfile: &Justfile,
+  loader: &Loader,
+  path: &Path,
+  defines: &BTreeMap<&str, &str>,
+  old: &str,
+  new: &str,
+) -> Result<(), i32> {
+  let kind = match (
+    justfile.recipes.contains_key(old),
+    justfile.assignments.contains_key(old),
+  ) {
+    (true, false) => Kind::Recipe,
+    (false, true) => Kind::Variable,
+    (true, true) => {
+      eprintln!(
+        "Justfile contains both a recipe and a variable named `{}`.",
+        old
+      );
+      return Err(EXIT_FAILURE);
+    }
+    (false, false) => {
+      eprintln!("Justfile does not contain recipe or variable `{}`.", old);
+      return Err(EXIT_FAILURE);
+    }
+  };
+
+  match Lexer::lex(new).as_deref() {
+    Ok([name, eof]) if name.kind == Name && eof.kind == Eof && name.lexeme() == new => {}
+    _ => {
+      eprintln!("`{}` is not a valid {} name.", new, kind);
+      return Err(EXIT_FAILURE);
+    }
+  }
+
+  let text = loader.root();
+
+  let tokens = match Lexer::lex(text) {
+    Ok(tokens) => tokens,
+    Err(error) => {
+      eprintln!("{}", error);
+      return Err(error.exit_code());
+    }
+  };
+
+  let mut renamer = Renamer::new(kind, old);
+  renamer.tokens(&tokens);
+
+  let mut offsets = renamer.offsets;
+  offsets.sort_unstable();
+
+  let mut renamed = String::new();
+  let mut end = 0;
+  for offset in offsets {
+    renamed += &text[end..offset];
+    renamed += new;
+    end = offset + old.len();
+  }
+  renamed += &text[end..];
+
+  let renamed_loader = loader.with_root(renamed);
+  if let Err(error) = Parser::parse_with_loader(&renamed_loader, defines) {
+    eprintln!(
+      "Renaming {} `{}` to `{}` would produce an invalid justfile:",
+      kind, old, new
+    );
+    eprintln!("{}", error);
+    return Err(EXIT_FAILURE);
+  }
+
+  if let Err(error) = fs::write(path, renamed_loader.root()) {
+    eprintln!(
+      "Failed to write justfile to `{}`: {}",
+      path.display(),
+      error
+    );
+    return Err(EXIT_FAILURE);
+  }
+
+  eprintln!("Renamed {} `{}` to `{}`", kind, old, new);
+
+  Ok(())
+}
@@ -0,0 +1,126 @@
+use crate::common::*;
+
+use rustyline::{error::ReadlineError, Editor};
+
+const HELP: &str = "\
+Enter an expression to print its value, or one of:
+  :run RECIPE [ARGUMENTS...]  Run RECIPE with ARGUMENTS
+  :show RECIPE                Print RECIPE
+  :vars                       Print the value of each variable
+  :help                       Print this message
+  :quit                       Exit";
+
+/// Read expressions to evaluate and recipes to run from an interactive
+/// prompt, until end of input. Lines entered are kept in a history file
+/// alongside the invocation history, so that they can be recalled in later
+/// sessions.
+pub(crate) fn repl(justfile: &Justfile, config: &Config, path: &Path) -> Result<(), i32> {
+  let mut editor = Editor::<()>::new();
+
+  let history = history::state_path("repl", path);
+
+  if let Some(history) = &history {
+    editor.load_history(history).ok();
+  }
+
+  loop {
+    let line = match editor.readline("just> ") {
+      Ok(line) => line,
+      // Ctrl-C abandons the line being entered, like in a shell
+      Err(ReadlineError::Interrupted) => continue,
+      Err(ReadlineError::Eof) => break,
+      Err(error) => {
+        eprintln!("Failed to read line: {}", error);
+        return Err(EXIT_FAILURE);
+      }
+    };
+
+    let line = line.trim();
+
+    if line.is_empty() {
+      continue;
+    }
+
+    editor.add_history_entry(line);
+
+    if !evaluate_line(justfile, config, line) {
+      break;
+    }
+  }
+
+  if let Some(history) = &history {
+    let saved = history
+      .parent()
+      .map_or(Ok(()), fs::create_dir_all)
+      .map_err(ReadlineError::from)
+      .and_then(|()| editor.save_history(history));
+
+    if let Err(error) = saved {
+      warn!(
+        "Failed to save history in `{}`: {}",
+        history.display(),
+        error
+      );
+    }
+  }
+
+  Ok(())
+}
+
+/// Evaluate a line entered at the prompt, returning false if it asks to exit
+fn evaluate_line<'a>(justfile: &'a Justfile<'a>, config: &'a Config<'a>, line: &'a str) -> bool {
+  let mut words = line.split_whitespace();
+
+  match words.next() {
+    Some(":quit") | Some(":q") => return false,
+    Some(":help") => println!("{}", HELP),
+    Some(":run") => {
+      let arguments = words.collect::<Vec<&str>>();
+      if arguments.is_empty() {
+        eprintln!("error: `:run` requires a recipe");
+      } else if let Err(error) = justfile.run(&arguments, config) {
+        if !config.quiet {
+          report(config, error);
+        }
+      }
+    }
+    Some(":show") => match words.next().map(|name| (name, justfile.get_recipe(name))) {
+      Some((_, Some(recipe))) => println!("{}", recipe),
+      Some((name, None)) => eprintln!("error: Justfile does not contain recipe `{}`.", name),
+      None => eprintln!("error: `:show` requires a recipe"),
+    },
+    Some(":vars") => match justfile.evaluate_variables(config) {
+      Ok(scope) => {
+        let width = scope.keys().map(|name| name.len()).max().unwrap_or(0);
+        for (name, value) in scope {
+          println!("{0:1$} := \"{2}\"", name, width, value);
+        }
+      }
+      Err(error) => report(config, error),
+    },
+    Some(command) if command.starts_with(':') => {
+      eprintln!(
+        "error: Unknown command `{}`, enter `:help` for help",
+        command
+      );
+    }
+    _ => match Parser::parse_expression(line, justfile) {
+      Ok(expression) => match justfile.evaluate_expression(&expression, config) {
+        Ok(value) => println!("{}", value),
+        Err(error) => report(config, error),
+      },
+      Err(error) => report(config, error),
+    },
+  }
+
+  true
+}
+
+/// Print `error`, in color if standard error is colored
+fn report(config: &Config, error: impl Display) {
+  if config.color.stderr().active() {
+    eprintln!("{:#}", error);
+  } else {
+    eprintln!("{}", error);
+  }
+}
@@ -0,0 +1,184 @@
+use crate::common::*;
+
+use std::time::Duration;
+
+/// The results of running the recipes given on the command line with
+/// `--report`, each of which is treated as a test case
+#[derive(Debug, Default)]
+pub(crate) struct Report {
+  cases: Vec<TestCase>,
+}
+
+#[derive(Debug)]
+struct TestCase {
+  /// The recipe's path and arguments, as given on the command line
+  name: String,
+  duration: Duration,
+  /// The error the recipe failed with, if it failed
+  failure: Option<String>,
+}
+
+impl Report {
+  pub(crate) fn record(&mut self, name: String, duration: Duration, failure: Option<String>) {
+    self.cases.push(TestCase {
+      name,
+      duration,
+      failure,
+    });
+  }
+
+  pub(crate) fn total(&self) -> usize {
+    self.cases.len()
+  }
+
+  /// The names of the test cases that failed
+  pub(crate) fn failed(&self) -> Vec<String> {
+    self
+      .cases
+      .iter()
+      .filter(|case| case.failure.is_some())
+      .map(|case| case.name.clone())
+      .collect()
+  }
+
+  pub(crate) fn render(&self, format: ReportFormat) -> String {
+    match format {
+      ReportFormat::Junit => self.junit(),
+      ReportFormat::Tap => self.tap(),
+    }
+  }
+
+  fn tap(&self) -> String {
+    let mut tap = format!("TAP version 13\n1..{}\n", self.cases.len());
+
+    for (i, case) in self.cases.iter().enumerate() {
+      let status = if case.failure.is_some() {
+        "not ok"
+      } else {
+        "ok"
+      };
+      tap.push_str(&format!("{} {} - {}\n", status, i + 1, case.name));
+      tap.push_str("  ---\n");
+      tap.push_str(&format!("  duration_ms: {}\n", case.duration.as_millis()));
+      if let Some(failure) = &case.failure {
+        // JSON strings are also valid YAML
+        tap.push_str(&format!(
+          "  message: {}\n",
+          serde_json::Value::from(failure.as_str())
+        ));
+      }
+      tap.push_str("  ...\n");
+    }
+
+    tap
+  }
+
+  fn junit(&self) -> String {
+    let failures = self.failed().len();
+    let time = seconds(self.cases.iter().map(|case| case.duration).sum());
+
+    let mut junit = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    junit.push_str(&format!(
+      "<testsuites tests=\"{}\" failures=\"{}\" time=\"{}\">\n",
+      self.cases.len(),
+      failures,
+      time
+    ));
+    junit.push_str(&format!(
+      "  <testsuite name=\"just\" tests=\"{}\" failures=\"{}\" time=\"{}\">\n",
+      self.cases.len(),
+      failures,
+      time
+    ));
+
+    for case in &self.cases {
+      junit.push_str(&format!(
+        "    <testcase name=\"{}\" classname=\"just\" time=\"{}\"",
+        escape_xml(&case.name),
+        seconds(case.duration)
+      ));
+      match &case.failure {
+        Some(failure) => {
+          let message = failure.lines().next().unwrap_or_default();
+          junit.push_str(&format!(
+            ">\n      <failure message=\"{}\">{}</failure>\n    </testcase>\n",
+            escape_xml(message),
+            escape_xml(failure)
+          ));
+        }
+        None => junit.push_str("/>\n"),
+      }
+    }
+
+    junit.push_str("  </testsuite>\n</testsuites>\n");
+
+    junit
+  }
+}
+
+fn seconds(duration: Duration) -> String {
+  format!("{:.3}", duration.as_secs_f64())
+}
+
+fn escape_xml(text: &str) -> String {
+  text
+    .replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+    .replace('"', "&quot;")
+    .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn report() -> Report {
+    let mut report = Report::default();
+    report.record("build".to_owned(), Duration::from_millis(1500), None);
+    report.record(
+      "test <unit>".to_owned(),
+      Duration::from_millis(20),
+      Some("error: Recipe `test` failed\ncommand:\n    \"exit\" 1".to_owned()),
+    );
+    report
+  }
+
+  #[test]
+  fn tap() {
+    assert_eq!(
+      report().render(ReportFormat::Tap),
+      "TAP version 13
+1..2
+ok 1 - build
+  ---
+  duration_ms: 1500
+  ...
+not ok 2 - test <unit>
+  ---
+  duration_ms: 20
+  message: \"error: Recipe `test` failed\\ncommand:\\n    \\\"exit\\\" 1\"
+  ...
+"
+    );
+  }
+
+  #[test]
+  fn junit() {
+    assert_eq!(
+      report().render(ReportFormat::Junit),
+      "<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<testsuites tests=\"2\" failures=\"1\" time=\"1.520\">
+  <testsuite name=\"just\" tests=\"2\" failures=\"1\" time=\"1.520\">
+    <testcase name=\"build\" classname=\"just\" time=\"1.500\"/>
+    <testcase name=\"test &lt;unit&gt;\" classname=\"just\" time=\"0.020\">
+      <failure message=\"error: Recipe `test` failed\">error: Recipe `test` failed
+command:
+    &quot;exit&quot; 1</failure>
+    </testcase>
+  </testsuite>
+</testsuites>
+"
+    );
+  }
+}
@@ -0,0 +1,8 @@
+/// The format of the test report written with `--report`
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub(crate) enum ReportFormat {
+  /// JUnit XML, as read by most CI test report viewers
+  Junit,
+  /// The Test Anything Protocol, version 13
+  Tap,
+}
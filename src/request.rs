@@ -0,0 +1,259 @@
+use crate::common::*;
+
+use std::process::Stdio;
+
+use serde::{Deserialize, Serialize};
+
+/// A request read from standard input by `--request`. Fields that aren't
+/// recognized are ignored, so that clients may send fields that only later
+/// versions understand.
+#[derive(Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+enum Request {
+  /// List the recipes in the justfile and its modules
+  List,
+  /// Evaluate the justfile's variables
+  Evaluate,
+  /// Run `recipe` with `arguments`, with variables set to the values in
+  /// `overrides`
+  Run {
+    recipe: String,
+    #[serde(default)]
+    arguments: Vec<String>,
+    #[serde(default)]
+    overrides: BTreeMap<String, String>,
+  },
+}
+
+/// The response to a request, written to standard output. Responses have
+/// the same `kind` as the request, or `error` if the request couldn't be
+/// carried out. Later versions may add fields, which clients should ignore.
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+enum Response<'a> {
+  List {
+    recipes: Vec<RecipeSummary<'a>>,
+  },
+  Evaluate {
+    variables: BTreeMap<&'a str, String>,
+  },
+  Run {
+    exit_code: i32,
+    stdout: String,
+    stderr: String,
+  },
+  Error {
+    message: String,
+  },
+}
+
+/// A description of a recipe, for tools, also used by the C interface
+#[derive(Serialize)]
+pub(crate) struct RecipeSummary<'a> {
+  /// The path of the recipe, prefixed by the modules containing it
+  name: String,
+  doc: Option<&'a str>,
+  parameters: Vec<ParameterSummary<'a>>,
+  dependencies: &'a [&'a str],
+  private: bool,
+  groups: &'a BTreeSet<String>,
+  /// The recipe's metadata, from `[meta: KEY='VALUE', ...]`
+  meta: &'a BTreeMap<&'a str, String>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct ParameterSummary<'a> {
+  name: &'a str,
+  /// The default value of the parameter, if it's a string literal
+  default: Option<&'a str>,
+  /// The default value of the parameter, as written in the justfile, if it
+  /// must be evaluated
+  default_expression: Option<String>,
+  variadic: bool,
+}
+
+/// Read a JSON request from standard input, and write a JSON response to
+/// standard output
+pub(crate) fn request(justfile: &Justfile, config: &Config) -> Result<(), i32> {
+  let mut input = String::new();
+
+  let response = match io::stdin().read_to_string(&mut input) {
+    Ok(_) => match serde_json::from_str(&input) {
+      Ok(request) => respond(justfile, config, request),
+      Err(error) => Response::Error {
+        message: format!("Invalid request: {}", error),
+      },
+    },
+    Err(io_error) => Response::Error {
+      message: format!("Failed to read request: {}", io_error),
+    },
+  };
+
+  match serde_json::to_string(&response) {
+    Ok(json) => println!("{}", json),
+    Err(error) => die!("Failed to write response: {}", error),
+  }
+
+  match response {
+    Response::Error { .. } => Err(EXIT_FAILURE),
+    _ => Ok(()),
+  }
+}
+
+/// Print the recipes in `justfile`, or those in `group`, as JSON, for
+/// `--list --json`, in the same format as the response to a `list` request
+pub(crate) fn list(justfile: &Justfile, group: Option<&str>) {
+  let mut recipes = Vec::new();
+  summarize(justfile, "", &mut recipes);
+
+  if let Some(group) = group {
+    recipes.retain(|recipe| recipe.groups.contains(group));
+  }
+
+  match serde_json::to_string(&Response::List { recipes }) {
+    Ok(json) => println!("{}", json),
+    Err(error) => die!("Failed to write recipes: {}", error),
+  }
+}
+
+fn respond<'a>(
+  justfile: &'a Justfile<'a>,
+  config: &'a Config<'a>,
+  request: Request,
+) -> Response<'a> {
+  match request {
+    Request::List => {
+      let mut recipes = Vec::new();
+      summarize(justfile, "", &mut recipes);
+      Response::List { recipes }
+    }
+    Request::Evaluate => match justfile.evaluate_variables(config) {
+      Ok(variables) => Response::Evaluate { variables },
+      Err(error) => Response::Error {
+        message: error.to_string(),
+      },
+    },
+    Request::Run {
+      recipe,
+      arguments,
+      overrides,
+    } => run(justfile, config, &recipe, &arguments, &overrides),
+  }
+}
+
+/// Add a summary of each recipe in `justfile`, and those in its modules, to
+/// `recipes`, with their names prefixed by `prefix`
+pub(crate) fn summarize<'a>(
+  justfile: &'a Justfile<'a>,
+  prefix: &str,
+  recipes: &mut Vec<RecipeSummary<'a>>,
+) {
+  for recipe in justfile.recipes.sorted_values() {
+    recipes.push(RecipeSummary {
+      name: format!("{}{}", prefix, recipe.name),
+      doc: recipe.doc,
+      parameters: recipe
+        .parameters
+        .iter()
+        .map(|parameter| {
+          let (default, default_expression) = match &parameter.default {
+            Some(Expression::String { cooked_string }) => {
+              (Some(cooked_string.cooked.as_ref()), None)
+            }
+            Some(expression) => (None, Some(expression.to_string())),
+            None => (None, None),
+          };
+          ParameterSummary {
+            name: parameter.name,
+            default,
+            default_expression,
+            variadic: parameter.kind.is_variadic(),
+          }
+        })
+        .collect(),
+      dependencies: &recipe.dependencies,
+      private: recipe.private,
+      groups: &recipe.groups,
+      meta: &recipe.meta,
+    });
+  }
+
+  for (name, module) in &justfile.modules {
+    summarize(module, &format!("{}{}::", prefix, name), recipes);
+  }
+}
+
+/// Run `recipe` by running `just` again, with the same options, so that its
+/// output can be collected without mixing it with the response. The recipe
+/// and overrides must exist, and are passed after the options, so that they
+/// can't be taken for options themselves.
+fn run(
+  justfile: &Justfile,
+  config: &Config,
+  recipe: &str,
+  arguments: &[String],
+  overrides: &BTreeMap<String, String>,
+) -> Response<'static> {
+  if justfile.get_recipe_path(recipe).is_none() {
+    return Response::Error {
+      message: format!("Justfile does not contain recipe `{}`", recipe),
+    };
+  }
+
+  if let Some(name) = overrides
+    .keys()
+    .find(|name| !justfile.assignments.contains_key(name.as_str()))
+  {
+    return Response::Error {
+      message: format!("Justfile does not contain variable `{}`", name),
+    };
+  }
+
+  let executable = match env::current_exe() {
+    Ok(executable) => executable,
+    Err(io_error) => {
+      return Response::Error {
+        message: format!("Failed to find just executable: {}", io_error),
+      }
+    }
+  };
+
+  let invocation_directory = match &config.invocation_directory {
+    Ok(invocation_directory) => invocation_directory,
+    Err(message) => {
+      return Response::Error {
+        message: message.clone(),
+      }
+    }
+  };
+
+  let mut command = Command::new(executable);
+
+  command.current_dir(invocation_directory).args(
+    env::args_os()
+      .skip(1)
+      .filter(|argument| argument != "--request"),
+  );
+
+  for (name, value) in overrides {
+    command.arg(format!("{}={}", name, value));
+  }
+
+  let output = command
+    .arg("--")
+    .arg(recipe)
+    .args(arguments)
+    .stdin(Stdio::null())
+    .output();
+
+  match output {
+    Ok(output) => Response::Run {
+      exit_code: output.status.code().unwrap_or(EXIT_FAILURE),
+      stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+      stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+    },
+    Err(io_error) => Response::Error {
+      message: format!("Failed to run just: {}", io_error),
+    },
+  }
+}
@@ -1,12 +1,12 @@
 use crate::common::*;
 
-use crate::interrupt_handler::InterruptHandler;
+use std::time::{Duration, Instant, SystemTime};
 use unicode_width::UnicodeWidthStr;
 
 fn edit<P: AsRef<OsStr>>(path: P) -> Result<(), i32> {
   let editor = match env::var_os("EDITOR") {
     None => {
-      eprintln!("Error getting EDITOR environment variable");
+      eprintln!("{}", message!("subcommand.editor-unset"));
       return Err(EXIT_FAILURE);
     }
     Some(editor) => editor,
@@ -19,248 +19,844 @@ fn edit<P: AsRef<OsStr>>(path: P) -> Result<(), i32> {
       if status.success() {
         Ok(())
       } else {
-        eprintln!("Editor failed: {}", status);
+        eprintln!("{}", message!("subcommand.editor-failed", status = status));
         Err(status.code().unwrap_or(EXIT_FAILURE))
       }
     }
     Err(error) => {
-      eprintln!("Failed to invoke editor: {}", error);
+      eprintln!("{}", message!("subcommand.editor-io", io_error = error));
       Err(EXIT_FAILURE)
     }
   }
 }
 
-pub fn run() -> Result<(), i32> {
-  #[cfg(windows)]
-  ansi_term::enable_ansi_support().ok();
+const INIT_JUSTFILE: &str = "default:\n    echo 'Hello, world!'\n";
 
-  env_logger::Builder::from_env(
-    env_logger::Env::new()
-      .filter("JUST_LOG")
-      .write_style("JUST_LOG_STYLE"),
-  )
-  .init();
+fn init() -> Result<(), i32> {
+  let current_dir = match env::current_dir() {
+    Ok(current_dir) => current_dir,
+    Err(io_error) => die!(
+      "{}",
+      message!("subcommand.current-dir", io_error = io_error)
+    ),
+  };
 
-  let app = Config::app();
+  if let Ok(justfile) = search::justfile(&current_dir) {
+    if justfile.parent() == Some(&current_dir) {
+      eprintln!(
+        "{}",
+        message!("subcommand.init-exists", path = justfile.display())
+      );
+      return Err(EXIT_FAILURE);
+    }
+  }
 
-  let matches = app.get_matches();
+  let justfile = current_dir.join(search::FILENAME);
 
-  let config = match Config::from_matches(&matches) {
-    Ok(config) => config,
-    Err(error) => {
-      eprintln!("error: {}", error);
+  if let Err(error) = fs::write(&justfile, INIT_JUSTFILE) {
+    eprintln!(
+      "{}",
+      message!(
+        "subcommand.init-write",
+        path = justfile.display(),
+        io_error = error,
+      )
+    );
+    return Err(EXIT_FAILURE);
+  }
+
+  eprintln!(
+    "{}",
+    message!("subcommand.init-wrote", path = justfile.display())
+  );
+
+  Ok(())
+}
+
+const MAN_PAGE: &str = include_str!("../man/just.1");
+
+/// Get the base directory named by the XDG environment variable `name`,
+/// falling back to `default` under the user's home directory
+fn xdg_directory(name: &str, default: &str) -> PathBuf {
+  if let Some(directory) = env::var_os(name).filter(|directory| !directory.is_empty()) {
+    return PathBuf::from(directory);
+  }
+
+  match env::var_os("HOME").filter(|home| !home.is_empty()) {
+    Some(home) => Path::new(&home).join(default),
+    None => die!(
+      "{}",
+      message!("subcommand.install-directory", variable = name)
+    ),
+  }
+}
+
+fn install(path: &Path, contents: &[u8], what: &str) -> Result<(), i32> {
+  if let Some(parent) = path.parent() {
+    if let Err(error) = fs::create_dir_all(parent) {
+      eprintln!(
+        "{}",
+        message!(
+          "subcommand.install-create-directory",
+          directory = parent.display(),
+          io_error = error,
+        )
+      );
       return Err(EXIT_FAILURE);
     }
+  }
+
+  if let Err(error) = fs::write(path, contents) {
+    eprintln!(
+      "{}",
+      message!(
+        "subcommand.install-write",
+        what = what,
+        path = path.display(),
+        io_error = error,
+      )
+    );
+    return Err(EXIT_FAILURE);
+  }
+
+  eprintln!(
+    "{}",
+    message!(
+      "subcommand.install-wrote",
+      what = what,
+      path = path.display(),
+    )
+  );
+
+  Ok(())
+}
+
+const BASH_DYNAMIC_COMPLETIONS: &str = r#"
+_just_dynamic() {
+    if [[ ${COMP_WORDS[COMP_CWORD]} != -* ]]; then
+        mapfile -t COMPREPLY < <(just --complete "$COMP_CWORD" -- "${COMP_WORDS[@]}" 2>/dev/null)
+        if [[ ${#COMPREPLY[@]} -gt 0 ]]; then
+            return 0
+        fi
+    fi
+    _just "$@"
+}
+
+complete -F _just_dynamic -o bashdefault -o default just
+"#;
+
+const FISH_DYNAMIC_COMPLETIONS: &str = r#"
+complete -c just -f -a '(just --complete (count (commandline -opc)) -- (commandline -opc) (commandline -ct) 2>/dev/null)'
+"#;
+
+const ZSH_DYNAMIC_COMPLETIONS: &str = r#"
+_just_dynamic() {
+    if [[ ${words[CURRENT]} != -* ]]; then
+        local -a candidates
+        candidates=(${(f)"$(just --complete $((CURRENT - 1)) -- "${words[@]}" 2>/dev/null)"})
+        if (( ${#candidates} )); then
+            compadd -a candidates
+            return
+        fi
+    fi
+    _just "$@"
+}
+
+_just_dynamic "$@"
+"#;
+
+fn install_completions(shell: &str) -> Result<(), i32> {
+  let path = match shell {
+    "bash" => xdg_directory("XDG_DATA_HOME", ".local/share")
+      .join("bash-completion")
+      .join("completions")
+      .join("just"),
+    "fish" => xdg_directory("XDG_CONFIG_HOME", ".config")
+      .join("fish")
+      .join("completions")
+      .join("just.fish"),
+    "zsh" => xdg_directory("XDG_DATA_HOME", ".local/share")
+      .join("zsh")
+      .join("site-functions")
+      .join("_just"),
+    _ => die!("Unsupported completion shell: {}", shell),
   };
 
-  let justfile = config.justfile;
+  let mut completions = Vec::new();
+  Config::app().gen_completions_to(
+    env!("CARGO_PKG_NAME"),
+    shell.parse().unwrap(),
+    &mut completions,
+  );
 
-  let mut working_directory = config.working_directory.map(PathBuf::from);
+  // Recipes, their arguments, and variables are completed by calling
+  // `just --complete`, so that the completions don't need to be regenerated
+  // when the justfile changes
+  let mut completions = String::from_utf8_lossy(&completions).into_owned();
+  match shell {
+    "bash" => completions += BASH_DYNAMIC_COMPLETIONS,
+    "fish" => completions += FISH_DYNAMIC_COMPLETIONS,
+    "zsh" => {
+      completions = completions
+        .trim_end()
+        .trim_end_matches("_just \"$@\"")
+        .trim_end()
+        .to_owned();
+      completions.push('\n');
+      completions += ZSH_DYNAMIC_COMPLETIONS;
+    }
+    _ => {}
+  }
 
-  if let (Some(justfile), None) = (justfile, working_directory.as_ref()) {
-    let mut justfile = justfile.to_path_buf();
+  install(
+    &path,
+    completions.as_bytes(),
+    &message!("subcommand.completions"),
+  )?;
 
-    if !justfile.is_absolute() {
-      match justfile.canonicalize() {
-        Ok(canonical) => justfile = canonical,
-        Err(err) => {
-          eprintln!(
-            "Could not canonicalize justfile path `{}`: {}",
-            justfile.display(),
-            err
-          );
-          return Err(EXIT_FAILURE);
+  if shell == "zsh" {
+    eprintln!(
+      "{}",
+      message!(
+        "subcommand.completions-fpath",
+        directory = path.parent().unwrap().display(),
+      )
+    );
+  }
+
+  Ok(())
+}
+
+fn install_man() -> Result<(), i32> {
+  let path = xdg_directory("XDG_DATA_HOME", ".local/share")
+    .join("man")
+    .join("man1")
+    .join("just.1");
+
+  install(&path, MAN_PAGE.as_bytes(), &message!("subcommand.man-page"))
+}
+
+fn list_functions() {
+  let functions = Function::list()
+    .map(|(name, argc, variadic, description)| {
+      let signature = format!("{}/{}{}", name, argc, if variadic { "+" } else { "" });
+      (signature, description)
+    })
+    .collect::<Vec<_>>();
+
+  let width = functions
+    .iter()
+    .map(|(signature, _)| UnicodeWidthStr::width(signature.as_str()))
+    .max()
+    .unwrap_or(0);
+
+  println!("{}", message!("subcommand.available-functions"));
+
+  for (signature, description) in functions {
+    println!("    {:width$} # {}", signature, description, width = width);
+  }
+}
+
+fn grammar(format: &str) {
+  match format {
+    "ebnf" => print!("{}", grammar::ebnf()),
+    "tree-sitter" => print!("{}", grammar::tree_sitter()),
+    _ => die!("{}", message!("subcommand.grammar-format", format = format)),
+  }
+}
+
+fn system_info() {
+  println!("just v{}", env!("CARGO_PKG_VERSION"));
+  println!("arch: {}", env::consts::ARCH);
+  println!("os: {}", env::consts::OS);
+  println!("os_family: {}", env::consts::FAMILY);
+}
+
+/// Print the recipes in `justfile`, each indented by `depth` levels, followed
+/// by the recipes in each of its modules, under the name of the module.
+/// Recipes without a group come first, followed by those in each group,
+/// under the group's name, or, with `--group`, only those in that group.
+fn list(justfile: &Justfile, config: &Config, depth: usize) {
+  let indentation = "    ".repeat(depth + 1);
+
+  // Construct a target to alias map.
+  let mut recipe_aliases: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+  for alias in justfile.aliases.values() {
+    if alias.private {
+      continue;
+    }
+
+    if !recipe_aliases.contains_key(alias.target) {
+      recipe_aliases.insert(alias.target, vec![alias.name]);
+    } else {
+      let aliases = recipe_aliases.get_mut(alias.target).unwrap();
+      aliases.push(alias.name);
+    }
+  }
+
+  let mut line_widths: BTreeMap<&str, usize> = BTreeMap::new();
+
+  for (name, recipe) in justfile.recipes.sorted() {
+    if recipe.private {
+      continue;
+    }
+
+    for name in iter::once(name).chain(recipe_aliases.get(name).unwrap_or(&Vec::new())) {
+      let mut line_width = UnicodeWidthStr::width(*name);
+
+      for parameter in &recipe.parameters {
+        line_width += UnicodeWidthStr::width(format!(" {}", parameter).as_str());
+      }
+
+      if line_width <= 30 {
+        line_widths.insert(name, line_width);
+      }
+    }
+  }
+
+  let max_line_width = cmp::min(line_widths.values().cloned().max().unwrap_or(0), 30);
+
+  let doc_color = config.color.stdout().doc();
+
+  let print_recipe = |name: &str, recipe: &Recipe| {
+    let alias_doc = message!("subcommand.alias-doc", recipe = recipe.name);
+
+    for (i, name) in iter::once(&name)
+      .chain(recipe_aliases.get(name).unwrap_or(&Vec::new()))
+      .enumerate()
+    {
+      print!("{}{}", indentation, name);
+      for parameter in &recipe.parameters {
+        if config.color.stdout().active() {
+          print!(" {:#}", parameter);
+        } else {
+          print!(" {}", parameter);
+        }
+      }
+
+      // Declaring this outside of the nested loops will probably be more efficient, but
+      // it creates all sorts of lifetime issues with variables inside the loops.
+      // If this is inlined like the docs say, it shouldn't make any difference.
+      let print_doc = |doc| {
+        print!(
+          " {:padding$}{} {}",
+          "",
+          doc_color.paint("#"),
+          doc_color.paint(doc),
+          padding =
+            max_line_width.saturating_sub(line_widths.get(name).cloned().unwrap_or(max_line_width))
+        );
+      };
+
+      match (i, recipe.doc) {
+        (0, Some(doc)) => print_doc(doc),
+        (0, None) => (),
+        _ => print_doc(&alias_doc),
+      }
+      println!();
+
+      if i == 0 && config.matrix && !recipe.matrix.is_empty() {
+        for arguments in recipe.matrix_arguments() {
+          println!("{}    {} {}", indentation, name, arguments.join(" "));
         }
       }
     }
+  };
 
-    justfile.pop();
+  let recipes = justfile
+    .recipes
+    .sorted()
+    .filter(|(_, recipe)| !recipe.private)
+    .collect::<Vec<(&&str, &Recipe)>>();
 
-    working_directory = Some(justfile);
+  if let Some(group) = config.group {
+    for (name, recipe) in &recipes {
+      if recipe.groups.contains(group) {
+        print_recipe(name, recipe);
+      }
+    }
+  } else {
+    for (name, recipe) in &recipes {
+      if recipe.groups.is_empty() {
+        print_recipe(name, recipe);
+      }
+    }
+
+    for group in groups(justfile, false) {
+      println!("{}[{}]", indentation, group);
+      for (name, recipe) in &recipes {
+        if recipe.groups.contains(group) {
+          print_recipe(name, recipe);
+        }
+      }
+    }
   }
 
-  let text;
-  if let (Some(justfile), Some(directory)) = (justfile, working_directory) {
-    if config.subcommand == Subcommand::Edit {
-      return edit(justfile);
+  for (name, module) in &justfile.modules {
+    if let Some(group) = config.group {
+      if !groups(module, true).contains(group) {
+        continue;
+      }
     }
 
-    text = fs::read_to_string(justfile)
-      .unwrap_or_else(|error| die!("Error reading justfile: {}", error));
+    println!("{}{}:", indentation, name);
+    list(module, config, depth + 1);
+  }
+}
 
-    if let Err(error) = env::set_current_dir(&directory) {
-      die!(
-        "Error changing directory to {}: {}",
-        directory.display(),
-        error
+/// The groups of the public recipes in `justfile`, and, if `modules`, in
+/// its modules
+fn groups<'a>(justfile: &'a Justfile, modules: bool) -> BTreeSet<&'a str> {
+  let mut names = justfile
+    .recipes
+    .values()
+    .filter(|recipe| !recipe.private)
+    .flat_map(|recipe| recipe.groups.iter().map(String::as_str))
+    .collect::<BTreeSet<&str>>();
+
+  if modules {
+    for module in justfile.modules.values() {
+      names.extend(groups(module, true));
+    }
+  }
+
+  names
+}
+
+/// Print where the recipe at `path`, which may be prefixed by modules, and
+/// each of its dependencies are defined, as `PATH:LINE:COLUMN: NAME`, with
+/// lines and columns counted from one, for editors to jump to
+fn locate(
+  justfile: &Justfile,
+  loader: &Loader,
+  justfile_path: &Path,
+  path: &str,
+) -> Result<(), i32> {
+  let (module, recipe) = match justfile.get_recipe_path(path) {
+    Some(found) => found,
+    None => {
+      eprintln!(
+        "{}",
+        message!(
+          "runtime.unknown-recipes.one",
+          recipes = List::or_ticked(&[path])
+        )
       );
+      if let Some(suggestion) = justfile.suggest(path) {
+        eprintln!(
+          "{}",
+          message!("runtime.did-you-mean", suggestion = suggestion)
+        );
+      }
+      return Err(EXIT_FAILURE);
+    }
+  };
+
+  let dependencies = recipe
+    .dependencies
+    .iter()
+    .filter_map(|name| module.get_recipe(name));
+
+  for recipe in iter::once(recipe).chain(dependencies) {
+    let token = &recipe.name_token;
+    println!(
+      "{}:{}:{}: {}",
+      loader.path(token.text).unwrap_or(justfile_path).display(),
+      token.line + 1,
+      token.column + 1,
+      recipe.name
+    );
+  }
+
+  Ok(())
+}
+
+/// Run `arguments` `runs` times with their output suppressed, and print the
+/// shortest, mean, and longest time that they took
+fn bench<'a>(
+  justfile: &'a Justfile<'a>,
+  arguments: &[&'a str],
+  config: &'a mut Config<'a>,
+  runs: usize,
+) -> Result<(), i32> {
+  config.quiet = true;
+
+  let mut durations = Vec::with_capacity(runs);
+
+  for _ in 0..runs {
+    let start = Instant::now();
+
+    if let Err(run_error) = justfile.run(arguments, config) {
+      if config.color.stderr().active() {
+        eprintln!("{:#}", run_error);
+      } else {
+        eprintln!("{}", run_error);
+      }
+      return Err(run_error.exit_code());
+    }
+
+    durations.push(start.elapsed());
+  }
+
+  let total = durations.iter().sum::<Duration>();
+
+  println!(
+    "{}",
+    message!(
+      &plural("subcommand.bench", runs),
+      command = arguments.join(" "),
+      runs = runs,
+    )
+  );
+  println!(
+    "{}",
+    message!(
+      "subcommand.bench-min",
+      duration = format!("{:.1?}", durations.iter().min().unwrap()),
+    )
+  );
+  println!(
+    "{}",
+    message!(
+      "subcommand.bench-mean",
+      duration = format!("{:.1?}", total / runs as u32),
+    )
+  );
+  println!(
+    "{}",
+    message!(
+      "subcommand.bench-max",
+      duration = format!("{:.1?}", durations.iter().max().unwrap()),
+    )
+  );
+
+  Ok(())
+}
+
+pub fn run() -> Result<(), i32> {
+  #[cfg(windows)]
+  ansi_term::enable_ansi_support().ok();
+
+  env_logger::Builder::from_env(
+    env_logger::Env::new()
+      .filter("JUST_LOG")
+      .write_style("JUST_LOG_STYLE"),
+  )
+  .init();
+
+  let app = Config::app();
+
+  let matches = app.get_matches();
+
+  let mut config = match Config::from_matches(&matches) {
+    Ok(config) => config,
+    Err(error) => {
+      eprintln!("{} {}", message!("label.error"), error);
+      return Err(error.exit_code());
+    }
+  };
+
+  // These subcommands do not need a justfile, so they are handled before
+  // searching for one
+  match config.subcommand {
+    Subcommand::Init => return init(),
+    Subcommand::InstallCompletions { shell } => return install_completions(shell),
+    Subcommand::InstallMan => return install_man(),
+    Subcommand::Merge {
+      ref justfiles,
+      output,
+    } => return merge(justfiles, output, &config.defines),
+    Subcommand::ListFunctions => {
+      list_functions();
+      return Ok(());
+    }
+    Subcommand::Grammar { format } => {
+      grammar(format);
+      return Ok(());
+    }
+    Subcommand::SystemInfo => {
+      system_info();
+      return Ok(());
     }
+    _ => {}
+  }
+
+  let path = if let Some(justfile) = config.justfile {
+    justfile.to_path_buf()
   } else {
-    let current_dir = match env::current_dir() {
-      Ok(current_dir) => current_dir,
-      Err(io_error) => die!("Error getting current dir: {}", io_error),
+    let current_dir = match config.search_directory.clone() {
+      Some(search_directory) => search_directory,
+      None => match env::current_dir() {
+        Ok(current_dir) => current_dir,
+        Err(io_error) => die!(
+          "{}",
+          message!("subcommand.current-dir", io_error = io_error)
+        ),
+      },
     };
-    match search::justfile(&current_dir) {
-      Ok(name) => {
-        if config.subcommand == Subcommand::Edit {
-          return edit(name);
-        }
-        text = match fs::read_to_string(&name) {
-          Err(error) => {
-            eprintln!("Error reading justfile: {}", error);
-            return Err(EXIT_FAILURE);
+    let search_result = search::justfile(&current_dir).or_else(|error| match error {
+      SearchError::MultipleCandidates { ref candidates } => {
+        match search::preferred(candidates, &config.justfile_names) {
+          Some(preferred) => {
+            eprintln!(
+              "{} {}",
+              config
+                .color
+                .stderr()
+                .warning()
+                .paint(&message!("label.warning")),
+              message!(
+                "search.preferred",
+                error = error,
+                path = preferred.display()
+              ),
+            );
+            Ok(preferred.clone())
           }
-          Ok(text) => text,
-        };
-
-        let parent = name.parent().unwrap();
-
-        if let Err(error) = env::set_current_dir(&parent) {
-          eprintln!(
-            "Error changing directory to {}: {}",
-            parent.display(),
-            error
-          );
-          return Err(EXIT_FAILURE);
+          None => Err(error),
         }
       }
+      _ => Err(error),
+    });
+
+    match search_result {
+      Ok(path) => path,
       Err(search_error) => {
         eprintln!("{}", search_error);
         return Err(EXIT_FAILURE);
       }
     }
+  };
+
+  if config.subcommand == Subcommand::Edit {
+    return edit(path);
   }
 
-  let justfile = match Parser::parse(&text) {
+  let text = match fs::read_to_string(&path) {
+    Err(error) => {
+      eprintln!("{}", message!("subcommand.read-justfile", io_error = error));
+      return Err(EXIT_FAILURE);
+    }
+    Ok(text) => text,
+  };
+
+  let loader = Loader::load(&path, text, &config.defines);
+
+  let justfile = match Parser::parse_with_loader(&loader, &config.defines) {
     Err(error) => {
+      if let Some(import) = loader.path(error.text).filter(|import| *import != path) {
+        eprintln!(
+          "{}",
+          message!("subcommand.import-error", path = import.display())
+        );
+      }
       if config.color.stderr().active() {
         eprintln!("{:#}", error);
       } else {
         eprintln!("{}", error);
       }
-      return Err(EXIT_FAILURE);
+      if config.output_format == OutputFormat::Github {
+        eprintln!(
+          "{}",
+          github::error(
+            &github::annotation_path(&config, loader.path(error.text).unwrap_or(&path)),
+            Some(error.line + 1),
+            Some(error.column + 1),
+            &error,
+          )
+        );
+      }
+      return Err(error.exit_code());
     }
     Ok(justfile) => justfile,
   };
 
-  for warning in &justfile.warnings {
-    if config.color.stderr().active() {
-      eprintln!("{:#}", warning);
-    } else {
-      eprintln!("{}", warning);
-    }
-  }
-
-  if config.subcommand == Subcommand::Summary {
-    if justfile.count() == 0 {
-      eprintln!("Justfile contains no recipes.");
-    } else {
-      let summary = justfile
-        .recipes
-        .iter()
-        .filter(|&(_, recipe)| !recipe.private)
-        .map(|(name, _)| name)
-        .cloned()
-        .collect::<Vec<_>>()
-        .join(" ");
-      println!("{}", summary);
+  if let Subcommand::Complete { index, ref words } = config.subcommand {
+    for candidate in complete(&justfile, index, words) {
+      println!("{}", candidate);
     }
     return Ok(());
   }
 
-  if config.subcommand == Subcommand::Dump {
-    println!("{}", justfile);
-    return Ok(());
+  if let Some(verbosity) = justfile.settings.verbosity {
+    config.verbosity = cmp::max(config.verbosity, verbosity);
   }
 
-  if config.subcommand == Subcommand::List {
-    // Construct a target to alias map.
-    let mut recipe_aliases: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
-    for alias in justfile.aliases.values() {
-      if alias.private {
-        continue;
-      }
+  if let (Some(shell), false) = (
+    Platform::shell_setting(&justfile.settings),
+    config.shell_present,
+  ) {
+    config.shell = shell[0];
+    config.shell_arguments = shell[1..].to_vec();
+  }
 
-      if !recipe_aliases.contains_key(alias.target) {
-        recipe_aliases.insert(alias.target, vec![alias.name]);
+  if !config.quiet_warnings {
+    for warning in &justfile.warnings {
+      if config.color.stderr().active() {
+        eprintln!("{:#}", warning);
       } else {
-        let aliases = recipe_aliases.get_mut(alias.target).unwrap();
-        aliases.push(alias.name);
+        eprintln!("{}", warning);
       }
     }
+  }
 
-    let mut line_widths: BTreeMap<&str, usize> = BTreeMap::new();
+  if let Subcommand::Rename { old, new } = config.subcommand {
+    return rename(&justfile, &loader, &path, &config.defines, old, new);
+  }
 
-    for (name, recipe) in &justfile.recipes {
-      if recipe.private {
-        continue;
-      }
+  let history = history::path(&path);
 
-      for name in iter::once(name).chain(recipe_aliases.get(name).unwrap_or(&Vec::new())) {
-        let mut line_width = UnicodeWidthStr::width(*name);
+  if config.subcommand == Subcommand::History {
+    let invocations = history.as_deref().map(history::load).unwrap_or_default();
 
-        for parameter in &recipe.parameters {
-          line_width += UnicodeWidthStr::width(format!(" {}", parameter).as_str());
-        }
+    if invocations.is_empty() {
+      eprintln!("{}", message!("subcommand.history-empty"));
+    }
 
-        if line_width <= 30 {
-          line_widths.insert(name, line_width);
-        }
-      }
+    let now = SystemTime::now();
+    for invocation in invocations {
+      println!("{}", invocation.summary(now));
     }
 
-    let max_line_width = cmp::min(line_widths.values().cloned().max().unwrap_or(0), 30);
+    return Ok(());
+  }
 
-    let doc_color = config.color.stdout().doc();
-    println!("Available recipes:");
+  let resume = progress::path(&path);
 
-    for (name, recipe) in &justfile.recipes {
-      if recipe.private {
-        continue;
-      }
+  let mut progress = Progress::default();
 
-      let alias_doc = format!("alias for `{}`", recipe.name);
+  let last = match config.subcommand {
+    Subcommand::Last => match history
+      .as_deref()
+      .map(history::load)
+      .and_then(|mut invocations| invocations.pop())
+    {
+      Some(invocation) => invocation.arguments,
+      None => {
+        eprintln!("{}", message!("subcommand.history-empty"));
+        return Err(EXIT_FAILURE);
+      }
+    },
+    Subcommand::Resume => match resume.as_deref().and_then(progress::load) {
+      Some(failed) => {
+        progress = failed;
+        mem::take(&mut progress.arguments)
+      }
+      None => {
+        eprintln!("{}", message!("subcommand.resume-none"));
+        return Err(EXIT_FAILURE);
+      }
+    },
+    _ => Vec::new(),
+  };
 
-      for (i, name) in iter::once(name)
-        .chain(recipe_aliases.get(name).unwrap_or(&Vec::new()))
-        .enumerate()
-      {
-        print!("    {}", name);
-        for parameter in &recipe.parameters {
-          if config.color.stdout().active() {
-            print!(" {:#}", parameter);
-          } else {
-            print!(" {}", parameter);
-          }
-        }
+  // Overrides aren't recorded, since their values may be secret, but those
+  // recorded by earlier versions are skipped
+  let is_override = |argument: &&String| argument.chars().skip(1).any(|c| c == '=');
 
-        // Declaring this outside of the nested loops will probably be more efficient, but
-        // it creates all sorts of lifetime issues with variables inside the loops.
-        // If this is inlined like the docs say, it shouldn't make any difference.
-        let print_doc = |doc| {
-          print!(
-            " {:padding$}{} {}",
-            "",
-            doc_color.paint("#"),
-            doc_color.paint(doc),
-            padding = max_line_width
-              .saturating_sub(line_widths.get(name).cloned().unwrap_or(max_line_width))
-          );
-        };
+  config
+    .arguments
+    .extend(last.iter().skip_while(is_override).map(String::as_str));
 
-        match (i, recipe.doc) {
-          (0, Some(doc)) => print_doc(doc),
-          (0, None) => (),
-          _ => print_doc(&alias_doc),
-        }
-        println!();
+  let working_directory = match config.working_directory {
+    Some(working_directory) => working_directory.to_path_buf(),
+    None => match search::working_directory(&path, justfile.settings.follow_symlinks) {
+      Ok(working_directory) => working_directory,
+      Err(io_error) => {
+        eprintln!(
+          "{}",
+          message!(
+            "subcommand.working-directory-io",
+            path = path.display(),
+            io_error = io_error,
+          )
+        );
+        return Err(EXIT_FAILURE);
       }
+    },
+  };
+
+  config.justfile_path = Some(if path.is_absolute() {
+    path.clone()
+  } else {
+    env::current_dir()
+      .map(|current_dir| current_dir.join(&path))
+      .unwrap_or_else(|_| path.clone())
+  });
+
+  config.justfile_directory = match config.working_directory {
+    Some(_) => search::working_directory(&path, justfile.settings.follow_symlinks).ok(),
+    None => Some(working_directory.clone()),
+  };
+
+  match working_directory.metadata() {
+    Ok(metadata) if metadata.is_dir() => {}
+    Ok(_) => {
+      eprintln!(
+        "{}",
+        message!(
+          "subcommand.working-directory-not-directory",
+          directory = working_directory.display(),
+        )
+      );
+      return Err(EXIT_FAILURE);
+    }
+    Err(io_error) => {
+      eprintln!(
+        "{}",
+        message!(
+          "subcommand.working-directory-access",
+          directory = working_directory.display(),
+          io_error = io_error,
+        )
+      );
+      return Err(EXIT_FAILURE);
     }
+  }
+
+  config.run_directory = Some(working_directory);
+
+  if config.subcommand == Subcommand::Summary {
+    if justfile.count() == 0 && justfile.modules.is_empty() {
+      eprintln!("{}", message!("subcommand.no-recipes"));
+    } else {
+      println!("{}", justfile.public_recipe_paths().join(" "));
+    }
+    return Ok(());
+  }
+
+  if let Subcommand::Dump { order } = config.subcommand {
+    println!("{}", justfile.dump(order));
+    return Ok(());
+  }
+
+  if let Subcommand::Lint { linter } = config.subcommand {
+    return lint(&justfile, &path, &config, linter);
+  }
+
+  if config.subcommand == Subcommand::List && config.json {
+    request::list(&justfile, config.group);
+    return Ok(());
+  }
 
+  if config.subcommand == Subcommand::List {
+    println!("{}", message!("subcommand.available-recipes"));
+    list(&justfile, &config, 0);
     return Ok(());
   }
 
-  if let Subcommand::Show { name } = config.subcommand {
+  if config.subcommand == Subcommand::Groups {
+    for group in groups(&justfile, true) {
+      println!("{}", group);
+    }
+    return Ok(());
+  }
+
+  if let Subcommand::Show { name, locate: true } = config.subcommand {
+    return locate(&justfile, &loader, &path, name);
+  }
+
+  if let Subcommand::Show { name, .. } = config.subcommand {
     if let Some(alias) = justfile.get_alias(name) {
       let recipe = justfile.get_recipe(alias.target).unwrap();
       println!("{}", alias);
@@ -271,14 +867,112 @@ pub fn run() -> Result<(), i32> {
       println!("{}", recipe);
       return Ok(());
     } else {
-      eprintln!("Justfile does not contain recipe `{}`.", name);
+      eprintln!(
+        "{}",
+        message!(
+          "runtime.unknown-recipes.one",
+          recipes = List::or_ticked(&[name])
+        )
+      );
       if let Some(suggestion) = justfile.suggest(name) {
-        eprintln!("Did you mean `{}`?", suggestion);
+        eprintln!(
+          "{}",
+          message!("runtime.did-you-mean", suggestion = suggestion)
+        );
       }
       return Err(EXIT_FAILURE);
     }
   }
 
+  if let Subcommand::Why { recipe, dependency } = config.subcommand {
+    for name in &[recipe, dependency] {
+      if justfile.get_recipe(name).is_none() {
+        eprintln!(
+          "{}",
+          message!(
+            "runtime.unknown-recipes.one",
+            recipes = List::or_ticked(&[name])
+          )
+        );
+        if let Some(suggestion) = justfile.suggest(name) {
+          eprintln!(
+            "{}",
+            message!("runtime.did-you-mean", suggestion = suggestion)
+          );
+        }
+        return Err(EXIT_FAILURE);
+      }
+    }
+
+    let recipe = justfile.get_recipe(recipe).unwrap();
+    let dependency = justfile.get_recipe(dependency).unwrap();
+    let paths = justfile.dependency_paths(recipe, dependency.name);
+
+    if paths.is_empty() {
+      eprintln!(
+        "{}",
+        message!(
+          "subcommand.why-independent",
+          recipe = recipe.name,
+          dependency = dependency.name,
+        )
+      );
+      return Err(EXIT_FAILURE);
+    }
+
+    for path in paths {
+      println!("{}", path.join(" -> "));
+    }
+    return Ok(());
+  }
+
+  if let Subcommand::Expression { expression } = config.subcommand {
+    let expression = match Parser::parse_expression(expression, &justfile) {
+      Ok(expression) => expression,
+      Err(error) => {
+        if config.color.stderr().active() {
+          eprintln!("{:#}", error);
+        } else {
+          eprintln!("{}", error);
+        }
+        return Err(error.exit_code());
+      }
+    };
+
+    match justfile.evaluate_expression(&expression, &config) {
+      Ok(value) => {
+        println!("{}", value);
+        return Ok(());
+      }
+      Err(error) => {
+        if !config.quiet {
+          if config.color.stderr().active() {
+            eprintln!("{:#}", error);
+          } else {
+            eprintln!("{}", error);
+          }
+        }
+        return Err(error.exit_code());
+      }
+    }
+  }
+
+  #[cfg(not(target_arch = "wasm32"))]
+  {
+    if config.subcommand == Subcommand::Repl {
+      return repl(&justfile, &config, &path);
+    }
+  }
+
+  if config.subcommand == Subcommand::Request {
+    return request(&justfile, &config);
+  }
+
+  if let Err(error) = config.take_options(&justfile) {
+    eprintln!("{} {}", message!("label.error"), error);
+    return Err(error.exit_code());
+  }
+
   let arguments = if !config.arguments.is_empty() {
     config.arguments.clone()
   } else if let Some(recipe) = justfile.first() {
@@ -296,20 +990,84 @@ pub fn run() -> Result<(), i32> {
     die!("Justfile contains no recipes.");
   };
 
-  if let Err(error) = InterruptHandler::install() {
-    warn!("Failed to set CTRL-C handler: {}", error)
+  #[cfg(not(target_arch = "wasm32"))]
+  {
+    if let Err(error) = InterruptHandler::install() {
+      warn!("Failed to set CTRL-C handler: {}", error)
+    }
+  }
+
+  if let Subcommand::Bench { runs } = config.subcommand {
+    return bench(&justfile, &arguments, &mut config, runs);
   }
 
-  if let Err(run_error) = justfile.run(&arguments, &config) {
+  let start = SystemTime::now();
+
+  let result = justfile.run_from(&arguments, &config, &mut progress);
+
+  if !(config.dry_run || config.each || config.evaluate) {
+    let arguments = arguments
+      .iter()
+      .map(|argument| (*argument).to_owned())
+      .collect::<Vec<String>>();
+
+    let exit_code = match &result {
+      Ok(()) => 0,
+      Err(run_error) => run_error.exit_code(),
+    };
+
+    if let Some(history) = history.filter(|_| history::enabled()) {
+      let invocation = Invocation::new(start, exit_code, arguments.clone());
+      if let Err(error) = history::record(&history, invocation) {
+        warn!(
+          "Failed to record invocation in `{}`: {}",
+          history.display(),
+          error
+        );
+      }
+    }
+
+    // Only the progress of the most recent run is kept, and only if it failed
+    if let Some(resume) = resume {
+      let saved = if result.is_ok() {
+        progress::clear(&resume)
+      } else {
+        progress.arguments = arguments;
+        progress::save(&resume, &progress)
+      };
+
+      if let Err(error) = saved {
+        warn!(
+          "Failed to record progress in `{}`: {}",
+          resume.display(),
+          error
+        );
+      }
+    }
+  }
+
+  if let Err(run_error) = result {
     if !config.quiet {
       if config.color.stderr().active() {
         eprintln!("{:#}", run_error);
       } else {
         eprintln!("{}", run_error);
       }
+
+      if config.output_format == OutputFormat::Github {
+        eprintln!(
+          "{}",
+          github::error(
+            &github::annotation_path(&config, &path),
+            run_error.line_number(),
+            None,
+            &run_error,
+          )
+        );
+      }
     }
 
-    return Err(run_error.code().unwrap_or(EXIT_FAILURE));
+    return Err(run_error.exit_code());
   }
 
   Ok(())
@@ -17,14 +17,38 @@ pub(crate) enum RuntimeError<'a> {
     recipe: &'a str,
     line_number: Option<usize>,
     code: i32,
+    /// The failing command, if it wasn't echoed, with secrets masked
+    command: Option<String>,
   },
   Cygpath {
     recipe: &'a str,
     output_error: OutputError,
   },
-  Dotenv {
+  Each {
+    recipe: &'a str,
+    failed: Vec<&'a str>,
+    total: usize,
+  },
+  EnvFileLoad {
+    path: PathBuf,
     dotenv_error: dotenv::Error,
   },
+  EnvFileWrite {
+    io_error: io::Error,
+  },
+  /// Raised by a call to the `error` function
+  ErrorFunction {
+    token: Token<'a>,
+    message: String,
+  },
+  ExternalDependency {
+    dependency: &'a str,
+    code: i32,
+  },
+  ExternalDependencyIo {
+    dependency: &'a str,
+    io_error: io::Error,
+  },
   FunctionCall {
     token: Token<'a>,
     message: String,
@@ -32,14 +56,41 @@ pub(crate) enum RuntimeError<'a> {
   Internal {
     message: String,
   },
+  InvalidShebang {
+    recipe: &'a str,
+    line: String,
+  },
+  InvocationContext {
+    recipe: &'a str,
+    invocation_context: InvocationContext,
+  },
   IoError {
     recipe: &'a str,
     io_error: io::Error,
   },
+  NetworkIsolationUnsupported {
+    recipe: &'a str,
+  },
+  NoExecuteBackticks,
+  NotConfirmed {
+    recipe: &'a str,
+  },
+  RecursiveInvocation {
+    recipe: &'a str,
+  },
+  /// Some of the recipes run with `--report` failed
+  Report {
+    failed: Vec<String>,
+    total: usize,
+  },
+  ReportWrite {
+    path: &'a Path,
+    io_error: io::Error,
+  },
   Shebang {
     recipe: &'a str,
     command: String,
-    argument: Option<String>,
+    arguments: Vec<String>,
     io_error: io::Error,
   },
   Signal {
@@ -53,11 +104,18 @@ pub(crate) enum RuntimeError<'a> {
   },
   UnknownOverrides {
     overrides: Vec<&'a str>,
+    suggestion: Option<&'a str>,
   },
   UnknownRecipes {
     recipes: Vec<&'a str>,
     suggestion: Option<&'a str>,
   },
+  /// A recipe that was left out because of its OS attributes
+  UnavailableRecipe {
+    recipe: &'a str,
+    platforms: Vec<&'a str>,
+    alternatives: Vec<String>,
+  },
   Unknown {
     recipe: &'a str,
     line_number: Option<usize>,
@@ -76,6 +134,38 @@ impl<'a> RuntimeError<'a> {
       _ => None,
     }
   }
+
+  /// The line of the justfile, counted from one, that this error occurred
+  /// on, if known
+  pub(crate) fn line_number(&self) -> Option<usize> {
+    use RuntimeError::*;
+    match self {
+      Code { line_number, .. } | Signal { line_number, .. } | Unknown { line_number, .. } => {
+        *line_number
+      }
+      Backtick { token, .. } | ErrorFunction { token, .. } | FunctionCall { token, .. } => {
+        Some(token.line + 1)
+      }
+      _ => None,
+    }
+  }
+
+  /// The code that just should exit with when this error occurs
+  pub(crate) fn exit_code(&self) -> i32 {
+    use RuntimeError::*;
+
+    if let Some(code) = self.code() {
+      return code;
+    }
+
+    match self {
+      ArgumentCountMismatch { .. } | UnknownOverrides { .. } => EXIT_USAGE,
+      UnknownRecipes { .. } | UnavailableRecipe { .. } => EXIT_UNKNOWN_RECIPE,
+      Internal { .. } => EXIT_INTERNAL,
+      ExternalDependency { code, .. } => *code,
+      _ => EXIT_FAILURE,
+    }
+  }
 }
 
 impl<'a> Display for RuntimeError<'a> {
@@ -89,9 +179,15 @@ impl<'a> Display for RuntimeError<'a> {
     };
     let error = color.error();
     let message = color.message();
-    write!(f, "{} {}", error.paint("error:"), message.prefix())?;
+    write!(
+      f,
+      "{} {}",
+      error.paint(&message!("label.error")),
+      message.prefix()
+    )?;
 
     let mut error_token = None;
+    let mut failed_command = None;
 
     match *self {
       UnknownRecipes {
@@ -100,21 +196,65 @@ impl<'a> Display for RuntimeError<'a> {
       } => {
         write!(
           f,
-          "Justfile does not contain {} {}.",
-          Count("recipe", recipes.len()),
-          List::or_ticked(recipes),
+          "{}",
+          message!(
+            &plural("runtime.unknown-recipes", recipes.len()),
+            recipes = List::or_ticked(recipes),
+          )
         )?;
         if let Some(suggestion) = *suggestion {
-          write!(f, "\nDid you mean `{}`?", suggestion)?;
+          write!(
+            f,
+            "\n{}",
+            message!("runtime.did-you-mean", suggestion = suggestion)
+          )?;
         }
       }
-      UnknownOverrides { ref overrides } => {
+      UnavailableRecipe {
+        recipe,
+        ref platforms,
+        ref alternatives,
+      } => {
         write!(
           f,
-          "{} {} overridden on the command line but not present in justfile",
-          Count("Variable", overrides.len()),
-          List::and_ticked(overrides),
+          "{}",
+          message!(
+            "runtime.unavailable-recipe",
+            recipe = recipe,
+            platforms = List::or_ticked(platforms),
+            os = env::consts::OS,
+          )
         )?;
+        if !alternatives.is_empty() {
+          write!(
+            f,
+            "\n{}",
+            message!(
+              "runtime.did-you-mean-any",
+              alternatives = List::or_ticked(alternatives)
+            )
+          )?;
+        }
+      }
+      UnknownOverrides {
+        ref overrides,
+        ref suggestion,
+      } => {
+        write!(
+          f,
+          "{}",
+          message!(
+            &plural("runtime.unknown-overrides", overrides.len()),
+            overrides = List::and_ticked(overrides),
+          )
+        )?;
+        if let Some(suggestion) = *suggestion {
+          write!(
+            f,
+            "\n{}",
+            message!("runtime.did-you-mean", suggestion = suggestion)
+          )?;
+        }
       }
       ArgumentCountMismatch {
         recipe,
@@ -123,37 +263,37 @@ impl<'a> Display for RuntimeError<'a> {
         min,
         max,
       } => {
-        if min == max {
-          let expected = min;
-          write!(
-            f,
-            "Recipe `{}` got {} {} but {}takes {}",
-            recipe,
-            found,
-            Count("argument", found),
-            if expected < found { "only " } else { "" },
-            expected
-          )?;
+        let text = if min == max && min < found {
+          message!(
+            &plural("runtime.argument-count-mismatch-only", found),
+            recipe = recipe,
+            found = found,
+            expected = min,
+          )
+        } else if min == max {
+          message!(
+            &plural("runtime.argument-count-mismatch", found),
+            recipe = recipe,
+            found = found,
+            expected = min,
+          )
         } else if found < min {
-          write!(
-            f,
-            "Recipe `{}` got {} {} but takes at least {}",
-            recipe,
-            found,
-            Count("argument", found),
-            min
-          )?;
-        } else if found > max {
-          write!(
-            f,
-            "Recipe `{}` got {} {} but takes at most {}",
-            recipe,
-            found,
-            Count("argument", found),
-            max
-          )?;
-        }
-        write!(f, "\nusage:\n    just {}", recipe)?;
+          message!(
+            &plural("runtime.argument-count-mismatch-at-least", found),
+            recipe = recipe,
+            found = found,
+            min = min,
+          )
+        } else {
+          message!(
+            &plural("runtime.argument-count-mismatch-at-most", found),
+            recipe = recipe,
+            found = found,
+            max = max,
+          )
+        };
+        write!(f, "{}", text)?;
+        write!(f, "\n{}\n    just {}", message!("label.usage"), recipe)?;
         for param in parameters {
           if color.stderr().active() {
             write!(f, " {:#}", param)?;
@@ -161,21 +301,40 @@ impl<'a> Display for RuntimeError<'a> {
             write!(f, " {}", param)?;
           }
         }
+        if parameters.iter().any(|param| param.doc.is_some()) {
+          write!(f, "\n{}", message!("label.arguments"))?;
+          for param in parameters {
+            if let Some(doc) = param.doc {
+              write!(f, "\n    {}: {}", color.parameter().paint(param.name), doc)?;
+            }
+          }
+        }
       }
       Code {
         recipe,
         line_number,
         code,
+        ref command,
       } => {
         if let Some(n) = line_number {
           write!(
             f,
-            "Recipe `{}` failed on line {} with exit code {}",
-            recipe, n, code
+            "{}",
+            message!(
+              "runtime.code-on-line",
+              recipe = recipe,
+              line = n,
+              code = code,
+            )
           )?;
         } else {
-          write!(f, "Recipe `{}` failed with exit code {}", recipe, code)?;
+          write!(
+            f,
+            "{}",
+            message!("runtime.code", recipe = recipe, code = code)
+          )?;
         }
+        failed_command = command.as_ref();
       }
       Cygpath {
         recipe,
@@ -184,55 +343,65 @@ impl<'a> Display for RuntimeError<'a> {
         OutputError::Code(code) => {
           write!(
             f,
-            "Cygpath failed with exit code {} while translating recipe `{}` \
-             shebang interpreter path",
-            code, recipe
+            "{}",
+            message!("runtime.cygpath-code", recipe = recipe, code = code)
           )?;
         }
         OutputError::Signal(signal) => {
           write!(
             f,
-            "Cygpath terminated by signal {} while translating recipe `{}` \
-             shebang interpreter path",
-            signal, recipe
+            "{}",
+            message!("runtime.cygpath-signal", recipe = recipe, signal = signal)
           )?;
         }
         OutputError::Unknown => {
           write!(
             f,
-            "Cygpath experienced an unknown failure while translating recipe `{}` \
-             shebang interpreter path",
-            recipe
+            "{}",
+            message!("runtime.cygpath-unknown", recipe = recipe)
           )?;
         }
         OutputError::Io(ref io_error) => {
           match io_error.kind() {
             io::ErrorKind::NotFound => write!(
               f,
-              "Could not find `cygpath` executable to translate recipe `{}` \
-               shebang interpreter path:\n{}",
-              recipe, io_error
+              "{}",
+              message!(
+                "runtime.cygpath-not-found",
+                recipe = recipe,
+                io_error = io_error,
+              )
             ),
             io::ErrorKind::PermissionDenied => write!(
               f,
-              "Could not run `cygpath` executable to translate recipe `{}` \
-               shebang interpreter path:\n{}",
-              recipe, io_error
+              "{}",
+              message!(
+                "runtime.cygpath-permission-denied",
+                recipe = recipe,
+                io_error = io_error,
+              )
             ),
-            _ => write!(f, "Could not run `cygpath` executable:\n{}", io_error),
+            _ => write!(f, "{}", message!("runtime.cygpath-io", io_error = io_error)),
           }?;
         }
         OutputError::Utf8(ref utf8_error) => {
           write!(
             f,
-            "Cygpath successfully translated recipe `{}` shebang interpreter path, \
-             but output was not utf8: {}",
-            recipe, utf8_error
+            "{}",
+            message!(
+              "runtime.cygpath-utf8",
+              recipe = recipe,
+              utf8_error = utf8_error,
+            )
           )?;
         }
       },
-      Dotenv { ref dotenv_error } => {
-        writeln!(f, "Failed to load .env: {}", dotenv_error)?;
+      ErrorFunction {
+        ref token,
+        ref message,
+      } => {
+        writeln!(f, "{}", message)?;
+        error_token = Some(token);
       }
       FunctionCall {
         ref token,
@@ -240,31 +409,156 @@ impl<'a> Display for RuntimeError<'a> {
       } => {
         writeln!(
           f,
-          "Call to function `{}` failed: {}",
-          token.lexeme(),
-          message
+          "{}",
+          message!(
+            "runtime.function-call",
+            function = token.lexeme(),
+            message = message,
+          )
         )?;
         error_token = Some(token);
       }
+      InvocationContext {
+        recipe,
+        invocation_context,
+      } => match invocation_context {
+        self::InvocationContext::Ci => {
+          write!(f, "{}", message!("runtime.ci-only", recipe = recipe))?;
+        }
+        self::InvocationContext::Local => {
+          write!(f, "{}", message!("runtime.local-only", recipe = recipe))?;
+        }
+      },
+      InvalidShebang { recipe, ref line } => {
+        write!(
+          f,
+          "{}",
+          message!("compile.invalid-shebang", recipe = recipe, line = line)
+        )?;
+      }
+      Each {
+        recipe,
+        ref failed,
+        total,
+      } => {
+        write!(
+          f,
+          "{}",
+          message!(
+            &plural("runtime.each", total),
+            recipe = recipe,
+            failed = failed.len(),
+            total = total,
+            arguments = List::and_ticked(failed),
+          )
+        )?;
+      }
+      EnvFileLoad {
+        ref path,
+        ref dotenv_error,
+      } => {
+        write!(
+          f,
+          "{}",
+          message!(
+            "runtime.env-file-load",
+            path = path.display(),
+            dotenv_error = dotenv_error,
+          )
+        )?;
+      }
+      EnvFileWrite { ref io_error } => {
+        write!(
+          f,
+          "{}",
+          message!("runtime.env-file-write", io_error = io_error)
+        )?;
+      }
+      ExternalDependency { dependency, .. } => {
+        write!(
+          f,
+          "{}",
+          message!("runtime.external-dependency", dependency = dependency)
+        )?;
+      }
+      ExternalDependencyIo {
+        dependency,
+        ref io_error,
+      } => {
+        write!(
+          f,
+          "{}",
+          message!(
+            "runtime.external-dependency-io",
+            dependency = dependency,
+            io_error = io_error,
+          )
+        )?;
+      }
+      NetworkIsolationUnsupported { recipe } => {
+        write!(
+          f,
+          "{}",
+          message!("runtime.network-isolation-unsupported", recipe = recipe)
+        )?;
+      }
+      NoExecuteBackticks => {
+        write!(f, "{}", message!("runtime.no-execute-backticks"))?;
+      }
+      NotConfirmed { recipe } => {
+        write!(f, "{}", message!("runtime.not-confirmed", recipe = recipe))?;
+      }
+      RecursiveInvocation { recipe } => {
+        write!(
+          f,
+          "{}",
+          message!("runtime.recursive-invocation", recipe = recipe)
+        )?;
+      }
+      Report { ref failed, total } => {
+        write!(
+          f,
+          "{}",
+          message!(
+            &plural("runtime.report", total),
+            failed = failed.len(),
+            total = total,
+            recipes = List::and_ticked(failed),
+          )
+        )?;
+      }
+      ReportWrite { path, ref io_error } => {
+        write!(
+          f,
+          "{}",
+          message!(
+            "runtime.report-write",
+            path = path.display(),
+            io_error = io_error,
+          )
+        )?;
+      }
       Shebang {
         recipe,
         ref command,
-        ref argument,
+        ref arguments,
         ref io_error,
       } => {
-        if let Some(ref argument) = *argument {
-          write!(
-            f,
-            "Recipe `{}` with shebang `#!{} {}` execution error: {}",
-            recipe, command, argument, io_error
-          )?;
+        let shebang = if arguments.is_empty() {
+          command.clone()
         } else {
-          write!(
-            f,
-            "Recipe `{}` with shebang `#!{}` execution error: {}",
-            recipe, command, io_error
-          )?;
-        }
+          format!("{} {}", command, arguments.join(" "))
+        };
+        write!(
+          f,
+          "{}",
+          message!(
+            "runtime.shebang",
+            recipe = recipe,
+            shebang = shebang,
+            io_error = io_error,
+          )
+        )?;
       }
       Signal {
         recipe,
@@ -274,11 +568,20 @@ impl<'a> Display for RuntimeError<'a> {
         if let Some(n) = line_number {
           write!(
             f,
-            "Recipe `{}` was terminated on line {} by signal {}",
-            recipe, n, signal
+            "{}",
+            message!(
+              "runtime.signal-on-line",
+              recipe = recipe,
+              line = n,
+              signal = signal,
+            )
           )?;
         } else {
-          write!(f, "Recipe `{}` was terminated by signal {}", recipe, signal)?;
+          write!(
+            f,
+            "{}",
+            message!("runtime.signal", recipe = recipe, signal = signal)
+          )?;
         }
       }
       Unknown {
@@ -288,8 +591,8 @@ impl<'a> Display for RuntimeError<'a> {
         if let Some(n) = line_number {
           write!(
             f,
-            "Recipe `{}` failed on line {} for an unknown reason",
-            recipe, n
+            "{}",
+            message!("runtime.unknown-on-line", recipe = recipe, line = n)
           )?;
         } else {
         }
@@ -301,19 +604,22 @@ impl<'a> Display for RuntimeError<'a> {
         match io_error.kind() {
           io::ErrorKind::NotFound => writeln!(
             f,
-            "Recipe `{}` could not be run because just could not find `sh`:{}",
-            recipe, io_error
+            "{}",
+            message!("runtime.io-not-found", recipe = recipe, io_error = io_error,)
           ),
           io::ErrorKind::PermissionDenied => writeln!(
             f,
-            "Recipe `{}` could not be run because just could not run `sh`:{}",
-            recipe, io_error
+            "{}",
+            message!(
+              "runtime.io-permission-denied",
+              recipe = recipe,
+              io_error = io_error,
+            )
           ),
           _ => writeln!(
             f,
-            "Recipe `{}` could not be run because of an IO error while \
-             launching `sh`:{}",
-            recipe, io_error
+            "{}",
+            message!("runtime.io", recipe = recipe, io_error = io_error)
           ),
         }?;
       }
@@ -322,43 +628,45 @@ impl<'a> Display for RuntimeError<'a> {
         ref io_error,
       } => writeln!(
         f,
-        "Recipe `{}` could not be run because of an IO error while trying \
-         to create a temporary directory or write a file to that directory`:{}",
-        recipe, io_error
+        "{}",
+        message!("runtime.tmpdir-io", recipe = recipe, io_error = io_error)
       )?,
       Backtick {
         ref token,
         ref output_error,
       } => match *output_error {
         OutputError::Code(code) => {
-          writeln!(f, "Backtick failed with exit code {}", code)?;
+          writeln!(f, "{}", message!("runtime.backtick-code", code = code))?;
           error_token = Some(token);
         }
         OutputError::Signal(signal) => {
-          writeln!(f, "Backtick was terminated by signal {}", signal)?;
+          writeln!(
+            f,
+            "{}",
+            message!("runtime.backtick-signal", signal = signal)
+          )?;
           error_token = Some(token);
         }
         OutputError::Unknown => {
-          writeln!(f, "Backtick failed for an unknown reason")?;
+          writeln!(f, "{}", message!("runtime.backtick-unknown"))?;
           error_token = Some(token);
         }
         OutputError::Io(ref io_error) => {
           match io_error.kind() {
             io::ErrorKind::NotFound => write!(
               f,
-              "Backtick could not be run because just could not find `sh`:\n{}",
-              io_error
+              "{}",
+              message!("runtime.backtick-not-found", io_error = io_error)
             ),
             io::ErrorKind::PermissionDenied => write!(
               f,
-              "Backtick could not be run because just could not run `sh`:\n{}",
-              io_error
+              "{}",
+              message!("runtime.backtick-permission-denied", io_error = io_error)
             ),
             _ => write!(
               f,
-              "Backtick could not be run because of an IO \
-               error while launching `sh`:\n{}",
-              io_error
+              "{}",
+              message!("runtime.backtick-io", io_error = io_error)
             ),
           }?;
           error_token = Some(token);
@@ -366,24 +674,26 @@ impl<'a> Display for RuntimeError<'a> {
         OutputError::Utf8(ref utf8_error) => {
           writeln!(
             f,
-            "Backtick succeeded but stdout was not utf8: {}",
-            utf8_error
+            "{}",
+            message!("runtime.backtick-utf8", utf8_error = utf8_error)
           )?;
           error_token = Some(token);
         }
       },
       Internal { ref message } => {
-        write!(
-          f,
-          "Internal runtime error, this may indicate a bug in just: {} \
-           consider filing an issue: https://github.com/casey/just/issues/new",
-          message
-        )?;
+        write!(f, "{}", message!("runtime.internal", message = message))?;
       }
     }
 
     write!(f, "{}", message.suffix())?;
 
+    if let Some(command) = failed_command {
+      write!(f, "\n{}", message!("label.command"))?;
+      for line in command.lines() {
+        write!(f, "\n    {}", line)?;
+      }
+    }
+
     if let Some(token) = error_token {
       write_message_context(
         f,
@@ -0,0 +1,165 @@
+use crate::common::*;
+
+/// Runs a set of recipes on several threads, starting each as soon as the
+/// recipes it must wait for have finished
+pub(crate) struct Scheduler<'a> {
+  /// The recipes to run, in the order they would run one at a time
+  order: Vec<&'a str>,
+  /// The recipes that each recipe must wait for
+  waits: BTreeMap<&'a str, BTreeSet<&'a str>>,
+}
+
+impl<'a> Scheduler<'a> {
+  pub(crate) fn new(order: Vec<&'a str>) -> Scheduler<'a> {
+    Scheduler {
+      waits: order.iter().map(|name| (*name, BTreeSet::new())).collect(),
+      order,
+    }
+  }
+
+  /// Make `after` wait for `before`, if both are to be run and `before` comes
+  /// first in the order they would run one at a time. Otherwise, `after`
+  /// wouldn't have waited for `before` when run one at a time either, so
+  /// there is nothing to do, and leaving it out means there can't be cycles.
+  pub(crate) fn order(&mut self, before: &'a str, after: &'a str) {
+    let position = |name| self.order.iter().position(|other| *other == name);
+    if let (Some(before_position), Some(after_position)) = (position(before), position(after)) {
+      if before_position < after_position {
+        self.waits.get_mut(after).unwrap().insert(before);
+      }
+    }
+  }
+
+  /// Run each recipe with `run` on up to `jobs` threads, with each recipe
+  /// taking `weight(name)` job slots. Once a recipe fails, no more are
+  /// started, and the error of the first of those that failed, in the order
+  /// they would run one at a time, is returned.
+  pub(crate) fn run<E: Send>(
+    self,
+    jobs: usize,
+    weight: impl Fn(&str) -> usize + Sync,
+    run: impl Fn(&'a str) -> Result<(), E> + Sync,
+  ) -> Result<(), E> {
+    struct Status<'a> {
+      started: BTreeSet<&'a str>,
+      finished: BTreeSet<&'a str>,
+      failed: bool,
+    }
+
+    let state = Mutex::new(Status {
+      started: BTreeSet::new(),
+      finished: BTreeSet::new(),
+      failed: false,
+    });
+    let changed = Condvar::new();
+    let slots = JobSlots::new(jobs);
+    let errors = Mutex::new(Vec::new());
+
+    thread::scope(|threads| {
+      for _ in 0..cmp::min(jobs, self.order.len()) {
+        threads.spawn(|| loop {
+          let name = {
+            let mut state = state.lock().unwrap();
+            loop {
+              if state.failed || state.started.len() == self.order.len() {
+                return;
+              }
+
+              let ready = self.order.iter().cloned().find(|name| {
+                !state.started.contains(name) && self.waits[name].is_subset(&state.finished)
+              });
+
+              match ready {
+                Some(name) => {
+                  state.started.insert(name);
+                  break name;
+                }
+                None => state = changed.wait(state).unwrap(),
+              }
+            }
+          };
+
+          let result = {
+            let _slots = slots.acquire(weight(name));
+            run(name)
+          };
+
+          let mut state = state.lock().unwrap();
+          if let Err(error) = result {
+            state.failed = true;
+            errors.lock().unwrap().push((name, error));
+          }
+          state.finished.insert(name);
+          changed.notify_all();
+        });
+      }
+    });
+
+    match errors
+      .into_inner()
+      .unwrap()
+      .into_iter()
+      .min_by_key(|(name, _)| self.order.iter().position(|other| other == name))
+    {
+      Some((_, error)) => Err(error),
+      None => Ok(()),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::time::Duration;
+
+  #[test]
+  fn waits_for_dependencies() {
+    let mut scheduler = Scheduler::new(vec!["a", "b", "c", "d"]);
+    scheduler.order("a", "c");
+    scheduler.order("b", "c");
+    scheduler.order("c", "d");
+    // Backwards, so ignored
+    scheduler.order("d", "a");
+
+    let log = Mutex::new(Vec::new());
+
+    scheduler
+      .run(
+        4,
+        |_| 1,
+        |name| {
+          if name == "a" {
+            thread::sleep(Duration::from_millis(100));
+          }
+          log.lock().unwrap().push(name);
+          Ok::<(), ()>(())
+        },
+      )
+      .unwrap();
+
+    assert_eq!(log.into_inner().unwrap(), &["b", "a", "c", "d"]);
+  }
+
+  #[test]
+  fn first_error() {
+    let scheduler = Scheduler::new(vec!["a", "b", "c"]);
+
+    let result = scheduler.run(
+      3,
+      |_| 1,
+      |name| {
+        if name == "a" {
+          thread::sleep(Duration::from_millis(100));
+        }
+        if name == "c" {
+          Ok(())
+        } else {
+          Err(name)
+        }
+      },
+    );
+
+    assert_eq!(result, Err("a"));
+  }
+}
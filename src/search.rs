@@ -2,7 +2,7 @@ use crate::common::*;
 use std::fs;
 use std::path::{Path, PathBuf};
 
-const FILENAME: &str = "justfile";
+pub(crate) const FILENAME: &str = "justfile";
 
 pub(crate) fn justfile(directory: &Path) -> Result<PathBuf, SearchError> {
   let mut candidates = Vec::new();
@@ -32,6 +32,33 @@ pub(crate) fn justfile(directory: &Path) -> Result<PathBuf, SearchError> {
   }
 }
 
+/// Find the directory that recipes in the justfile at `path` should be run
+/// in. This is the directory containing `path`, unless `follow_symlinks` is
+/// set, in which case symlinks are resolved and it is the directory
+/// containing the justfile that `path` ultimately points to.
+pub(crate) fn working_directory(path: &Path, follow_symlinks: bool) -> io::Result<PathBuf> {
+  let mut path = if follow_symlinks {
+    path.canonicalize()?
+  } else if path.is_absolute() {
+    path.to_path_buf()
+  } else {
+    env::current_dir()?.join(path)
+  };
+
+  path.pop();
+
+  Ok(path)
+}
+
+/// Pick the candidate whose file name comes first in `names`, if any
+pub(crate) fn preferred<'a>(candidates: &'a [PathBuf], names: &[&str]) -> Option<&'a PathBuf> {
+  names.iter().find_map(|name| {
+    candidates
+      .iter()
+      .find(|candidate| candidate.file_name() == Some(OsStr::new(name)))
+  })
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -153,4 +180,63 @@ mod tests {
       _ => panic!("No errors were expected"),
     }
   }
+
+  #[test]
+  fn preferred_first_matching_name() {
+    let candidates = vec![
+      PathBuf::from("/foo/JUSTFILE"),
+      PathBuf::from("/foo/Justfile"),
+      PathBuf::from("/foo/justfile"),
+    ];
+
+    assert_eq!(
+      preferred(&candidates, &["justfile", "Justfile"]),
+      Some(&candidates[2])
+    );
+    assert_eq!(
+      preferred(&candidates, &["jUSTFILE", "Justfile"]),
+      Some(&candidates[1])
+    );
+  }
+
+  #[test]
+  fn preferred_no_matching_name() {
+    let candidates = vec![
+      PathBuf::from("/foo/JUSTFILE"),
+      PathBuf::from("/foo/justfile"),
+    ];
+
+    assert_eq!(preferred(&candidates, &["Justfile"]), None);
+    assert_eq!(preferred(&candidates, &[]), None);
+  }
+
+  #[test]
+  fn working_directory_is_parent() {
+    let tmp = testing::tempdir();
+    let path = tmp.path().join(FILENAME);
+    fs::write(&path, "default:\n\techo ok").unwrap();
+
+    assert_eq!(working_directory(&path, false).unwrap(), tmp.path());
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn working_directory_symlink() {
+    let tmp = testing::tempdir();
+    let target = tmp.path().join("target");
+    let link = tmp.path().join("link");
+    fs::create_dir(&target).unwrap();
+    fs::create_dir(&link).unwrap();
+    fs::write(target.join(FILENAME), "default:\n\techo ok").unwrap();
+    std::os::unix::fs::symlink(target.join(FILENAME), link.join(FILENAME)).unwrap();
+
+    assert_eq!(
+      working_directory(&link.join(FILENAME), false).unwrap(),
+      link
+    );
+    assert_eq!(
+      working_directory(&link.join(FILENAME), true).unwrap(),
+      target.canonicalize().unwrap()
+    );
+  }
 }
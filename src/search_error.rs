@@ -13,28 +13,28 @@ pub(crate) enum SearchError {
 
 impl fmt::Display for SearchError {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-    match self {
+    let message = match self {
       SearchError::Io {
         directory,
         io_error,
-      } => write!(
-        f,
-        "I/O error reading directory `{}`: {}",
-        directory.display(),
-        io_error
+      } => message!(
+        "search.io",
+        directory = directory.display(),
+        io_error = io_error
       ),
-      SearchError::MultipleCandidates { candidates } => write!(
-        f,
-        "Multiple candidate justfiles found in `{}`: {}",
-        candidates[0].parent().unwrap().display(),
-        List::and_ticked(
+      SearchError::MultipleCandidates { candidates } => message!(
+        "search.multiple-candidates",
+        directory = candidates[0].parent().unwrap().display(),
+        candidates = List::and_ticked(
           candidates
             .iter()
             .map(|candidate| candidate.file_name().unwrap().to_string_lossy())
         ),
       ),
-      SearchError::NotFound => write!(f, "No justfile found"),
-    }
+      SearchError::NotFound => message!("search.not-found"),
+    };
+
+    write!(f, "{}", message)
   }
 }
 
@@ -0,0 +1,102 @@
+use crate::common::*;
+
+#[derive(Debug, PartialEq, Default)]
+pub(crate) struct Settings {
+  /// The name of the file to load environment variables from, instead of
+  /// `.env`, which implies `dotenv_load`
+  pub(crate) dotenv_filename: Option<String>,
+  /// Whether to load environment variables from a `.env` file in the
+  /// justfile's directory or one of its ancestors
+  pub(crate) dotenv_load: bool,
+  /// The path of the file to load environment variables from, relative to
+  /// the justfile's directory, which must exist
+  pub(crate) dotenv_path: Option<String>,
+  pub(crate) env_file: bool,
+  pub(crate) export_prefix: String,
+  pub(crate) follow_symlinks: bool,
+  pub(crate) force_color: bool,
+  /// Whether all of the lines of a recipe without a shebang are run by a
+  /// single shell
+  pub(crate) one_shell: bool,
+  /// Whether recipe arguments are passed to the shell as positional
+  /// arguments, after the command
+  pub(crate) positional_arguments: bool,
+  /// Whether the standard error of shebang recipes is read, to find the line
+  /// the interpreter reported an error on
+  pub(crate) shebang_error_line: bool,
+  /// The shell and the arguments passed to it before each command
+  pub(crate) shell: Option<Vec<String>>,
+  pub(crate) verbosity: Option<Verbosity>,
+  /// Whether recipes are run with PowerShell on Windows
+  pub(crate) windows_powershell: bool,
+  /// The shell and the arguments passed to it before each command on
+  /// Windows, in place of `shell`
+  pub(crate) windows_shell: Option<Vec<String>>,
+}
+
+impl Settings {
+  pub(crate) fn is_default(&self) -> bool {
+    *self == Self::default()
+  }
+
+  /// Format a list of strings, like the value of `set shell`
+  fn list(strings: &[String]) -> String {
+    let strings = strings
+      .iter()
+      .map(|string| Quoted(string).to_string())
+      .collect::<Vec<String>>();
+    format!("[{}]", strings.join(", "))
+  }
+}
+
+impl Display for Settings {
+  fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+    let mut settings = Vec::new();
+    if let Some(filename) = &self.dotenv_filename {
+      settings.push(format!("set dotenv-filename := {}", Quoted(filename)));
+    }
+    if self.dotenv_load {
+      settings.push("set dotenv-load := true".to_owned());
+    }
+    if let Some(path) = &self.dotenv_path {
+      settings.push(format!("set dotenv-path := {}", Quoted(path)));
+    }
+    if self.env_file {
+      settings.push("set env-file := true".to_owned());
+    }
+    if !self.export_prefix.is_empty() {
+      settings.push(format!(
+        "set export-prefix := {}",
+        Quoted(&self.export_prefix)
+      ));
+    }
+    if self.follow_symlinks {
+      settings.push("set follow-symlinks := true".to_owned());
+    }
+    if self.force_color {
+      settings.push("set force-color := true".to_owned());
+    }
+    if self.one_shell {
+      settings.push("set one-shell := true".to_owned());
+    }
+    if self.positional_arguments {
+      settings.push("set positional-arguments := true".to_owned());
+    }
+    if self.shebang_error_line {
+      settings.push("set shebang-error-line := true".to_owned());
+    }
+    if let Some(shell) = &self.shell {
+      settings.push(format!("set shell := {}", Self::list(shell)));
+    }
+    if let Some(verbosity) = self.verbosity {
+      settings.push(format!("set verbosity := '{}'", verbosity));
+    }
+    if self.windows_powershell {
+      settings.push("set windows-powershell := true".to_owned());
+    }
+    if let Some(shell) = &self.windows_shell {
+      settings.push(format!("set windows-shell := {}", Self::list(shell)));
+    }
+    write!(f, "{}", settings.join("\n"))
+  }
+}
@@ -1,6 +1,8 @@
+use std::path::Path;
+
 pub(crate) struct Shebang<'a> {
   pub(crate) interpreter: &'a str,
-  pub(crate) argument: Option<&'a str>,
+  pub(crate) arguments: Vec<&'a str>,
 }
 
 impl<'a> Shebang<'a> {
@@ -23,11 +25,29 @@ impl<'a> Shebang<'a> {
       return None;
     }
 
+    // Like the kernel, pass everything after the interpreter as a single
+    // argument, unless the interpreter is `env -S`, which splits it into
+    // separate arguments.
+    let arguments = match argument {
+      Some(argument) if Self::is_env(interpreter) && argument.starts_with("-S") => {
+        argument[2..].split_whitespace().collect()
+      }
+      Some(argument) => vec![argument],
+      None => Vec::new(),
+    };
+
     Some(Shebang {
       interpreter,
-      argument,
+      arguments,
     })
   }
+
+  fn is_env(interpreter: &str) -> bool {
+    Path::new(interpreter)
+      .file_name()
+      .map(|name| name == "env")
+      .unwrap_or(false)
+  }
 }
 
 #[cfg(test)]
@@ -39,8 +59,8 @@ mod test {
     fn check(text: &str, expected_split: Option<(&str, Option<&str>)>) {
       let shebang = Shebang::new(text);
       assert_eq!(
-        shebang.map(|shebang| (shebang.interpreter, shebang.argument)),
-        expected_split
+        shebang.map(|shebang| (shebang.interpreter, shebang.arguments)),
+        expected_split.map(|(interpreter, argument)| (interpreter, argument.into_iter().collect()))
       );
     }
 
@@ -93,4 +113,31 @@ mod test {
     );
     check("#  /usr/bin/env python \t-x\t", None);
   }
+
+  #[test]
+  fn split_env_s_shebang() {
+    fn check(text: &str, expected: (&str, &[&str])) {
+      let shebang = Shebang::new(text).unwrap();
+      assert_eq!(
+        (shebang.interpreter, shebang.arguments.as_slice()),
+        expected
+      );
+    }
+
+    check("#!/usr/bin/env -S python", ("/usr/bin/env", &["python"]));
+    check(
+      "#!/usr/bin/env -S python -x",
+      ("/usr/bin/env", &["python", "-x"]),
+    );
+    check(
+      "#!/usr/bin/env -S  python \t-x  -y\t",
+      ("/usr/bin/env", &["python", "-x", "-y"]),
+    );
+    check(
+      "#!/usr/bin/env -Spython -x",
+      ("/usr/bin/env", &["python", "-x"]),
+    );
+    check("#!env -S python -x", ("env", &["python", "-x"]));
+    check("#!/bin/sh -S -x", ("/bin/sh", &["-S -x"]));
+  }
 }
@@ -0,0 +1,8 @@
+use crate::common::*;
+
+/// A part of a cooked string containing interpolations
+#[derive(PartialEq, Debug)]
+pub(crate) enum StringFragment<'a> {
+  Text { cooked: String },
+  Expression { expression: Expression<'a> },
+}
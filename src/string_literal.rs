@@ -16,34 +16,9 @@ impl<'a> StringLiteral<'a> {
         raw,
       })
     } else if let TokenKind::StringCooked = token.kind {
-      let mut cooked = String::new();
-      let mut escape = false;
-      for c in raw.chars() {
-        if escape {
-          match c {
-            'n' => cooked.push('\n'),
-            'r' => cooked.push('\r'),
-            't' => cooked.push('\t'),
-            '\\' => cooked.push('\\'),
-            '"' => cooked.push('"'),
-            other => {
-              return Err(
-                token.error(CompilationErrorKind::InvalidEscapeSequence { character: other }),
-              );
-            }
-          }
-          escape = false;
-          continue;
-        }
-        if c == '\\' {
-          escape = true;
-          continue;
-        }
-        cooked.push(c);
-      }
       Ok(StringLiteral {
         raw,
-        cooked: Cow::Owned(cooked),
+        cooked: Cow::Owned(Self::cook(token, raw)?),
       })
     } else {
       Err(token.error(CompilationErrorKind::Internal {
@@ -51,6 +26,37 @@ impl<'a> StringLiteral<'a> {
       }))
     }
   }
+
+  /// Process the escape sequences in `raw`, which is the contents of the
+  /// cooked string `token`, or a part of it
+  pub(crate) fn cook(token: &Token<'a>, raw: &str) -> CompilationResult<'a, String> {
+    let mut cooked = String::new();
+    let mut escape = false;
+    for c in raw.chars() {
+      if escape {
+        match c {
+          'n' => cooked.push('\n'),
+          'r' => cooked.push('\r'),
+          't' => cooked.push('\t'),
+          '\\' => cooked.push('\\'),
+          '"' => cooked.push('"'),
+          other => {
+            return Err(
+              token.error(CompilationErrorKind::InvalidEscapeSequence { character: other }),
+            );
+          }
+        }
+        escape = false;
+        continue;
+      }
+      if c == '\\' {
+        escape = true;
+        continue;
+      }
+      cooked.push(c);
+    }
+    Ok(cooked)
+  }
 }
 
 impl<'a> Display for StringLiteral<'a> {
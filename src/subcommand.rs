@@ -1,9 +1,57 @@
+use crate::common::*;
+
 #[derive(PartialEq)]
 pub(crate) enum Subcommand<'a> {
+  Bench {
+    runs: usize,
+  },
+  Complete {
+    index: usize,
+    words: Vec<&'a str>,
+  },
   Edit,
+  Expression {
+    expression: &'a str,
+  },
+  Grammar {
+    format: &'a str,
+  },
+  Groups,
+  History,
+  Init,
+  InstallCompletions {
+    shell: &'a str,
+  },
+  InstallMan,
+  Last,
+  Lint {
+    linter: &'a str,
+  },
+  Merge {
+    justfiles: Vec<&'a Path>,
+    output: Option<&'a Path>,
+  },
   Summary,
-  Dump,
+  SystemInfo,
+  Dump {
+    order: DumpOrder,
+  },
   List,
-  Show { name: &'a str },
+  ListFunctions,
+  Rename {
+    old: &'a str,
+    new: &'a str,
+  },
+  Repl,
+  Request,
+  Resume,
+  Show {
+    name: &'a str,
+    locate: bool,
+  },
+  Why {
+    recipe: &'a str,
+    dependency: &'a str,
+  },
   Run,
 }
@@ -12,18 +12,19 @@
 //! ensuring that changes to just do not inadvertently break or
 //! change the interpretation of existing justfiles.
 
-use std::{
-  collections::{BTreeMap, BTreeSet},
-  fs, io,
-  path::Path,
-};
+use std::{collections::BTreeMap, fs, io, path::Path};
 
-use crate::{expression, fragment, justfile::Justfile, parameter, parser::Parser, recipe};
+use crate::{
+  alias, export::Export, expression, fragment, justfile::Justfile, loader::Loader, parameter,
+  parser::Parser, recipe, string_fragment::StringFragment,
+};
 
 pub fn summary(path: &Path) -> Result<Result<Summary, String>, io::Error> {
   let text = fs::read_to_string(path)?;
 
-  match Parser::parse(&text) {
+  let loader = Loader::load(path, text, &BTreeMap::new());
+
+  match Parser::parse_with_loader(&loader, &BTreeMap::new()) {
     Ok(justfile) => Ok(Ok(Summary::new(justfile))),
     Err(compilation_error) => Ok(Err(compilation_error.to_string())),
   }
@@ -31,6 +32,7 @@ pub fn summary(path: &Path) -> Result<Result<Summary, String>, io::Error> {
 
 #[derive(Eq, PartialEq, Hash, Ord, PartialOrd, Debug, Clone)]
 pub struct Summary {
+  pub aliases: BTreeMap<String, Alias>,
   pub assignments: BTreeMap<String, Assignment>,
   pub recipes: BTreeMap<String, Recipe>,
 }
@@ -49,6 +51,11 @@ impl Summary {
     }
 
     Summary {
+      aliases: justfile
+        .aliases
+        .into_iter()
+        .map(|(name, alias)| (name.to_string(), Alias::new(alias)))
+        .collect(),
       recipes: justfile
         .recipes
         .into_iter()
@@ -76,7 +83,8 @@ impl Summary {
 #[derive(Eq, PartialEq, Hash, Ord, PartialOrd, Debug, Clone)]
 pub struct Recipe {
   pub aliases: Vec<String>,
-  pub dependencies: BTreeSet<String>,
+  pub dependencies: Vec<String>,
+  pub doc: Option<String>,
   pub lines: Vec<Line>,
   pub private: bool,
   pub quiet: bool,
@@ -91,6 +99,7 @@ impl Recipe {
       shebang: recipe.shebang,
       quiet: recipe.quiet,
       dependencies: recipe.dependencies.into_iter().map(str::to_owned).collect(),
+      doc: recipe.doc.map(str::to_owned),
       lines: recipe.lines.into_iter().map(Line::new).collect(),
       parameters: recipe.parameters.into_iter().map(Parameter::new).collect(),
       aliases,
@@ -98,6 +107,21 @@ impl Recipe {
   }
 }
 
+#[derive(Eq, PartialEq, Hash, Ord, PartialOrd, Debug, Clone)]
+pub struct Alias {
+  pub private: bool,
+  pub target: String,
+}
+
+impl Alias {
+  fn new(alias: alias::Alias) -> Alias {
+    Alias {
+      private: alias.private,
+      target: alias.target.to_owned(),
+    }
+  }
+}
+
 #[derive(Eq, PartialEq, Hash, Ord, PartialOrd, Debug, Clone)]
 pub struct Parameter {
   pub variadic: bool,
@@ -108,7 +132,7 @@ pub struct Parameter {
 impl Parameter {
   fn new(parameter: parameter::Parameter) -> Parameter {
     Parameter {
-      variadic: parameter.variadic,
+      variadic: parameter.kind.is_variadic(),
       name: parameter.name.to_owned(),
       default: parameter.default.map(Expression::new),
     }
@@ -154,9 +178,13 @@ pub struct Assignment {
 }
 
 impl Assignment {
-  fn new(name: &str, expression: expression::Expression, exports: &BTreeSet<&str>) -> Assignment {
+  fn new(
+    name: &str,
+    expression: expression::Expression,
+    exports: &BTreeMap<&str, Export>,
+  ) -> Assignment {
     Assignment {
-      exported: exports.contains(name),
+      exported: exports.contains_key(name),
       expression: Expression::new(expression),
     }
   }
@@ -175,9 +203,22 @@ pub enum Expression {
     lhs: Box<Expression>,
     rhs: Box<Expression>,
   },
+  Join {
+    lhs: Box<Expression>,
+    rhs: Box<Expression>,
+  },
+  Conditional {
+    lhs: Box<Expression>,
+    rhs: Box<Expression>,
+    then: Box<Expression>,
+    otherwise: Box<Expression>,
+  },
   String {
     text: String,
   },
+  InterpolatedString {
+    fragments: Vec<Fragment>,
+  },
   Variable {
     name: String,
   },
@@ -200,9 +241,35 @@ impl Expression {
         lhs: Box::new(Expression::new(*lhs)),
         rhs: Box::new(Expression::new(*rhs)),
       },
+      Join { lhs, rhs } => Expression::Join {
+        lhs: Box::new(Expression::new(*lhs)),
+        rhs: Box::new(Expression::new(*rhs)),
+      },
+      Conditional {
+        lhs,
+        rhs,
+        then,
+        otherwise,
+      } => Expression::Conditional {
+        lhs: Box::new(Expression::new(*lhs)),
+        rhs: Box::new(Expression::new(*rhs)),
+        then: Box::new(Expression::new(*then)),
+        otherwise: Box::new(Expression::new(*otherwise)),
+      },
       String { cooked_string } => Expression::String {
         text: cooked_string.cooked.to_string(),
       },
+      InterpolatedString { fragments, .. } => Expression::InterpolatedString {
+        fragments: fragments
+          .into_iter()
+          .map(|fragment| match fragment {
+            StringFragment::Text { cooked } => Fragment::Text { text: cooked },
+            StringFragment::Expression { expression } => Fragment::Expression {
+              expression: Expression::new(expression),
+            },
+          })
+          .collect(),
+      },
       Variable { name, .. } => Expression::Variable {
         name: name.to_owned(),
       },
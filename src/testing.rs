@@ -39,6 +39,7 @@ macro_rules! error_test {
         line,
         column,
         width,
+        secondary: None,
         kind,
       };
 
@@ -22,6 +22,7 @@ impl<'a> Token<'a> {
       line: self.line,
       text: self.text,
       width: self.length,
+      secondary: None,
       kind,
     }
   }
@@ -2,30 +2,73 @@ use crate::common::*;
 
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub(crate) enum TokenKind {
+  AmpersandAmpersand,
+  Asterisk,
   At,
   Backtick,
+  Bar,
+  BraceL,
+  BraceR,
+  BracketL,
+  BracketR,
   Colon,
   ColonEquals,
   Comma,
   Comment,
   Dedent,
+  Dollar,
   Eof,
   Eol,
   Equals,
+  EqualsEquals,
   Indent,
   InterpolationEnd,
   InterpolationStart,
   Line,
   Name,
+  Number,
   ParenL,
   ParenR,
+  Path,
   Plus,
+  Slash,
   StringRaw,
   StringCooked,
   Text,
   Whitespace,
 }
 
+impl TokenKind {
+  /// The text of tokens of this kind, if it's always the same
+  pub(crate) fn fixed_lexeme(self) -> Option<&'static str> {
+    use TokenKind::*;
+    match self {
+      AmpersandAmpersand => Some("&&"),
+      Asterisk => Some("*"),
+      At => Some("@"),
+      Bar => Some("|"),
+      BraceL => Some("{"),
+      BraceR => Some("}"),
+      BracketL => Some("["),
+      BracketR => Some("]"),
+      Colon => Some(":"),
+      ColonEquals => Some(":="),
+      Comma => Some(","),
+      Dollar => Some("$"),
+      Equals => Some("="),
+      EqualsEquals => Some("=="),
+      InterpolationEnd => Some("}}"),
+      InterpolationStart => Some("{{"),
+      ParenL => Some("("),
+      ParenR => Some(")"),
+      Plus => Some("+"),
+      Slash => Some("/"),
+      Backtick | Comment | Dedent | Eof | Eol | Indent | Line | Name | Number | Path
+      | StringRaw | StringCooked | Text | Whitespace => None,
+    }
+  }
+}
+
 impl Display for TokenKind {
   fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
     use TokenKind::*;
@@ -33,24 +76,36 @@ impl Display for TokenKind {
       f,
       "{}",
       match *self {
+        AmpersandAmpersand => "'&&'",
+        Asterisk => "'*'",
         At => "'@'",
         Backtick => "backtick",
+        Bar => "'|'",
+        BraceL => "'{'",
+        BraceR => "'}'",
+        BracketL => "'['",
+        BracketR => "']'",
         Colon => "':'",
         ColonEquals => "':='",
         Comma => "','",
         Comment => "comment",
         Dedent => "dedent",
+        Dollar => "'$'",
         Eof => "end of file",
         Eol => "end of line",
         Equals => "'='",
+        EqualsEquals => "'=='",
         Indent => "indent",
         InterpolationEnd => "'}}'",
         InterpolationStart => "'{{'",
         Line => "command",
         Name => "name",
+        Number => "number",
         ParenL => "'('",
         ParenR => "')'",
+        Path => "path",
         Plus => "'+'",
+        Slash => "'/'",
         StringRaw => "raw string",
         StringCooked => "cooked string",
         Text => "command text",
@@ -1,30 +1,50 @@
 use crate::common::*;
 
-pub(crate) struct Variables<'a> {
-  stack: Vec<&'a Expression<'a>>,
+pub(crate) struct Variables<'a: 'b, 'b> {
+  stack: Vec<&'b Expression<'a>>,
 }
 
-impl<'a> Variables<'a> {
-  pub(crate) fn new(root: &'a Expression<'a>) -> Variables<'a> {
+impl<'a, 'b> Variables<'a, 'b> {
+  pub(crate) fn new(root: &'b Expression<'a>) -> Variables<'a, 'b> {
     Variables { stack: vec![root] }
   }
 }
 
-impl<'a> Iterator for Variables<'a> {
-  type Item = &'a Token<'a>;
+impl<'a, 'b> Iterator for Variables<'a, 'b> {
+  type Item = &'b Token<'a>;
 
-  fn next(&mut self) -> Option<&'a Token<'a>> {
+  fn next(&mut self) -> Option<&'b Token<'a>> {
     match self.stack.pop() {
       None
       | Some(Expression::String { .. })
       | Some(Expression::Backtick { .. })
       | Some(Expression::Call { .. }) => None,
       Some(Expression::Variable { token, .. }) => Some(token),
-      Some(Expression::Concatination { lhs, rhs }) => {
+      Some(Expression::Concatination { lhs, rhs }) | Some(Expression::Join { lhs, rhs }) => {
         self.stack.push(lhs);
         self.stack.push(rhs);
         self.next()
       }
+      Some(Expression::Conditional {
+        lhs,
+        rhs,
+        then,
+        otherwise,
+      }) => {
+        self.stack.push(lhs);
+        self.stack.push(rhs);
+        self.stack.push(then);
+        self.stack.push(otherwise);
+        self.next()
+      }
+      Some(Expression::InterpolatedString { fragments, .. }) => {
+        for fragment in fragments {
+          if let StringFragment::Expression { expression } = fragment {
+            self.stack.push(expression);
+          }
+        }
+        self.next()
+      }
       Some(Expression::Group { expression }) => {
         self.stack.push(expression);
         self.next()
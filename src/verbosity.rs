@@ -1,6 +1,8 @@
+use crate::common::*;
+
 use Verbosity::*;
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub(crate) enum Verbosity {
   Taciturn,
   Loquacious,
@@ -32,3 +34,48 @@ impl Verbosity {
     }
   }
 }
+
+/// Parses either a verbosity's name, or a number, which is treated like that
+/// many `-v` flags
+impl FromStr for Verbosity {
+  type Err = ();
+
+  fn from_str(s: &str) -> Result<Verbosity, ()> {
+    match s {
+      "taciturn" => Ok(Taciturn),
+      "loquacious" => Ok(Loquacious),
+      "grandiloquent" => Ok(Grandiloquent),
+      _ => s
+        .parse()
+        .map(Verbosity::from_flag_occurrences)
+        .map_err(|_| ()),
+    }
+  }
+}
+
+impl Display for Verbosity {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    match self {
+      Taciturn => write!(f, "taciturn"),
+      Loquacious => write!(f, "loquacious"),
+      Grandiloquent => write!(f, "grandiloquent"),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parse() {
+    assert_eq!("taciturn".parse(), Ok(Taciturn));
+    assert_eq!("loquacious".parse(), Ok(Loquacious));
+    assert_eq!("grandiloquent".parse(), Ok(Grandiloquent));
+    assert_eq!("0".parse(), Ok(Taciturn));
+    assert_eq!("1".parse(), Ok(Loquacious));
+    assert_eq!("3".parse(), Ok(Grandiloquent));
+    assert_eq!("loud".parse::<Verbosity>(), Err(()));
+    assert_eq!("-1".parse::<Verbosity>(), Err(()));
+  }
+}
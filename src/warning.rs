@@ -4,13 +4,22 @@ use Warning::*;
 
 #[derive(Debug)]
 pub(crate) enum Warning<'a> {
-  DeprecatedEquals { equals: Token<'a> },
+  DeprecatedEquals {
+    equals: Token<'a>,
+  },
+  /// A recipe line that only changes directory, followed by lines that are
+  /// run by other shells, in the original directory
+  LostDirectoryChange {
+    recipe: &'a str,
+    cd: Token<'a>,
+  },
 }
 
 impl Warning<'_> {
-  fn context(&self) -> Option<&Token> {
+  pub(crate) fn context(&self) -> Option<&Token<'_>> {
     match self {
       DeprecatedEquals { equals } => Some(equals),
+      LostDirectoryChange { cd, .. } => Some(cd),
     }
   }
 }
@@ -20,18 +29,25 @@ impl Display for Warning<'_> {
     let warning = Color::fmt(f).warning();
     let message = Color::fmt(f).message();
 
-    write!(f, "{} {}", warning.paint("warning:"), message.prefix())?;
+    write!(
+      f,
+      "{} {}",
+      warning.paint(&message!("label.warning")),
+      message.prefix()
+    )?;
 
     match self {
       DeprecatedEquals { .. } => {
+        writeln!(f, "{}", message!("warning.deprecated-equals"))?;
+        write!(f, "{}", message!("warning.deprecated-equals-help"))?;
+      }
+      LostDirectoryChange { recipe, .. } => {
         writeln!(
           f,
-          "`=` in assignments, exports, and aliases is being phased out on favor of `:=`"
-        )?;
-        write!(
-          f,
-          "Please see this issue for more details: https://github.com/casey/just/issues/379"
+          "{}",
+          message!("warning.lost-directory-change", recipe = recipe)
         )?;
+        write!(f, "{}", message!("warning.lost-directory-change-help"))?;
       }
     }
 
@@ -0,0 +1,94 @@
+//! A WebAssembly interface to the compiler and formatter, so that web
+//! playgrounds and editors running in the browser can check and format
+//! justfiles without running `just`.
+//!
+//! This module is only built with the `wasm` feature. Recipes can't be run
+//! from WebAssembly, since it can't spawn processes. To build:
+//!
+//! ```sh
+//! cargo build --release --lib --target wasm32-unknown-unknown --features wasm
+//! wasm-bindgen --target web target/wasm32-unknown-unknown/release/just.wasm --out-dir pkg
+//! ```
+//!
+//! Results are JSON strings, in the same format as those of the C interface
+//! in `ffi`.
+
+use crate::common::*;
+
+use wasm_bindgen::prelude::*;
+
+use crate::{compilation_report, compiler};
+
+/// Compile the justfile `source`, returning `{"diagnostics": [...]}`, which
+/// is empty if the justfile has no errors or warnings
+#[wasm_bindgen]
+pub fn compile(source: &str) -> String {
+  compilation_report::report(source, false).to_string()
+}
+
+/// Compile the justfile `source`, returning
+/// `{"recipes": [...], "diagnostics": [...]}`
+#[wasm_bindgen(js_name = listRecipes)]
+pub fn list_recipes(source: &str) -> String {
+  compilation_report::report(source, true).to_string()
+}
+
+/// Format the justfile `source`, as `just --dump` would, throwing the error
+/// if it doesn't compile
+#[wasm_bindgen]
+pub fn format(source: &str) -> Result<String, JsValue> {
+  match Parser::parse(source) {
+    Ok(justfile) => Ok(format!("{}\n", justfile)),
+    Err(error) => Err(JsValue::from_str(&error.to_string())),
+  }
+}
+
+/// A justfile that is open in an editor, and is compiled again after every
+/// edit, reusing what it can from the last compilation
+#[wasm_bindgen]
+pub struct Compiler {
+  compiler: compiler::Compiler,
+}
+
+#[wasm_bindgen]
+impl Compiler {
+  /// Compile the justfile `source`
+  #[wasm_bindgen(constructor)]
+  pub fn new(source: &str) -> Compiler {
+    Compiler {
+      compiler: compiler::Compiler::new(source.to_owned()),
+    }
+  }
+
+  /// Replace the UTF-8 bytes from `start` up to `end` of the justfile with
+  /// `text`, and compile it again, returning
+  /// `{"recipes": [...], "diagnostics": [...]}`. Only the recipe being edited
+  /// is parsed again if the edit is within a single line of its body. Throws
+  /// if `start` and `end` aren't character boundaries of the justfile, with
+  /// `start` before `end`.
+  pub fn update(&mut self, start: usize, end: usize, text: &str) -> Result<String, JsValue> {
+    self
+      .compiler
+      .update(start..end, text)
+      .map_err(|message| JsValue::from_str(&message))?;
+    Ok(self.report())
+  }
+
+  /// The result of the last compilation, as returned by `update`
+  pub fn report(&self) -> String {
+    self.compiler.report(true).to_string()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn format_justfile() {
+    assert_eq!(
+      format("foo   :  bar\n  echo foo\nbar:").unwrap(),
+      "bar:\n\nfoo: bar\n    echo foo\n"
+    );
+  }
+}
@@ -0,0 +1,164 @@
+mod testing;
+
+use std::{error::Error, fs, process::Command, str};
+
+use executable_path::executable_path;
+
+use testing::tempdir;
+
+#[test]
+fn bench() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  fs::write(
+    tmp.path().join("justfile"),
+    "
+build target:
+  echo {{target}} >> log.txt
+
+script:
+  #!/bin/sh
+  echo out
+  echo err >&2
+",
+  )?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .args(vec!["--bench", "--runs", "3", "build", "x", "script"])
+    .output()?;
+
+  assert!(output.status.success());
+  assert_eq!(str::from_utf8(&output.stderr)?, "");
+
+  let stdout = str::from_utf8(&output.stdout)?;
+  let lines = stdout.lines().collect::<Vec<&str>>();
+  assert_eq!(lines.len(), 4);
+  assert_eq!(lines[0], "Ran `build x script` 3 times");
+  assert!(lines[1].starts_with("min:  "));
+  assert!(lines[2].starts_with("mean: "));
+  assert!(lines[3].starts_with("max:  "));
+
+  assert_eq!(fs::read_to_string(tmp.path().join("log.txt"))?, "x\nx\nx\n");
+
+  Ok(())
+}
+
+#[test]
+fn failure() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  fs::write(
+    tmp.path().join("justfile"),
+    "fail:\n  echo run >> log.txt\n  exit 3\n",
+  )?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .args(vec!["--bench", "-n", "5"])
+    .output()?;
+
+  assert_eq!(output.status.code(), Some(3));
+  assert_eq!(str::from_utf8(&output.stdout)?, "");
+  assert!(str::from_utf8(&output.stderr)?
+    .starts_with("error: Recipe `fail` failed on line 3 with exit code 3"));
+  assert_eq!(fs::read_to_string(tmp.path().join("log.txt"))?, "run\n");
+
+  Ok(())
+}
+
+#[test]
+fn invalid_runs() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  fs::write(tmp.path().join("justfile"), "build:\n")?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .args(vec!["--bench", "--runs", "0"])
+    .output()?;
+
+  assert_eq!(output.status.code(), Some(64));
+  assert_eq!(
+    str::from_utf8(&output.stderr)?,
+    "error: Invalid argument `0` to --runs, expected a positive integer\n"
+  );
+
+  Ok(())
+}
+
+#[test]
+fn runs_after_recipe() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  fs::write(
+    tmp.path().join("justfile"),
+    "build:\n  echo run >> log.txt\n",
+  )?;
+
+  for args in &[
+    &["--bench", "build", "-n", "2"][..],
+    &["--bench", "build", "--runs", "2"],
+    &["--bench", "build", "--runs=2"],
+  ] {
+    let output = Command::new(executable_path("just"))
+      .current_dir(tmp.path())
+      .env("XDG_STATE_HOME", tmp.path().join(".state"))
+      .args(*args)
+      .output()?;
+
+    assert!(output.status.success());
+    assert!(str::from_utf8(&output.stdout)?.starts_with("Ran `build` 2 times\n"));
+  }
+
+  assert_eq!(
+    fs::read_to_string(tmp.path().join("log.txt"))?,
+    "run\n".repeat(6)
+  );
+
+  Ok(())
+}
+
+/// Test that `-n` is passed to a recipe that takes arguments
+#[test]
+fn runs_recipe_argument() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  fs::write(
+    tmp.path().join("justfile"),
+    "grep *args:\n  echo grep {{args}} >> log.txt\n",
+  )?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .args(["--bench", "grep", "-n", "foo"])
+    .output()?;
+
+  assert!(output.status.success());
+  assert!(str::from_utf8(&output.stdout)?.starts_with("Ran `grep -n foo` 10 times\n"));
+  assert_eq!(
+    fs::read_to_string(tmp.path().join("log.txt"))?,
+    "grep -n foo\n".repeat(10)
+  );
+
+  Ok(())
+}
+
+#[test]
+fn runs_after_recipe_missing_value() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  fs::write(tmp.path().join("justfile"), "build:\n")?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .args(vec!["--bench", "build", "-n"])
+    .output()?;
+
+  assert_eq!(output.status.code(), Some(64));
+  assert_eq!(
+    str::from_utf8(&output.stderr)?,
+    "error: Expected a number of runs after `--runs`\n"
+  );
+
+  Ok(())
+}
@@ -0,0 +1,53 @@
+mod testing;
+
+use std::{error::Error, fs, process::Command, str};
+
+use executable_path::executable_path;
+
+use testing::tempdir;
+
+/// Test that `JUST_LANG` selects a catalog from `$XDG_DATA_HOME`, and that
+/// messages missing from it are in English
+#[test]
+fn translated() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  let messages = tmp.path().join("share/just/messages");
+  fs::create_dir_all(&messages)?;
+  fs::write(
+    messages.join("de.messages"),
+    "# German
+label.error = Fehler:
+config.invalid-jobs = Ungültiges Argument `{value}` für --jobs
+",
+  )?;
+  fs::write(tmp.path().join("justfile"), "foo:\n  cd bar\n  echo foo")?;
+
+  let just = |lang: &str, args: &[&str]| {
+    Command::new(executable_path("just"))
+      .current_dir(tmp.path())
+      .env("XDG_STATE_HOME", tmp.path().join(".state"))
+      .env("XDG_DATA_HOME", tmp.path().join("share"))
+      .env("JUST_LANG", lang)
+      .args(args)
+      .output()
+  };
+
+  let output = just("de_DE.UTF-8", &["--jobs", "x", "foo"])?;
+  assert_eq!(output.status.code(), Some(64));
+  assert_eq!(
+    str::from_utf8(&output.stderr)?,
+    "Fehler: Ungültiges Argument `x` für --jobs\n"
+  );
+
+  let output = just("de", &["--dry-run", "foo"])?;
+  assert!(str::from_utf8(&output.stderr)?
+    .starts_with("warning: Recipe `foo` changes directory with `cd` on a line of its own"));
+
+  let output = just("fr", &["--jobs", "x", "foo"])?;
+  assert_eq!(
+    str::from_utf8(&output.stderr)?,
+    "error: Invalid argument `x` to --jobs, expected a positive integer\n"
+  );
+
+  Ok(())
+}
@@ -0,0 +1,64 @@
+#![cfg(unix)]
+
+mod testing;
+
+use std::{error::Error, fs, process::Command, str};
+
+use executable_path::executable_path;
+
+use testing::tempdir;
+
+const JUSTFILE: &str = "
+[cfg: env = 'CI']
+runner := 'ci'
+
+[cfg: not(env = 'CI')]
+runner := 'local'
+
+[cfg: define = 'TARGET=release']
+build:
+  @echo {{runner}} release
+
+[cfg: not(define = 'TARGET=release')]
+build:
+  @echo {{runner}} debug
+";
+
+fn build(ci: bool, arguments: &[&str]) -> Result<String, Box<dyn Error>> {
+  let tmp = tempdir();
+  fs::write(tmp.path().join("justfile"), JUSTFILE)?;
+
+  let mut command = Command::new(executable_path("just"));
+  command
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .args(arguments);
+
+  if ci {
+    command.env("CI", "true");
+  } else {
+    command.env_remove("CI");
+  }
+
+  let output = command.output()?;
+  assert!(output.status.success());
+  Ok(str::from_utf8(&output.stdout)?.to_owned())
+}
+
+/// Test that `[cfg]` includes recipes and assignments depending on the
+/// environment and constants given with `--define`
+#[test]
+fn cfg() -> Result<(), Box<dyn Error>> {
+  assert_eq!(build(false, &[])?, "local debug\n");
+  assert_eq!(build(true, &[])?, "ci debug\n");
+  assert_eq!(
+    build(true, &["--define", "TARGET=release"])?,
+    "ci release\n"
+  );
+  assert_eq!(
+    build(false, &["--define", "TARGET=debug"])?,
+    "local debug\n"
+  );
+
+  Ok(())
+}
@@ -0,0 +1,125 @@
+mod testing;
+
+use std::{error::Error, fs, path::Path, process::Command, str};
+
+use executable_path::executable_path;
+
+use testing::tempdir;
+
+fn git(directory: &Path, arguments: &[&str]) -> Result<(), Box<dyn Error>> {
+  let status = Command::new("git")
+    .current_dir(directory)
+    .args(["-c", "user.name=just", "-c", "user.email=just@example.com"])
+    .args(arguments)
+    .status()?;
+  assert!(status.success());
+  Ok(())
+}
+
+#[test]
+fn changed_files() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  let path = tmp.path();
+
+  fs::write(
+    path.join("justfile"),
+    "files:\n  @echo {{changed_files('HEAD')}}",
+  )?;
+  fs::write(path.join("a.txt"), "a")?;
+  fs::write(path.join("b.txt"), "b")?;
+
+  git(path, &["init", "--quiet"])?;
+  git(path, &["add", "."])?;
+  git(path, &["commit", "--quiet", "--message", "initial"])?;
+
+  fs::write(path.join("a.txt"), "changed")?;
+  fs::write(path.join("c.txt"), "c")?;
+  fs::remove_file(path.join("b.txt"))?;
+  git(path, &["add", "c.txt"])?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(path)
+    .env("XDG_STATE_HOME", path.join(".state"))
+    .output()?;
+
+  assert!(output.status.success());
+  assert_eq!(str::from_utf8(&output.stdout)?, "a.txt c.txt\n");
+
+  Ok(())
+}
+
+#[test]
+fn unknown_ref() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  let path = tmp.path();
+
+  fs::write(
+    path.join("justfile"),
+    "files:\n  @echo {{changed_files('nonexistent')}}",
+  )?;
+
+  git(path, &["init", "--quiet"])?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(path)
+    .env("XDG_STATE_HOME", path.join(".state"))
+    .output()?;
+
+  assert_eq!(output.status.code(), Some(1));
+  assert!(str::from_utf8(&output.stderr)?.contains(
+    "Could not list files changed since `nonexistent`: Process exited with status code 128"
+  ));
+
+  Ok(())
+}
+
+#[test]
+fn option_ref() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  let path = tmp.path();
+
+  fs::write(
+    path.join("justfile"),
+    "files:\n  @echo {{changed_files('--output=pwned')}}",
+  )?;
+
+  git(path, &["init", "--quiet"])?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(path)
+    .env("XDG_STATE_HOME", path.join(".state"))
+    .output()?;
+
+  assert_eq!(output.status.code(), Some(1));
+  assert!(str::from_utf8(&output.stderr)?
+    .contains("Invalid git ref `--output=pwned`, refs may not begin with `-`"));
+  assert!(!path.join("pwned").exists());
+
+  Ok(())
+}
+
+/// Test that git isn't run by `--dry-run`
+#[test]
+fn dry_run() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  let path = tmp.path();
+
+  fs::write(
+    path.join("justfile"),
+    "files:\n  echo {{changed_files('nonexistent')}}",
+  )?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(path)
+    .env("XDG_STATE_HOME", path.join(".state"))
+    .arg("--dry-run")
+    .output()?;
+
+  assert!(output.status.success());
+  assert_eq!(
+    str::from_utf8(&output.stderr)?,
+    "echo changed_files('nonexistent')\n"
+  );
+
+  Ok(())
+}
@@ -0,0 +1,82 @@
+mod testing;
+
+use std::{error::Error, fs, process::Command, str};
+
+use executable_path::executable_path;
+
+use testing::tempdir;
+
+const JUSTFILE: &str = r#"
+set export-prefix := PREFIX
+
+[export: HOME_VARIABLE]
+home := "/srv/app"
+
+export mode := "release"
+
+[nice: NICENESS]
+wut:
+  echo $APP_HOME $APP_mode
+"#;
+
+const DEFINES: &[&str] = &[
+  "--define",
+  "PREFIX=APP_",
+  "--define",
+  "HOME_VARIABLE=APP_HOME",
+  "--define",
+  "NICENESS=1",
+];
+
+/// Test that constants given with `--define` are substituted into settings
+/// and attributes
+#[test]
+#[cfg(unix)]
+fn define() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  fs::write(tmp.path().join("justfile"), JUSTFILE)?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .args(DEFINES)
+    .output()?;
+
+  assert!(output.status.success());
+  assert_eq!(str::from_utf8(&output.stdout)?, "/srv/app release\n");
+
+  Ok(())
+}
+
+/// Test that constants are replaced by their values when the justfile is
+/// dumped
+#[test]
+fn define_dump() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  fs::write(tmp.path().join("justfile"), JUSTFILE)?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .args(DEFINES)
+    .arg("--dump")
+    .output()?;
+
+  assert!(output.status.success());
+  assert_eq!(
+    str::from_utf8(&output.stdout)?,
+    "set export-prefix := 'APP_'
+
+[export: 'APP_HOME']
+home := \"/srv/app\"
+
+export mode := \"release\"
+
+[nice: '1']
+wut:
+    echo $APP_HOME $APP_mode
+"
+  );
+
+  Ok(())
+}
@@ -0,0 +1,90 @@
+mod testing;
+
+use std::{error::Error, fs, process::Command, str};
+
+use executable_path::executable_path;
+
+use testing::tempdir;
+
+/// Test that `.env` is looked for in the justfile's directory and its
+/// ancestors, not the directory `just` was invoked from
+#[test]
+fn dotenv_load_searches_from_justfile_directory() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  let project = tmp.path().join("project");
+  let subdir = project.join("subdir");
+  fs::create_dir_all(&subdir)?;
+  fs::write(tmp.path().join(".env"), "KEY=parent")?;
+  fs::write(subdir.join(".env"), "KEY=subdir")?;
+  fs::write(
+    project.join("justfile"),
+    "set dotenv-load\n\nfoo:\n  @echo $KEY\n",
+  )?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(&subdir)
+    .env("XDG_STATE_HOME", subdir.join(".state"))
+    .arg("foo")
+    .output()?;
+
+  assert!(output.status.success());
+  assert_eq!(str::from_utf8(&output.stdout)?, "parent\n");
+
+  Ok(())
+}
+
+#[test]
+fn dotenv_filename() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  fs::write(tmp.path().join(".env"), "KEY=dotenv")?;
+  fs::write(tmp.path().join(".env.local"), "KEY=local")?;
+  fs::write(
+    tmp.path().join("justfile"),
+    "set dotenv-filename := '.env.local'\n\nfoo:\n  @echo $KEY\n",
+  )?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .arg("foo")
+    .output()?;
+
+  assert!(output.status.success());
+  assert_eq!(str::from_utf8(&output.stdout)?, "local\n");
+
+  Ok(())
+}
+
+#[test]
+fn dotenv_path() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  let config = tmp.path().join("config");
+  fs::create_dir(&config)?;
+  fs::write(config.join("dev.env"), "KEY=dev")?;
+  fs::write(
+    tmp.path().join("justfile"),
+    "set dotenv-path := 'config/dev.env'\n\nfoo:\n  @echo $KEY\n",
+  )?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .arg("foo")
+    .output()?;
+
+  assert!(output.status.success());
+  assert_eq!(str::from_utf8(&output.stdout)?, "dev\n");
+
+  fs::remove_file(config.join("dev.env"))?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .arg("foo")
+    .output()?;
+
+  assert_eq!(output.status.code(), Some(1));
+  assert!(str::from_utf8(&output.stderr)?.starts_with("error: Failed to load env file `"));
+
+  Ok(())
+}
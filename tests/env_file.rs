@@ -0,0 +1,132 @@
+mod testing;
+
+use std::{error::Error, fs, process::Command, str};
+
+use executable_path::executable_path;
+
+use testing::tempdir;
+
+const JUSTFILE: &str = "
+set dotenv-load
+
+foo:
+  @echo $DOTENV_KEY $CI_KEY
+";
+
+/// Test that `--env-file` after a recipe name is layered over `.env`
+#[test]
+fn env_file_after_recipe() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  fs::write(tmp.path().join("justfile"), JUSTFILE)?;
+  fs::write(tmp.path().join(".env"), "DOTENV_KEY=dotenv\nCI_KEY=local")?;
+  fs::write(tmp.path().join("ci.env"), "CI_KEY=ci")?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .arg("foo")
+    .arg("--env-file")
+    .arg("ci.env")
+    .output()?;
+
+  assert!(output.status.success());
+
+  assert_eq!(str::from_utf8(&output.stdout)?, "dotenv ci\n");
+
+  Ok(())
+}
+
+/// Test that `--env-file` before a recipe name is relative to the
+/// invocation directory, and is overridden by `--env`
+#[test]
+fn env_file_before_recipe() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  let subdir = tmp.path().join("subdir");
+  fs::create_dir(&subdir)?;
+  fs::write(tmp.path().join("justfile"), JUSTFILE)?;
+  fs::write(subdir.join("ci.env"), "DOTENV_KEY=ci\nCI_KEY=ci")?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(&subdir)
+    .env("XDG_STATE_HOME", subdir.join(".state"))
+    .arg("--env-file=ci.env")
+    .arg("--env")
+    .arg("CI_KEY=flag")
+    .arg("foo")
+    .output()?;
+
+  assert!(output.status.success());
+
+  assert_eq!(str::from_utf8(&output.stdout)?, "ci flag\n");
+
+  Ok(())
+}
+
+/// Test that arguments after `--` are not treated as env files
+#[test]
+fn env_file_pass_through() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  fs::write(tmp.path().join("justfile"), "foo +args:\n  @echo {{args}}")?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .arg("foo")
+    .arg("--")
+    .arg("--env-file")
+    .arg("ci.env")
+    .output()?;
+
+  assert!(output.status.success());
+
+  assert_eq!(str::from_utf8(&output.stdout)?, "--env-file ci.env\n");
+
+  Ok(())
+}
+
+/// Test that `--env-file` is passed to a recipe that takes it as an
+/// argument, and is only recognized after the recipe's arguments
+#[test]
+fn env_file_recipe_argument() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  fs::write(
+    tmp.path().join("justfile"),
+    "foo a b:\n  @echo {{a}} {{b}} $CI_KEY",
+  )?;
+  fs::write(tmp.path().join("ci.env"), "CI_KEY=ci")?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .args(["foo", "--env-file", "x", "--env-file", "ci.env"])
+    .output()?;
+
+  assert!(output.status.success());
+
+  assert_eq!(str::from_utf8(&output.stdout)?, "--env-file x ci\n");
+
+  Ok(())
+}
+
+/// Test that a missing env file is reported
+#[test]
+fn env_file_missing() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  fs::write(tmp.path().join("justfile"), JUSTFILE)?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .arg("foo")
+    .arg("--env-file")
+    .arg("missing.env")
+    .output()?;
+
+  assert!(!output.status.success());
+
+  let stderr = str::from_utf8(&output.stderr)?;
+  assert!(stderr.starts_with("error: Failed to load env file `"));
+  assert_eq!(stderr.lines().count(), 1);
+
+  Ok(())
+}
@@ -0,0 +1,144 @@
+mod testing;
+
+use std::{error::Error, fs, process::Command, str};
+
+use executable_path::executable_path;
+
+use testing::tempdir;
+
+/// Test that a recipe in another justfile runs first, once, in that
+/// justfile's directory
+#[test]
+fn external_dependency() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  let lib = tmp.path().join("lib");
+  let app = tmp.path().join("app");
+  fs::create_dir(&lib)?;
+  fs::create_dir(&app)?;
+  fs::write(
+    lib.join("justfile"),
+    "x := 'lib'\n\nbuild:\n  @echo build {{x}} $(basename $(pwd))\n",
+  )?;
+  fs::write(
+    app.join("justfile"),
+    "a: ../lib/justfile::build b\n  @echo a $(basename $(pwd))\n\nb: ../lib/justfile::build\n  @echo b\n",
+  )?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(&app)
+    .env("XDG_STATE_HOME", app.join(".state"))
+    .arg("a")
+    .output()?;
+
+  assert!(output.status.success());
+
+  assert_eq!(str::from_utf8(&output.stdout)?, "build lib lib\nb\na app\n");
+
+  Ok(())
+}
+
+/// Test that a failing external dependency stops the run with its exit code
+#[test]
+fn external_dependency_failure() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  fs::write(tmp.path().join("lib.just"), "build:\n  @exit 3\n")?;
+  fs::write(
+    tmp.path().join("justfile"),
+    "a: ./lib.just::build\n  echo a\n",
+  )?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .arg("a")
+    .output()?;
+
+  assert_eq!(output.status.code(), Some(3));
+
+  assert_eq!(str::from_utf8(&output.stdout)?, "");
+
+  assert_eq!(
+    str::from_utf8(&output.stderr)?,
+    "error: Recipe `build` failed on line 2 with exit code 3\n\
+     command:\n    exit 3\n\
+     error: External dependency `./lib.just::build` failed\n"
+  );
+
+  Ok(())
+}
+
+/// Test that a missing external justfile is reported
+#[test]
+fn external_dependency_missing() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  fs::write(
+    tmp.path().join("justfile"),
+    "a: ./missing::build\n  echo a\n",
+  )?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .arg("a")
+    .output()?;
+
+  assert_eq!(output.status.code(), Some(1));
+
+  assert!(str::from_utf8(&output.stderr)?
+    .starts_with("error: I/O error running external dependency `./missing::build`: "));
+
+  Ok(())
+}
+
+/// Test that `--yes` applies to `[confirm]` recipes in other justfiles
+#[test]
+fn external_dependency_yes() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  fs::write(
+    tmp.path().join("lib.just"),
+    "[confirm]\nbuild:\n  @echo build\n",
+  )?;
+  fs::write(
+    tmp.path().join("justfile"),
+    "a: ./lib.just::build\n  @echo a\n",
+  )?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .args(["--yes", "a"])
+    .output()?;
+
+  assert!(output.status.success());
+  assert_eq!(str::from_utf8(&output.stdout)?, "build\na\n");
+
+  Ok(())
+}
+
+/// Test that paths are relative to the justfile that declares the
+/// dependency, even if it was imported from another directory or commands
+/// run in another directory
+#[test]
+fn external_dependency_relative_to_importer() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  let ci = tmp.path().join("ci");
+  fs::create_dir(&ci)?;
+  fs::write(ci.join("lib.just"), "build:\n  @echo build\n")?;
+  fs::write(ci.join("recipes.just"), "a: ./lib.just::build\n  @echo a\n")?;
+  fs::write(tmp.path().join("justfile"), "import 'ci/recipes.just'\n")?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .args(["--justfile", "justfile", "--working-directory", "..", "a"])
+    .output()?;
+
+  assert!(
+    output.status.success(),
+    "{}",
+    str::from_utf8(&output.stderr)?
+  );
+  assert_eq!(str::from_utf8(&output.stdout)?, "build\na\n");
+
+  Ok(())
+}
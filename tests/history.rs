@@ -0,0 +1,141 @@
+mod testing;
+
+use std::{error::Error, fs, process::Command, str};
+
+use executable_path::executable_path;
+
+use testing::tempdir;
+
+const JUSTFILE: &str = "
+mode := 'debug'
+
+build target='x':
+  @echo build {{target}} {{mode}}
+
+fail:
+  @exit 2
+";
+
+#[test]
+fn last() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  let state = tempdir();
+  fs::write(tmp.path().join("justfile"), JUSTFILE)?;
+
+  let just = |arguments: &[&str]| {
+    Command::new(executable_path("just"))
+      .current_dir(tmp.path())
+      .env("XDG_STATE_HOME", state.path())
+      .args(arguments)
+      .output()
+  };
+
+  let output = just(&["--last"])?;
+  assert_eq!(output.status.code(), Some(1));
+  assert_eq!(
+    str::from_utf8(&output.stderr)?,
+    "No recipes have been run with this justfile.\n"
+  );
+
+  let output = just(&["mode=release", "build", "y"])?;
+  assert!(output.status.success());
+
+  // Overrides aren't recorded
+  let output = just(&["--last"])?;
+  assert!(output.status.success());
+  assert_eq!(str::from_utf8(&output.stdout)?, "build y debug\n");
+
+  let output = just(&["--set", "mode", "test", "--last"])?;
+  assert!(output.status.success());
+  assert_eq!(str::from_utf8(&output.stdout)?, "build y test\n");
+
+  let output = just(&["fail"])?;
+  assert_eq!(output.status.code(), Some(2));
+
+  let output = just(&["--last"])?;
+  assert_eq!(output.status.code(), Some(2));
+
+  Ok(())
+}
+
+#[test]
+fn history() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  let state = tempdir();
+  fs::write(tmp.path().join("justfile"), JUSTFILE)?;
+
+  let just = |arguments: &[&str]| {
+    Command::new(executable_path("just"))
+      .current_dir(tmp.path())
+      .env("XDG_STATE_HOME", state.path())
+      .args(arguments)
+      .output()
+  };
+
+  let output = just(&["--history"])?;
+  assert!(output.status.success());
+  assert_eq!(str::from_utf8(&output.stdout)?, "");
+
+  just(&["build"])?;
+  just(&["fail"])?;
+  just(&["--dry-run", "build", "z"])?;
+
+  let output = just(&["--history"])?;
+  assert!(output.status.success());
+
+  let stdout = str::from_utf8(&output.stdout)?;
+  let lines = stdout.lines().collect::<Vec<&str>>();
+  assert_eq!(lines.len(), 2);
+  assert!(lines[0].contains(" ok "));
+  assert!(lines[0].ends_with("s  build"));
+  assert!(lines[1].contains(" exit 2 "));
+  assert!(lines[1].ends_with("s  fail"));
+
+  Ok(())
+}
+
+#[test]
+fn override_values_not_recorded() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  let state = tempdir();
+  fs::write(tmp.path().join("justfile"), JUSTFILE)?;
+
+  for arguments in [
+    &["mode=hunter2", "build"][..],
+    &["--set", "mode", "hunter3", "build"],
+  ] {
+    let output = Command::new(executable_path("just"))
+      .current_dir(tmp.path())
+      .env("XDG_STATE_HOME", state.path())
+      .args(arguments)
+      .output()?;
+    assert!(output.status.success());
+  }
+
+  for entry in fs::read_dir(state.path().join("just/history"))? {
+    let history = fs::read_to_string(entry?.path())?;
+    assert!(history.ends_with("\tbuild\n"), "{}", history);
+    assert!(!history.contains("hunter"));
+  }
+
+  Ok(())
+}
+
+#[test]
+fn disabled() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  let state = tempdir();
+  fs::write(tmp.path().join("justfile"), JUSTFILE)?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", state.path())
+    .env("JUST_NO_HISTORY", "1")
+    .arg("build")
+    .output()?;
+  assert!(output.status.success());
+
+  assert!(!state.path().join("just/history").exists());
+
+  Ok(())
+}
@@ -0,0 +1,187 @@
+mod testing;
+
+use std::{error::Error, fs, process::Command, str};
+
+use executable_path::executable_path;
+
+use testing::tempdir;
+
+#[test]
+fn import() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  fs::create_dir(tmp.path().join("lib"))?;
+  fs::write(
+    tmp.path().join("justfile"),
+    "
+import 'lib/build.just'
+
+version := '1.0'
+
+default: build
+  echo done
+",
+  )?;
+  fs::write(
+    tmp.path().join("lib/build.just"),
+    "import 'common.just'\n\nbuild: common\n  echo build {{version}}\n",
+  )?;
+  fs::write(
+    tmp.path().join("lib/common.just"),
+    "import 'build.just'\n\ncommon:\n  echo common",
+  )?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .output()?;
+
+  assert!(output.status.success());
+  assert_eq!(str::from_utf8(&output.stdout)?, "common\nbuild 1.0\ndone\n");
+
+  Ok(())
+}
+
+#[test]
+fn conflict() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  fs::write(
+    tmp.path().join("justfile"),
+    "import 'other.just'\n\nbuild:\n  echo a\n",
+  )?;
+  fs::write(tmp.path().join("other.just"), "build:\n  echo b\n")?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .args(vec!["--justfile", "justfile"])
+    .output()?;
+
+  assert_eq!(output.status.code(), Some(65));
+  assert_eq!(
+    str::from_utf8(&output.stderr)?,
+    "error: Recipe `build` is defined in both `other.just` and `justfile`
+ --> justfile:3:1
+  |
+3 | build:
+  | ^^^^^
+ --> other.just:1:1
+  |
+1 | build:
+  | ^^^^^
+"
+  );
+
+  Ok(())
+}
+
+#[test]
+fn conflicting_variable() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  fs::write(
+    tmp.path().join("justfile"),
+    "import 'other.just'\n\nversion := '2'\n",
+  )?;
+  fs::write(
+    tmp.path().join("other.just"),
+    "\n# the version\nversion := '1'\n",
+  )?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .args(vec!["--justfile", "justfile", "--evaluate"])
+    .output()?;
+
+  assert_eq!(output.status.code(), Some(65));
+  assert_eq!(
+    str::from_utf8(&output.stderr)?,
+    "error: Variable `version` is defined in both `other.just` and `justfile`
+ --> justfile:3:1
+  |
+3 | version := '2'
+  | ^^^^^^^
+ --> other.just:3:1
+  |
+3 | version := '1'
+  | ^^^^^^^
+"
+  );
+
+  Ok(())
+}
+
+#[test]
+fn missing() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  fs::write(tmp.path().join("justfile"), "import 'missing.just'\n")?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .args(vec!["--justfile", "justfile"])
+    .output()?;
+
+  assert_eq!(output.status.code(), Some(65));
+  assert!(str::from_utf8(&output.stderr)?
+    .starts_with("error: Failed to read imported justfile `missing.just`: "));
+
+  Ok(())
+}
+
+#[test]
+fn error_in_import() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  fs::write(tmp.path().join("justfile"), "import 'other.just'\n\na:\n")?;
+  fs::write(tmp.path().join("other.just"), "x := y\n")?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .args(vec!["--justfile", "justfile"])
+    .output()?;
+
+  assert_eq!(output.status.code(), Some(65));
+  assert_eq!(
+    str::from_utf8(&output.stderr)?,
+    "Error in imported justfile `other.just`:
+error: Variable `y` not defined
+  |
+1 | x := y
+  |      ^
+"
+  );
+
+  Ok(())
+}
+
+#[test]
+fn show_where() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  fs::write(
+    tmp.path().join("justfile"),
+    "import 'ci.just'\n\n# run the tests\ntest: lint build\n  cargo test\n\nbuild:\n  \
+     cargo build\n",
+  )?;
+  fs::write(
+    tmp.path().join("ci.just"),
+    "\n[private]\nlint:\n  cargo clippy\n",
+  )?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .args(["--show", "test", "--where"])
+    .output()?;
+
+  assert!(output.status.success());
+  assert_eq!(
+    str::from_utf8(&output.stdout)?,
+    format!(
+      "{0}:4:1: test\n{1}:3:1: lint\n{0}:7:1: build\n",
+      tmp.path().join("justfile").display(),
+      tmp.path().join("ci.just").display(),
+    )
+  );
+
+  Ok(())
+}
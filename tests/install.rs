@@ -0,0 +1,61 @@
+mod testing;
+
+use std::{error::Error, fs, process::Command, str};
+
+use executable_path::executable_path;
+
+use testing::tempdir;
+
+/// Test that `--install-completions` writes to the per-user completions
+/// directory under `$XDG_DATA_HOME`
+#[test]
+fn install_completions() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .env("HOME", tmp.path())
+    .env("XDG_DATA_HOME", tmp.path().join("data"))
+    .arg("--install-completions")
+    .arg("bash")
+    .output()?;
+
+  assert!(output.status.success());
+
+  let path = tmp.path().join("data/bash-completion/completions/just");
+
+  assert_eq!(
+    str::from_utf8(&output.stderr)?,
+    format!("Wrote completions to `{}`\n", path.display())
+  );
+
+  let completions = fs::read_to_string(path)?;
+  assert!(completions.contains("_just()"));
+  assert!(completions.contains("just --complete"));
+
+  Ok(())
+}
+
+/// Test that `--install-man` falls back to `~/.local/share` when
+/// `$XDG_DATA_HOME` is unset
+#[test]
+fn install_man() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .env("HOME", tmp.path())
+    .env_remove("XDG_DATA_HOME")
+    .arg("--install-man")
+    .output()?;
+
+  assert!(output.status.success());
+
+  let path = tmp.path().join(".local/share/man/man1/just.1");
+
+  assert!(fs::read_to_string(path)?.contains(".TH JUST"));
+
+  Ok(())
+}
@@ -13,6 +13,10 @@ use libc::{EXIT_FAILURE, EXIT_SUCCESS};
 use pretty_assertions::assert_eq;
 use testing::{tempdir, unindent};
 
+const EXIT_USAGE: i32 = 64;
+const EXIT_COMPILE_ERROR: i32 = 65;
+const EXIT_UNKNOWN_RECIPE: i32 = 66;
+
 /// Instantiate an integration test.
 macro_rules! integration_test {
   (
@@ -77,8 +81,12 @@ impl<'a> Test<'a> {
     dotenv_path.push(".env");
     fs::write(dotenv_path, "DOTENV_KEY=dotenv-value").unwrap();
 
+    // Keep test runs out of the user's invocation history
+    let state = tempdir();
+
     let mut child = Command::new(&executable_path("just"))
       .current_dir(tmp.path())
+      .env("XDG_STATE_HOME", state.path())
       .args(&["--shell", "bash"])
       .args(self.args)
       .stdin(Stdio::piped())
@@ -131,6 +139,7 @@ fn test_round_trip(tmpdir: &Path) {
 
   let output = Command::new(&executable_path("just"))
     .current_dir(tmpdir)
+    .env("XDG_STATE_HOME", tmpdir.join(".state"))
     .arg("--dump")
     .output()
     .expect("just invocation failed");
@@ -147,6 +156,7 @@ fn test_round_trip(tmpdir: &Path) {
 
   let output = Command::new(&executable_path("just"))
     .current_dir(tmpdir)
+    .env("XDG_STATE_HOME", tmpdir.join(".state"))
     .arg("--justfile")
     .arg(&reparsed_path)
     .arg("--dump")
@@ -190,6 +200,22 @@ integration_test! {
   ",
 }
 
+integration_test! {
+  name: private_attribute_listing,
+  justfile: "
+    [private]
+    foo:
+      echo foo
+
+    bar: foo
+  ",
+  args: ("--list"),
+  stdout: "
+    Available recipes:
+        bar
+  ",
+}
+
 integration_test! {
   name: alias_listing_parameters,
   justfile: "foo PARAM='foo':\n  echo {{PARAM}}\nalias f := foo",
@@ -244,7 +270,7 @@ integration_test! {
     2 | alias foo := baz
       |       ^^^
   ",
-  status: EXIT_FAILURE,
+  status: EXIT_COMPILE_ERROR,
 }
 
 integration_test! {
@@ -256,7 +282,7 @@ integration_test! {
     1 | alias foo := bar
       |       ^^^
   ",
-  status: EXIT_FAILURE,
+  status: EXIT_COMPILE_ERROR,
 }
 
 integration_test! {
@@ -268,7 +294,7 @@ integration_test! {
     3 | alias foo := bar
       |       ^^^
   ",
-  status: EXIT_FAILURE,
+  status: EXIT_COMPILE_ERROR,
 }
 
 integration_test! {
@@ -292,7 +318,7 @@ integration_test! {
     1 | alias f := foo
       |       ^
   ",
-  status: EXIT_FAILURE,
+  status: EXIT_COMPILE_ERROR,
 }
 
 integration_test! {
@@ -404,7 +430,31 @@ hello:
 recipe:
   @exit 100",
   args:     ("recipe"),
-  stderr:   "error: Recipe `recipe` failed on line 6 with exit code 100\n",
+  stderr:   "error: Recipe `recipe` failed on line 6 with exit code 100\ncommand:\n    exit 100\n",
+  status:   100,
+}
+
+integration_test! {
+  name:     failed_command_secrets_masked,
+  justfile: "
+github_token := 'hunter2'
+
+deploy api-key:
+  @echo {{github_token}} {{api-key}} > /dev/null && exit 3",
+  args:     ("deploy", "swordfish"),
+  stderr:   "error: Recipe `deploy` failed on line 5 with exit code 3
+command:
+    echo *** *** > /dev/null && exit 3
+",
+  status:   3,
+}
+
+integration_test! {
+  name:     failed_command_echoed,
+  justfile: "
+recipe:
+  exit 100",
+  stderr:   "exit 100\nerror: Recipe `recipe` failed on line 3 with exit code 100\n",
   status:   100,
 }
 
@@ -417,7 +467,7 @@ integration_test! {
     3 | foo: bar baaaaaaaz hello
       |          ^^^^^^^^^
   ",
-  status:   EXIT_FAILURE,
+  status:   EXIT_COMPILE_ERROR,
 }
 
 integration_test! {
@@ -427,6 +477,13 @@ integration_test! {
   stderr:   "printf 'Hello, world.'\n",
 }
 
+integration_test! {
+  name:     backtick_source_order,
+  justfile: "b := `echo b >&2`\na := `echo a >&2`\nc := `echo c >&2`\nfoo:\n @echo {{a}}{{b}}{{c}}",
+  stdout:   "\n",
+  stderr:   "b\na\nc\n",
+}
+
 integration_test! {
   name:     backtick_trimming,
   justfile: "a := `echo Hello,`\nbar:\n echo '{{a + `echo ' world.'`}}'",
@@ -612,7 +669,7 @@ a := `exit 222`",
   args:     ("--set", "foo", "bar", "--set", "baz", "bob", "--set", "a", "b", "a", "b"),
   stderr:   "error: Variables `baz` and `foo` overridden on the command line but not present \
     in justfile\n",
-  status:   EXIT_FAILURE,
+  status:   EXIT_USAGE,
 }
 
 integration_test! {
@@ -624,7 +681,7 @@ a := `exit 222`",
   args:     ("foo=bar", "baz=bob", "a=b", "a", "b"),
   stderr:   "error: Variables `baz` and `foo` overridden on the command line but not present \
     in justfile\n",
-  status:   EXIT_FAILURE,
+  status:   EXIT_USAGE,
 }
 
 integration_test! {
@@ -635,7 +692,7 @@ integration_test! {
 a := `exit 222`",
   args:     ("foo=bar", "a=b", "a", "b"),
   stderr:   "error: Variable `foo` overridden on the command line but not present in justfile\n",
-  status:   EXIT_FAILURE,
+  status:   EXIT_USAGE,
 }
 
 integration_test! {
@@ -715,1474 +772,3588 @@ hello := "c"
 }
 
 integration_test! {
-  name:     export_success,
-  justfile: r#"
-export FOO := "a"
-baz := "c"
-export BAR := "b"
-export ABC := FOO + BAR + baz
-
-wut:
-  echo $FOO $BAR $ABC
-"#,
-  stdout:   "a b abc\n",
-  stderr:   "echo $FOO $BAR $ABC\n",
+  name:     show_script,
+  justfile: "
+foo:
+  #!/bin/sh
+  echo hello
+",
+  args:     ("--show-script"),
+  stdout:   "hello\n",
+  stderr:   "Script for recipe `foo`:\n1 | #!/bin/sh\n4 | echo hello\n",
 }
 
 integration_test! {
-  name:     export_override,
-  justfile: r#"
-export FOO := "a"
-baz := "c"
-export BAR := "b"
-export ABC := FOO + "-" + BAR + "-" + baz
+  name:     why,
+  justfile: "
+alias t := test
 
-wut:
-  echo $FOO $BAR $ABC
-"#,
-  args:     ("--set", "BAR", "bye", "FOO=hello"),
-  stdout:   "hello bye hello-bye-c\n",
-  stderr:   "echo $FOO $BAR $ABC\n",
+test: lint unit
+lint: build
+unit: build fmt
+build:
+fmt:
+",
+  args:     ("--why", "t", "build"),
+  stdout:   "test -> lint -> build\ntest -> unit -> build\n",
 }
 
 integration_test! {
-  name:     export_shebang,
-  justfile: r#"
-export FOO := "a"
-baz := "c"
-export BAR := "b"
-export ABC := FOO + BAR + baz
-
-wut:
-  #!/bin/sh
-  echo $FOO $BAR $ABC
-"#,
-  stdout:   "a b abc\n",
+  name:     why_not,
+  justfile: "
+test: build
+build:
+fmt:
+",
+  args:     ("--why", "test", "fmt"),
+  stderr:   "Recipe `test` does not depend on `fmt`.\n",
+  status:   EXIT_FAILURE,
 }
 
 integration_test! {
-  name:     export_recipe_backtick,
-  justfile: r#"
-export EXPORTED_VARIABLE := "A-IS-A"
+  name:     why_unknown_recipe,
+  justfile: "
+test: build
+build:
+",
+  args:     ("--why", "test", "biuld"),
+  stderr:   "Justfile does not contain recipe `biuld`.\nDid you mean `build`?\n",
+  status:   EXIT_FAILURE,
+}
 
-recipe:
-  echo {{`echo recipe $EXPORTED_VARIABLE`}}
-"#,
-  stdout:   "recipe A-IS-A\n",
-  stderr:   "echo recipe A-IS-A\n",
+integration_test! {
+  name:     expr,
+  justfile: "
+x := 'a'
+y := x + 'b'
+",
+  args:     ("--expr", "y + 'c' + env_var_or_default('JUST_UNSET', 'd')"),
+  stdout:   "abcd\n",
 }
 
 integration_test! {
-  name:     raw_string,
-  justfile: r#"
-export EXPORTED_VARIABLE := '\z'
+  name:     expr_override,
+  justfile: "
+x := 'a'
+y := x + 'b'
+",
+  args:     ("--set", "x", "z", "--expr", "y"),
+  stdout:   "zb\n",
+}
 
-recipe:
-  printf "$EXPORTED_VARIABLE"
-"#,
-  stdout:   "\\z",
-  stderr:   "printf \"$EXPORTED_VARIABLE\"\n",
+integration_test! {
+  name:     expr_undefined_variable,
+  justfile: "
+x := 'a'
+",
+  args:     ("--expr", "x + z"),
+  stdout:   "",
+  stderr:   "error: Variable `z` not defined
+  |
+1 | x + z
+  |     ^
+",
+  status:   EXIT_COMPILE_ERROR,
 }
 
 integration_test! {
-  name:     line_error_spacing,
-  justfile: r#"
+  name:     repl,
+  justfile: "
+x := 'a'
+y := x + 'b'
 
+hello name:
+  @echo hello {{name}} {{y}}
+",
+  args:     ("--repl"),
+  stdin:    "
+y + 'c'
+:vars
+:run hello bob
+z
+:quit
+x
+",
+  stdout:   "abc
+x := \"a\"
+y := \"ab\"
+hello bob ab
+",
+  stderr:   "error: Variable `z` not defined
+  |
+1 | z
+  | ^
+",
+}
 
+integration_test! {
+  name:     repl_unknown_command,
+  justfile: "
+hello:
+",
+  args:     ("--repl"),
+  stdin:    ":hello",
+  stderr:   "error: Unknown command `:hello`, enter `:help` for help\n",
+}
 
+integration_test! {
+  name:     confirm_yes,
+  justfile: "
+[confirm]
+clean:
+  @echo cleaned
+",
+  stdin:    "y\n",
+  stdout:   "cleaned\n",
+  stderr:   "Run recipe `clean`? [y/N] ",
+}
 
+integration_test! {
+  name:     confirm_no,
+  justfile: "
+[confirm: 'Deploy to production?']
+deploy:
+  @echo deployed
+",
+  stdin:    "n\n",
+  stderr:   "Deploy to production? [y/N] error: Recipe `deploy` was not confirmed\n",
+  status:   EXIT_FAILURE,
+}
 
+integration_test! {
+  name:     confirm_end_of_input,
+  justfile: "
+[confirm]
+clean:
+  @echo cleaned
+",
+  stderr:   "Run recipe `clean`? [y/N] error: Recipe `clean` was not confirmed\n",
+  status:   EXIT_FAILURE,
+}
 
+integration_test! {
+  name:     confirm_yes_flag,
+  justfile: "
+[confirm]
+clean:
+  @echo cleaned
+",
+  args:     ("--yes", "clean"),
+  stdout:   "cleaned\n",
+}
 
+integration_test! {
+  name:     confirm_dependency,
+  justfile: "
+[confirm]
+deploy: build
+  @echo deployed
 
-???
-"#,
-  stdout:   "",
-  stderr:   "error: Unknown start of token:
-   |
-10 | ???
-   | ^
+build:
+  @echo built
 ",
+  stdin:    "no\n",
+  stdout:   "built\n",
+  stderr:   "Run recipe `deploy`? [y/N] error: Recipe `deploy` was not confirmed\n",
   status:   EXIT_FAILURE,
 }
 
 integration_test! {
-  name:     quiet_flag_no_stdout,
-  justfile: r#"
-default:
-  @echo hello
+  name:     request_list,
+  justfile: "
+# Say hello
+hello name='world' greeting=\"hi\\tthere\" sep=('a' + arch()) *rest: greet
+
+[private]
+[meta: owner='infra', sla='p1']
+greet:
+",
+  args:     ("--request"),
+  stdin:    r#"{"kind": "list"}"#,
+  stdout:   r#"{"kind":"list","recipes":[{"name":"greet","doc":null,"parameters":[],"dependencies":[],"private":true,"groups":[],"meta":{"owner":"infra","sla":"p1"}},{"name":"hello","doc":"Say hello","parameters":[{"name":"name","default":"world","default_expression":null,"variadic":false},{"name":"greeting","default":"hi\tthere","default_expression":null,"variadic":false},{"name":"sep","default":null,"default_expression":"('a' + arch())","variadic":false},{"name":"rest","default":null,"default_expression":null,"variadic":true}],"dependencies":["greet"],"private":false,"groups":[],"meta":{}}]}
 "#,
-  args:     ("--quiet"),
-  stdout:   "",
 }
 
 integration_test! {
-  name:     quiet_flag_no_stderr,
-  justfile: r#"
-default:
-  @echo hello 1>&2
+  name:     list_json,
+  justfile: "
+# Build it
+[group: 'ci']
+[meta: owner='infra']
+build mode='debug':
+
+test:
+",
+  args:     ("--list", "--json"),
+  stdout:   r#"{"kind":"list","recipes":[{"name":"build","doc":"Build it","parameters":[{"name":"mode","default":"debug","default_expression":null,"variadic":false}],"dependencies":[],"private":false,"groups":["ci"],"meta":{"owner":"infra"}},{"name":"test","doc":null,"parameters":[],"dependencies":[],"private":false,"groups":[],"meta":{}}]}
 "#,
-  args:     ("--quiet"),
-  stdout:   "",
 }
 
 integration_test! {
-  name:     quiet_flag_no_command_echoing,
-  justfile: r#"
-default:
-  exit
+  name:     list_json_group,
+  justfile: "
+[group: 'ci']
+build:
+
+test:
+",
+  args:     ("--list", "--json", "--group", "ci"),
+  stdout:   r#"{"kind":"list","recipes":[{"name":"build","doc":null,"parameters":[],"dependencies":[],"private":false,"groups":["ci"],"meta":{}}]}
 "#,
-  args:     ("--quiet"),
-  stdout:   "",
 }
 
 integration_test! {
-  name:     quiet_flag_no_error_messages,
-  justfile: r#"
-default:
-  exit 100
+  name:     request_evaluate,
+  justfile: "
+x := 'a'
+y := x + 'b'
+",
+  args:     ("--request"),
+  stdin:    r#"{"kind": "evaluate"}"#,
+  stdout:   r#"{"kind":"evaluate","variables":{"x":"a","y":"ab"}}
 "#,
-  args:     ("--quiet"),
-  stdout:   "",
-  status:   100,
 }
 
 integration_test! {
-  name:     quiet_flag_no_assignment_backtick_stderr,
-  justfile: r#"
-a := `echo hello 1>&2`
-default:
-  exit 100
+  name:     request_run,
+  justfile: "
+x := 'a'
+
+hello name:
+  echo {{name}} {{x}}
+  exit 3
+",
+  args:     ("--request"),
+  stdin:    r#"{"kind": "run", "recipe": "hello", "arguments": ["b"], "overrides": {"x": "c"}}"#,
+  stdout:   r#"{"kind":"run","exit_code":3,"stdout":"b c\n","stderr":"echo b c\nexit 3\nerror: Recipe `hello` failed on line 6 with exit code 3\n"}
 "#,
-  args:     ("--quiet"),
-  stdout:   "",
-  status:   100,
 }
 
 integration_test! {
-  name:     quiet_flag_no_interpolation_backtick_stderr,
-  justfile: r#"
-default:
-  echo `echo hello 1>&2`
-  exit 100
+  name:     request_run_unknown_recipe,
+  justfile: "
+x := 'a'
+
+hello:
+",
+  args:     ("--request"),
+  stdin:    r#"{"kind": "run", "recipe": "--evaluate"}"#,
+  stdout:   r#"{"kind":"error","message":"Justfile does not contain recipe `--evaluate`"}
 "#,
-  args:     ("--quiet"),
-  stdout:   "",
-  status:   100,
+  status:   EXIT_FAILURE,
 }
 
 integration_test! {
-  name:     argument_single,
+  name:     request_run_unknown_override,
   justfile: "
-foo A:
-  echo {{A}}
-    ",
-  args:     ("foo", "ARGUMENT"),
-  stdout:   "ARGUMENT\n",
-  stderr:   "echo ARGUMENT\n",
+hello:
+",
+  args:     ("--request"),
+  stdin:    r#"{"kind": "run", "recipe": "hello", "overrides": {"--shell": "touch"}}"#,
+  stdout:   r#"{"kind":"error","message":"Justfile does not contain variable `--shell`"}
+"#,
+  status:   EXIT_FAILURE,
 }
 
 integration_test! {
-  name:     argument_multiple,
+  name:     request_run_option_arguments,
   justfile: "
-foo A B:
-  echo A:{{A}} B:{{B}}
-    ",
-  args:     ("foo", "ONE", "TWO"),
-  stdout:   "A:ONE B:TWO\n",
-  stderr:   "echo A:ONE B:TWO\n",
+x := 'a'
+
+hello +args:
+  @echo {{args}} {{x}}
+",
+  args:     ("--request"),
+  stdin:    r#"{"kind": "run", "recipe": "hello", "arguments": ["--shell=touch", "x=b"], "overrides": {"x": "--evaluate"}}"#,
+  stdout:   r#"{"kind":"run","exit_code":0,"stdout":"--shell=touch x=b --evaluate\n","stderr":""}
+"#,
 }
 
 integration_test! {
-  name:     argument_mismatch_more,
+  name:     request_invalid,
   justfile: "
-foo A B:
-  echo A:{{A}} B:{{B}}
-    ",
-  args:     ("foo", "ONE", "TWO", "THREE"),
-  stdout:   "",
-  stderr:   "error: Justfile does not contain recipe `THREE`.\n",
+hello:
+",
+  args:     ("--request"),
+  stdin:    r#"{"kind": "fly"}"#,
+  stdout:   r#"{"kind":"error","message":"Invalid request: unknown variant `fly`, expected one of `list`, `evaluate`, `run` at line 1 column 14"}
+"#,
   status:   EXIT_FAILURE,
 }
 
 integration_test! {
-  name:     argument_mismatch_fewer,
-  justfile: "
-foo A B:
-  echo A:{{A}} B:{{B}}
-    ",
-  args:     ("foo", "ONE"),
-  stdout:   "",
-  stderr:   "error: Recipe `foo` got 1 argument but takes 2\nusage:\n    just foo A B\n",
-  status:   EXIT_FAILURE,
+  name:     evaluate_dry_run,
+  justfile: r#"
+foo := `echo stderr 1>&2; echo foo`
+bar := "bar-" + foo
+
+wut:
+  touch /this/is/not/a/file
+"#,
+  args:     ("--evaluate", "--dry-run"),
+  stdout:   r#"bar := "bar-`echo stderr 1>&2; echo foo`"
+foo := "`echo stderr 1>&2; echo foo`"
+"#,
 }
 
 integration_test! {
-  name:     argument_mismatch_more_with_default,
-  justfile: "
-foo A B='B':
-  echo A:{{A}} B:{{B}}
-    ",
-  args:     ("foo", "ONE", "TWO", "THREE"),
-  stdout:   "",
-  stderr:   "error: Justfile does not contain recipe `THREE`.\n",
-  status:   EXIT_FAILURE,
+  name:     export_success,
+  justfile: r#"
+export FOO := "a"
+baz := "c"
+export BAR := "b"
+export ABC := FOO + BAR + baz
+
+wut:
+  echo $FOO $BAR $ABC
+"#,
+  stdout:   "a b abc\n",
+  stderr:   "echo $FOO $BAR $ABC\n",
 }
 
 integration_test! {
-  name:     argument_mismatch_fewer_with_default,
-  justfile: "
-foo A B C='C':
-  echo A:{{A}} B:{{B}} C:{{C}}
-    ",
-  args:     ("foo", "bar"),
-  stdout:   "",
-  stderr:   "error: Recipe `foo` got 1 argument but takes at least 2\nusage:\n    just foo A B C='C'\n",
-  status:   EXIT_FAILURE,
+  name:     no_execute_backticks_evaluate,
+  justfile: r#"
+foo := `echo stderr 1>&2; echo foo`
+
+wut:
+  echo {{foo}}
+"#,
+  args:     ("--no-execute-backticks", "--evaluate"),
+  stdout:   "foo := \"`echo stderr 1>&2; echo foo`\"\n",
 }
 
 integration_test! {
-  name:     unknown_recipe,
-  justfile: "hello:",
-  args:     ("foo"),
+  name:     no_execute_backticks_run,
+  justfile: r#"
+foo := `echo stderr 1>&2; echo foo`
+
+wut:
+  echo {{foo}}
+"#,
+  args:     ("--no-execute-backticks", "wut"),
   stdout:   "",
-  stderr:   "error: Justfile does not contain recipe `foo`.\n",
+  stderr:   "error: Recipes may only be run with `--no-execute-backticks` if `--dry-run` is also \
+             given\n",
   status:   EXIT_FAILURE,
 }
 
 integration_test! {
-  name:     unknown_recipes,
-  justfile: "hello:",
-  args:     ("foo", "bar"),
+  name:     no_execute_backticks_dry_run,
+  justfile: r#"
+foo := `echo stderr 1>&2; echo foo`
+
+wut:
+  echo {{foo}}
+"#,
+  args:     ("--no-execute-backticks", "--dry-run", "wut"),
   stdout:   "",
-  stderr:   "error: Justfile does not contain recipes `foo` or `bar`.\n",
-  status:   EXIT_FAILURE,
+  stderr:   "echo `echo stderr 1>&2; echo foo`\n",
 }
 
 integration_test! {
-  name:     color_always,
-  justfile: "b := a\na := `exit 100`\nbar:\n echo '{{`exit 200`}}'",
-  args:     ("--color", "always"),
-  stdout:   "",
-  stderr:   "\u{1b}[1;31merror:\u{1b}[0m \u{1b}[1mBacktick failed with exit code 100
-\u{1b}[0m  |\n2 | a := `exit 100`\n  |      \u{1b}[1;31m^^^^^^^^^^\u{1b}[0m\n",
-  status:   100,
+  name:     export_attribute,
+  justfile: r#"
+set export-prefix := "APP_"
+
+[export]
+foo := "a"
+
+[export: "BAR"]
+bar := "b"
+
+export baz := "c"
+
+wut:
+  echo $APP_foo $BAR $APP_baz
+"#,
+  stdout:   "a b c\n",
+  stderr:   "echo $APP_foo $BAR $APP_baz\n",
 }
 
 integration_test! {
-  name:     color_never,
-  justfile: "b := a\na := `exit 100`\nbar:\n echo '{{`exit 200`}}'",
-  args:     ("--color", "never"),
-  stdout:   "",
-  stderr:   "error: Backtick failed with exit code 100
+  name:     define_undefined_constant,
+  justfile: "
+[nice: NICENESS]
+wut:
+  echo hello
+",
+  stderr:   "error: Constant `NICENESS` not defined, constants are given with `--define`
   |
-2 | a := `exit 100`
-  |      ^^^^^^^^^^
+2 | [nice: NICENESS]
+  |        ^^^^^^^^
 ",
-  status:   100,
+  status:   EXIT_COMPILE_ERROR,
 }
 
 integration_test! {
-  name:     color_auto,
-  justfile: "b := a\na := `exit 100`\nbar:\n echo '{{`exit 200`}}'",
-  args:     ("--color", "auto"),
-  stdout:   "",
-  stderr:   "error: Backtick failed with exit code 100
-  |
-2 | a := `exit 100`
-  |      ^^^^^^^^^^
-",
-  status:   100,
+  name:     define_invalid,
+  justfile: "wut:\n  echo hello",
+  args:     ("--define", "NICENESS"),
+  stderr:   "error: Invalid argument `NICENESS` to --define, expected `KEY=VALUE` where `KEY` is a \
+    valid name\n",
+  status:   EXIT_USAGE,
 }
 
 integration_test! {
-  name:     colors_no_context,
+  name:     conditional,
   justfile: "
-recipe:
-  @exit 100",
-  args:     ("--color=always"),
-  stdout:   "",
-  stderr:   "\u{1b}[1;31merror:\u{1b}[0m \u{1b}[1m\
-Recipe `recipe` failed on line 3 with exit code 100\u{1b}[0m\n",
-  status:   100,
+    a := 'x'
+    b := if a == 'x' { 'then' } else { 'otherwise' }
+    c := if a + 'y' == 'x' { 'then' } else { 'otherwise' }
+
+    wut:
+      echo {{b}} {{c}}
+  ",
+  stdout:   "then otherwise\n",
+  stderr:   "echo then otherwise\n",
 }
 
 integration_test! {
-  name:     dump,
-  justfile: r#"
-# this recipe does something
-recipe a b +d:
- @exit 100"#,
-  args:     ("--dump"),
-  stdout:   "# this recipe does something
-recipe a b +d:
-    @exit 100
-",
+  name:     conditional_branch_not_evaluated,
+  justfile: "
+    a := if 'x' == 'x' { 'then' } else { `exit 1` }
+
+    wut:
+      echo {{a}}
+  ",
+  stdout:   "then\n",
+  stderr:   "echo then\n",
 }
 
 integration_test! {
-  name:     mixed_whitespace,
-  justfile: "bar:\n\t echo hello",
-  stdout:   "",
-  stderr:   "error: Found a mix of tabs and spaces in leading whitespace: `␉␠`
-Leading whitespace may consist of tabs or spaces, but not both
-  |
-2 |      echo hello
-  | ^^^^^
-",
-  status:   EXIT_FAILURE,
+  name:     conditional_interpolation,
+  justfile: "
+    wut mode:
+      echo {{ if mode == 'release' { '-O' } else { '-g' } }}
+  ",
+  args:     ("wut", "debug"),
+  stdout:   "-g\n",
+  stderr:   "echo -g\n",
 }
 
 integration_test! {
-  name:     extra_leading_whitespace,
-  justfile: "bar:\n\t\techo hello\n\t\t\techo goodbye",
-  stdout:   "",
-  stderr:   "error: Recipe line has extra leading whitespace
-  |
-3 |             echo goodbye
-  |         ^^^^^^^^^^^^^^^^
-",
-  status:   EXIT_FAILURE,
+  name:     interpolated_string,
+  justfile: r#"
+    version := "1.0"
+    tag := "v{{version}}-{{ if version == '1.0' { 'stable' } else { 'beta' } }}"
+
+    wut dir="{{tag}}/bin":
+      echo {{dir}}
+  "#,
+  stdout:   "v1.0-stable/bin\n",
+  stderr:   "echo v1.0-stable/bin\n",
 }
 
 integration_test! {
-  name:     inconsistent_leading_whitespace,
-  justfile: "bar:\n\t\techo hello\n\t echo goodbye",
-  stdout:   "",
-  stderr:   "error: Recipe line has inconsistent leading whitespace. \
-            Recipe started with `␉␉` but found line with `␉␠`
-  |
-3 |      echo goodbye
-  | ^^^^^
-",
-  status:   EXIT_FAILURE,
+  name:     interpolated_string_evaluate,
+  justfile: r#"
+    a := "x"
+    b := "{{a}}\t{{a + 'y'}}"
+
+    wut:
+  "#,
+  args:     ("--evaluate"),
+  stdout:   "a := \"x\"\nb := \"x\txy\"\n",
 }
 
 integration_test! {
-  name:     required_after_default,
-  justfile: "bar:\nhello baz arg='foo' bar:",
-  stdout:   "",
-  stderr:   "error: Non-default parameter `bar` follows default parameter
-  |
-2 | hello baz arg='foo' bar:
-  |                     ^^^
-",
-  status:   EXIT_FAILURE,
+  name:     interpolation_escape,
+  justfile: r#"
+    a := "${{{{ a }}"
+    b := 'b'
+
+    wut:
+      echo '${{{{ github.sha }} {{{{{{b}}' '{{a}}'
+  "#,
+  stdout:   "${{ github.sha }} {{b ${{ a }}\n",
+  stderr:   "echo '${{ github.sha }} {{b' '${{ a }}'\n",
 }
 
 integration_test! {
-  name:     required_after_variadic,
-  justfile: "bar:\nhello baz +arg bar:",
-  stdout:   "",
-  stderr:   "error: Parameter `bar` follows variadic parameter
-  |
-2 | hello baz +arg bar:
-  |                ^^^
-",
-  status:   EXIT_FAILURE,
+  name:     no_interpolation,
+  justfile: r#"
+    [no-interpolation]
+    wut:
+      echo '{{ name }} {{{{ x }}'
+
+    other:
+      echo {{ "{{" }}
+  "#,
+  stdout:   "{{ name }} {{{{ x }}\n",
+  stderr:   "echo '{{ name }} {{{{ x }}'\n",
 }
 
 integration_test! {
-  name:     use_string_default,
+  name:     no_interpolation_shebang,
   justfile: r#"
-bar:
-hello baz arg="XYZ\t\"	":
-  echo '{{baz}}...{{arg}}'
-"#,
-  args:     ("hello", "ABC"),
-  stdout:   "ABC...XYZ\t\"\t\n",
-  stderr:   "echo 'ABC...XYZ\t\"\t'\n",
+    [no-interpolation]
+    wut:
+      #!/usr/bin/env bash
+      echo '{{ name }}'
+  "#,
+  stdout:   "{{ name }}\n",
 }
 
 integration_test! {
-  name:     use_raw_string_default,
+  name:     export_path_prepend,
   justfile: r#"
-bar:
-hello baz arg='XYZ"	':
-  printf '{{baz}}...{{arg}}'
+[export-path: prepend "PATH"]
+bin := "/just-test-bin"
+
+wut:
+  @echo ${PATH%%:*}
 "#,
-  args:     ("hello", "ABC"),
-  stdout:   "ABC...XYZ\"\t",
-  stderr:   "printf 'ABC...XYZ\"\t'\n",
+  stdout:   "/just-test-bin\n",
 }
 
 integration_test! {
-  name:     supply_use_default,
+  name:     export_path_append,
   justfile: r#"
-hello a b='B' c='C':
-  echo {{a}} {{b}} {{c}}
+[export-path: append "PATH"]
+bin := "/just-test-bin"
+
+wut:
+  @echo ${PATH##*:}
 "#,
-  args:     ("hello", "0", "1"),
-  stdout:   "0 1 C\n",
-  stderr:   "echo 0 1 C\n",
+  stdout:   "/just-test-bin\n",
 }
 
 integration_test! {
-  name:     supply_defaults,
+  name:     export_override,
   justfile: r#"
-hello a b='B' c='C':
-  echo {{a}} {{b}} {{c}}
+export FOO := "a"
+baz := "c"
+export BAR := "b"
+export ABC := FOO + "-" + BAR + "-" + baz
+
+wut:
+  echo $FOO $BAR $ABC
 "#,
-  args:     ("hello", "0", "1", "2"),
-  stdout:   "0 1 2\n",
-  stderr:   "echo 0 1 2\n",
+  args:     ("--set", "BAR", "bye", "FOO=hello"),
+  stdout:   "hello bye hello-bye-c\n",
+  stderr:   "echo $FOO $BAR $ABC\n",
 }
 
 integration_test! {
-  name:     list,
+  name:     export_shebang,
   justfile: r#"
+export FOO := "a"
+baz := "c"
+export BAR := "b"
+export ABC := FOO + BAR + baz
 
-# this does a thing
-hello a b='B	' c='C':
-  echo {{a}} {{b}} {{c}}
-
-# this comment will be ignored
-
-a Z="\t z":
-
-# this recipe will not appear
-_private-recipe:
+wut:
+  #!/bin/sh
+  echo $FOO $BAR $ABC
 "#,
-  args:     ("--list"),
-  stdout:   r#"
-    Available recipes:
-        a Z="\t z"
-        hello a b='B	' c='C' # this does a thing
-  "#,
+  stdout:   "a b abc\n",
 }
 
 integration_test! {
-  name:     list_alignment,
+  name:     export_recipe_backtick,
   justfile: r#"
+export EXPORTED_VARIABLE := "A-IS-A"
 
-# this does a thing
-hello a b='B	' c='C':
-  echo {{a}} {{b}} {{c}}
+recipe:
+  echo {{`echo recipe $EXPORTED_VARIABLE`}}
+"#,
+  stdout:   "recipe A-IS-A\n",
+  stderr:   "echo recipe A-IS-A\n",
+}
 
-# something else
-a Z="\t z":
+integration_test! {
+  name:     raw_string,
+  justfile: r#"
+export EXPORTED_VARIABLE := '\z'
 
-# this recipe will not appear
-_private-recipe:
+recipe:
+  printf "$EXPORTED_VARIABLE"
 "#,
-  args:     ("--list"),
-  stdout:   r#"
-    Available recipes:
-        a Z="\t z"          # something else
-        hello a b='B	' c='C' # this does a thing
-  "#,
+  stdout:   "\\z",
+  stderr:   "printf \"$EXPORTED_VARIABLE\"\n",
 }
 
 integration_test! {
-  name:     list_alignment_long,
+  name:     line_error_spacing,
   justfile: r#"
 
-# this does a thing
-hello a b='B	' c='C':
-  echo {{a}} {{b}} {{c}}
 
-# this does another thing
-x a b='B	' c='C':
-  echo {{a}} {{b}} {{c}}
 
-# something else
-this-recipe-is-very-very-very-important Z="\t z":
 
-# this recipe will not appear
-_private-recipe:
-"#,
-  args:     ("--list"),
-  stdout:   r#"
-    Available recipes:
-        hello a b='B	' c='C' # this does a thing
-        this-recipe-is-very-very-very-important Z="\t z" # something else
-        x a b='B	' c='C'     # this does another thing
-  "#,
-}
 
-integration_test! {
-  name:     show_suggestion,
-  justfile: r#"
-hello a b='B	' c='C':
-  echo {{a}} {{b}} {{c}}
 
-a Z="\t z":
+
+
+???
 "#,
-  args:     ("--show", "hell"),
   stdout:   "",
-  stderr:   "Justfile does not contain recipe `hell`.\nDid you mean `hello`?\n",
-  status:   EXIT_FAILURE,
+  stderr:   "error: Unknown start of token:
+   |
+10 | ???
+   | ^
+",
+  status:   EXIT_COMPILE_ERROR,
 }
 
 integration_test! {
-  name:     show_no_suggestion,
+  name:     quiet_flag_no_stdout,
   justfile: r#"
-helloooooo a b='B	' c='C':
-  echo {{a}} {{b}} {{c}}
-
-a Z="\t z":
+default:
+  @echo hello
 "#,
-  args:     ("--show", "hell"),
+  args:     ("--quiet"),
   stdout:   "",
-  stderr:   "Justfile does not contain recipe `hell`.\n",
-  status:   EXIT_FAILURE,
 }
 
 integration_test! {
-  name:     run_suggestion,
+  name:     quiet_flag_no_stderr,
   justfile: r#"
-hello a b='B	' c='C':
-  echo {{a}} {{b}} {{c}}
-
-a Z="\t z":
+default:
+  @echo hello 1>&2
 "#,
-  args:     ("hell"),
+  args:     ("--quiet"),
   stdout:   "",
-  stderr:   "error: Justfile does not contain recipe `hell`.\nDid you mean `hello`?\n",
-  status:   EXIT_FAILURE,
 }
 
 integration_test! {
-  name:     line_continuation_with_space,
+  name:     quiet_flag_no_command_echoing,
   justfile: r#"
-foo:
-  echo a\
-         b  \
-             c
+default:
+  exit
 "#,
-  stdout:   "a b c\n",
-  stderr:   "echo a       b             c\n",
+  args:     ("--quiet"),
+  stdout:   "",
 }
 
 integration_test! {
-  name:     line_continuation_with_quoted_space,
+  name:     quiet_flag_no_error_messages,
   justfile: r#"
-foo:
-  echo 'a\
-         b  \
-             c'
+default:
+  exit 100
 "#,
-  stdout:   "a       b             c\n",
-  stderr:   "echo 'a       b             c'\n",
+  args:     ("--quiet"),
+  stdout:   "",
+  status:   100,
 }
 
 integration_test! {
-  name:     line_continuation_no_space,
+  name:     quiet_flag_no_assignment_backtick_stderr,
   justfile: r#"
-foo:
-  echo a\
-  b\
-  c
+a := `echo hello 1>&2`
+default:
+  exit 100
 "#,
-  stdout:   "abc\n",
-  stderr:   "echo abc\n",
+  args:     ("--quiet"),
+  stdout:   "",
+  status:   100,
 }
 
 integration_test! {
-  name:     test_os_arch_functions_in_interpolation,
+  name:     quiet_flag_no_interpolation_backtick_stderr,
   justfile: r#"
-foo:
-  echo {{arch()}} {{os()}} {{os_family()}}
+default:
+  echo `echo hello 1>&2`
+  exit 100
 "#,
-  stdout:   format!("{} {} {}\n", target::arch(), target::os(), target::os_family()).as_str(),
-  stderr:   format!("echo {} {} {}\n", target::arch(), target::os(), target::os_family()).as_str(),
+  args:     ("--quiet"),
+  stdout:   "",
+  status:   100,
 }
 
 integration_test! {
-  name:     test_os_arch_functions_in_expression,
-  justfile: r#"
-a := arch()
-o := os()
-f := os_family()
-
-foo:
-  echo {{a}} {{o}} {{f}}
-"#,
-  stdout:   format!("{} {} {}\n", target::arch(), target::os(), target::os_family()).as_str(),
-  stderr:   format!("echo {} {} {}\n", target::arch(), target::os(), target::os_family()).as_str(),
+  name:     argument_single,
+  justfile: "
+foo A:
+  echo {{A}}
+    ",
+  args:     ("foo", "ARGUMENT"),
+  stdout:   "ARGUMENT\n",
+  stderr:   "echo ARGUMENT\n",
 }
 
-#[cfg(not(windows))]
 integration_test! {
-  name:     env_var_functions,
-  justfile: r#"
-p := env_var('USER')
-b := env_var_or_default('ZADDY', 'HTAP')
-x := env_var_or_default('XYZ', 'ABC')
+  name:     argument_multiple,
+  justfile: "
+foo A B:
+  echo A:{{A}} B:{{B}}
+    ",
+  args:     ("foo", "ONE", "TWO"),
+  stdout:   "A:ONE B:TWO\n",
+  stderr:   "echo A:ONE B:TWO\n",
+}
 
-foo:
-  /bin/echo '{{p}}' '{{b}}' '{{x}}'
-"#,
-  stdout:   format!("{} HTAP ABC\n", env::var("USER").unwrap()).as_str(),
-  stderr:   format!("/bin/echo '{}' 'HTAP' 'ABC'\n", env::var("USER").unwrap()).as_str(),
+integration_test! {
+  name:     argument_mismatch_more,
+  justfile: "
+foo A B:
+  echo A:{{A}} B:{{B}}
+    ",
+  args:     ("foo", "ONE", "TWO", "THREE"),
+  stdout:   "",
+  stderr:   "error: Justfile does not contain recipe `THREE`.\n",
+  status:   EXIT_UNKNOWN_RECIPE,
 }
 
-#[cfg(windows)]
 integration_test! {
-  name:     env_var_functions,
-  justfile: r#"
-p := env_var('USERNAME')
-b := env_var_or_default('ZADDY', 'HTAP')
-x := env_var_or_default('XYZ', 'ABC')
+  name:     argument_mismatch_fewer,
+  justfile: "
+foo A B:
+  echo A:{{A}} B:{{B}}
+    ",
+  args:     ("foo", "ONE"),
+  stdout:   "",
+  stderr:   "error: Recipe `foo` got 1 argument but takes 2\nusage:\n    just foo A B\n",
+  status:   EXIT_USAGE,
+}
 
-foo:
-  /bin/echo '{{p}}' '{{b}}' '{{x}}'
-"#,
-  stdout:   format!("{} HTAP ABC\n", env::var("USERNAME").unwrap()).as_str(),
-  stderr:   format!("/bin/echo '{}' 'HTAP' 'ABC'\n", env::var("USERNAME").unwrap()).as_str(),
+integration_test! {
+  name:     argument_mismatch_more_with_default,
+  justfile: "
+foo A B='B':
+  echo A:{{A}} B:{{B}}
+    ",
+  args:     ("foo", "ONE", "TWO", "THREE"),
+  stdout:   "",
+  stderr:   "error: Justfile does not contain recipe `THREE`.\n",
+  status:   EXIT_UNKNOWN_RECIPE,
 }
 
 integration_test! {
-  name:     env_var_failure,
-  justfile: "a:\n  echo {{env_var('ZADDY')}}",
-  args:     ("a"),
+  name:     argument_mismatch_fewer_with_default,
+  justfile: "
+foo A B C='C':
+  echo A:{{A}} B:{{B}} C:{{C}}
+    ",
+  args:     ("foo", "bar"),
   stdout:   "",
-  stderr:   "error: Call to function `env_var` failed: environment variable `ZADDY` not present
-  |
-2 |   echo {{env_var('ZADDY')}}
-  |          ^^^^^^^
-",
-  status:   EXIT_FAILURE,
+  stderr:   "error: Recipe `foo` got 1 argument but takes at least 2\nusage:\n    just foo A B C='C'\n",
+  status:   EXIT_USAGE,
 }
 
 integration_test! {
-  name:     quiet_recipe,
-  justfile: r#"
-@quiet:
-  # a
-  # b
-  @echo c
-"#,
-  stdout:   "c\n",
-  stderr:   "echo c\n",
+  name:     unknown_recipe,
+  justfile: "hello:",
+  args:     ("foo"),
+  stdout:   "",
+  stderr:   "error: Justfile does not contain recipe `foo`.\n",
+  status:   EXIT_UNKNOWN_RECIPE,
 }
 
 integration_test! {
-  name:     quiet_shebang_recipe,
-  justfile: r#"
-@quiet:
-  #!/bin/sh
-  echo hello
-"#,
-  stdout:   "hello\n",
-  stderr:   "#!/bin/sh\necho hello\n",
+  name:     unknown_recipes,
+  justfile: "hello:",
+  args:     ("foo", "bar"),
+  stdout:   "",
+  stderr:   "error: Justfile does not contain recipes `foo` or `bar`.\n",
+  status:   EXIT_UNKNOWN_RECIPE,
 }
 
 integration_test! {
-  name:     shebang_line_numbers,
-  justfile: r#"
-quiet:
-  #!/usr/bin/env cat
-
-  a
-
-  b
-
-
-  c
-
-
-"#,
-  stdout:   "#!/usr/bin/env cat
-
-
-
-a
-
-b
-
-
-c
-",
-}
-
-integration_test! {
-  name:     complex_dependencies,
-  justfile: r#"
-a: b
-b:
-c: b a
-"#,
-  args:     ("b"),
+  name:     color_always,
+  justfile: "b := a\na := `exit 100`\nbar:\n echo '{{`exit 200`}}'",
+  args:     ("--color", "always"),
   stdout:   "",
+  stderr:   "\u{1b}[1;31merror:\u{1b}[0m \u{1b}[1mBacktick failed with exit code 100
+\u{1b}[0m  |\n2 | a := `exit 100`\n  |      \u{1b}[1;31m^^^^^^^^^^\u{1b}[0m\n",
+  status:   100,
 }
 
 integration_test! {
-  name:     parameter_shadows_variable,
-  justfile: "FOO := 'hello'\na FOO:",
-  args:     ("a"),
+  name:     color_never,
+  justfile: "b := a\na := `exit 100`\nbar:\n echo '{{`exit 200`}}'",
+  args:     ("--color", "never"),
   stdout:   "",
-  stderr:   "error: Parameter `FOO` shadows variable of the same name
+  stderr:   "error: Backtick failed with exit code 100
   |
-2 | a FOO:
-  |   ^^^
+2 | a := `exit 100`
+  |      ^^^^^^^^^^
 ",
-  status:   EXIT_FAILURE,
+  status:   100,
 }
 
 integration_test! {
-  name:     unknown_function_in_assignment,
-  justfile: r#"foo := foo() + "hello"
-bar:"#,
-  args:     ("bar"),
+  name:     color_auto,
+  justfile: "b := a\na := `exit 100`\nbar:\n echo '{{`exit 200`}}'",
+  args:     ("--color", "auto"),
   stdout:   "",
-  stderr:   r#"error: Call to unknown function `foo`
+  stderr:   "error: Backtick failed with exit code 100
   |
-1 | foo := foo() + "hello"
-  |        ^^^
-"#,
-  status:   EXIT_FAILURE,
+2 | a := `exit 100`
+  |      ^^^^^^^^^^
+",
+  status:   100,
 }
 
 integration_test! {
-  name:     dependency_takes_arguments,
-  justfile: "b: a\na FOO:",
-  args:     ("b"),
+  name:     colors_no_context,
+  justfile: "
+recipe:
+  @exit 100",
+  args:     ("--color=always"),
   stdout:   "",
-  stderr:   "error: Recipe `b` depends on `a` which requires arguments. \
-             Dependencies may not require arguments
-  |
-1 | b: a
-  |    ^
-",
-  status:   EXIT_FAILURE,
+  stderr:   "\u{1b}[1;31merror:\u{1b}[0m \u{1b}[1m\
+Recipe `recipe` failed on line 3 with exit code 100\u{1b}[0m\ncommand:\n    exit 100\n",
+  status:   100,
 }
 
 integration_test! {
-  name:     duplicate_parameter,
-  justfile: "a foo foo:",
-  args:     ("a"),
-  stdout:   "",
-  stderr:   "error: Recipe `a` has duplicate parameter `foo`
-  |
-1 | a foo foo:
-  |       ^^^
+  name:     dump,
+  justfile: r#"
+# this recipe does something
+recipe a b +d:
+ @exit 100"#,
+  args:     ("--dump"),
+  stdout:   "# this recipe does something
+recipe a b +d:
+    @exit 100
 ",
-  status:   EXIT_FAILURE,
 }
 
 integration_test! {
-  name:     duplicate_dependency,
-  justfile: "b:\na: b b",
-  args:     ("a"),
-  stdout:   "",
-  stderr:   "error: Recipe `a` has duplicate dependency `b`
-  |
-2 | a: b b
-  |      ^
-",
-  status:   EXIT_FAILURE,
+  name:     dump_order_alphabetical,
+  justfile: "
+    set positional-arguments
+
+    z := 'z'
+
+    build: test
+
+    alias b := build
+
+    a := 'a'
+
+    test:
+  ",
+  args:     ("--dump", "--order", "alphabetical"),
+  stdout:   "
+    set positional-arguments := true
+
+    a := 'a'
+
+    z := 'z'
+
+    alias b := build
+
+    build: test
+
+    test:
+  ",
 }
 
 integration_test! {
-  name:     duplicate_recipe,
-  justfile: "b:\nb:",
-  args:     ("b"),
-  stdout:   "",
-  stderr:   "error: Recipe `b` first defined on line 1 is redefined on line 2
-  |
-2 | b:
-  | ^
-",
-  status:   EXIT_FAILURE,
+  name:     dump_order_source,
+  justfile: "
+    set positional-arguments
+
+    z := 'z'
+
+    build: test
+
+    alias b := build
+
+    a := 'a'
+
+    test:
+  ",
+  args:     ("--dump", "--order", "source"),
+  stdout:   "
+    set positional-arguments := true
+
+    z := 'z'
+
+    build: test
+
+    alias b := build
+
+    a := 'a'
+
+    test:
+  ",
 }
 
 integration_test! {
-  name:     duplicate_variable,
-  justfile: "a := 'hello'\na := 'hello'\nfoo:",
-  args:     ("foo"),
+  name:     mixed_whitespace,
+  justfile: "bar:\n\t echo hello",
   stdout:   "",
-  stderr:   "error: Variable `a` has multiple definitions
+  stderr:   "error: Found a mix of tabs and spaces in leading whitespace: `␉␠`
+Leading whitespace may consist of tabs or spaces, but not both
   |
-2 | a := 'hello'
-  | ^
+2 |      echo hello
+  | ^^^^^
 ",
-  status:   EXIT_FAILURE,
+  status:   EXIT_COMPILE_ERROR,
 }
 
 integration_test! {
-  name:     unexpected_token_in_dependency_position,
-  justfile: "foo: 'bar'",
-  args:     ("foo"),
+  name:     extra_leading_whitespace,
+  justfile: "bar:\n\t\techo hello\n\t\t\techo goodbye",
   stdout:   "",
-  stderr:   "error: Expected name, end of line, or end of file, but found raw string
+  stderr:   "error: Recipe line has extra leading whitespace
   |
-1 | foo: 'bar'
-  |      ^^^^^
+3 |             echo goodbye
+  |         ^^^^^^^^^^^^^^^^
 ",
-  status:   EXIT_FAILURE,
+  status:   EXIT_COMPILE_ERROR,
 }
 
 integration_test! {
-  name:     unexpected_token_after_name,
-  justfile: "foo 'bar'",
-  args:     ("foo"),
+  name:     inconsistent_leading_whitespace,
+  justfile: "bar:\n\t\techo hello\n\t echo goodbye",
   stdout:   "",
-  stderr:   "error: Expected name, '+', ':', or ':=', but found raw string
+  stderr:   "error: Recipe line has inconsistent leading whitespace. \
+            Recipe started with `␉␉` but found line with `␉␠`
   |
-1 | foo 'bar'
-  |     ^^^^^
+3 |      echo goodbye
+  | ^^^^^
 ",
-  status:   EXIT_FAILURE,
+  status:   EXIT_COMPILE_ERROR,
 }
 
 integration_test! {
-  name:     self_dependency,
-  justfile: "a: a",
-  args:     ("a"),
+  name:     required_after_default,
+  justfile: "bar:\nhello baz arg='foo' bar:",
   stdout:   "",
-  stderr:   "error: Recipe `a` depends on itself
+  stderr:   "error: Non-default parameter `bar` follows default parameter
   |
-1 | a: a
-  |    ^
+2 | hello baz arg='foo' bar:
+  |                     ^^^
 ",
-  status:   EXIT_FAILURE,
+  status:   EXIT_COMPILE_ERROR,
 }
 
 integration_test! {
-  name:     long_circular_recipe_dependency,
-  justfile: "a: b\nb: c\nc: d\nd: a",
-  args:     ("a"),
+  name:     required_after_variadic,
+  justfile: "bar:\nhello baz +arg bar:",
   stdout:   "",
-  stderr:   "error: Recipe `d` has circular dependency `a -> b -> c -> d -> a`
+  stderr:   "error: Parameter `bar` follows variadic parameter
   |
-4 | d: a
-  |    ^
+2 | hello baz +arg bar:
+  |                ^^^
 ",
-  status:   EXIT_FAILURE,
+  status:   EXIT_COMPILE_ERROR,
 }
 
 integration_test! {
-  name:     variable_self_dependency,
-  justfile: "z := z\na:",
-  args:     ("a"),
-  stdout:   "",
-  stderr:   "error: Variable `z` is defined in terms of itself
-  |
-1 | z := z
-  | ^
-",
-  status:   EXIT_FAILURE,
+  name:     use_string_default,
+  justfile: r#"
+bar:
+hello baz arg="XYZ\t\"	":
+  echo '{{baz}}...{{arg}}'
+"#,
+  args:     ("hello", "ABC"),
+  stdout:   "ABC...XYZ\t\"\t\n",
+  stderr:   "echo 'ABC...XYZ\t\"\t'\n",
 }
 
 integration_test! {
-  name:     variable_circular_dependency,
-  justfile: "x := y\ny := z\nz := x\na:",
-  args:     ("a"),
-  stdout:   "",
-  stderr:   "error: Variable `x` depends on its own value: `x -> y -> z -> x`
-  |
-1 | x := y
-  | ^
-",
-  status:   EXIT_FAILURE,
+  name:     use_raw_string_default,
+  justfile: r#"
+bar:
+hello baz arg='XYZ"	':
+  printf '{{baz}}...{{arg}}'
+"#,
+  args:     ("hello", "ABC"),
+  stdout:   "ABC...XYZ\"\t",
+  stderr:   "printf 'ABC...XYZ\"\t'\n",
 }
 
 integration_test! {
-  name:     invalid_escape_sequence,
-  justfile: r#"x := "\q"
-a:"#,
-  args:     ("a"),
-  stdout:   "",
-  stderr:   "error: `\\q` is not a valid escape sequence
-  |
-1 | x := \"\\q\"
-  |      ^^^^
-",
-  status:   EXIT_FAILURE,
+  name:     supply_use_default,
+  justfile: r#"
+hello a b='B' c='C':
+  echo {{a}} {{b}} {{c}}
+"#,
+  args:     ("hello", "0", "1"),
+  stdout:   "0 1 C\n",
+  stderr:   "echo 0 1 C\n",
 }
 
 integration_test! {
-  name:     multiline_raw_string,
+  name:     supply_defaults,
+  justfile: r#"
+hello a b='B' c='C':
+  echo {{a}} {{b}} {{c}}
+"#,
+  args:     ("hello", "0", "1", "2"),
+  stdout:   "0 1 2\n",
+  stderr:   "echo 0 1 2\n",
+}
+
+integration_test! {
+  name:     list,
+  justfile: r#"
+
+# this does a thing
+hello a b='B	' c='C':
+  echo {{a}} {{b}} {{c}}
+
+# this comment will be ignored
+
+a Z="\t z":
+
+# this recipe will not appear
+_private-recipe:
+"#,
+  args:     ("--list"),
+  stdout:   r#"
+    Available recipes:
+        a Z="\t z"
+        hello a b='B	' c='C' # this does a thing
+  "#,
+}
+
+integration_test! {
+  name:     list_groups,
   justfile: "
-string := 'hello
-whatever'
+    # build everything
+    [group: 'dev']
+    build:
 
-a:
-  echo '{{string}}'
-",
-  args:     ("a"),
-  stdout:   "hello
-whatever
-",
-  stderr:   "echo 'hello
-whatever'
-",
+    [group: 'dev']
+    [group: 'ci']
+    test:
+
+    alias t := test
+
+    help:
+
+    [group: 'secret']
+    _hidden:
+  ",
+  args:     ("--list"),
+  stdout:   "
+    Available recipes:
+        help
+        [ci]
+        test
+        t     # alias for `test`
+        [dev]
+        build # build everything
+        test
+        t     # alias for `test`
+  ",
 }
 
 integration_test! {
-  name:     error_line_after_multiline_raw_string,
+  name:     list_group,
   justfile: "
-string := 'hello
+    [group: 'dev']
+    build:
 
-whatever' + 'yo'
+    [group: 'ci']
+    test:
 
-a:
-  echo '{{foo}}'
-",
-  args:     ("a"),
+    help:
+  ",
+  args:     ("--list", "--group", "ci"),
+  stdout:   "
+    Available recipes:
+        test
+  ",
+}
+
+integration_test! {
+  name:     groups,
+  justfile: "
+    [group: 'dev']
+    build:
+
+    [group: 'dev']
+    [group: 'ci']
+    test:
+
+    [private]
+    [group: 'secret']
+    hidden:
+  ",
+  args:     ("--groups"),
+  stdout:   "
+    ci
+    dev
+  ",
+}
+
+integration_test! {
+  name:     list_alignment,
+  justfile: r#"
+
+# this does a thing
+hello a b='B	' c='C':
+  echo {{a}} {{b}} {{c}}
+
+# something else
+a Z="\t z":
+
+# this recipe will not appear
+_private-recipe:
+"#,
+  args:     ("--list"),
+  stdout:   r#"
+    Available recipes:
+        a Z="\t z"          # something else
+        hello a b='B	' c='C' # this does a thing
+  "#,
+}
+
+integration_test! {
+  name:     list_alignment_long,
+  justfile: r#"
+
+# this does a thing
+hello a b='B	' c='C':
+  echo {{a}} {{b}} {{c}}
+
+# this does another thing
+x a b='B	' c='C':
+  echo {{a}} {{b}} {{c}}
+
+# something else
+this-recipe-is-very-very-very-important Z="\t z":
+
+# this recipe will not appear
+_private-recipe:
+"#,
+  args:     ("--list"),
+  stdout:   r#"
+    Available recipes:
+        hello a b='B	' c='C' # this does a thing
+        this-recipe-is-very-very-very-important Z="\t z" # something else
+        x a b='B	' c='C'     # this does another thing
+  "#,
+}
+
+integration_test! {
+  name:     show_suggestion,
+  justfile: r#"
+hello a b='B	' c='C':
+  echo {{a}} {{b}} {{c}}
+
+a Z="\t z":
+"#,
+  args:     ("--show", "hell"),
   stdout:   "",
-  stderr:   "error: Variable `foo` not defined
-  |
-7 |   echo '{{foo}}'
-  |           ^^^
-",
+  stderr:   "Justfile does not contain recipe `hell`.\nDid you mean `hello`?\n",
+  status:   EXIT_FAILURE,
+}
+
+integration_test! {
+  name:     show_no_suggestion,
+  justfile: r#"
+helloooooo a b='B	' c='C':
+  echo {{a}} {{b}} {{c}}
+
+a Z="\t z":
+"#,
+  args:     ("--show", "hell"),
+  stdout:   "",
+  stderr:   "Justfile does not contain recipe `hell`.\n",
   status:   EXIT_FAILURE,
 }
 
 integration_test! {
-  name:     error_column_after_multiline_raw_string,
+  name:     run_suggestion,
+  justfile: r#"
+hello a b='B	' c='C':
+  echo {{a}} {{b}} {{c}}
+
+a Z="\t z":
+"#,
+  args:     ("hell"),
+  stdout:   "",
+  stderr:   "error: Justfile does not contain recipe `hell`.\nDid you mean `hello`?\n",
+  status:   EXIT_UNKNOWN_RECIPE,
+}
+
+integration_test! {
+  name:     line_continuation_with_space,
+  justfile: r#"
+foo:
+  echo a\
+         b  \
+             c
+"#,
+  stdout:   "a b c\n",
+  stderr:   "echo a       b             c\n",
+}
+
+integration_test! {
+  name:     line_continuation_with_quoted_space,
+  justfile: r#"
+foo:
+  echo 'a\
+         b  \
+             c'
+"#,
+  stdout:   "a       b             c\n",
+  stderr:   "echo 'a       b             c'\n",
+}
+
+integration_test! {
+  name:     line_continuation_no_space,
+  justfile: r#"
+foo:
+  echo a\
+  b\
+  c
+"#,
+  stdout:   "abc\n",
+  stderr:   "echo abc\n",
+}
+
+integration_test! {
+  name:     heredoc,
+  justfile: r#"
+foo:
+  cat <<EOF
+  a
+    b {{"c"}}
+d
+  EOF
+  echo e
+"#,
+  stdout:   "a\n  b c\nd\ne\n",
+  stderr:   "cat <<EOF\na\n  b c\nd\nEOF\necho e\n",
+}
+
+integration_test! {
+  name:     heredoc_strip_tabs,
+  justfile: "
+foo:
+  cat <<-'END'
+  \ta
+  \tEND
+",
+  stdout:   "a\n",
+  stderr:   "cat <<-'END'\n\ta\n\tEND\n",
+}
+
+integration_test! {
+  name:     test_os_arch_functions_in_interpolation,
+  justfile: r#"
+foo:
+  echo {{arch()}} {{os()}} {{os_family()}}
+"#,
+  stdout:   format!("{} {} {}\n", env::consts::ARCH, env::consts::OS, env::consts::FAMILY).as_str(),
+  stderr:   format!("echo {} {} {}\n", env::consts::ARCH, env::consts::OS, env::consts::FAMILY).as_str(),
+}
+
+integration_test! {
+  name:     test_os_arch_functions_in_expression,
+  justfile: r#"
+a := arch()
+o := os()
+f := os_family()
+
+foo:
+  echo {{a}} {{o}} {{f}}
+"#,
+  stdout:   format!("{} {} {}\n", env::consts::ARCH, env::consts::OS, env::consts::FAMILY).as_str(),
+  stderr:   format!("echo {} {} {}\n", env::consts::ARCH, env::consts::OS, env::consts::FAMILY).as_str(),
+}
+
+#[cfg(not(windows))]
+integration_test! {
+  name:     env_var_functions,
+  justfile: r#"
+p := env_var('USER')
+b := env_var_or_default('ZADDY', 'HTAP')
+x := env_var_or_default('XYZ', 'ABC')
+
+foo:
+  /bin/echo '{{p}}' '{{b}}' '{{x}}'
+"#,
+  stdout:   format!("{} HTAP ABC\n", env::var("USER").unwrap()).as_str(),
+  stderr:   format!("/bin/echo '{}' 'HTAP' 'ABC'\n", env::var("USER").unwrap()).as_str(),
+}
+
+#[cfg(windows)]
+integration_test! {
+  name:     env_var_functions,
+  justfile: r#"
+p := env_var('USERNAME')
+b := env_var_or_default('ZADDY', 'HTAP')
+x := env_var_or_default('XYZ', 'ABC')
+
+foo:
+  /bin/echo '{{p}}' '{{b}}' '{{x}}'
+"#,
+  stdout:   format!("{} HTAP ABC\n", env::var("USERNAME").unwrap()).as_str(),
+  stderr:   format!("/bin/echo '{}' 'HTAP' 'ABC'\n", env::var("USERNAME").unwrap()).as_str(),
+}
+
+integration_test! {
+  name:     env_var_failure,
+  justfile: "a:\n  echo {{env_var('ZADDY')}}",
+  args:     ("a"),
+  stdout:   "",
+  stderr:   "error: Call to function `env_var` failed: environment variable `ZADDY` not present
+  |
+2 |   echo {{env_var('ZADDY')}}
+  |          ^^^^^^^
+",
+  status:   EXIT_FAILURE,
+}
+
+integration_test! {
+  name:     quiet_recipe,
+  justfile: r#"
+@quiet:
+  # a
+  # b
+  @echo c
+"#,
+  stdout:   "c\n",
+  stderr:   "echo c\n",
+}
+
+integration_test! {
+  name:     quiet_shebang_recipe,
+  justfile: r#"
+@quiet:
+  #!/bin/sh
+  echo hello
+"#,
+  stdout:   "hello\n",
+  stderr:   "#!/bin/sh\necho hello\n",
+}
+
+integration_test! {
+  name:     shebang_line_numbers,
+  justfile: r#"
+quiet:
+  #!/usr/bin/env cat
+
+  a
+
+  b
+
+
+  c
+
+
+"#,
+  stdout:   "#!/usr/bin/env cat
+
+
+
+a
+
+b
+
+
+c
+",
+}
+
+integration_test! {
+  name:     complex_dependencies,
+  justfile: r#"
+a: b
+b:
+c: b a
+"#,
+  args:     ("b"),
+  stdout:   "",
+}
+
+integration_test! {
+  name:     complete_recipes,
+  justfile: "
+    version := '1.0'
+
+    build:
+    bench:
+    test:
+    _helper:
+
+    alias b := build
+  ",
+  args:     ("--complete", "1", "--", "just", "b"),
+  stdout:   "
+    bench
+    build
+    b
+  ",
+}
+
+integration_test! {
+  name:     complete_overrides,
+  justfile: "
+    version := '1.0'
+
+    build:
+  ",
+  args:     ("--complete", "1", "--", "just"),
+  stdout:   "
+    build
+    version=
+  ",
+}
+
+integration_test! {
+  name:     complete_matrix_values,
+  justfile: "
+    [matrix: os=('linux'|'macos')]
+    build os mode='debug':
+
+    test:
+  ",
+  args:     ("--complete", "3", "--", "just", "build", "linux"),
+  stdout:   "
+    build
+    test
+  ",
+}
+
+integration_test! {
+  name:     complete_parameter_values,
+  justfile: "
+    [matrix: os=('linux'|'macos')]
+    build os:
+  ",
+  args:     ("--complete", "2", "--", "just", "build", "m"),
+  stdout:   "macos\n",
+}
+
+integration_test! {
+  name:     complete_set_variable,
+  justfile: "
+    version := '1.0'
+    target := 'x86'
+
+    build:
+  ",
+  args:     ("--complete", "2", "--", "just", "--set"),
+  stdout:   "
+    target
+    version
+  ",
+}
+
+integration_test! {
+  name:     complete_option_value,
+  justfile: "build:",
+  args:     ("--complete", "2", "--", "just", "--justfile"),
+  stdout:   "",
+}
+
+integration_test! {
+  name:     parameter_shadows_variable,
+  justfile: "FOO := 'hello'\na FOO:",
+  args:     ("a"),
+  stdout:   "",
+  stderr:   "error: Parameter `FOO` shadows variable of the same name
+  |
+2 | a FOO:
+  |   ^^^
+",
+  status:   EXIT_COMPILE_ERROR,
+}
+
+integration_test! {
+  name:     unknown_function_in_assignment,
+  justfile: r#"foo := foo() + "hello"
+bar:"#,
+  args:     ("bar"),
+  stdout:   "",
+  stderr:   r#"error: Call to unknown function `foo`
+  |
+1 | foo := foo() + "hello"
+  |        ^^^
+"#,
+  status:   EXIT_COMPILE_ERROR,
+}
+
+integration_test! {
+  name:     dependency_takes_arguments,
+  justfile: "b: a\na FOO:",
+  args:     ("b"),
+  stdout:   "",
+  stderr:   "error: Recipe `b` depends on `a` which requires arguments. \
+             Dependencies may not require arguments
+  |
+1 | b: a
+  |    ^
+",
+  status:   EXIT_COMPILE_ERROR,
+}
+
+integration_test! {
+  name:     duplicate_parameter,
+  justfile: "a foo foo:",
+  args:     ("a"),
+  stdout:   "",
+  stderr:   "error: Recipe `a` has duplicate parameter `foo`
+  |
+1 | a foo foo:
+  |       ^^^
+",
+  status:   EXIT_COMPILE_ERROR,
+}
+
+integration_test! {
+  name:     duplicate_dependency,
+  justfile: "b:\na: b b",
+  args:     ("a"),
+  stdout:   "",
+  stderr:   "error: Recipe `a` has duplicate dependency `b`
+  |
+2 | a: b b
+  |      ^
+",
+  status:   EXIT_COMPILE_ERROR,
+}
+
+integration_test! {
+  name:     duplicate_recipe,
+  justfile: "b:\nb:",
+  args:     ("b"),
+  stdout:   "",
+  stderr:   "error: Recipe `b` first defined on line 1 is redefined on line 2
+  |
+2 | b:
+  | ^
+",
+  status:   EXIT_COMPILE_ERROR,
+}
+
+integration_test! {
+  name:     duplicate_variable,
+  justfile: "a := 'hello'\na := 'hello'\nfoo:",
+  args:     ("foo"),
+  stdout:   "",
+  stderr:   "error: Variable `a` has multiple definitions
+  |
+2 | a := 'hello'
+  | ^
+",
+  status:   EXIT_COMPILE_ERROR,
+}
+
+integration_test! {
+  name:     unexpected_token_in_dependency_position,
+  justfile: "foo: 'bar'",
+  args:     ("foo"),
+  stdout:   "",
+  stderr:   "error: Expected '&&', name, '[', path, end of line, or end of file, but found raw string
+  |
+1 | foo: 'bar'
+  |      ^^^^^
+",
+  status:   EXIT_COMPILE_ERROR,
+}
+
+integration_test! {
+  name:     unexpected_token_after_name,
+  justfile: "foo 'bar'",
+  args:     ("foo"),
+  stdout:   "",
+  stderr:   "error: Expected name, '+', '*', '$', ':', or ':=', but found raw string
+  |
+1 | foo 'bar'
+  |     ^^^^^
+",
+  status:   EXIT_COMPILE_ERROR,
+}
+
+integration_test! {
+  name:     self_dependency,
+  justfile: "a: a",
+  args:     ("a"),
+  stdout:   "",
+  stderr:   "error: Recipe `a` depends on itself
+  |
+1 | a: a
+  |    ^
+",
+  status:   EXIT_COMPILE_ERROR,
+}
+
+integration_test! {
+  name:     long_circular_recipe_dependency,
+  justfile: "a: b\nb: c\nc: d\nd: a",
+  args:     ("a"),
+  stdout:   "",
+  stderr:   "error: Recipe `d` has circular dependency `a -> b -> c -> d -> a`
+  |
+4 | d: a
+  |    ^
+",
+  status:   EXIT_COMPILE_ERROR,
+}
+
+integration_test! {
+  name:     variable_self_dependency,
+  justfile: "z := z\na:",
+  args:     ("a"),
+  stdout:   "",
+  stderr:   "error: Variable `z` is defined in terms of itself
+  |
+1 | z := z
+  | ^
+",
+  status:   EXIT_COMPILE_ERROR,
+}
+
+integration_test! {
+  name:     variable_circular_dependency,
+  justfile: "x := y\ny := z\nz := x\na:",
+  args:     ("a"),
+  stdout:   "",
+  stderr:   "error: Variable `x` depends on its own value: `x -> y -> z -> x`
+  |
+1 | x := y
+  | ^
+",
+  status:   EXIT_COMPILE_ERROR,
+}
+
+integration_test! {
+  name:     invalid_escape_sequence,
+  justfile: r#"x := "\q"
+a:"#,
+  args:     ("a"),
+  stdout:   "",
+  stderr:   "error: `\\q` is not a valid escape sequence
+  |
+1 | x := \"\\q\"
+  |      ^^^^
+",
+  status:   EXIT_COMPILE_ERROR,
+}
+
+integration_test! {
+  name:     multiline_raw_string,
+  justfile: "
+string := 'hello
+whatever'
+
+a:
+  echo '{{string}}'
+",
+  args:     ("a"),
+  stdout:   "hello
+whatever
+",
+  stderr:   "echo 'hello
+whatever'
+",
+}
+
+integration_test! {
+  name:     error_line_after_multiline_raw_string,
+  justfile: "
+string := 'hello
+
+whatever' + 'yo'
+
+a:
+  echo '{{foo}}'
+",
+  args:     ("a"),
+  stdout:   "",
+  stderr:   "error: Variable `foo` not defined
+  |
+7 |   echo '{{foo}}'
+  |           ^^^
+",
+  status:   EXIT_COMPILE_ERROR,
+}
+
+integration_test! {
+  name:     error_column_after_multiline_raw_string,
+  justfile: "
+string := 'hello
+
+whatever' + bar
+
+a:
+  echo '{{string}}'
+",
+  args:     ("a"),
+  stdout:   "",
+  stderr:   "error: Variable `bar` not defined
+  |
+4 | whatever' + bar
+  |             ^^^
+",
+  status:   EXIT_COMPILE_ERROR,
+}
+
+integration_test! {
+  name:     multiline_raw_string_in_interpolation,
+  justfile: r#"
+a:
+  echo '{{"a" + '
+  ' + "b"}}'
+"#,
+  args:     ("a"),
+  stdout:   "
+    a
+      b
+  ",
+  stderr:   "
+    echo 'a
+      b'
+  ",
+}
+
+integration_test! {
+  name:     error_line_after_multiline_raw_string_in_interpolation,
+  justfile: r#"
+a:
+  echo '{{"a" + '
+  ' + "b"}}'
+
+  echo {{b}}
+"#,
+  args:     ("a"),
+  stdout:   "",
+  stderr:   "error: Variable `b` not defined
+  |
+6 |   echo {{b}}
+  |          ^
+",
+  status:   EXIT_COMPILE_ERROR,
+}
+
+integration_test! {
+  name:     unterminated_raw_string,
+  justfile: "
+a b= ':
+",
+  args:     ("a"),
+  stdout:   "",
+  stderr:   "error: Unterminated string
+  |
+2 | a b= ':
+  |      ^
+",
+  status:   EXIT_COMPILE_ERROR,
+}
+
+integration_test! {
+  name:     unterminated_string,
+  justfile: r#"
+a b= ":
+"#,
+  args:     ("a"),
+  stdout:   "",
+  stderr:   r#"error: Unterminated string
+  |
+2 | a b= ":
+  |      ^
+"#,
+  status:   EXIT_COMPILE_ERROR,
+}
+
+integration_test! {
+  name:     variadic_recipe,
+  justfile: "
+a x y +z:
+  echo {{x}} {{y}} {{z}}
+",
+  args:     ("a", "0", "1", "2", "3", " 4 "),
+  stdout:   "0 1 2 3 4\n",
+  stderr:   "echo 0 1 2 3  4 \n",
+}
+
+integration_test! {
+  name:     variadic_ignore_default,
+  justfile: "
+a x y +z='HELLO':
+  echo {{x}} {{y}} {{z}}
+",
+  args:     ("a", "0", "1", "2", "3", " 4 "),
+  stdout:   "0 1 2 3 4\n",
+  stderr:   "echo 0 1 2 3  4 \n",
+}
+
+integration_test! {
+  name:     variadic_use_default,
+  justfile: "
+a x y +z='HELLO':
+  echo {{x}} {{y}} {{z}}
+",
+  args:     ("a", "0", "1"),
+  stdout:   "0 1 HELLO\n",
+  stderr:   "echo 0 1 HELLO\n",
+}
+
+integration_test! {
+  name:     star_variadic_recipe,
+  justfile: "
+a x *y:
+  echo {{x}} {{y}}
+",
+  args:     ("a", "0", "1", "2"),
+  stdout:   "0 1 2\n",
+  stderr:   "echo 0 1 2\n",
+}
+
+integration_test! {
+  name:     star_variadic_none,
+  justfile: "
+a x *y:
+  echo {{x}} [{{y}}]
+",
+  args:     ("a", "0"),
+  stdout:   "0 []\n",
+  stderr:   "echo 0 []\n",
+}
+
+integration_test! {
+  name:     star_variadic_use_default,
+  justfile: "
+a *x='HELLO':
+  echo {{x}}
+",
+  args:     ("a"),
+  stdout:   "HELLO\n",
+  stderr:   "echo HELLO\n",
+}
+
+integration_test! {
+  name:     star_variadic_too_few,
+  justfile: "
+a x *y:
+  echo {{x}} {{y}}
+",
+  args:     ("a"),
+  stdout:   "",
+  stderr:   "error: Recipe `a` got 0 arguments but takes at least 1\nusage:\n    just a x *y\n",
+  status:   EXIT_USAGE,
+}
+
+integration_test! {
+  name:     variadic_too_few,
+  justfile: "
+a x y +z:
+  echo {{x}} {{y}} {{z}}
+",
+  args:     ("a", "0", "1"),
+  stdout:   "",
+  stderr:   "error: Recipe `a` got 2 arguments but takes at least 3\nusage:\n    just a x y +z\n",
+  status:   EXIT_USAGE,
+}
+
+integration_test! {
+  name:     argument_grouping,
+  justfile: "
+FOO A B='blarg':
+  echo foo: {{A}} {{B}}
+
+BAR X:
+  echo bar: {{X}}
+
+BAZ +Z:
+  echo baz: {{Z}}
+",
+  args:     ("BAR", "0", "FOO", "1", "2", "BAZ", "3", "4", "5"),
+  stdout:   "bar: 0\nfoo: 1 2\nbaz: 3 4 5\n",
+  stderr:   "echo bar: 0\necho foo: 1 2\necho baz: 3 4 5\n",
+}
+
+integration_test! {
+  name:     missing_second_dependency,
+  justfile: "
+x:
+
+a: x y
+",
+  stdout:   "",
+  stderr:   "error: Recipe `a` has unknown dependency `y`
+  |
+4 | a: x y
+  |      ^
+",
+  status:   EXIT_COMPILE_ERROR,
+}
+
+integration_test! {
+  name:     list_colors,
+  justfile: "
+# comment
+a B C +D='hello':
+  echo {{B}} {{C}} {{D}}
+",
+  args:     ("--color", "always", "--list"),
+  stdout:   "
+    Available recipes:
+        a \
+    \u{1b}[36mB\u{1b}[0m \u{1b}[36mC\u{1b}[0m \u{1b}[35m+\
+    \u{1b}[0m\u{1b}[36mD\u{1b}[0m=\u{1b}[32m'hello'\u{1b}[0m \
+     \u{1b}[34m#\u{1b}[0m \u{1b}[34mcomment\u{1b}[0m
+  ",
+}
+
+integration_test! {
+  name:     run_colors,
+  justfile: "
+# comment
+a:
+  echo hi
+",
+  args:     ("--color", "always", "--highlight", "--verbose"),
+  stdout:   "hi\n",
+  stderr:   "\u{1b}[1;36m===> Running recipe `a`...\u{1b}[0m\n\u{1b}[1mecho hi\u{1b}[0m\n",
+}
+
+integration_test! {
+  name:     trailing_flags,
+  justfile: "
+echo A B C:
+  echo {{A}} {{B}} {{C}}
+",
+  args:     ("echo", "--some", "--awesome", "--flags"),
+  stdout:   "--some --awesome --flags\n",
+  stderr:   "echo --some --awesome --flags\n",
+}
+
+integration_test! {
+   name:     comment_before_variable,
+   justfile: "
+#
+A:='1'
+echo:
+  echo {{A}}
+ ",
+   args:     ("echo"),
+   stdout:   "1\n",
+   stderr:   "echo 1\n",
+}
+
+integration_test! {
+   name:     dotenv_variable_in_recipe,
+   justfile: "
+set dotenv-load
+echo:
+  echo $DOTENV_KEY
+ ",
+   stdout:   "dotenv-value\n",
+   stderr:   "echo $DOTENV_KEY\n",
+}
+
+integration_test! {
+   name:     dotenv_variable_in_backtick,
+   justfile: "
+set dotenv-load
+X:=`echo $DOTENV_KEY`
+echo:
+  echo {{X}}
+ ",
+   stdout:   "dotenv-value\n",
+   stderr:   "echo dotenv-value\n",
+}
+integration_test! {
+   name:     dotenv_variable_in_function_in_recipe,
+   justfile: "
+set dotenv-load
+echo:
+  echo {{env_var_or_default('DOTENV_KEY', 'foo')}}
+  echo {{env_var('DOTENV_KEY')}}
+ ",
+   stdout:   "dotenv-value\ndotenv-value\n",
+   stderr:   "echo dotenv-value\necho dotenv-value\n",
+}
+
+integration_test! {
+   name:     dotenv_variable_in_function_in_backtick,
+   justfile: "
+set dotenv-load
+X:=env_var_or_default('DOTENV_KEY', 'foo')
+Y:=env_var('DOTENV_KEY')
+echo:
+  echo {{X}}
+  echo {{Y}}
+ ",
+   stdout:   "dotenv-value\ndotenv-value\n",
+   stderr:   "echo dotenv-value\necho dotenv-value\n",
+}
+
+integration_test! {
+   name:     invalid_escape_sequence_message,
+   justfile: r#"
+X := "\'"
+"#,
+   stdout:   "",
+   stderr:   r#"error: `\'` is not a valid escape sequence
+  |
+2 | X := "\'"
+  |      ^^^^
+"#,
+   status:   EXIT_COMPILE_ERROR,
+}
+
+integration_test! {
+   name:     unknown_variable_in_default,
+   justfile: "
+foo x=bar:
+",
+   stdout:   "",
+   stderr:   r#"error: Variable `bar` not defined
+  |
+2 | foo x=bar:
+  |       ^^^
+"#,
+   status:   EXIT_COMPILE_ERROR,
+}
+
+integration_test! {
+   name:     unknown_function_in_default,
+   justfile: "
+foo x=bar():
+",
+   stdout:   "",
+   stderr:   r#"error: Call to unknown function `bar`
+  |
+2 | foo x=bar():
+  |       ^^^
+"#,
+   status:   EXIT_COMPILE_ERROR,
+}
+
+integration_test! {
+   name:     default_string,
+   justfile: "
+foo x='bar':
+  echo {{x}}
+",
+   stdout:   "bar\n",
+   stderr:   "echo bar\n",
+}
+
+integration_test! {
+   name:     default_concatination,
+   justfile: "
+foo x=(`echo foo` + 'bar'):
+  echo {{x}}
+",
+   stdout:   "foobar\n",
+   stderr:   "echo foobar\n",
+}
+
+integration_test! {
+   name:     default_backtick,
+   justfile: "
+foo x=`echo foo`:
+  echo {{x}}
+",
+   stdout:   "foo\n",
+   stderr:   "echo foo\n",
+}
+
+integration_test! {
+   name:     default_variable,
+   justfile: "
+y := 'foo'
+foo x=y:
+  echo {{x}}
+",
+   stdout:   "foo\n",
+   stderr:   "echo foo\n",
+}
+
+integration_test! {
+  name:     test_os_arch_functions_in_default,
+  justfile: r#"
+foo a=arch() o=os() f=os_family():
+  echo {{a}} {{o}} {{f}}
+"#,
+  stdout:   format!("{} {} {}\n", env::consts::ARCH, env::consts::OS, env::consts::FAMILY).as_str(),
+  stderr:   format!("echo {} {} {}\n", env::consts::ARCH, env::consts::OS, env::consts::FAMILY).as_str(),
+}
+
+integration_test! {
+  name:     unterminated_interpolation_eol,
+  justfile: "
+foo:
+  echo {{
+",
+  stderr:   r#"
+    error: Unterminated interpolation
+      |
+    3 |   echo {{
+      |        ^^
+  "#,
+  status:   EXIT_COMPILE_ERROR,
+}
+
+integration_test! {
+  name:     unterminated_interpolation_eof,
+  justfile: "
+foo:
+  echo {{",
+  stderr:   r#"
+    error: Unterminated interpolation
+      |
+    3 |   echo {{
+      |        ^^
+  "#,
+  status:   EXIT_COMPILE_ERROR,
+}
+
+integration_test! {
+  name:     unterminated_backtick,
+  justfile: "
+foo a=\t`echo blaaaaaah:
+  echo {{a}}",
+  stderr:   r#"
+    error: Unterminated backtick
+      |
+    2 | foo a=    `echo blaaaaaah:
+      |           ^
+  "#,
+  status:   EXIT_COMPILE_ERROR,
+}
+
+integration_test! {
+  name:     unknown_start_of_token,
+  justfile: "
+assembly_source_files = $(wildcard src/arch/$(arch)/*.s)
+",
+  stderr:   r#"
+    error: Unknown start of token:
+      |
+    2 | assembly_source_files = $(wildcard src/arch/$(arch)/*.s)
+      |                         ^
+  "#,
+   status:   EXIT_COMPILE_ERROR,
+}
+
+integration_test! {
+  name:     backtick_variable_cat,
+  justfile: "
+stdin := `cat`
+
+default:
+  echo {{stdin}}
+",
+  stdin:    "STDIN",
+  stdout:   "STDIN\n",
+  stderr:   "echo STDIN\n",
+}
+
+integration_test! {
+   name:     backtick_default_cat_stdin,
+   justfile: "
+default stdin = `cat`:
+  echo {{stdin}}
+",
+   stdin:    "STDIN",
+   stdout:   "STDIN\n",
+   stderr:   "echo STDIN\n",
+}
+
+integration_test! {
+  name:     backtick_default_cat_justfile,
+  justfile: "
+default stdin = `cat justfile`:
+  echo '{{stdin}}'
+",
+  stdout:   "
+
+    default stdin = `cat justfile`:
+      echo {{stdin}}
+  ",
+  stderr:   "
+    echo '
+    default stdin = `cat justfile`:
+      echo '{{stdin}}''
+  ",
+}
+
+integration_test! {
+   name:     backtick_variable_read_single,
+   justfile: "
+password := `read PW && echo $PW`
+
+default:
+  echo {{password}}
+",
+   stdin:    "foobar\n",
+   stdout:   "foobar\n",
+   stderr:   "echo foobar\n",
+}
+
+integration_test! {
+   name:     backtick_variable_read_multiple,
+   justfile: "
+a := `read A && echo $A`
+b := `read B && echo $B`
+
+default:
+  echo {{a}}
+  echo {{b}}
+",
+   stdin:    "foo\nbar\n",
+   stdout:   "foo\nbar\n",
+   stderr:   "echo foo\necho bar\n",
+}
+
+integration_test! {
+   name:     backtick_default_read_multiple,
+   justfile: "
+
+default a=`read A && echo $A` b=`read B && echo $B`:
+  echo {{a}}
+  echo {{b}}
+",
+   stdin:    "foo\nbar\n",
+   stdout:   "foo\nbar\n",
+   stderr:   "echo foo\necho bar\n",
+}
+
+integration_test! {
+  name: equals_deprecated_assignment,
+  justfile: "
+    foo = 'bar'
+
+    default:
+      echo {{foo}}
+  ",
+  stdout: "bar\n",
+  stderr: "
+    warning: `=` in assignments, exports, and aliases is being phased out on favor of `:=`
+    Please see this issue for more details: https://github.com/casey/just/issues/379
+      |
+    1 | foo = 'bar'
+      |     ^
+    echo bar
+  ",
+}
+
+integration_test! {
+  name: lost_directory_change,
+  justfile: "
+    default:
+      @cd /
+      @echo done
+  ",
+  stdout: "done\n",
+  stderr: "
+    warning: Recipe `default` changes directory with `cd` on a line of its own, but each line is run by a new shell, so later lines run in the original directory
+    Use `cd DIR && COMMAND`, a shebang recipe, or `set one-shell` to run commands in DIR
+      |
+    2 |   @cd /
+      |   ^^^^^
+  ",
+}
+
+integration_test! {
+  name: lost_directory_change_joined,
+  justfile: "
+    default:
+      @cd / && echo joined
+      @cd / \\
+        && echo continued
+      @echo done
+  ",
+  stdout: "joined\ncontinued\ndone\n",
+}
+
+integration_test! {
+  name: lost_directory_change_one_shell,
+  justfile: "
+    set one-shell
+
+    default:
+      @cd /
+      @echo $(pwd)
+  ",
+  stdout: "/\n",
+}
+
+integration_test! {
+  name: equals_deprecated_quiet_warnings,
+  justfile: "
+    foo = 'bar'
+
+    default:
+      echo {{foo}}
+  ",
+  args: ("--quiet-warnings"),
+  stdout: "bar\n",
+  stderr: "echo bar\n",
+}
+
+integration_test! {
+  name: equals_deprecated_color,
+  justfile: "
+    foo = 'bar'
+
+    default:
+      @echo {{foo}}
+  ",
+  args: ("--color", "always"),
+  stdout: "bar\n",
+  stderr: "
+    \u{1b}[1;33mwarning:\u{1b}[0m \u{1b}[1m`=` in assignments, exports, and aliases is being phased out on favor of `:=`
+    Please see this issue for more details: https://github.com/casey/just/issues/379\u{1b}[0m
+      |
+    1 | foo = 'bar'
+      |     \u{1b}[1;33m^\u{1b}[0m
+  ",
+}
+
+integration_test! {
+  name: verbosity_setting,
+  justfile: "
+    set verbosity := 'loquacious'
+
+    default:
+      @echo hello
+  ",
+  stdout: "hello\n",
+  stderr: "===> Running recipe `default`...\necho hello\n",
+}
+
+integration_test! {
+  name: equals_deprecated_export,
+  justfile: "
+    export FOO = 'bar'
+
+    default:
+      echo $FOO
+    ",
+  stdout: "bar\n",
+  stderr: "
+    warning: `=` in assignments, exports, and aliases is being phased out on favor of `:=`
+    Please see this issue for more details: https://github.com/casey/just/issues/379
+      |
+    1 | export FOO = 'bar'
+      |            ^
+    echo $FOO
+  ",
+}
+
+integration_test! {
+  name: equals_deprecated_alias,
+  justfile: "
+    alias foo = default
+
+    default:
+      echo default
+  ",
+  args: ("foo"),
+  stdout: "default\n",
+  stderr: "
+    warning: `=` in assignments, exports, and aliases is being phased out on favor of `:=`
+    Please see this issue for more details: https://github.com/casey/just/issues/379
+      |
+    1 | alias foo = default
+      |           ^
+    echo default
+  ",
+}
+
+integration_test! {
+  name: pass_through_flag,
+  justfile: "
+    run +args:
+      echo {{args}}
+  ",
+  args: ("run", "--", "--help"),
+  stdout: "--help\n",
+  stderr: "echo --help\n",
+}
+
+integration_test! {
+  name: pass_through_override,
+  justfile: "
+    foo := 'foo'
+
+    run +args:
+      echo {{foo}} {{args}}
+  ",
+  args: ("foo=bar", "run", "--", "foo=baz"),
+  stdout: "bar foo=baz\n",
+  stderr: "echo bar foo=baz\n",
+}
+
+integration_test! {
+  name: flag_like_arguments,
+  justfile: "
+    test +flags:
+      echo {{flags}}
+  ",
+  args: ("test", "-v", "--release"),
+  stdout: "-v --release\n",
+  stderr: "echo -v --release\n",
+}
+
+integration_test! {
+  name: negative_argument,
+  justfile: "
+    add a b:
+      echo $(({{a}} + {{b}}))
+  ",
+  args: ("add", "-1", "3"),
+  stdout: "2\n",
+  stderr: "echo $((-1 + 3))\n",
+}
+
+integration_test! {
+  name: unknown_override_suggestion,
+  justfile: "
+    version := '0.0.0'
+
+    foo:
+      echo {{version}}
+  ",
+  args: ("verison=1.0.0", "foo"),
+  stderr: "
+    error: Variable `verison` overridden on the command line but not present in justfile
+    Did you mean `version`?
+  ",
+  status: EXIT_USAGE,
+}
+
+integration_test! {
+  name: invalid_override_name,
+  justfile: "
+    foo:
+      echo foo
+  ",
+  args: ("--set", "foo bar", "baz", "foo"),
+  stderr: "
+    error: Cannot override `foo bar` on the command line, it is not a valid variable name
+  ",
+  status: EXIT_USAGE,
+}
+
+integration_test! {
+  name: env_flag,
+  justfile: "
+    bar := `echo $FOO`
+
+    foo:
+      echo $FOO {{bar}} {{env_var('FOO')}}
+  ",
+  args: ("--env", "FOO=hello", "foo"),
+  stdout: "hello hello hello\n",
+  stderr: "echo $FOO hello hello\n",
+}
+
+integration_test! {
+  name: env_flag_overrides_dotenv,
+  justfile: "
+    set dotenv-load
+
+    foo:
+      echo $DOTENV_KEY
+  ",
+  args: ("--env", "DOTENV_KEY=flag-value", "foo"),
+  stdout: "flag-value\n",
+  stderr: "echo $DOTENV_KEY\n",
+}
+
+integration_test! {
+  name: env_flag_invalid,
+  justfile: "
+    foo:
+      echo foo
+  ",
+  args: ("--env", "FOO", "foo"),
+  stderr: "error: Invalid argument `FOO` to --env, expected `KEY=VALUE`\n",
+  status: EXIT_USAGE,
+}
+
+integration_test! {
+  name: argument_mismatch_parameter_doc,
+  justfile: "
+    # deploy the site
+    # host: server to deploy to
+    # path: directory on the server
+    deploy host path='/var/www':
+      echo {{host}} {{path}}
+  ",
+  args: ("deploy"),
+  stderr: "
+    error: Recipe `deploy` got 0 arguments but takes at least 1
+    usage:
+        just deploy host path='/var/www'
+    arguments:
+        host: server to deploy to
+        path: directory on the server
+  ",
+  status: EXIT_USAGE,
+}
+
+integration_test! {
+  name: list_functions,
+  justfile: "",
+  args: ("--list-functions"),
+  stdout: "
+    Available functions:
+        arch/0                 # Instruction set architecture, e.g. `x86_64`
+        changed_files/1        # Files changed since a git ref, separated by spaces
+        choose/2               # A random string of a given length, drawn from an alphabet
+        datetime/1             # The current local time, formatted with strftime-style specifications
+        datetime_utc/1         # The current UTC time, formatted with strftime-style specifications
+        env_var/1              # Value of an environment variable, failing if it is not present
+        env_var_or_default/2   # Value of an environment variable, or a default if it is not present
+        error/1                # Fail with an error message
+        extension/1            # The extension of the final component of a path
+        file_name/1            # The final component of a path
+        file_stem/1            # The final component of a path, without its extension
+        invocation_directory/0 # Directory from which just was invoked
+        invoke/1+              # Run a recipe with the given arguments, evaluating to the empty string
+        join/2                 # Two paths joined with the platform's path separator
+        just_executable/0      # Path of the just executable
+        justfile/0             # Path of the current justfile
+        justfile_directory/0   # Directory of the current justfile
+        lowercase/1            # A string converted to lowercase
+        os/0                   # Operating system, e.g. `linux`
+        os_family/0            # Operating system family, `unix` or `windows`
+        parent_directory/1     # The parent directory of a path
+        replace/3              # A string with every occurrence of a substring replaced by another
+        sha256/1               # SHA-256 digest of a string, in hexadecimal
+        sha256_file/1          # SHA-256 digest of a file's contents, in hexadecimal
+        trim/1                 # A string without leading and trailing whitespace
+        uppercase/1            # A string converted to uppercase
+        uuid/0                 # A random version 4 UUID
+        without_extension/1    # A path without the extension of its final component
+  ",
+}
+
+integration_test! {
+  name: string_functions,
+  justfile: "
+    name := trim('  Hello World  ')
+    slug := lowercase(replace(name, ' ', '-'))
+
+    foo:
+      echo {{uppercase(name)}} {{slug}}
+  ",
+  stdout: "HELLO WORLD hello-world\n",
+  stderr: "echo HELLO WORLD hello-world\n",
+}
+
+integration_test! {
+  name: ternary_function_argument_count,
+  justfile: "
+    x := replace('a', 'b')
+  ",
+  stderr: "
+    error: Function `replace` called with 2 arguments but takes 3
+      |
+    1 | x := replace('a', 'b')
+      |      ^^^^^^^
+  ",
+  status: EXIT_COMPILE_ERROR,
+}
+
+#[cfg(unix)]
+integration_test! {
+  name: path_functions,
+  justfile: "
+    source := join('src', 'assets/logo.png')
+
+    foo:
+      echo {{parent_directory(source)}} {{file_name(source)}} {{file_stem(source)}}
+      echo {{extension(source)}} {{without_extension(source)}}.svg
+  ",
+  stdout: "src/assets logo.png logo\npng src/assets/logo.svg\n",
+  stderr: "echo src/assets logo.png logo\necho png src/assets/logo.svg\n",
+}
+
+#[cfg(unix)]
+integration_test! {
+  name: join_operator,
+  justfile: "
+    target := 'target'
+    bin := target / 'debug' / `echo app`
+
+    foo:
+      echo {{bin}} {{target/'release'}} {{ ('/' + target) / 'bin' }}
+  ",
+  stdout: "target/debug/app target/release /target/bin\n",
+  stderr: "echo target/debug/app target/release /target/bin\n",
+}
+
+#[cfg(unix)]
+integration_test! {
+  name: join_operator_without_spaces,
+  justfile: "
+    x := 'a' /\"b\"
+    y := x /\"/c\"
+
+    foo:
+      echo {{x}} {{y}}
+  ",
+  stdout: "a/b a/b//c\n",
+  stderr: "echo a/b a/b//c\n",
+}
+
+integration_test! {
+  name: path_function_error,
+  justfile: "
+    foo:
+      echo {{extension('README')}}
+  ",
+  stderr: "
+    error: Call to function `extension` failed: Could not extract extension from `README`
+      |
+    2 |   echo {{extension('README')}}
+      |          ^^^^^^^^^
+  ",
+  status: EXIT_FAILURE,
+}
+
+integration_test! {
+  name: sha256,
+  justfile: "
+    foo:
+      echo {{sha256('hello')}}
+  ",
+  stdout: "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824\n",
+  stderr: "echo 2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824\n",
+}
+
+integration_test! {
+  name: sha256_file,
+  justfile: "
+    foo:
+      echo {{sha256_file('.env')}}
+  ",
+  stdout: "f729ce633ebed51d86f87642c10b27542cb3cb33f3bd0b34bed3bdff5be5f5dc\n",
+  stderr: "echo f729ce633ebed51d86f87642c10b27542cb3cb33f3bd0b34bed3bdff5be5f5dc\n",
+}
+
+#[cfg(unix)]
+integration_test! {
+  name: sha256_file_missing,
+  justfile: "
+    foo:
+      echo {{sha256_file('missing')}}
+  ",
+  stderr: "
+    error: Call to function `sha256_file` failed: Failed to open `missing`: No such file or directory (os error 2)
+      |
+    2 |   echo {{sha256_file('missing')}}
+      |          ^^^^^^^^^^^
+  ",
+  status: EXIT_FAILURE,
+}
+
+integration_test! {
+  name: datetime,
+  justfile: "
+    stamp := datetime_utc('%Y-%m-%dT%H:%M:%S%z')
+
+    foo:
+      @[[ '{{stamp}}' =~ ^[0-9]{4}-[0-9]{2}-[0-9]{2}T[0-9]{2}:[0-9]{2}:[0-9]{2}[+]0000$ ]]
+      @[[ '{{datetime('%s')}}' -ge '{{datetime_utc('%s')}}' ]]
+      @echo '{{datetime('%%')}}'
+  ",
+  stdout: "%\n",
+}
+
+integration_test! {
+  name: datetime_unsupported_specification,
+  justfile: "
+    foo:
+      echo {{datetime_utc('%Y%Q')}}
+  ",
+  stderr: "
+    error: Call to function `datetime_utc` failed: Unsupported conversion specification `%Q`
+      |
+    2 |   echo {{datetime_utc('%Y%Q')}}
+      |          ^^^^^^^^^^^^
+  ",
+  status: EXIT_FAILURE,
+}
+
+integration_test! {
+  name: uuid,
+  justfile: "
+    x := uuid()
+    y := uuid()
+
+    foo:
+      @[[ '{{x}}' =~ ^[0-9a-f]{8}-[0-9a-f]{4}-4[0-9a-f]{3}-[89ab][0-9a-f]{3}-[0-9a-f]{12}$ ]]
+      @[[ '{{x}}' != '{{y}}' ]]
+  ",
+}
+
+integration_test! {
+  name: choose,
+  justfile: "
+    foo:
+      @[[ '{{choose('32', 'ab')}}' =~ ^[ab]{32}$ ]]
+      @echo '{{choose('0', 'ab')}}'
+  ",
+  stdout: "\n",
+}
+
+integration_test! {
+  name: choose_repeated_character,
+  justfile: "
+    foo:
+      echo {{choose('8', 'aba')}}
+  ",
+  stderr: "
+    error: Call to function `choose` failed: Alphabet contains repeated character `a`
+      |
+    2 |   echo {{choose('8', 'aba')}}
+      |          ^^^^^^
+  ",
+  status: EXIT_FAILURE,
+}
+
+integration_test! {
+  name: choose_invalid_length,
+  justfile: "
+    foo:
+      echo {{choose('-1', 'ab')}}
+  ",
+  stderr: "
+    error: Call to function `choose` failed: Invalid length `-1`: invalid digit found in string
+      |
+    2 |   echo {{choose('-1', 'ab')}}
+      |          ^^^^^^
+  ",
+  status: EXIT_FAILURE,
+}
+
+integration_test! {
+  name: error_function,
+  justfile: "
+    target := if os() == 'plan9' { 'plan9' } else { error('Unsupported OS: ' + os()) }
+
+    build:
+      echo {{target}}
+  ",
+  stderr: format!("
+    error: Unsupported OS: {}
+      |
+    1 | target := if os() == 'plan9' {{ 'plan9' }} else {{ error('Unsupported OS: ' + os()) }}
+      |                                                 ^^^^^
+  ", env::consts::OS).as_str(),
+  status: EXIT_FAILURE,
+}
+
+integration_test! {
+  name: error_function_not_evaluated,
+  justfile: "
+    target := if os() == 'plan9' { error('Unsupported OS') } else { 'ok' }
+
+    build:
+      echo {{target}}
+  ",
+  stdout: "ok\n",
+  stderr: "echo ok\n",
+}
+
+integration_test! {
+  name: invoke_from_shebang_recipe,
+  justfile: "
+    build:
+      echo build
+
+    script:
+      #!/usr/bin/env bash
+      {{invoke('build')}}
+      echo script
+  ",
+  args: ("script"),
+  stdout: "build\nscript\n",
+  stderr: "echo build\n",
+}
+
+integration_test! {
+  name: invoke_honors_ran_dependencies,
+  justfile: "
+    build:
+      echo build
+
+    test: build
+      {{invoke('build')}}echo test
+  ",
+  args: ("test"),
+  stdout: "build\ntest\n",
+  stderr: "echo build\necho test\n",
+}
+
+integration_test! {
+  name: invoke_with_arguments,
+  justfile: "
+    greet name:
+      echo hello {{name}}
+
+    all:
+      {{invoke('greet', 'a')}}{{invoke('greet', 'b')}}echo done
+  ",
+  args: ("all"),
+  stdout: "hello a\nhello b\ndone\n",
+  stderr: "echo hello a\necho hello b\necho done\n",
+}
+
+integration_test! {
+  name: invoke_recursive,
+  justfile: "
+    a: b
+
+    b:
+      {{invoke('a')}}
+  ",
+  args: ("a"),
+  stderr: "error: Recipe `a` was invoked while it was already running\n",
+  status: EXIT_FAILURE,
+}
+
+integration_test! {
+  name: invoke_unknown_recipe,
+  justfile: "
+    a:
+      {{invoke('b')}}
+  ",
+  args: ("a"),
+  stderr: "
+    error: Call to function `invoke` failed: unknown recipe `b`
+      |
+    2 |   {{invoke('b')}}
+      |     ^^^^^^
+  ",
+  status: EXIT_FAILURE,
+}
+
+integration_test! {
+  name: invoke_in_assignment,
+  justfile: "
+    x := invoke('a')
+
+    a:
+      echo a
+  ",
+  args: ("a"),
+  stderr: "
+    error: Call to function `invoke` failed: `invoke` may only be used in recipes
+      |
+    1 | x := invoke('a')
+      |      ^^^^^^
+  ",
+  status: EXIT_FAILURE,
+}
+
+integration_test! {
+  name: shebang_interpolation_without_interpreter,
+  justfile: "
+    interpreter := ''
+
+    a:
+      #!{{interpreter}}
+      echo a
+  ",
+  args: ("a"),
+  stderr: "error: Recipe `a` has shebang line `#!`, which does not name an interpreter\n",
+  status: EXIT_FAILURE,
+}
+
+integration_test! {
+  name: env_file_setting,
+  justfile: "
+    set env-file
+
+    foo := 'bar'
+    baz := foo + '-qux'
+
+    a:
+      @cat $JUST_ENV_FILE
+  ",
+  args: ("a"),
+  stdout: "baz=bar-qux\nfoo=bar\n",
+}
+
+integration_test! {
+  name: env_file_setting_disabled,
+  justfile: "
+    foo := 'bar'
+
+    a:
+      @echo ${JUST_ENV_FILE:-unset}
+  ",
+  args: ("a"),
+  stdout: "unset\n",
+}
+
+integration_test! {
+  name: force_color_setting,
+  justfile: "
+    set force-color
+
+    x := `echo $FORCE_COLOR`
+
+    a:
+      @echo {{x}} $FORCE_COLOR $CLICOLOR_FORCE
+  ",
+  args: ("--color", "always", "a"),
+  stdout: "1 1 1\n",
+}
+
+integration_test! {
+  name: force_color_setting_without_color,
+  justfile: "
+    set force-color
+
+    a:
+      @echo ${FORCE_COLOR:-unset} ${CLICOLOR_FORCE:-unset}
+  ",
+  args: ("--color", "never", "a"),
+  stdout: "unset unset\n",
+}
+
+integration_test! {
+  name: one_shell_setting,
   justfile: "
-string := 'hello
-
-whatever' + bar
+    set one-shell
 
-a:
-  echo '{{string}}'
-",
-  args:     ("a"),
-  stdout:   "",
-  stderr:   "error: Variable `bar` not defined
-  |
-4 | whatever' + bar
-  |             ^^^
-",
-  status:   EXIT_FAILURE,
+    a:
+      @cd /
+      x=1
+      @echo $(pwd) $x
+  ",
+  args: ("a"),
+  stdout: "/ 1\n",
+  stderr: "x=1\n",
 }
 
 integration_test! {
-  name:     multiline_raw_string_in_interpolation,
-  justfile: r#"
-a:
-  echo '{{"a" + '
-  ' + "b"}}'
-"#,
-  args:     ("a"),
-  stdout:   "
-    a
-      b
-  ",
-  stderr:   "
-    echo 'a
-      b'
+  name: one_shell_setting_failure,
+  justfile: "
+    set one-shell
+
+    a:
+      @echo a
+      @exit 3
   ",
+  args: ("a"),
+  stdout: "a\n",
+  stderr: "error: Recipe `a` failed with exit code 3\ncommand:\n    echo a\n    exit 3\n",
+  status: 3,
 }
 
 integration_test! {
-  name:     error_line_after_multiline_raw_string_in_interpolation,
-  justfile: r#"
-a:
-  echo '{{"a" + '
-  ' + "b"}}'
-
-  echo {{b}}
-"#,
-  args:     ("a"),
-  stdout:   "",
-  stderr:   "error: Variable `b` not defined
-  |
-6 |   echo {{b}}
-  |          ^
+  name: ignored_error,
+  justfile: "
+    a:
+      -exit 3
+      @-exit 4
+      -@echo done
+  ",
+  args: ("a"),
+  stdout: "done\n",
+  stderr: "exit 3
+warning: Recipe `a` failed on line 2 with exit code 3, ignoring
+warning: Recipe `a` failed on line 3 with exit code 4, ignoring
 ",
-  status:   EXIT_FAILURE,
 }
 
 integration_test! {
-  name:     unterminated_raw_string,
+  name: ignored_error_quiet,
   justfile: "
-a b= ':
-",
-  args:     ("a"),
-  stdout:   "",
-  stderr:   "error: Unterminated string
-  |
-2 | a b= ':
-  |      ^
-",
-  status:   EXIT_FAILURE,
+    a:
+      -exit 3
+      @echo done
+  ",
+  args: ("--quiet", "a"),
+  stdout: "",
 }
 
 integration_test! {
-  name:     unterminated_string,
-  justfile: r#"
-a b= ":
-"#,
-  args:     ("a"),
-  stdout:   "",
-  stderr:   r#"error: Unterminated string
-  |
-2 | a b= ":
-  |      ^
-"#,
-  status:   EXIT_FAILURE,
+  name: ignored_error_one_shell,
+  justfile: "
+    set one-shell
+
+    a:
+      @echo a
+      -@exit 3
+  ",
+  args: ("a"),
+  stdout: "a\n",
+  stderr: "warning: Recipe `a` failed with exit code 3, ignoring\n",
 }
 
 integration_test! {
-  name:     variadic_recipe,
+  name: process_options,
   justfile: "
-a x y +z:
-  echo {{x}} {{y}} {{z}}
-",
-  args:     ("a", "0", "1", "2", "3", " 4 "),
-  stdout:   "0 1 2 3 4\n",
-  stderr:   "echo 0 1 2 3  4 \n",
+    [umask: '027']
+    [nice: '7']
+    a:
+      @umask
+      @nice
+  ",
+  args: ("a"),
+  stdout: "0027\n7\n",
 }
 
 integration_test! {
-  name:     variadic_ignore_default,
+  name: resource_limits,
   justfile: "
-a x y +z='HELLO':
-  echo {{x}} {{y}} {{z}}
-",
-  args:     ("a", "0", "1", "2", "3", " 4 "),
-  stdout:   "0 1 2 3 4\n",
-  stderr:   "echo 0 1 2 3  4 \n",
+    [max-memory: '1G']
+    [max-cpu-time: '2m']
+    a:
+      @ulimit -v
+      @ulimit -t
+  ",
+  args: ("a"),
+  stdout: "1048576\n120\n",
 }
 
 integration_test! {
-  name:     variadic_use_default,
+  name: each,
   justfile: "
-a x y +z='HELLO':
-  echo {{x}} {{y}} {{z}}
-",
-  args:     ("a", "0", "1"),
-  stdout:   "0 1 HELLO\n",
-  stderr:   "echo 0 1 HELLO\n",
+    setup:
+      echo setup
+
+    lint file: setup
+      echo {{file}}
+  ",
+  args: ("--each", "lint", "--", "a", "-b"),
+  stdout: "setup\na\n-b\n",
+  stderr: "echo setup\necho a\necho -b\n",
 }
 
 integration_test! {
-  name:     variadic_too_few,
+  name: each_failures,
   justfile: "
-a x y +z:
-  echo {{x}} {{y}} {{z}}
-",
-  args:     ("a", "0", "1"),
-  stdout:   "",
-  stderr:   "error: Recipe `a` got 2 arguments but takes at least 3\nusage:\n    just a x y +z\n",
-  status:   EXIT_FAILURE,
+    check n:
+      @[ {{n}} != 2 ] && [ {{n}} != 3 ]
+  ",
+  args: ("--each", "check", "1", "2", "3", "4"),
+  stderr: "
+    error: Recipe `check` failed on line 2 with exit code 1
+    command:
+        [ 2 != 2 ] && [ 2 != 3 ]
+    error: Recipe `check` failed on line 2 with exit code 1
+    command:
+        [ 3 != 2 ] && [ 3 != 3 ]
+    error: Recipe `check` failed for 2 of 4 arguments: `2` and `3`
+  ",
+  status: EXIT_FAILURE,
 }
 
 integration_test! {
-  name:     argument_grouping,
+  name: each_parallel,
   justfile: "
-FOO A B='blarg':
-  echo foo: {{A}} {{B}}
-
-BAR X:
-  echo bar: {{X}}
-
-BAZ +Z:
-  echo baz: {{Z}}
-",
-  args:     ("BAR", "0", "FOO", "1", "2", "BAZ", "3", "4", "5"),
-  stdout:   "bar: 0\nfoo: 1 2\nbaz: 3 4 5\n",
-  stderr:   "echo bar: 0\necho foo: 1 2\necho baz: 3 4 5\n",
+    wait name:
+      @if [ {{name}} = a ]; then \\
+        for i in $(seq 100); do [ -f b ] && exit 0; sleep 0.1; done; exit 1; \\
+      else \\
+        touch b; \\
+      fi
+  ",
+  args: ("--each", "--jobs", "2", "wait", "a", "b"),
 }
 
 integration_test! {
-  name:     missing_second_dependency,
+  name: each_argument_count,
   justfile: "
-x:
+    add a b:
+      echo {{a}} {{b}}
+  ",
+  args: ("--each", "add", "1", "2"),
+  stderr: "
+    error: Recipe `add` got 1 argument but takes 2
+    usage:
+        just add a b
+  ",
+  status: EXIT_USAGE,
+}
 
-a: x y
-",
-  stdout:   "",
-  stderr:   "error: Recipe `a` has unknown dependency `y`
-  |
-4 | a: x y
-  |      ^
-",
-  status:   EXIT_FAILURE,
+integration_test! {
+  name: each_without_recipe,
+  justfile: "
+    foo:
+      echo foo
+  ",
+  args: ("--each"),
+  stderr: "error: --each requires the name of a recipe to run\n",
+  status: EXIT_USAGE,
 }
 
 integration_test! {
-  name:     list_colors,
+  name: each_invalid_jobs,
   justfile: "
-# comment
-a B C +D='hello':
-  echo {{B}} {{C}} {{D}}
-",
-  args:     ("--color", "always", "--list"),
-  stdout:   "
-    Available recipes:
-        a \
-    \u{1b}[36mB\u{1b}[0m \u{1b}[36mC\u{1b}[0m \u{1b}[35m+\
-    \u{1b}[0m\u{1b}[36mD\u{1b}[0m=\u{1b}[32m'hello'\u{1b}[0m \
-     \u{1b}[34m#\u{1b}[0m \u{1b}[34mcomment\u{1b}[0m
+    foo:
+      echo foo
   ",
+  args: ("--each", "--jobs", "0", "foo"),
+  stderr: "error: Invalid argument `0` to --jobs, expected a positive integer\n",
+  status: EXIT_USAGE,
 }
 
 integration_test! {
-  name:     run_colors,
+  name: matrix_all,
   justfile: "
-# comment
-a:
-  echo hi
-",
-  args:     ("--color", "always", "--highlight", "--verbose"),
-  stdout:   "hi\n",
-  stderr:   "\u{1b}[1;36m===> Running recipe `a`...\u{1b}[0m\n\u{1b}[1mecho hi\u{1b}[0m\n",
+    [matrix: os=('linux'|'mac'), mode=('debug'|'release')]
+    build mode os:
+      echo {{os}} {{mode}}
+  ",
+  args: ("--all", "build"),
+  stdout: "linux debug\nlinux release\nmac debug\nmac release\n",
+  stderr: "echo linux debug\necho linux release\necho mac debug\necho mac release\n",
 }
 
 integration_test! {
-  name:     trailing_flags,
+  name: matrix_without_all,
   justfile: "
-echo A B C:
-  echo {{A}} {{B}} {{C}}
-",
-  args:     ("echo", "--some", "--awesome", "--flags"),
-  stdout:   "--some --awesome --flags\n",
-  stderr:   "echo --some --awesome --flags\n",
+    [matrix: os=('linux'|'mac')]
+    build os:
+      echo {{os}}
+  ",
+  args: ("build", "windows"),
+  stdout: "windows\n",
+  stderr: "echo windows\n",
 }
 
 integration_test! {
-   name:     comment_before_variable,
-   justfile: "
-#
-A:='1'
-echo:
-  echo {{A}}
- ",
-   args:     ("echo"),
-   stdout:   "1\n",
-   stderr:   "echo 1\n",
+  name: list_matrix,
+  justfile: "
+    # build it
+    [matrix: os=('linux'|'mac'), mode=('debug'|'release')]
+    build os mode:
+      echo {{os}} {{mode}}
+
+    test:
+      echo test
+  ",
+  args: ("--list", "--matrix"),
+  stdout: "
+    Available recipes:
+        build os mode # build it
+            build linux debug
+            build linux release
+            build mac debug
+            build mac release
+        test
+  ",
 }
 
 integration_test! {
-   name:     dotenv_variable_in_recipe,
-   justfile: "
-#
-echo:
-  echo $DOTENV_KEY
- ",
-   stdout:   "dotenv-value\n",
-   stderr:   "echo $DOTENV_KEY\n",
+  name: positional_arguments,
+  justfile: "
+    set positional-arguments
+
+    a b c='C' *d:
+      @echo $# \"$1\" \"$2\" \"$3\" \"$4\"
+  ",
+  args: ("a", "B x", "c", "d 1", "d2"),
+  stdout: "4 B x c d 1 d2\n",
 }
 
 integration_test! {
-   name:     dotenv_variable_in_backtick,
-   justfile: "
-#
-X:=`echo $DOTENV_KEY`
-echo:
-  echo {{X}}
- ",
-   stdout:   "dotenv-value\n",
-   stderr:   "echo dotenv-value\n",
+  name: positional_arguments_shebang,
+  justfile: "
+    set positional-arguments
+
+    script x:
+      #!/bin/sh
+      echo \"$1\"
+  ",
+  args: ("script", "X y"),
+  stdout: "X y\n",
 }
+
 integration_test! {
-   name:     dotenv_variable_in_function_in_recipe,
-   justfile: "
-#
-echo:
-  echo {{env_var_or_default('DOTENV_KEY', 'foo')}}
-  echo {{env_var('DOTENV_KEY')}}
- ",
-   stdout:   "dotenv-value\ndotenv-value\n",
-   stderr:   "echo dotenv-value\necho dotenv-value\n",
+  name: positional_arguments_defaults,
+  justfile: "
+    set positional-arguments
+
+    a b='B' *c:
+      @echo $# $1
+  ",
+  args: ("a"),
+  stdout: "1 B\n",
 }
 
 integration_test! {
-   name:     dotenv_variable_in_function_in_backtick,
-   justfile: "
-#
-X:=env_var_or_default('DOTENV_KEY', 'foo')
-Y:=env_var('DOTENV_KEY')
-echo:
-  echo {{X}}
-  echo {{Y}}
- ",
-   stdout:   "dotenv-value\ndotenv-value\n",
-   stderr:   "echo dotenv-value\necho dotenv-value\n",
+  name: positional_arguments_one_shell,
+  justfile: "
+    set positional-arguments
+    set one-shell
+
+    a b:
+      x=\"$1\"
+      @echo \"$x\"
+  ",
+  args: ("a", "b c"),
+  stdout: "b c\n",
+  stderr: "x=\"$1\"\n",
 }
 
 integration_test! {
-   name:     invalid_escape_sequence_message,
-   justfile: r#"
-X := "\'"
-"#,
-   stdout:   "",
-   stderr:   r#"error: `\'` is not a valid escape sequence
-  |
-2 | X := "\'"
-  |      ^^^^
-"#,
-   status:   EXIT_FAILURE,
+  name: parallel_dependencies,
+  justfile: "
+    a: [b, c] d
+      @echo a
+
+    b: x
+      @for i in $(seq 100); do [ -f c ] && exit 0; sleep 0.1; done; exit 1
+
+    c: x
+      @touch c
+
+    d:
+      @[ -f c ] && echo d
+
+    x:
+      @echo x
+  ",
+  args: ("--jobs", "2", "a"),
+  stdout: "x\nd\na\n",
 }
 
 integration_test! {
-   name:     unknown_variable_in_default,
-   justfile: "
-foo x=bar:
-",
-   stdout:   "",
-   stderr:   r#"error: Variable `bar` not defined
-  |
-2 | foo x=bar:
-  |       ^^^
-"#,
-   status:   EXIT_FAILURE,
+  name: parallel_dependencies_without_jobs,
+  justfile: "
+    a: [b, c]
+
+    b:
+      @echo b
+
+    c:
+      @echo c
+  ",
+  args: ("a"),
+  stdout: "b\nc\n",
 }
 
 integration_test! {
-   name:     unknown_function_in_default,
-   justfile: "
-foo x=bar():
-",
-   stdout:   "",
-   stderr:   r#"error: Call to unknown function `bar`
-  |
-2 | foo x=bar():
-  |       ^^^
-"#,
-   status:   EXIT_FAILURE,
+  name: parallel_dependencies_failure,
+  justfile: "
+    a: [b, c]
+      @echo a
+
+    b:
+      @exit 3
+
+    c:
+      @exit 4
+  ",
+  args: ("--jobs", "2", "a"),
+  stderr: "error: Recipe `b` failed on line 5 with exit code 3\ncommand:\n    exit 3\n",
+  status: 3,
 }
 
 integration_test! {
-   name:     default_string,
-   justfile: "
-foo x='bar':
-  echo {{x}}
-",
-   stdout:   "bar\n",
-   stderr:   "echo bar\n",
+  name: subsequent_dependencies,
+  justfile: "
+    a: b && c d
+      @echo a
+
+    b:
+      @echo b
+
+    c: b
+      @echo c
+
+    d:
+      @echo d
+  ",
+  args: ("a"),
+  stdout: "b\na\nc\nd\n",
 }
 
 integration_test! {
-   name:     default_concatination,
-   justfile: "
-foo x=(`echo foo` + 'bar'):
-  echo {{x}}
-",
-   stdout:   "foobar\n",
-   stderr:   "echo foobar\n",
+  name: subsequent_dependencies_not_run_after_failure,
+  justfile: "
+    a: && b
+      @echo a
+      @exit 3
+
+    b:
+      @echo b
+  ",
+  args: ("a"),
+  stdout: "a\n",
+  stderr: "error: Recipe `a` failed on line 3 with exit code 3\ncommand:\n    exit 3\n",
+  status: 3,
 }
 
 integration_test! {
-   name:     default_backtick,
-   justfile: "
-foo x=`echo foo`:
-  echo {{x}}
-",
-   stdout:   "foo\n",
-   stderr:   "echo foo\n",
+  name: subsequent_dependencies_already_run,
+  justfile: "
+    a: && b
+      @echo a
+
+    b:
+      @echo b
+  ",
+  args: ("b", "a"),
+  stdout: "b\na\n",
 }
 
 integration_test! {
-   name:     default_variable,
-   justfile: "
-y := 'foo'
-foo x=y:
-  echo {{x}}
-",
-   stdout:   "foo\n",
-   stderr:   "echo foo\n",
+  name: subsequent_dependencies_each,
+  justfile: "
+    a x: && b
+      @echo {{x}}
+
+    b:
+      @echo b
+  ",
+  args: ("--each", "a", "x"),
+  stdout: "x\nb\n",
 }
 
 integration_test! {
-  name:     test_os_arch_functions_in_default,
-  justfile: r#"
-foo a=arch() o=os() f=os_family():
-  echo {{a}} {{o}} {{f}}
-"#,
-  stdout:   format!("{} {} {}\n", target::arch(), target::os(), target::os_family()).as_str(),
-  stderr:   format!("echo {} {} {}\n", target::arch(), target::os(), target::os_family()).as_str(),
+  name: parallel_dependencies_output_not_interleaved,
+  justfile: "
+    a: [b, c]
+
+    b:
+      @echo b1
+      @sleep 0.5
+      @echo b2
+
+    c:
+      @sleep 0.2
+      echo c
+  ",
+  args: ("--jobs", "2", "a"),
+  stdout: "c\nb1\nb2\n",
+  stderr: "echo c\n",
 }
 
 integration_test! {
-  name:     unterminated_interpolation_eol,
+  name: parallel_dependencies_of_dependencies,
   justfile: "
-foo:
-  echo {{
-",
-  stderr:   r#"
-    error: Unterminated interpolation
-      |
-    3 |   echo {{
-      |        ^^
-  "#,
-  status:   EXIT_FAILURE,
+    a: [b, c]
+
+    b: x
+
+    c: y
+
+    x:
+      @for i in $(seq 100); do [ -f y ] && exit 0; sleep 0.1; done; exit 1
+
+    y:
+      @touch y
+  ",
+  args: ("--jobs", "2", "a"),
 }
 
 integration_test! {
-  name:     unterminated_interpolation_eof,
+  name: dotenv_not_loaded_by_default,
   justfile: "
-foo:
-  echo {{",
-  stderr:   r#"
-    error: Unterminated interpolation
-      |
-    3 |   echo {{
-      |        ^^
-  "#,
-  status:   EXIT_FAILURE,
+    foo:
+      @echo ${DOTENV_KEY:-unset}
+  ",
+  args: ("foo"),
+  stdout: "unset\n",
 }
 
 integration_test! {
-  name:     unterminated_backtick,
+  name: weighted_parallel_dependencies,
   justfile: "
-foo a=\t`echo blaaaaaah:
-  echo {{a}}",
-  stderr:   r#"
-    error: Unterminated backtick
-      |
-    2 | foo a=    `echo blaaaaaah:
-      |           ^
-  "#,
-  status:   EXIT_FAILURE,
+    a: [b, c]
+
+    [weight: '2']
+    b:
+      @touch b && [ ! -f c ] && sleep 0.3 && rm b
+
+    c:
+      @touch c && [ ! -f b ] && sleep 0.3 && rm c
+  ",
+  args: ("--jobs", "2", "a"),
 }
 
 integration_test! {
-  name:     unknown_start_of_token,
+  name: weighted_each,
   justfile: "
-assembly_source_files = $(wildcard src/arch/$(arch)/*.s)
-",
-  stderr:   r#"
-    error: Unknown start of token:
-      |
-    2 | assembly_source_files = $(wildcard src/arch/$(arch)/*.s)
-      |                         ^
-  "#,
-   status:   EXIT_FAILURE,
+    [weight: 2]
+    a x:
+      @touch {{x}} && [ $(ls x y 2>/dev/null | wc -l) -eq 1 ] && sleep 0.3 && rm {{x}}
+  ",
+  args: ("--each", "--jobs", "3", "a", "x", "y"),
 }
 
 integration_test! {
-  name:     backtick_variable_cat,
+  name: skip_if_succeeds,
   justfile: "
-stdin := `cat`
+    build: install
+      @echo build
 
-default:
-  echo {{stdin}}
-",
-  stdin:    "STDIN",
-  stdout:   "STDIN\n",
-  stderr:   "echo STDIN\n",
+    [skip-if: 'echo checking && test -f justfile']
+    install: fetch
+      @echo install
+
+    fetch:
+      @echo fetch
+  ",
+  args: ("build"),
+  stdout: "build\n",
+  stderr: "===> Skipping recipe `install`, since `echo checking && test -f justfile` succeeded\n",
 }
 
 integration_test! {
-   name:     backtick_default_cat_stdin,
-   justfile: "
-default stdin = `cat`:
-  echo {{stdin}}
-",
-   stdin:    "STDIN",
-   stdout:   "STDIN\n",
-   stderr:   "echo STDIN\n",
+  name: skip_if_fails,
+  justfile: "
+    [skip-if: 'test -d node_modules']
+    install:
+      @echo install
+  ",
+  args: ("install"),
+  stdout: "install\n",
 }
 
 integration_test! {
-  name:     backtick_default_cat_justfile,
+  name: skip_if_dry_run,
   justfile: "
-default stdin = `cat justfile`:
-  echo '{{stdin}}'
-",
-  stdout:   "
+    [skip-if: 'true']
+    install:
+      echo install
+  ",
+  args: ("--dry-run", "install"),
+  stderr: "echo install\n",
+}
 
-    default stdin = `cat justfile`:
-      echo {{stdin}}
+#[cfg(target_os = "linux")]
+integration_test! {
+  name: unavailable_recipe,
+  justfile: "
+    [windows]
+    build-windows:
+      echo windows
+
+    [unix]
+    build-unix:
+      echo unix
+
+    [linux]
+    build_linux:
+      echo linux
+
+    test:
+      echo test
   ",
-  stderr:   "
-    echo '
-    default stdin = `cat justfile`:
-      echo '{{stdin}}''
+  args: ("build-windows"),
+  stderr: "
+    error: Recipe `build-windows` is only available on `windows`, not `linux`.
+    Did you mean `build-unix` or `build_linux`?
   ",
+  status: EXIT_UNKNOWN_RECIPE,
 }
 
+#[cfg(target_os = "linux")]
 integration_test! {
-   name:     backtick_variable_read_single,
-   justfile: "
-password := `read PW && echo $PW`
+  name: unavailable_recipe_without_alternatives,
+  justfile: "
+    [macos]
+    [windows]
+    open:
+      open .
 
-default:
-  echo {{password}}
-",
-   stdin:    "foobar\n",
-   stdout:   "foobar\n",
-   stderr:   "echo foobar\n",
+    test:
+      echo test
+  ",
+  args: ("test", "open"),
+  stderr: "error: Recipe `open` is only available on `macos` or `windows`, not `linux`.\n",
+  status: EXIT_UNKNOWN_RECIPE,
 }
 
+#[cfg(target_os = "linux")]
 integration_test! {
-   name:     backtick_variable_read_multiple,
-   justfile: "
-a := `read A && echo $A`
-b := `read B && echo $B`
+  name: unavailable_recipe_available_elsewhere,
+  justfile: "
+    [windows]
+    open:
+      start .
 
-default:
-  echo {{a}}
-  echo {{b}}
-",
-   stdin:    "foo\nbar\n",
-   stdout:   "foo\nbar\n",
-   stderr:   "echo foo\necho bar\n",
+    [linux]
+    open:
+      @echo linux
+  ",
+  args: ("open"),
+  stdout: "linux\n",
 }
 
+#[cfg(unix)]
 integration_test! {
-   name:     backtick_default_read_multiple,
-   justfile: "
+  name: exported_parameter,
+  justfile: "
+    foo $bar baz *$bob:
+      @echo $bar {{baz}} \"$bob\"
+  ",
+  args: ("foo", "a", "b", "c", "d"),
+  stdout: "a b c d\n",
+}
 
-default a=`read A && echo $A` b=`read B && echo $B`:
-  echo {{a}}
-  echo {{b}}
-",
-   stdin:    "foo\nbar\n",
-   stdout:   "foo\nbar\n",
-   stderr:   "echo foo\necho bar\n",
+#[cfg(unix)]
+integration_test! {
+  name: parameter_not_exported,
+  justfile: "
+    foo bar:
+      @echo \"${bar:-unset}\"
+  ",
+  args: ("foo", "a"),
+  stdout: "unset\n",
 }
 
+#[cfg(unix)]
 integration_test! {
-  name: equals_deprecated_assignment,
+  name: github_output_format,
   justfile: "
-    foo = 'bar'
+    a: b
+      echo a
 
-    default:
-      echo {{foo}}
+    b:
+      echo b
+      exit 3
   ",
-  stdout: "bar\n",
+  args: ("--output-format", "github", "a"),
+  stdout: "b\n",
   stderr: "
-    warning: `=` in assignments, exports, and aliases is being phased out on favor of `:=`
-    Please see this issue for more details: https://github.com/casey/just/issues/379
-      |
-    1 | foo = 'bar'
-      |     ^
-    echo bar
+    ::group::b
+    echo b
+    exit 3
+    ::endgroup::
+    error: Recipe `b` failed on line 6 with exit code 3
+    ::error file=justfile,line=6::Recipe `b` failed on line 6 with exit code 3
   ",
+  status: 3,
 }
 
 integration_test! {
-  name: equals_deprecated_export,
+  name: github_output_format_compile_error,
   justfile: "
-    export FOO = 'bar'
-
-    default:
-      echo $FOO
-    ",
-  stdout: "bar\n",
+    a:
+      echo {{x}}
+  ",
+  args: ("--output-format", "github", "a"),
   stderr: "
-    warning: `=` in assignments, exports, and aliases is being phased out on favor of `:=`
-    Please see this issue for more details: https://github.com/casey/just/issues/379
+    error: Variable `x` not defined
       |
-    1 | export FOO = 'bar'
-      |            ^
-    echo $FOO
+    2 |   echo {{x}}
+      |          ^
+    ::error file=justfile,line=2,col=10::Variable `x` not defined
   ",
+  status: EXIT_COMPILE_ERROR,
 }
 
 integration_test! {
-  name: equals_deprecated_alias,
+  name: timestamps,
   justfile: "
-    alias foo = default
-
-    default:
-      echo default
+    a:
+      echo a
+      @echo b
   ",
-  args: ("foo"),
-  stdout: "default\n",
-  stderr: "
-    warning: `=` in assignments, exports, and aliases is being phased out on favor of `:=`
-    Please see this issue for more details: https://github.com/casey/just/issues/379
-      |
-    1 | alias foo = default
-      |           ^
-    echo default
+  args: ("--timestamps", "--timestamp-format", "time %%"),
+  stdout: "a\nb\n",
+  stderr: "[time %] echo a\n",
+}
+
+integration_test! {
+  name: invalid_timestamp_format,
+  justfile: "
+    a:
+      echo a
   ",
+  args: ("--timestamps", "--timestamp-format", "%Q"),
+  stderr: "error: Invalid argument `%Q` to --timestamp-format: \
+           Unsupported conversion specification `%Q`\n",
+  status: EXIT_USAGE,
 }
@@ -0,0 +1,74 @@
+#![cfg(unix)]
+
+mod testing;
+
+use std::{
+  error::Error,
+  fs,
+  io::Write,
+  process::{Command, Stdio},
+  str,
+};
+
+use executable_path::executable_path;
+
+use testing::tempdir;
+
+/// Test that `[interactive]` recipes run attached to a terminal, with input
+/// forwarded to it, even when just's own stdio isn't a terminal
+#[test]
+fn interactive() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  fs::write(
+    tmp.path().join("justfile"),
+    "[interactive]\nfoo:\n  @test -t 0 && test -t 1 && test -t 2 && read x && echo got $x\n",
+  )?;
+
+  let mut child = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .stdin(Stdio::piped())
+    .stdout(Stdio::piped())
+    .spawn()?;
+
+  child.stdin.take().unwrap().write_all(b"hello\n")?;
+
+  let output = child.wait_with_output()?;
+
+  assert!(output.status.success());
+
+  // The terminal echoes input, and translates newlines
+  assert_eq!(str::from_utf8(&output.stdout)?, "hello\r\ngot hello\r\n");
+
+  Ok(())
+}
+
+/// Test that input forwarded to one line of an `[interactive]` recipe, but
+/// not read by it, is discarded rather than left for the next line
+#[test]
+fn unread_input() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  fs::write(
+    tmp.path().join("justfile"),
+    "[interactive]\nfoo:\n  @read x; echo 1 $x\n  @read y; echo 2 $y\n",
+  )?;
+
+  let mut child = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .stdin(Stdio::piped())
+    .stdout(Stdio::piped())
+    .spawn()?;
+
+  child.stdin.take().unwrap().write_all(b"a\nb\nc\n")?;
+
+  let output = child.wait_with_output()?;
+
+  assert!(output.status.success());
+
+  let stdout = str::from_utf8(&output.stdout)?;
+  assert!(stdout.contains("1 a\r\n"), "{:?}", stdout);
+  assert!(stdout.ends_with("2\r\n"), "{:?}", stdout);
+
+  Ok(())
+}
@@ -26,6 +26,7 @@ mod unix {
 
     let mut child = Command::new(&executable_path("just"))
       .current_dir(&tmp)
+      .env("XDG_STATE_HOME", tmp.path().join(".state"))
       .spawn()
       .expect("just invocation failed");
 
@@ -83,4 +84,46 @@ default:
 ",
     );
   }
+
+  #[test]
+  fn interrupt_parallel_dependencies() {
+    let tmp = tempdir();
+    fs::write(
+      tmp.path().join("justfile"),
+      "
+default: [a, b]
+
+a:
+  @sleep 10
+
+b:
+  @sleep 10
+",
+    )
+    .unwrap();
+
+    let start = Instant::now();
+
+    let mut child = Command::new(executable_path("just"))
+      .current_dir(&tmp)
+      .env("XDG_STATE_HOME", tmp.path().join(".state"))
+      .args(["--jobs", "2"])
+      .spawn()
+      .expect("just invocation failed");
+
+    while start.elapsed() < Duration::from_millis(500) {}
+
+    // Only just is interrupted, so it must kill the commands itself
+    kill(child.id());
+
+    let status = child.wait().unwrap();
+
+    let elapsed = start.elapsed();
+
+    if elapsed > Duration::from_secs(5) {
+      panic!("process returned too late: {:?}", elapsed);
+    }
+
+    assert_eq!(status.code(), Some(130));
+  }
 }
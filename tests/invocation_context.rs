@@ -0,0 +1,75 @@
+mod testing;
+
+use std::{error::Error, fs, process::Command, str};
+
+use executable_path::executable_path;
+
+use testing::tempdir;
+
+const JUSTFILE: &str = "
+[ci-only]
+release:
+  @echo release
+
+[local-only]
+clean:
+  @echo clean
+";
+
+/// Test that `[ci-only]` recipes run when `CI` is set, and `[local-only]`
+/// recipes are refused
+#[test]
+fn in_ci() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  fs::write(tmp.path().join("justfile"), JUSTFILE)?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .env("CI", "true")
+    .arg("release")
+    .output()?;
+
+  assert!(output.status.success());
+  assert_eq!(str::from_utf8(&output.stdout)?, "release\n");
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .env("CI", "true")
+    .arg("clean")
+    .output()?;
+
+  assert_eq!(output.status.code(), Some(1));
+  assert_eq!(
+    str::from_utf8(&output.stderr)?,
+    "error: Recipe `clean` may only be run locally, but `CI` is set\n"
+  );
+
+  Ok(())
+}
+
+/// Test that `[ci-only]` recipes are refused when `CI` is not set, before
+/// any other recipes run
+#[test]
+fn locally() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  fs::write(tmp.path().join("justfile"), JUSTFILE)?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .env_remove("CI")
+    .arg("clean")
+    .arg("release")
+    .output()?;
+
+  assert_eq!(output.status.code(), Some(1));
+  assert_eq!(str::from_utf8(&output.stdout)?, "");
+  assert_eq!(
+    str::from_utf8(&output.stderr)?,
+    "error: Recipe `release` may only be run in CI, but `CI` is not set\n"
+  );
+
+  Ok(())
+}
@@ -56,7 +56,8 @@ fn test_invocation_directory() {
 
   let output = process::Command::new(&executable_path("just"))
     .current_dir(&subdir)
-    .args(&["--shell", "sh"])
+    .env("XDG_STATE_HOME", subdir.join(".state"))
+    .args(&["--shell", "sh", "--quiet-warnings"])
     .output()
     .expect("just invocation failed");
 
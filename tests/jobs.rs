@@ -0,0 +1,37 @@
+mod testing;
+
+use std::{error::Error, fs, process::Command};
+
+use executable_path::executable_path;
+
+use testing::tempdir;
+
+/// Test that the output of a recipe run with `--jobs` is printed in the
+/// order it was written, with each echoed command before its output, even
+/// though standard output and standard error are collected separately
+#[test]
+fn output_order() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  fs::write(
+    tmp.path().join("justfile"),
+    "a: [b, c]\n\nb:\n  echo b1\n  echo b2 >&2\n  echo b3\n\nc:\n",
+  )?;
+
+  let log = fs::File::create(tmp.path().join("log.txt"))?;
+
+  let status = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .args(vec!["--jobs", "2", "a"])
+    .stdout(log.try_clone()?)
+    .stderr(log)
+    .status()?;
+
+  assert!(status.success());
+  assert_eq!(
+    fs::read_to_string(tmp.path().join("log.txt"))?,
+    "echo b1\nb1\necho b2 >&2\nb2\necho b3\nb3\n"
+  );
+
+  Ok(())
+}
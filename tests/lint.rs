@@ -0,0 +1,134 @@
+#![cfg(unix)]
+
+mod testing;
+
+use std::{error::Error, fs, process::Command, str};
+
+use executable_path::executable_path;
+
+use testing::tempdir;
+
+/// Stands in for `shellcheck`, reporting each occurrence of `bad`
+const LINTER: &str = r#"awk '/bad/ { print "-:" NR ":" index($0, "bad") ": warning: bad"; status = 1 }
+END { exit status }'"#;
+
+/// Test that diagnostics are mapped back to justfile lines and columns,
+/// and that shebang recipes in other languages are skipped
+#[test]
+fn lint() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  let justfile = tmp.path().join("justfile");
+  fs::write(
+    &justfile,
+    "
+x := 'y'
+
+foo:
+  echo good
+  @echo {{x}} bad
+
+bar:
+  #!/usr/bin/env bash
+  bad
+
+baz:
+  #!/usr/bin/env python3
+  bad
+",
+  )?;
+
+  let output = Command::new(executable_path("just"))
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .arg("--justfile")
+    .arg(&justfile)
+    .arg("--lint")
+    .arg("--linter")
+    .arg(LINTER)
+    .output()?;
+
+  assert_eq!(output.status.code(), Some(1));
+
+  let path = justfile.display();
+  assert_eq!(
+    str::from_utf8(&output.stdout)?,
+    format!("{}:10:3: warning: bad\n{}:6:15: warning: bad\n", path, path)
+  );
+
+  Ok(())
+}
+
+/// Test that linting succeeds when the linter has no complaints
+#[test]
+fn lint_clean() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  let justfile = tmp.path().join("justfile");
+  fs::write(&justfile, "foo:\n  echo good\n")?;
+
+  let output = Command::new(executable_path("just"))
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .arg("--justfile")
+    .arg(&justfile)
+    .arg("--lint")
+    .arg("--linter")
+    .arg(LINTER)
+    .output()?;
+
+  assert!(output.status.success());
+  assert_eq!(str::from_utf8(&output.stdout)?, "");
+
+  Ok(())
+}
+
+/// Test that the linter is run with the default shell, rather than the
+/// shell that recipes are run with
+#[test]
+fn lint_ignores_shell_setting() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  let justfile = tmp.path().join("justfile");
+  fs::write(
+    &justfile,
+    "set shell := ['bash', '-c', 'exit 7']\n\nfoo:\n  echo bad\n",
+  )?;
+
+  let output = Command::new(executable_path("just"))
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .arg("--justfile")
+    .arg(&justfile)
+    .arg("--lint")
+    .arg("--linter")
+    .arg(LINTER)
+    .output()?;
+
+  assert_eq!(output.status.code(), Some(1));
+  assert_eq!(
+    str::from_utf8(&output.stdout)?,
+    format!("{}:4:8: warning: bad\n", justfile.display())
+  );
+
+  Ok(())
+}
+
+/// Test that recipes aren't linted if the shell they're run with isn't one
+#[test]
+fn lint_skips_other_shells() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  let justfile = tmp.path().join("justfile");
+  fs::write(
+    &justfile,
+    "set shell := ['python3', '-c']\n\nfoo:\n  print('bad')\n",
+  )?;
+
+  let output = Command::new(executable_path("just"))
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .arg("--justfile")
+    .arg(&justfile)
+    .arg("--lint")
+    .arg("--linter")
+    .arg(LINTER)
+    .output()?;
+
+  assert!(output.status.success());
+  assert_eq!(str::from_utf8(&output.stdout)?, "");
+
+  Ok(())
+}
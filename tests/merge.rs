@@ -0,0 +1,189 @@
+mod testing;
+
+use std::{error::Error, fs, process::Command, str};
+
+use executable_path::executable_path;
+
+use testing::tempdir;
+
+#[test]
+fn merge() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  fs::write(
+    tmp.path().join("a.just"),
+    "
+set verbosity := 'loquacious'
+
+version := '1.0'
+
+alias b := build
+
+# Build everything
+build:
+  echo {{version}}
+",
+  )?;
+  fs::write(
+    tmp.path().join("b.just"),
+    "
+version := '1.0'
+
+test: build
+  echo test
+
+# Build everything
+build:
+  echo {{version}}
+",
+  )?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .args(vec!["--merge", "a.just", "b.just", "-o", "justfile"])
+    .output()?;
+
+  assert!(output.status.success());
+  assert_eq!(
+    str::from_utf8(&output.stderr)?,
+    "Wrote justfile to `justfile`\n"
+  );
+  assert_eq!(
+    fs::read_to_string(tmp.path().join("justfile"))?,
+    "set verbosity := 'loquacious'
+
+version := '1.0'
+
+alias b := build
+
+# Build everything
+build:
+    echo {{version}}
+
+test: build
+    echo test
+"
+  );
+
+  Ok(())
+}
+
+#[test]
+fn merge_to_stdout() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  fs::write(tmp.path().join("a.just"), "a:\n  echo a\n")?;
+  fs::write(tmp.path().join("b.just"), "b:\n  echo b\n")?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .args(vec!["--merge", "a.just", "b.just"])
+    .output()?;
+
+  assert!(output.status.success());
+  assert_eq!(
+    str::from_utf8(&output.stdout)?,
+    "a:\n    echo a\n\nb:\n    echo b\n"
+  );
+
+  Ok(())
+}
+
+#[test]
+fn merge_conflicts() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  fs::write(
+    tmp.path().join("a.just"),
+    "set verbosity := 'taciturn'\nx := 'a'\n\nbuild:\n  echo a\n",
+  )?;
+  fs::write(
+    tmp.path().join("b.just"),
+    "set verbosity := 'loquacious'\nx := 'b'\n\nbuild:\n  echo b\n\nalias c := build\n",
+  )?;
+  fs::write(tmp.path().join("c.just"), "c:\n  echo c\n")?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .args(vec![
+      "--merge", "a.just", "b.just", "c.just", "-o", "justfile",
+    ])
+    .output()?;
+
+  assert_eq!(output.status.code(), Some(1));
+  assert_eq!(
+    str::from_utf8(&output.stderr)?,
+    "error: Setting `verbosity` is defined differently in `a.just` and `b.just`
+error: Variable `x` is defined differently in `a.just` and `b.just`
+error: Recipe `build` is defined differently in `a.just` and `b.just`
+error: Alias `c` from `b.just` has the same name as recipe `c` from `c.just`
+"
+  );
+  assert!(!tmp.path().join("justfile").exists());
+
+  Ok(())
+}
+
+#[test]
+fn merge_shell() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  fs::write(
+    tmp.path().join("a.just"),
+    "set shell := ['bash', '-c']\n\na:\n  echo a\n",
+  )?;
+  fs::write(
+    tmp.path().join("b.just"),
+    "set shell := ['bash', '-c']\nset windows-shell := ['pwsh', '-c']\n\nb:\n  echo b\n",
+  )?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .args(vec!["--merge", "a.just", "b.just"])
+    .output()?;
+
+  assert!(output.status.success());
+  assert_eq!(
+    str::from_utf8(&output.stdout)?,
+    "set shell := ['bash', '-c']
+set windows-shell := ['pwsh', '-c']
+
+a:
+    echo a
+
+b:
+    echo b
+"
+  );
+
+  Ok(())
+}
+
+#[test]
+fn merge_shell_conflict() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  fs::write(
+    tmp.path().join("a.just"),
+    "set shell := ['bash', '-c']\nset windows-shell := ['cmd', '/c']\n",
+  )?;
+  fs::write(
+    tmp.path().join("b.just"),
+    "set shell := ['zsh', '-c']\nset windows-shell := ['pwsh', '-c']\n",
+  )?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .args(vec!["--merge", "a.just", "b.just"])
+    .output()?;
+
+  assert_eq!(output.status.code(), Some(1));
+  assert_eq!(
+    str::from_utf8(&output.stderr)?,
+    "error: Setting `shell` is defined differently in `a.just` and `b.just`
+error: Setting `windows-shell` is defined differently in `a.just` and `b.just`
+"
+  );
+
+  Ok(())
+}
@@ -0,0 +1,186 @@
+mod testing;
+
+use std::{error::Error, fs, process::Command, str};
+
+use executable_path::executable_path;
+
+use testing::tempdir;
+
+fn modules() -> Result<tempfile::TempDir, Box<dyn Error>> {
+  let tmp = tempdir();
+  fs::create_dir_all(tmp.path().join("foo/baz"))?;
+  fs::write(
+    tmp.path().join("justfile"),
+    "
+mod foo
+mod bar
+
+x := 'root'
+
+# build everything
+build:
+  @echo build {{x}}
+",
+  )?;
+  fs::write(
+    tmp.path().join("foo/justfile"),
+    "
+mod baz
+
+x := 'foo'
+
+# test something
+test arg='a': setup
+  @echo test {{x}} {{arg}}
+
+setup:
+  @echo setup
+",
+  )?;
+  fs::write(tmp.path().join("foo/baz/justfile"), "qux:\n  @echo qux\n")?;
+  fs::write(
+    tmp.path().join("bar.just"),
+    "_hidden:\n\nquux:\n  @echo quux\n",
+  )?;
+  Ok(tmp)
+}
+
+#[test]
+fn run() -> Result<(), Box<dyn Error>> {
+  let tmp = modules()?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .args(vec![
+      "foo::test",
+      "b",
+      "build",
+      "foo::baz::qux",
+      "bar::quux",
+    ])
+    .output()?;
+
+  assert!(output.status.success());
+  assert_eq!(
+    str::from_utf8(&output.stdout)?,
+    "setup\ntest foo b\nbuild root\nqux\nquux\n"
+  );
+
+  Ok(())
+}
+
+#[test]
+fn list() -> Result<(), Box<dyn Error>> {
+  let tmp = modules()?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .arg("--list")
+    .output()?;
+
+  assert!(output.status.success());
+  assert_eq!(
+    str::from_utf8(&output.stdout)?,
+    "Available recipes:
+    build # build everything
+    bar:
+        quux
+    foo:
+        setup
+        test arg='a' # test something
+        baz:
+            qux
+"
+  );
+
+  Ok(())
+}
+
+#[test]
+fn summary() -> Result<(), Box<dyn Error>> {
+  let tmp = modules()?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .arg("--summary")
+    .output()?;
+
+  assert!(output.status.success());
+  assert_eq!(
+    str::from_utf8(&output.stdout)?,
+    "build bar::quux foo::setup foo::test foo::baz::qux\n"
+  );
+
+  Ok(())
+}
+
+#[test]
+fn unknown_recipe() -> Result<(), Box<dyn Error>> {
+  let tmp = modules()?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .arg("foo::build")
+    .output()?;
+
+  assert_eq!(output.status.code(), Some(66));
+  assert_eq!(
+    str::from_utf8(&output.stderr)?,
+    "error: Justfile does not contain recipe `foo::build`.\n"
+  );
+
+  Ok(())
+}
+
+#[test]
+fn missing() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  fs::write(tmp.path().join("justfile"), "mod foo\n")?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .output()?;
+
+  assert_eq!(output.status.code(), Some(65));
+  assert_eq!(
+    str::from_utf8(&output.stderr)?,
+    "error: Source file for module `foo` not found, expected `foo/justfile` or `foo.just`
+  |
+1 | mod foo
+  |     ^^^
+"
+  );
+
+  Ok(())
+}
+
+#[test]
+fn circular() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  fs::write(tmp.path().join("justfile"), "mod foo\n")?;
+  fs::write(tmp.path().join("foo.just"), "mod foo\n")?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .args(vec!["--justfile", "justfile"])
+    .output()?;
+
+  assert_eq!(output.status.code(), Some(65));
+  assert_eq!(
+    str::from_utf8(&output.stderr)?,
+    "Error in imported justfile `foo.just`:
+error: Module `foo` contains itself
+  |
+1 | mod foo
+  |     ^^^
+"
+  );
+
+  Ok(())
+}
@@ -0,0 +1,113 @@
+mod testing;
+
+use std::{error::Error, fs, process::Command, str};
+
+use executable_path::executable_path;
+
+use testing::tempdir;
+
+/// Test that `--init` creates a runnable justfile in the current directory
+#[test]
+fn init() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .arg("--init")
+    .output()?;
+
+  assert!(output.status.success());
+
+  assert!(tmp.path().join("justfile").is_file());
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .output()?;
+
+  assert!(output.status.success());
+
+  assert_eq!(str::from_utf8(&output.stdout)?, "Hello, world!\n");
+
+  Ok(())
+}
+
+/// Test that `--init` does not overwrite an existing justfile
+#[test]
+fn init_justfile_exists() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  let justfile = tmp.path().join("justfile");
+  fs::write(&justfile, "foo:\n echo foo")?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .arg("--init")
+    .output()?;
+
+  assert!(!output.status.success());
+
+  assert_eq!(fs::read_to_string(&justfile)?, "foo:\n echo foo");
+
+  Ok(())
+}
+
+/// Test that `--system-info` works without a justfile
+#[test]
+fn system_info() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .arg("--system-info")
+    .output()?;
+
+  assert!(output.status.success());
+
+  let stdout = str::from_utf8(&output.stdout)?;
+
+  assert!(stdout.starts_with(concat!("just v", env!("CARGO_PKG_VERSION"), "\n")));
+  assert!(stdout.contains("\nos_family: "));
+
+  Ok(())
+}
+
+/// Test that `--grammar` works without a justfile, and that the EBNF grammar
+/// it prints is the one in GRAMMAR.md
+#[test]
+fn grammar() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .args(["--grammar", "ebnf"])
+    .output()?;
+
+  assert!(output.status.success());
+
+  let stdout = str::from_utf8(&output.stdout)?;
+  let grammar = fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/GRAMMAR.md"))?;
+
+  assert!(stdout.contains("\njustfile      : item* EOF\n"));
+  for section in stdout.split("\n\n") {
+    assert!(grammar.contains(section.trim_end()));
+  }
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .args(["--grammar", "tree-sitter"])
+    .output()?;
+
+  assert!(output.status.success());
+
+  let stdout = str::from_utf8(&output.stdout)?;
+
+  assert!(stdout.starts_with("module.exports = grammar({\n  name: 'just',\n"));
+  assert!(stdout.ends_with("  },\n});\n"));
+
+  Ok(())
+}
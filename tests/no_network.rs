@@ -0,0 +1,34 @@
+#![cfg(target_os = "linux")]
+
+mod testing;
+
+use std::{error::Error, fs, process::Command, str};
+
+use executable_path::executable_path;
+
+use testing::tempdir;
+
+/// Test that `[no-network]` recipes only see the loopback interface, and
+/// can still create files in the working directory
+#[test]
+fn no_network() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  fs::write(
+    tmp.path().join("justfile"),
+    "[no-network]\nfoo:\n  @tail -n +3 /proc/net/dev | cut -d: -f1 | tr -d ' '\n  @touch bar\n",
+  )?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .arg("foo")
+    .output()?;
+
+  assert!(output.status.success());
+
+  assert_eq!(str::from_utf8(&output.stdout)?, "lo\n");
+
+  assert!(tmp.path().join("bar").is_file());
+
+  Ok(())
+}
@@ -0,0 +1,196 @@
+mod testing;
+
+use std::{error::Error, fs, process::Command, str};
+
+use executable_path::executable_path;
+
+use testing::tempdir;
+
+/// Run `just --rename old new` on `justfile`, returning the output and the
+/// contents of the justfile afterwards
+fn rename(
+  justfile: &str,
+  old: &str,
+  new: &str,
+) -> Result<(std::process::Output, String), Box<dyn Error>> {
+  let tmp = tempdir();
+  let path = tmp.path().join("justfile");
+  fs::write(&path, justfile)?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .arg("--rename")
+    .arg(old)
+    .arg(new)
+    .output()?;
+
+  Ok((output, fs::read_to_string(&path)?))
+}
+
+#[test]
+fn recipe() -> Result<(), Box<dyn Error>> {
+  let (output, justfile) = rename(
+    "
+alias b := build
+
+# Run `build`, then `test`
+default: build test
+
+# Compile everything
+build:
+  echo release
+
+test: build
+  echo build
+",
+    "build",
+    "compile",
+  )?;
+
+  assert!(output.status.success());
+  assert_eq!(
+    str::from_utf8(&output.stderr)?,
+    "Renamed recipe `build` to `compile`\n"
+  );
+  assert_eq!(
+    justfile,
+    "
+alias b := compile
+
+# Run `compile`, then `test`
+default: compile test
+
+# Compile everything
+compile:
+  echo release
+
+test: compile
+  echo build
+"
+  );
+
+  Ok(())
+}
+
+#[test]
+fn variable() -> Result<(), Box<dyn Error>> {
+  let (output, justfile) = rename(
+    r#"
+export version := '1.0'
+tag := 'v' + version
+label := "{{ version }}-{{tag}}"
+override := env_var_or_default('VERSION', version)
+
+release ver=version: bump
+  echo {{version}} {{ver}} {{tag}} {{ "{{version}}" }}
+
+bump:
+  echo version
+"#,
+    "version",
+    "release_version",
+  )?;
+
+  assert!(output.status.success());
+  assert_eq!(
+    str::from_utf8(&output.stderr)?,
+    "Renamed variable `version` to `release_version`\n"
+  );
+  assert_eq!(
+    justfile,
+    r#"
+export release_version := '1.0'
+tag := 'v' + release_version
+label := "{{ release_version }}-{{tag}}"
+override := env_var_or_default('VERSION', release_version)
+
+release ver=release_version: bump
+  echo {{release_version}} {{ver}} {{tag}} {{ "{{release_version}}" }}
+
+bump:
+  echo version
+"#
+  );
+
+  Ok(())
+}
+
+#[test]
+fn shadowed_by_parameter() -> Result<(), Box<dyn Error>> {
+  let original = "
+target := 'all'
+
+build dir:
+  echo {{dir}} {{target}}
+";
+
+  let (output, justfile) = rename(original, "target", "dir")?;
+
+  assert_eq!(output.status.code(), Some(1));
+  assert_eq!(
+    str::from_utf8(&output.stderr)?,
+    "Renaming variable `target` to `dir` would produce an invalid justfile:
+error: Parameter `dir` shadows variable of the same name
+  |
+4 | build dir:
+  |       ^^^
+"
+  );
+  assert_eq!(justfile, original);
+
+  Ok(())
+}
+
+#[test]
+fn conflict() -> Result<(), Box<dyn Error>> {
+  let original = "a:\n  echo a\n\nb:\n  echo b\n";
+
+  let (output, justfile) = rename(original, "a", "b")?;
+
+  assert_eq!(output.status.code(), Some(1));
+  assert!(str::from_utf8(&output.stderr)?
+    .starts_with("Renaming recipe `a` to `b` would produce an invalid justfile:\n"));
+  assert_eq!(justfile, original);
+
+  Ok(())
+}
+
+#[test]
+fn unknown() -> Result<(), Box<dyn Error>> {
+  let (output, _) = rename("a:\n  echo a\n", "b", "c")?;
+
+  assert_eq!(output.status.code(), Some(1));
+  assert_eq!(
+    str::from_utf8(&output.stderr)?,
+    "Justfile does not contain recipe or variable `b`.\n"
+  );
+
+  Ok(())
+}
+
+#[test]
+fn invalid_name() -> Result<(), Box<dyn Error>> {
+  let (output, _) = rename("a:\n  echo a\n", "a", "b c")?;
+
+  assert_eq!(output.status.code(), Some(1));
+  assert_eq!(
+    str::from_utf8(&output.stderr)?,
+    "`b c` is not a valid recipe name.\n"
+  );
+
+  Ok(())
+}
+
+#[test]
+fn ambiguous() -> Result<(), Box<dyn Error>> {
+  let (output, _) = rename("a := 'a'\n\na:\n  echo {{a}}\n", "a", "b")?;
+
+  assert_eq!(output.status.code(), Some(1));
+  assert_eq!(
+    str::from_utf8(&output.stderr)?,
+    "Justfile contains both a recipe and a variable named `a`.\n"
+  );
+
+  Ok(())
+}
@@ -0,0 +1,107 @@
+mod testing;
+
+use std::{error::Error, fs, process::Command, str};
+
+use executable_path::executable_path;
+
+use testing::tempdir;
+
+const JUSTFILE: &str = "
+build:
+  @echo build
+
+test kind:
+  @test {{kind}} = unit
+
+lint:
+  @true
+";
+
+#[test]
+fn tap() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  fs::write(tmp.path().join("justfile"), JUSTFILE)?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .args([
+      "--report",
+      "report.tap",
+      "build",
+      "test",
+      "integration",
+      "lint",
+    ])
+    .output()?;
+
+  assert_eq!(output.status.code(), Some(1));
+  assert_eq!(str::from_utf8(&output.stdout)?, "build\n");
+  assert_eq!(
+    str::from_utf8(&output.stderr)?,
+    "error: Recipe `test` failed on line 6 with exit code 1
+command:
+    test integration = unit
+error: 1 of 3 recipes failed: `test integration`
+"
+  );
+
+  // Durations vary from run to run
+  let report = fs::read_to_string(tmp.path().join("report.tap"))?;
+  let report = report
+    .lines()
+    .filter(|line| !line.starts_with("  duration_ms: "))
+    .collect::<Vec<&str>>();
+
+  assert_eq!(
+    report,
+    &[
+      "TAP version 13",
+      "1..3",
+      "ok 1 - build",
+      "  ---",
+      "  ...",
+      "not ok 2 - test integration",
+      "  ---",
+      "  message: \"error: Recipe `test` failed on line 6 with exit code 1\\ncommand:\\n    test \
+       integration = unit\"",
+      "  ...",
+      "ok 3 - lint",
+      "  ---",
+      "  ...",
+    ]
+  );
+
+  Ok(())
+}
+
+#[test]
+fn junit() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  fs::write(tmp.path().join("justfile"), JUSTFILE)?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .args([
+      "--report",
+      "report.xml",
+      "--report-format",
+      "junit",
+      "test",
+      "unit",
+      "lint",
+    ])
+    .output()?;
+
+  assert!(output.status.success());
+
+  let report = fs::read_to_string(tmp.path().join("report.xml"))?;
+  assert!(report.starts_with(
+    "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites tests=\"2\" failures=\"0\""
+  ));
+  assert!(report.contains("<testcase name=\"test unit\" classname=\"just\" time=\""));
+  assert!(report.contains("<testcase name=\"lint\" classname=\"just\" time=\""));
+
+  Ok(())
+}
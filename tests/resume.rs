@@ -0,0 +1,97 @@
+mod testing;
+
+use std::{error::Error, fs, process::Command, str};
+
+use executable_path::executable_path;
+
+use testing::tempdir;
+
+const JUSTFILE: &str = "
+mode := 'debug'
+
+build:
+  @echo build {{mode}} >> log.txt
+
+test: build
+  @echo test {{mode}} >> log.txt
+  @test -f ok
+
+deploy: build
+  @echo deploy {{mode}} >> log.txt
+";
+
+#[test]
+fn resume() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  let state = tempdir();
+  fs::write(tmp.path().join("justfile"), JUSTFILE)?;
+
+  let just = |arguments: &[&str]| {
+    Command::new(executable_path("just"))
+      .current_dir(tmp.path())
+      .env("XDG_STATE_HOME", state.path())
+      .args(arguments)
+      .output()
+  };
+
+  let log = || fs::read_to_string(tmp.path().join("log.txt"));
+
+  let output = just(&["--resume"])?;
+  assert_eq!(output.status.code(), Some(1));
+  assert_eq!(
+    str::from_utf8(&output.stderr)?,
+    "There is no failed run of this justfile to resume.\n"
+  );
+
+  let output = just(&["mode=release", "build", "test", "deploy"])?;
+  assert_eq!(output.status.code(), Some(1));
+  assert_eq!(log()?, "build release\ntest release\n");
+
+  // Still failing, so only `test` runs again. Overrides aren't recorded, so
+  // they're given again.
+  let output = just(&["--set", "mode", "release", "--resume"])?;
+  assert_eq!(output.status.code(), Some(1));
+  assert_eq!(log()?, "build release\ntest release\ntest release\n");
+
+  fs::write(tmp.path().join("ok"), "")?;
+  fs::remove_file(tmp.path().join("log.txt"))?;
+
+  let output = just(&["--resume"])?;
+  assert!(output.status.success());
+  assert_eq!(log()?, "test debug\ndeploy debug\n");
+
+  let output = just(&["--resume"])?;
+  assert_eq!(output.status.code(), Some(1));
+
+  Ok(())
+}
+
+#[test]
+fn success_clears_progress() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  let state = tempdir();
+  fs::write(tmp.path().join("justfile"), JUSTFILE)?;
+
+  let just = |arguments: &[&str]| {
+    Command::new(executable_path("just"))
+      .current_dir(tmp.path())
+      .env("XDG_STATE_HOME", state.path())
+      .args(arguments)
+      .output()
+  };
+
+  let output = just(&["test"])?;
+  assert_eq!(output.status.code(), Some(1));
+
+  let output = just(&["deploy"])?;
+  assert!(output.status.success());
+
+  let output = just(&["--resume"])?;
+  assert_eq!(output.status.code(), Some(1));
+  assert_eq!(
+    str::from_utf8(&output.stderr)?,
+    "There is no failed run of this justfile to resume.\n"
+  );
+
+  Ok(())
+}
@@ -12,6 +12,7 @@ fn search_test<P: AsRef<path::Path>>(path: P, args: &[&str]) {
   let binary = executable_path("just");
 
   let output = process::Command::new(binary)
+    .env("XDG_STATE_HOME", path.as_ref().join(".state"))
     .current_dir(path)
     .args(args)
     .output()
@@ -108,3 +109,37 @@ fn test_downwards_path_argument() {
   search_test(&path, &["./a/"]);
   search_test(&path, &["./a/default"]);
 }
+
+#[test]
+fn test_justfile_name_preference() {
+  let tmp = tempdir();
+  let mut path = tmp.path().to_path_buf();
+  path.push("justfile");
+  fs::write(&path, "default:\n\techo ok").unwrap();
+  path.pop();
+
+  path.push("JUSTFILE");
+  if fs::File::open(&path).is_ok() {
+    // We are in case-insensitive file system
+    return;
+  }
+  fs::write(&path, "default:\n\techo bad").unwrap();
+  path.pop();
+
+  let output = process::Command::new(executable_path("just"))
+    .current_dir(&path)
+    .env("XDG_STATE_HOME", path.join(".state"))
+    .arg("--justfile-name")
+    .arg("justfile")
+    .output()
+    .expect("just invocation failed");
+
+  assert_eq!(output.status.code().unwrap(), 0);
+
+  let stdout = str::from_utf8(&output.stdout).unwrap();
+  assert_eq!(stdout, "ok\n");
+
+  let stderr = str::from_utf8(&output.stderr).unwrap();
+  assert!(stderr.starts_with("warning: Multiple candidate justfiles found in "));
+  assert!(stderr.ends_with("/justfile`\necho ok\n"));
+}
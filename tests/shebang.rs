@@ -0,0 +1,63 @@
+#![cfg(unix)]
+
+mod testing;
+
+use std::{error::Error, fs, process::Command, str};
+
+use executable_path::executable_path;
+
+use testing::tempdir;
+
+/// Test that, with `set shebang-error-line`, the line a shebang recipe's
+/// interpreter reports an error on is included in just's error message
+#[test]
+fn shebang_failure_line() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  fs::write(
+    tmp.path().join("justfile"),
+    "set shebang-error-line\nfoo:\n  #!/bin/sh\n  true\n  nonexistent-command-for-just-test\n",
+  )?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .output()?;
+
+  assert_eq!(output.status.code(), Some(127));
+
+  let stderr = str::from_utf8(&output.stderr)?;
+  assert!(
+    stderr.ends_with("error: Recipe `foo` failed on line 5 with exit code 127\n"),
+    "unexpected stderr: {}",
+    stderr
+  );
+
+  Ok(())
+}
+
+/// Test that a shebang recipe's standard error is otherwise left alone, and
+/// only its exit code is reported
+#[test]
+fn shebang_failure_without_line() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  fs::write(
+    tmp.path().join("justfile"),
+    "\nfoo:\n  #!/bin/sh\n  true\n  nonexistent-command-for-just-test\n",
+  )?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .output()?;
+
+  assert_eq!(output.status.code(), Some(127));
+
+  let stderr = str::from_utf8(&output.stderr)?;
+  assert!(
+    stderr.ends_with("error: Recipe `foo` failed with exit code 127\n"),
+    "unexpected stderr: {}",
+    stderr
+  );
+
+  Ok(())
+}
@@ -0,0 +1,97 @@
+#![cfg(unix)]
+
+mod testing;
+
+use std::{
+  error::Error,
+  fs,
+  process::{Command, Output},
+  str,
+};
+
+use executable_path::executable_path;
+
+use testing::tempdir;
+
+const JUSTFILE: &str = "
+set shell := ['bash', '-euo', 'pipefail', '-c']
+
+shell := `echo $0`
+
+default:
+  @echo {{shell}}
+
+pipe:
+  @false | true
+";
+
+fn just(arguments: &[&str]) -> Result<Output, Box<dyn Error>> {
+  let tmp = tempdir();
+  fs::write(tmp.path().join("justfile"), JUSTFILE)?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .args(arguments)
+    .output()?;
+
+  Ok(output)
+}
+
+/// Test that recipes and backticks are run with the shell and arguments given
+/// by the `shell` setting
+#[test]
+fn shell_setting() -> Result<(), Box<dyn Error>> {
+  let output = just(&[])?;
+  assert!(output.status.success());
+  assert_eq!(str::from_utf8(&output.stdout)?, "bash\n");
+
+  let output = just(&["pipe"])?;
+  assert_eq!(output.status.code(), Some(1));
+  assert_eq!(
+    str::from_utf8(&output.stderr)?,
+    "error: Recipe `pipe` failed on line 10 with exit code 1\ncommand:\n    false | true\n"
+  );
+
+  Ok(())
+}
+
+/// Test that `--shell` takes precedence over the `shell` setting
+#[test]
+fn shell_flag_overrides_setting() -> Result<(), Box<dyn Error>> {
+  let output = just(&["--shell", "sh"])?;
+  assert!(output.status.success());
+  assert_eq!(str::from_utf8(&output.stdout)?, "sh\n");
+
+  let output = just(&["--shell", "sh", "pipe"])?;
+  assert!(output.status.success());
+
+  Ok(())
+}
+
+/// Test that the Windows shell settings are ignored on other platforms
+#[test]
+fn windows_shell_setting_ignored() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  fs::write(
+    tmp.path().join("justfile"),
+    "
+set shell := ['bash', '-c']
+set windows-shell := ['powershell.exe', '-Command']
+set windows-powershell
+
+default:
+  @echo $0
+",
+  )?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .output()?;
+
+  assert!(output.status.success());
+  assert_eq!(str::from_utf8(&output.stdout)?, "bash\n");
+
+  Ok(())
+}
@@ -0,0 +1,51 @@
+mod testing;
+
+use std::{error::Error, fs, process::Command, str};
+
+use executable_path::executable_path;
+
+use testing::tempdir;
+
+/// Test that `JUST_VERBOSE` makes just as verbose as the equivalent flags
+#[test]
+fn verbosity_from_environment() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  fs::write(tmp.path().join("justfile"), "default:\n  @echo hello\n")?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .env("JUST_VERBOSE", "1")
+    .output()?;
+
+  assert!(output.status.success());
+  assert_eq!(str::from_utf8(&output.stdout)?, "hello\n");
+  assert_eq!(
+    str::from_utf8(&output.stderr)?,
+    "===> Running recipe `default`...\necho hello\n"
+  );
+
+  Ok(())
+}
+
+/// Test that an invalid value for `JUST_VERBOSE` is a usage error
+#[test]
+fn invalid_verbosity_from_environment() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+  fs::write(tmp.path().join("justfile"), "default:\n  @echo hello\n")?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .env("JUST_VERBOSE", "loud")
+    .output()?;
+
+  assert_eq!(output.status.code(), Some(64));
+  assert_eq!(
+    str::from_utf8(&output.stderr)?,
+    "error: Invalid value `loud` for `JUST_VERBOSE`, expected `taciturn`, `loquacious`, \
+     `grandiloquent`, or a number\n"
+  );
+
+  Ok(())
+}
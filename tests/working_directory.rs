@@ -20,6 +20,7 @@ fn justfile_without_working_directory() -> Result<(), Box<dyn Error>> {
   fs::write(&data, "found it")?;
 
   let output = Command::new(executable_path("just"))
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
     .arg("--justfile")
     .arg(&justfile)
     .output()?;
@@ -53,7 +54,8 @@ fn change_working_directory_to_justfile_parent() -> Result<(), Box<dyn Error>> {
   fs::create_dir(&subdir)?;
 
   let output = Command::new(executable_path("just"))
-    .current_dir(subdir)
+    .current_dir(&subdir)
+    .env("XDG_STATE_HOME", subdir.join(".state"))
     .output()?;
 
   if !output.status.success() {
@@ -66,3 +68,78 @@ fn change_working_directory_to_justfile_parent() -> Result<(), Box<dyn Error>> {
 
   Ok(())
 }
+
+/// Test that a recipe prefixed with a path runs in the directory of the
+/// justfile found there
+#[test]
+fn path_prefixed_recipe_runs_in_justfile_directory() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+
+  let subdir = tmp.path().join("subdir");
+  fs::create_dir(&subdir)?;
+
+  fs::write(
+    subdir.join("justfile"),
+    "foo = `cat data`\ndefault:\n echo {{foo}}\n cat data",
+  )?;
+
+  fs::write(subdir.join("data"), "found it")?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(tmp.path())
+    .env("XDG_STATE_HOME", tmp.path().join(".state"))
+    .arg("subdir/default")
+    .output()?;
+
+  if !output.status.success() {
+    panic!("just invocation failed: {}", output.status)
+  }
+
+  let stdout = String::from_utf8(output.stdout).unwrap();
+
+  assert_eq!(stdout, "found it\nfound it");
+
+  Ok(())
+}
+
+/// Test that `justfile()` and `justfile_directory()` give the paths of the
+/// justfile and its directory, and `just_executable()` the path of just
+#[test]
+fn justfile_path_functions() -> Result<(), Box<dyn Error>> {
+  let tmp = tempdir();
+
+  let justfile = tmp.path().join("justfile");
+  fs::write(
+    &justfile,
+    "default:\n @echo {{justfile()}}\n @echo {{justfile_directory()}}\n \
+     @echo {{just_executable()}}",
+  )?;
+
+  let subdir = tmp.path().join("subdir");
+  fs::create_dir(&subdir)?;
+
+  let output = Command::new(executable_path("just"))
+    .current_dir(&subdir)
+    .env("XDG_STATE_HOME", subdir.join(".state"))
+    .arg("--justfile")
+    .arg(&justfile)
+    .output()?;
+
+  if !output.status.success() {
+    panic!("just invocation failed: {}", output.status)
+  }
+
+  let stdout = String::from_utf8(output.stdout).unwrap();
+
+  assert_eq!(
+    stdout,
+    format!(
+      "{}\n{}\n{}\n",
+      justfile.display(),
+      tmp.path().display(),
+      executable_path("just").display()
+    )
+  );
+
+  Ok(())
+}